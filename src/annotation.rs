@@ -0,0 +1,160 @@
+use egui::{PointerButton, Pos2, Response, Ui, Vec2};
+use serde::{Deserialize, Serialize};
+use crate::i18n::{Language, Text};
+
+/// Minimum pointer movement between two points of a freehand mark, in canvas units.
+const FREEHAND_STEP: f32 = 3.0;
+
+/// A note drawn on the canvas.
+///
+/// Positions are fractions of the canvas size, so that annotations stay in place when the
+/// canvas is resized. The projector doesn't expose figure coordinates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Annotation {
+    Text {
+        at: [f32; 2],
+        text: String
+    },
+    Arrow {
+        from: [f32; 2],
+        to: [f32; 2]
+    },
+    Freehand(Vec<[f32; 2]>)
+}
+
+impl Annotation {
+    /// All positions of the annotation scaled to a canvas of the given size.
+    #[must_use]
+    pub fn points(&self, size: (f32, f32)) -> Vec<(f32, f32)> {
+        let scale = |[x, y]: &[f32; 2]| (x * size.0, y * size.1);
+
+        match self {
+            Self::Text { at, .. } => vec![scale(at)],
+            Self::Arrow { from, to } => vec![scale(from), scale(to)],
+            Self::Freehand(points) => points.iter().map(scale).collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    Text,
+    Arrow,
+    Freehand
+}
+
+/// The annotation layer and the annotate mode.
+pub struct Annotations {
+    pub list: Vec<Annotation>,
+    /// Whether canvas clicks and drags annotate.
+    pub active: bool,
+    tool: Tool,
+    /// Content of the next text note.
+    text: String,
+    /// The arrow or freehand mark being drawn.
+    pub draft: Option<Annotation>
+}
+
+impl Default for Annotations {
+    fn default() -> Self {
+        Self {
+            list: Vec::new(),
+            active: false,
+            tool: Tool::Text,
+            text: String::new(),
+            draft: None
+        }
+    }
+}
+
+impl Annotations {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add annotations with the pointer. `origin` is the top-left corner of the canvas and `size` its size.
+    pub fn interact(&mut self, response: &Response, origin: Vec2, size: Vec2) {
+        let Some(pointer) = response.interact_pointer_pos() else {
+            return;
+        };
+
+        let at = {
+            let relative = (pointer - origin).to_vec2() / size;
+            [relative.x, relative.y]
+        };
+
+        match self.tool {
+            Tool::Text => {
+                if response.clicked() && !self.text.is_empty() {
+                    self.list.push(Annotation::Text { at, text: self.text.clone() });
+                }
+            }
+            Tool::Arrow => {
+                if response.drag_started_by(PointerButton::Primary) {
+                    self.draft = Some(Annotation::Arrow { from: at, to: at });
+                } else if let Some(Annotation::Arrow { to, .. }) = &mut self.draft {
+                    *to = at;
+                }
+            }
+            Tool::Freehand => {
+                if response.drag_started_by(PointerButton::Primary) {
+                    self.draft = Some(Annotation::Freehand(vec![at]));
+                } else if let Some(Annotation::Freehand(points)) = &mut self.draft {
+                    let last = points.last().map_or(Pos2::ZERO, |[x, y]| Pos2::new(*x, *y));
+
+                    if ((Pos2::new(at[0], at[1]) - last) * size).length() >= FREEHAND_STEP {
+                        points.push(at);
+                    }
+                }
+            }
+        }
+
+        if response.drag_stopped_by(PointerButton::Primary) {
+            self.list.extend(self.draft.take());
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, lang: Language) {
+        ui.checkbox(&mut self.active, Text::AnnotateOnCanvas.tr(lang));
+
+        ui.add_enabled_ui(self.active, |ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.tool, Tool::Text, Text::AnnotationText.tr(lang));
+                ui.radio_value(&mut self.tool, Tool::Arrow, Text::Arrow.tr(lang));
+                ui.radio_value(&mut self.tool, Tool::Freehand, Text::Freehand.tr(lang));
+            });
+
+            if self.tool == Tool::Text {
+                ui.horizontal(|ui| {
+                    ui.label(Text::Note.tr(lang));
+                    ui.text_edit_singleline(&mut self.text);
+                });
+            }
+        });
+
+        let mut remove = None;
+
+        for (i, annotation) in self.list.iter().enumerate() {
+            ui.horizontal(|ui| {
+                match annotation {
+                    Annotation::Text { text, .. } => ui.label(Text::TextNote.fill(lang, &[text])),
+                    Annotation::Arrow { .. } => ui.label(Text::Arrow.tr(lang)),
+                    Annotation::Freehand(_) => ui.label(Text::FreehandMark.tr(lang))
+                };
+
+                if ui.small_button(Text::Remove.tr(lang)).clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+
+        if let Some(i) = remove {
+            self.list.remove(i);
+        }
+
+        if ui.add_enabled(!self.list.is_empty(), egui::Button::new(Text::ClearAll.tr(lang))).clicked() {
+            self.list.clear();
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Compact storage of the states a long run goes through, for going back to any of them.
+//!
+//! Every published frame is stored. A full state is stored every [`KEYFRAME_INTERVAL`] frames and whenever
+//! a delta would take more space than it. In between, only the values that changed since the previous stored
+//! state are kept. States are rebuilt from the nearest keyframe on demand.
+
+use std::fmt::{Display, Formatter};
+use std::mem;
+#[cfg(feature = "ui")]
+use egui::Ui;
+#[cfg(feature = "ui")]
+use crate::i18n::{Language, Text};
+use crate::memory;
+use crate::runtime::Frame;
+
+/// Published frames between full states.
+pub const KEYFRAME_INTERVAL: usize = 50;
+/// Fewest records the archive is thinned down to.
+const MIN_RECORDS: usize = 16;
+
+enum Stored {
+    Full(Vec<f64>),
+    /// (index, new value) of changed adjustables.
+    Delta(Vec<(u32, f64)>)
+}
+
+struct Record {
+    iteration: usize,
+    quality: f64,
+    stored: Stored
+}
+
+impl Record {
+    fn size(&self) -> usize {
+        match &self.stored {
+            Stored::Full(state) => memory::vec_size(state),
+            Stored::Delta(delta) => memory::vec_size(delta)
+        }
+    }
+}
+
+/// Why a state couldn't be rebuilt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// There's no record at the index.
+    OutOfRange(usize),
+    /// No full state precedes the record.
+    NoKeyframe(usize),
+    /// A delta of the record changes an adjustable past the end of the state.
+    Corrupt(usize)
+}
+
+impl Display for ArchiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfRange(index) => write!(f, "no archived state #{index}"),
+            Self::NoKeyframe(index) => write!(f, "no full state precedes archived state #{index}"),
+            Self::Corrupt(index) => write!(f, "archived state #{index} doesn't match its full state")
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl ArchiveError {
+    #[must_use]
+    pub fn message(self, lang: Language) -> String {
+        match self {
+            Self::OutOfRange(index) => Text::NoArchivedState.fill(lang, &[&index]),
+            Self::NoKeyframe(index) => Text::NoKeyframe.fill(lang, &[&index]),
+            Self::Corrupt(index) => Text::CorruptState.fill(lang, &[&index])
+        }
+    }
+}
+
+/// Published states of a run, oldest first.
+#[derive(Default)]
+pub struct Archive {
+    records: Vec<Record>,
+    /// The state of the latest record.
+    latest: Vec<f64>,
+    /// Revision of the last frame seen.
+    revision: usize,
+    /// Frames seen since the last full state.
+    since_full: usize
+}
+
+impl Archive {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `frame` unless it was seen already.
+    pub fn record(&mut self, frame: &Frame) {
+        if frame.revision == self.revision || frame.state.is_empty() {
+            return;
+        }
+
+        self.revision = frame.revision;
+        self.since_full += 1;
+
+        let delta = (self.latest.len() == frame.state.len() && self.since_full < KEYFRAME_INTERVAL)
+            .then(|| {
+                self.latest.iter()
+                    .zip(&frame.state)
+                    .enumerate()
+                    .filter(|(_, (old, new))| old.to_bits() != new.to_bits())
+                    .map(|(i, (_, new))| (i as u32, *new))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|delta| mem::size_of_val(delta.as_slice()) < mem::size_of_val(frame.state.as_slice()));
+
+        let stored = if let Some(delta) = delta {
+            Stored::Delta(delta)
+        } else {
+            self.since_full = 0;
+            Stored::Full(frame.state.clone())
+        };
+
+        self.latest.clone_from(&frame.state);
+        self.records.push(Record {
+            iteration: frame.iteration,
+            quality: frame.quality,
+            stored
+        });
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// (iteration, quality) of the record at `index`.
+    #[must_use]
+    pub fn metrics(&self, index: usize) -> (usize, f64) {
+        let record = &self.records[index];
+        (record.iteration, record.quality)
+    }
+
+    /// Rebuild the state of the record at `index`.
+    ///
+    /// # Errors
+    /// Returns an error if there's no such record or the records don't add up to a state.
+    pub fn state(&self, index: usize) -> Result<Vec<f64>, ArchiveError> {
+        let records = self.records.get(..=index).ok_or(ArchiveError::OutOfRange(index))?;
+        let key = records.iter()
+            .rposition(|record| matches!(record.stored, Stored::Full(_)))
+            .ok_or(ArchiveError::NoKeyframe(index))?;
+
+        let Stored::Full(state) = &records[key].stored else {
+            unreachable!()
+        };
+
+        let mut state = state.clone();
+
+        for record in &records[key + 1..] {
+            if let Stored::Delta(delta) = &record.stored {
+                for &(i, value) in delta {
+                    *state.get_mut(i as usize).ok_or(ArchiveError::Corrupt(index))? = value;
+                }
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Approximate bytes used.
+    #[must_use]
+    pub fn memory(&self) -> usize {
+        memory::vec_size(&self.records)
+            + memory::vec_size(&self.latest)
+            + self.records.iter().map(Record::size).sum::<usize>()
+    }
+
+    /// Drop records from the older half: all deltas or, if there are none, every other full state.
+    /// Returns whether anything was dropped.
+    pub fn thin(&mut self) -> bool {
+        if self.records.len() <= MIN_RECORDS {
+            return false;
+        }
+
+        // Newer records may be built from older ones, so the first kept one becomes full.
+        let old = self.records.len() / 2;
+        if matches!(self.records[old].stored, Stored::Delta(_)) {
+            let Ok(state) = self.state(old) else {
+                return false;
+            };
+
+            self.records[old].stored = Stored::Full(state);
+        }
+
+        let deltas = self.records[..old].iter().any(|record| matches!(record.stored, Stored::Delta(_)));
+        let mut i = 0;
+        let mut fulls = 0;
+
+        self.records.retain(|record| {
+            let keep = i >= old || match record.stored {
+                Stored::Delta(_) => false,
+                Stored::Full(_) if deltas => true,
+                Stored::Full(_) => {
+                    fulls += 1;
+                    fulls % 2 == 1
+                }
+            };
+
+            i += 1;
+            keep
+        });
+        self.records.shrink_to_fit();
+
+        true
+    }
+}
+
+/// Going back to an archived state.
+#[cfg(feature = "ui")]
+#[derive(Default)]
+pub struct TimeTravel {
+    /// Index of the picked record.
+    index: usize,
+    /// Why the last restore failed.
+    error: Option<ArchiveError>
+}
+
+#[cfg(feature = "ui")]
+impl TimeTravel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the state to restore, if the user asked for it.
+    #[must_use]
+    pub fn ui(&mut self, ui: &mut Ui, archive: Option<&Archive>, lang: Language) -> Option<Vec<f64>> {
+        let Some(archive) = archive.filter(|archive| !archive.is_empty()) else {
+            ui.label(Text::NoStatesArchived.tr(lang));
+            return None;
+        };
+
+        self.index = self.index.min(archive.len() - 1);
+        ui.add(egui::Slider::new(&mut self.index, 0..=archive.len() - 1).text(Text::State.tr(lang)));
+
+        let (iteration, quality) = archive.metrics(self.index);
+        ui.label(Text::IterationQuality.fill(lang, &[&iteration, &format!("{quality:.6}")]));
+        ui.label(Text::StatesArchived.fill(lang, &[&archive.len(), &memory::Bytes(archive.memory())]));
+
+        if let Some(err) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, err.message(lang));
+        }
+
+        if !ui.button(Text::Restore.tr(lang)).on_hover_text(Text::RestoreStateHint.tr(lang)).clicked() {
+            return None;
+        }
+
+        let state = archive.state(self.index);
+        self.error = state.as_ref().err().copied();
+        state.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADJUSTABLES: usize = 8;
+
+    fn frame(revision: usize, state: Vec<f64>) -> Frame {
+        Frame {
+            revision,
+            iteration: revision * 10,
+            quality: revision as f64,
+            state,
+            ..Frame::default()
+        }
+    }
+
+    /// Record states changing one value each, with every fifth changing all of them.
+    fn recorded(count: usize) -> (Archive, Vec<Vec<f64>>) {
+        let mut archive = Archive::new();
+        let mut state = vec![0.0; ADJUSTABLES];
+        let mut states = Vec::new();
+
+        for revision in 1..=count {
+            if revision % 5 == 0 {
+                state.iter_mut().for_each(|value| *value += 1.0);
+            } else {
+                state[revision % ADJUSTABLES] += 0.5;
+            }
+
+            archive.record(&frame(revision, state.clone()));
+            states.push(state.clone());
+        }
+
+        (archive, states)
+    }
+
+    #[test]
+    fn every_frame_round_trips() {
+        let (archive, states) = recorded(3 * KEYFRAME_INTERVAL);
+
+        assert_eq!(archive.len(), states.len());
+        for (i, state) in states.iter().enumerate() {
+            assert_eq!(archive.state(i).as_ref(), Ok(state), "state {i}");
+            assert_eq!(archive.metrics(i), ((i + 1) * 10, (i + 1) as f64));
+        }
+    }
+
+    #[test]
+    fn dense_changes_are_stored_full() {
+        let (archive, _) = recorded(10);
+
+        assert!(matches!(archive.records[4].stored, Stored::Full(_)));
+        assert!(matches!(archive.records[5].stored, Stored::Delta(_)));
+    }
+
+    #[test]
+    fn repeated_and_resized_frames() {
+        let mut archive = Archive::new();
+        archive.record(&frame(1, vec![1.0; 4]));
+        archive.record(&frame(1, vec![2.0; 4]));
+        archive.record(&frame(2, vec![3.0; 6]));
+
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.state(0), Ok(vec![1.0; 4]));
+        assert_eq!(archive.state(1), Ok(vec![3.0; 6]));
+        assert_eq!(archive.state(2), Err(ArchiveError::OutOfRange(2)));
+    }
+
+    #[test]
+    fn thinning_keeps_states_intact() {
+        let (mut archive, states) = recorded(3 * KEYFRAME_INTERVAL);
+
+        while archive.thin() {
+            for i in 0..archive.len() {
+                let (iteration, _) = archive.metrics(i);
+                assert_eq!(archive.state(i).as_ref(), Ok(&states[iteration / 10 - 1]), "iteration {iteration}");
+            }
+        }
+
+        assert!(archive.len() <= MIN_RECORDS);
+        assert!(!archive.is_empty());
+    }
+}
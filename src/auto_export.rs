@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+use egui::{Color32, Context, RichText};
+use crate::export::Format;
+use crate::i18n::{Language, Text};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rule {
+    /// Every `every` iterations.
+    Iterations,
+    /// Whenever the quality rose by `improvement` since the last export.
+    Improvement
+}
+
+pub enum Action {
+    PickDirectory
+}
+
+/// The "Auto-export" window, saving the figure as a run goes on.
+pub struct AutoExport {
+    pub open: bool,
+    enabled: bool,
+    pub directory: Option<PathBuf>,
+    format: Format,
+    rule: Rule,
+    every: usize,
+    improvement: f64,
+    /// Iteration and quality of the last export.
+    last: Option<(usize, f64)>,
+    saved: usize,
+    pub error: Option<String>
+}
+
+impl Default for AutoExport {
+    fn default() -> Self {
+        Self {
+            open: false,
+            enabled: false,
+            directory: None,
+            format: Format::Svg,
+            rule: Rule::Iterations,
+            every: 1000,
+            improvement: 0.01,
+            last: None,
+            saved: 0,
+            error: None
+        }
+    }
+}
+
+impl AutoExport {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the last export, e.g. when the run it belonged to ends.
+    pub fn clear(&mut self) {
+        self.last = None;
+    }
+
+    /// Where to export a frame to, if the rule says it's time to.
+    pub fn due(&mut self, iteration: usize, quality: f64) -> Option<(Format, PathBuf)> {
+        if !self.enabled {
+            return None;
+        }
+
+        let directory = self.directory.as_ref()?;
+        let due = self.last.map_or(true, |(last_iteration, last_quality)| match self.rule {
+            Rule::Iterations => iteration >= last_iteration.saturating_add(self.every),
+            Rule::Improvement => quality >= last_quality + self.improvement
+        });
+
+        if !due {
+            return None;
+        }
+
+        self.last = Some((iteration, quality));
+        self.saved += 1;
+
+        Some((self.format, directory.join(format!("iteration-{iteration:08}.{}", self.format.extension()))))
+    }
+
+    #[must_use]
+    pub fn show(&mut self, ctx: &Context, lang: Language) -> Option<Action> {
+        let mut action = None;
+
+        egui::Window::new(Text::AutoExport.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                ui.add_enabled(
+                    self.directory.is_some(),
+                    egui::Checkbox::new(&mut self.enabled, Text::ExportWhileRunning.tr(lang))
+                );
+
+                egui::Grid::new("auto-export")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(Text::Directory.tr(lang));
+                        ui.horizontal(|ui| {
+                            if let Some(directory) = &self.directory {
+                                ui.label(directory.to_string_lossy());
+                            }
+
+                            if ui.button(Text::Pick.tr(lang)).clicked() {
+                                action = Some(Action::PickDirectory);
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label(Text::FormatField.tr(lang));
+                        egui::ComboBox::from_id_source("auto-export-format")
+                            .selected_text(self.format.name())
+                            .show_ui(ui, |ui| {
+                                for format in Format::ALL {
+                                    ui.selectable_value(&mut self.format, format, format.name());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.radio_value(&mut self.rule, Rule::Iterations, Text::Every.tr(lang));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::DragValue::new(&mut self.every).range(1..=usize::MAX));
+                            ui.label(Text::Iterations.tr(lang));
+                        });
+                        ui.end_row();
+
+                        ui.radio_value(&mut self.rule, Rule::Improvement, Text::WhenQualityImproves.tr(lang));
+                        ui.add(egui::DragValue::new(&mut self.improvement).range(0.0..=1.0).speed(0.001));
+                        ui.end_row();
+                    });
+
+                ui.label(Text::FilesNamedAfterIteration.tr(lang));
+
+                if self.saved > 0 {
+                    ui.label(Text::ExportedFiles.fill(lang, &[&self.saved]));
+                }
+
+                if let Some(err) = &self.error {
+                    ui.label(RichText::new(err).color(Color32::RED));
+                }
+            });
+
+        action
+    }
+}
@@ -0,0 +1,336 @@
+use std::cell::RefCell;
+use std::fs;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use egui::{Color32, Context, RichText};
+use geo_aid_internal::projector;
+use geo_aid_internal::script::math;
+use geo_aid_internal::script::math::Intermediate;
+use rhai::{Dynamic, Engine, EvalAltResult};
+use crate::colors::ItemColors;
+use crate::engine::DebuggableEngine;
+use crate::error;
+use crate::export;
+use crate::export::{Appearance, Format};
+use crate::i18n::{Language, Text};
+use crate::runtime;
+use crate::style::ItemStyle;
+
+/// Size of the canvas figures are projected to for exports.
+const EXPORT_SIZE: (f32, f32) = (800.0, 800.0);
+
+const EXAMPLE: &str = r#"load("figure.geo");
+
+for ma in [0.1, 0.3, 0.5] {
+    max_adjustment(ma);
+    restart();
+    step(1000);
+    print(`max adjustment ${ma}: quality ${quality()}`);
+    export_svg(`figure-${ma}.svg`);
+}
+"#;
+
+type Fallible<T> = Result<T, Box<EvalAltResult>>;
+
+/// The engine an automation script drives.
+struct State<E> {
+    intermediate: Option<Intermediate>,
+    worker_count: usize,
+    max_adjustment: f64,
+    engine: Option<E>,
+    magnitudes: Vec<f64>,
+    iteration: usize,
+    stop: Arc<AtomicBool>
+}
+
+impl<E: DebuggableEngine> State<E> {
+    /// The running engine, started on first use.
+    fn engine(&mut self) -> Fallible<&mut E> {
+        let Some(intermediate) = &self.intermediate else {
+            return Err("no script loaded, call load() first".into());
+        };
+
+        if self.engine.is_none() {
+            let engine = E::compile(self.worker_count, intermediate);
+            self.magnitudes = engine.bake(self.max_adjustment);
+            self.iteration = 0;
+            self.engine = Some(engine);
+        }
+
+        Ok(self.engine.as_mut().unwrap())
+    }
+
+    fn export(&mut self, format: Format, path: &str) -> Fallible<()> {
+        self.engine()?;
+
+        // Both exist once the engine is started.
+        let intermediate = self.intermediate.as_ref().unwrap();
+        let generated = self.engine.as_ref().unwrap().figure(E::figure_of(intermediate));
+        let figure = projector::project(
+            generated,
+            &intermediate.flags,
+            (EXPORT_SIZE.0 as usize, EXPORT_SIZE.1 as usize)
+        );
+
+        let colors = ItemColors::default();
+        let appearance = Appearance::new(
+            figure.items.iter().enumerate().map(|(i, item)| colors.rgba(i, item)).collect(),
+            &colors,
+            &ItemStyle::default()
+        );
+
+        export::write(format, &figure.items, &appearance, &[], &[], None, EXPORT_SIZE, Path::new(path))
+            .map_err(|err| format!("could not export to {path}: {err}").into())
+    }
+}
+
+/// Expose the debugger commands to `engine`.
+fn register<E: DebuggableEngine>(engine: &mut Engine, state: &Rc<RefCell<State<E>>>) {
+    let s = Rc::clone(state);
+    engine.register_fn("load", move |path: &str| -> Fallible<()> {
+        let script = fs::read_to_string(path).map_err(|err| format!("could not read {path}: {err}"))?;
+        let intermediate = math::load_script(&script).map_err(|err| format!("{path} failed to compile: {err:?}"))?;
+
+        let mut state = s.borrow_mut();
+        state.intermediate = Some(intermediate);
+        state.engine = None;
+        Ok(())
+    });
+
+    let s = Rc::clone(state);
+    engine.register_fn("workers", move |count: i64| -> Fallible<()> {
+        let count = usize::try_from(count).ok().filter(|c| *c > 0).ok_or("worker count must be positive")?;
+        let mut state = s.borrow_mut();
+        state.worker_count = count;
+        state.engine = None;
+        Ok(())
+    });
+
+    let s = Rc::clone(state);
+    engine.register_fn("max_adjustment", move |max_adjustment: f64| -> Fallible<()> {
+        let max_adjustment = error::check_max_adjustment(max_adjustment).map_err(|err| err.to_string())?;
+        let mut state = s.borrow_mut();
+        let state = &mut *state;
+        state.max_adjustment = max_adjustment;
+
+        if let Some(engine) = &state.engine {
+            state.magnitudes = engine.bake(max_adjustment);
+        }
+
+        Ok(())
+    });
+
+    let s = Rc::clone(state);
+    engine.register_fn("restart", move || s.borrow_mut().engine = None);
+
+    let s = Rc::clone(state);
+    engine.register_fn("step", move |count: i64| -> Fallible<()> {
+        let count = usize::try_from(count).unwrap_or_default();
+        let mut state = s.borrow_mut();
+        state.engine()?;
+
+        let State { engine, magnitudes, iteration, stop, .. } = &mut *state;
+        let engine = engine.as_mut().unwrap();
+
+        for _ in 0..count {
+            // Long steps would otherwise only stop once they're done.
+            if stop.load(Ordering::Relaxed) {
+                return Err("stopped".into());
+            }
+
+            engine.cycle(magnitudes);
+            *iteration += 1;
+        }
+
+        Ok(())
+    });
+
+    let s = Rc::clone(state);
+    engine.register_fn("quality", move || -> Fallible<f64> {
+        Ok(s.borrow_mut().engine()?.quality())
+    });
+
+    let s = Rc::clone(state);
+    engine.register_fn("iteration", move || s.borrow().iteration as i64);
+
+    let s = Rc::clone(state);
+    engine.register_fn("state", move || -> Fallible<rhai::Array> {
+        Ok(s.borrow_mut().engine()?.state().iter().map(|v| Dynamic::from_float(*v)).collect())
+    });
+
+    for (name, format) in [("export_svg", Format::Svg), ("export_latex", Format::Latex), ("export_json", Format::Json)] {
+        let s = Rc::clone(state);
+        engine.register_fn(name, move |path: &str| s.borrow_mut().export(format, path));
+    }
+}
+
+/// An automation script running on a background thread.
+pub struct Automation {
+    output: mpsc::Receiver<String>,
+    stop: Arc<AtomicBool>,
+    pub lines: Vec<String>,
+    pub finished: bool
+}
+
+impl Automation {
+    /// Run `source` with `E` as the engine. Panics of the engine end the script with an error.
+    #[must_use]
+    pub fn spawn<E: DebuggableEngine>(source: String, worker_count: usize, max_adjustment: f64) -> Self {
+        let (send, output) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop2 = Arc::clone(&stop);
+
+        thread::spawn(move || {
+            let state = Rc::new(RefCell::new(State::<E> {
+                intermediate: None,
+                worker_count,
+                max_adjustment,
+                engine: None,
+                magnitudes: Vec::new(),
+                iteration: 0,
+                stop: Arc::clone(&stop2)
+            }));
+
+            let mut engine = Engine::new();
+            register(&mut engine, &state);
+
+            let print = send.clone();
+            engine.on_print(move |text| {
+                let _ = print.send(text.to_string());
+            });
+            engine.on_progress(move |_| stop2.load(Ordering::Relaxed).then_some(Dynamic::UNIT));
+
+            match panic::catch_unwind(AssertUnwindSafe(|| engine.run(&source))) {
+                Ok(Ok(())) => (),
+                Ok(Err(err)) => {
+                    let _ = send.send(format!("error: {err}"));
+                }
+                Err(payload) => {
+                    let message = runtime::panic_message(payload.as_ref());
+                    let _ = send.send(format!("error: the engine panicked: {message}"));
+                }
+            }
+        });
+
+        Self {
+            output,
+            stop,
+            lines: Vec::new(),
+            finished: false
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn poll(&mut self) {
+        loop {
+            match self.output.try_recv() {
+                Ok(line) => self.lines.push(line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// The "Automation" window: a script editor and the output of the last run.
+pub struct AutomationTool {
+    pub open: bool,
+    source: String,
+    automation: Option<Automation>
+}
+
+impl Default for AutomationTool {
+    fn default() -> Self {
+        Self {
+            open: false,
+            source: String::from(EXAMPLE),
+            automation: None
+        }
+    }
+}
+
+impl AutomationTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, automation: Automation) {
+        self.automation = Some(automation);
+    }
+
+    /// Returns the script to run when the user asks for it.
+    #[must_use]
+    pub fn show(&mut self, ctx: &Context, lang: Language) -> Option<String> {
+        let mut run = None;
+
+        if let Some(automation) = &mut self.automation {
+            automation.poll();
+        }
+
+        egui::Window::new(Text::Automation.tr(lang))
+            .open(&mut self.open)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.label(Text::AutomationHelp.tr(lang));
+
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.source)
+                        .code_editor()
+                        .desired_rows(12)
+                        .desired_width(f32::INFINITY)
+                );
+
+                let running = self.automation.as_ref().is_some_and(|a| !a.finished);
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!running, egui::Button::new(Text::Run.tr(lang))).clicked() {
+                        run = Some(self.source.clone());
+                    }
+
+                    if let Some(automation) = &self.automation {
+                        if ui.add_enabled(running, egui::Button::new(Text::Stop.tr(lang))).clicked() {
+                            automation.stop();
+                        }
+                    }
+
+                    if running {
+                        ui.spinner();
+                    }
+                });
+
+                let Some(automation) = &self.automation else {
+                    return;
+                };
+
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in &automation.lines {
+                            if line.starts_with("error:") {
+                                ui.label(RichText::new(line).color(Color32::RED).monospace());
+                            } else {
+                                ui.label(RichText::new(line).monospace());
+                            }
+                        }
+                    });
+            });
+
+        run
+    }
+}
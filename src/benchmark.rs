@@ -0,0 +1,248 @@
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use std::time::Instant;
+use egui::{Color32, Context, RichText};
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::script::math::Intermediate;
+use crate::i18n::{Language, Text};
+use crate::runtime::Threading;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Measurement {
+    pub worker_count: usize,
+    pub cycles_per_second: f64,
+    /// Quality reached after the benchmarked cycles.
+    pub quality: f64,
+    /// Quality gained per second of generation.
+    pub gain_per_second: f64
+}
+
+/// A benchmark running on a background thread.
+pub struct Benchmark {
+    results: mpsc::Receiver<Measurement>,
+    pub measurements: Vec<Measurement>,
+    pub total: usize,
+    pub finished: bool
+}
+
+impl Benchmark {
+    #[must_use]
+    pub fn spawn(
+        intermediate: Intermediate,
+        worker_counts: Vec<usize>,
+        cycles: usize,
+        max_adjustment: f64,
+        threading: Threading
+    ) -> Self {
+        let (send, results) = mpsc::channel();
+        let total = worker_counts.len();
+
+        thread::spawn(move || {
+            threading.apply();
+
+            for worker_count in worker_counts {
+                let mut rage = Rage::new(worker_count, &intermediate);
+                let magnitudes = rage.gen().bake_magnitudes(max_adjustment);
+                let initial = rage.gen().get_total_quality();
+                let start = Instant::now();
+
+                for _ in 0..cycles {
+                    rage.gen_mut().cycle_prebaked(&magnitudes);
+                }
+
+                let elapsed = start.elapsed().as_secs_f64();
+                let quality = rage.gen().get_total_quality();
+
+                let measurement = Measurement {
+                    worker_count,
+                    cycles_per_second: cycles as f64 / elapsed,
+                    quality,
+                    gain_per_second: (quality - initial) / elapsed
+                };
+
+                if send.send(measurement).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            results,
+            measurements: Vec::new(),
+            total,
+            finished: false
+        }
+    }
+
+    pub fn poll(&mut self) {
+        loop {
+            match self.results.try_recv() {
+                Ok(measurement) => self.measurements.push(measurement),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// The worker count that gained the most quality per second.
+    ///
+    /// Raw cycles per second always favor fewer workers, since each cycle is cheaper,
+    /// so the recommendation weighs the work actually done.
+    #[must_use]
+    pub fn recommended(&self) -> Option<usize> {
+        self.measurements.iter()
+            .max_by(|a, b| a.gain_per_second.total_cmp(&b.gain_per_second))
+            .map(|m| m.worker_count)
+    }
+}
+
+pub enum Action {
+    Start {
+        worker_counts: Vec<usize>,
+        cycles: usize
+    },
+    Use(usize)
+}
+
+/// The "Benchmark" window.
+pub struct BenchmarkTool {
+    pub open: bool,
+    worker_counts: String,
+    worker_counts_valid: bool,
+    cycles: String,
+    cycles_valid: bool,
+    benchmark: Option<Benchmark>
+}
+
+impl Default for BenchmarkTool {
+    fn default() -> Self {
+        Self {
+            open: false,
+            worker_counts: String::from("64, 128, 256, 512, 1024"),
+            worker_counts_valid: true,
+            cycles: String::from("200"),
+            cycles_valid: true,
+            benchmark: None
+        }
+    }
+}
+
+impl BenchmarkTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, benchmark: Benchmark) {
+        self.benchmark = Some(benchmark);
+    }
+
+    #[must_use]
+    pub fn show(&mut self, ctx: &Context, lang: Language) -> Option<Action> {
+        let mut action = None;
+
+        if let Some(benchmark) = &mut self.benchmark {
+            benchmark.poll();
+        }
+
+        egui::Window::new(Text::Benchmark.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                let running = self.benchmark.as_ref().is_some_and(|b| !b.finished);
+
+                egui::Grid::new("benchmark-params")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(Text::WorkerCounts.tr(lang));
+                        ui.text_edit_singleline(&mut self.worker_counts);
+                        ui.end_row();
+
+                        if !self.worker_counts_valid {
+                            ui.label(RichText::new(Text::InvalidWorkerCounts.tr(lang)).color(Color32::RED));
+                            ui.label(Text::InvalidWorkerCountsHint.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label(Text::Cycles.tr(lang));
+                        ui.text_edit_singleline(&mut self.cycles);
+                        ui.end_row();
+
+                        if !self.cycles_valid {
+                            ui.label(RichText::new(Text::InvalidCycleCount.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBePositiveInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label("");
+                        if ui.add_enabled(!running, egui::Button::new(Text::RunBenchmark.tr(lang))).clicked() {
+                            let worker_counts = self.worker_counts.split(',')
+                                .map(|wc| usize::from_str(wc.trim()).ok().filter(|wc| *wc > 0))
+                                .collect::<Option<Vec<_>>>()
+                                .filter(|wcs| !wcs.is_empty());
+                            let cycles = usize::from_str(&self.cycles).ok().filter(|c| *c > 0);
+
+                            self.worker_counts_valid = worker_counts.is_some();
+                            self.cycles_valid = cycles.is_some();
+
+                            if let (Some(worker_counts), Some(cycles)) = (worker_counts, cycles) {
+                                action = Some(Action::Start { worker_counts, cycles });
+                            }
+                        }
+                        ui.end_row();
+                    });
+
+                let Some(benchmark) = &self.benchmark else {
+                    return;
+                };
+
+                ui.separator();
+
+                egui::Grid::new("benchmark-results")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong(Text::Workers.tr(lang));
+                        ui.strong(Text::CyclesPerSecond.tr(lang));
+                        ui.strong(Text::Quality.tr(lang));
+                        ui.strong(Text::GainPerSecond.tr(lang));
+                        ui.end_row();
+
+                        for m in &benchmark.measurements {
+                            ui.label(m.worker_count.to_string());
+                            ui.label(format!("{:.1}", m.cycles_per_second));
+                            ui.label(format!("{:.4}", m.quality));
+                            ui.label(format!("{:.5}", m.gain_per_second));
+                            ui.end_row();
+                        }
+                    });
+
+                if benchmark.finished {
+                    if let Some(recommended) = benchmark.recommended() {
+                        ui.horizontal(|ui| {
+                            ui.label(Text::RecommendedWorkerCount.fill(lang, &[&recommended]));
+
+                            if ui.button(Text::Use.tr(lang)).clicked() {
+                                action = Some(Action::Use(recommended));
+                            }
+                        });
+                    }
+                } else {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(Text::MeasuringProgress.fill(
+                            lang,
+                            &[&(benchmark.measurements.len() + 1), &benchmark.total]
+                        ));
+                    });
+                }
+            });
+
+        action
+    }
+}
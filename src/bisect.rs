@@ -0,0 +1,341 @@
+use egui::{Color32, RichText, Ui};
+use crate::breakpoint::Subject;
+use crate::history::History;
+use crate::i18n::{Language, Text};
+
+/// Which way the watched value must cross the threshold to count as bad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crossing {
+    Below,
+    Above
+}
+
+impl Crossing {
+    /// Whether `value` has crossed `threshold`. NaN always counts as crossed.
+    fn crossed(self, value: f64, threshold: f64) -> bool {
+        value.is_nan() || match self {
+            Self::Below => value < threshold,
+            Self::Above => value > threshold
+        }
+    }
+}
+
+/// A recorded frame, reduced to what bisecting needs.
+struct Sample {
+    iteration: usize,
+    value: f64,
+    state: Vec<f64>
+}
+
+/// An ongoing bisection. The frame at `good` hasn't crossed the threshold, the one at `bad` has.
+struct Run {
+    samples: Vec<Sample>,
+    good: usize,
+    bad: usize,
+    steps: usize
+}
+
+impl Run {
+    fn done(&self) -> bool {
+        self.bad - self.good <= 1
+    }
+
+    fn candidate(&self) -> usize {
+        (self.good + self.bad) / 2
+    }
+
+    fn mark(&mut self, bad: bool) {
+        let candidate = self.candidate();
+
+        if bad {
+            self.bad = candidate;
+        } else {
+            self.good = candidate;
+        }
+
+        self.steps += 1;
+    }
+}
+
+/// Finds the first recorded frame where a value crossed a threshold, halving the range on every step.
+///
+/// Like `git bisect`, it assumes the value stays crossed once it has crossed.
+pub struct Bisect {
+    subject: Subject,
+    threshold: f64,
+    crossing: Crossing,
+    run: Option<Run>,
+    error: Option<String>
+}
+
+impl Default for Bisect {
+    fn default() -> Self {
+        Self {
+            subject: Subject::Quality,
+            threshold: 0.9,
+            crossing: Crossing::Below,
+            run: None,
+            error: None
+        }
+    }
+}
+
+impl Bisect {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.run = None;
+        self.error = None;
+    }
+
+    fn value(&self, state: &[f64], quality: f64) -> f64 {
+        match self.subject {
+            Subject::Quality => quality,
+            Subject::Adjustable(i) => state.get(i).copied().unwrap_or(f64::NAN)
+        }
+    }
+
+    fn start(&mut self, history: &History, lang: Language) {
+        let samples = history.iter()
+            .map(|frame| Sample {
+                iteration: frame.iteration,
+                value: self.value(&frame.state, frame.quality),
+                state: frame.state.clone()
+            })
+            .collect::<Vec<_>>();
+
+        self.run = None;
+        self.error = match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) if samples.len() >= 2 => {
+                if self.crossing.crossed(first.value, self.threshold) {
+                    Some(Text::AlreadyCrossed.fill(lang, &[&first.iteration]))
+                } else if !self.crossing.crossed(last.value, self.threshold) {
+                    Some(Text::NotCrossed.fill(lang, &[&last.iteration]))
+                } else {
+                    None
+                }
+            }
+            _ => Some(Text::RecordTwoFrames.tr(lang).to_owned())
+        };
+
+        if self.error.is_none() {
+            let bad = samples.len() - 1;
+
+            self.run = Some(Run {
+                samples,
+                good: 0,
+                bad,
+                steps: 0
+            });
+        }
+    }
+
+    /// Show the bisect controls. Returns a state to restore when asked for.
+    pub fn ui(&mut self, ui: &mut Ui, history: Option<&History>, lang: Language) -> Option<Vec<f64>> {
+        let mut restore = None;
+
+        ui.horizontal(|ui| {
+            let mut quality = self.subject == Subject::Quality;
+
+            egui::ComboBox::from_id_source("bisect-subject")
+                .selected_text(if quality { Text::Quality.tr(lang) } else { Text::Adjustable.tr(lang) })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut quality, true, Text::Quality.tr(lang));
+                    ui.selectable_value(&mut quality, false, Text::Adjustable.tr(lang));
+                });
+
+            self.subject = match (quality, self.subject) {
+                (true, _) => Subject::Quality,
+                (false, Subject::Adjustable(i)) => Subject::Adjustable(i),
+                (false, Subject::Quality) => Subject::Adjustable(0)
+            };
+
+            if let Subject::Adjustable(i) = &mut self.subject {
+                ui.label("#");
+                ui.add(egui::DragValue::new(i));
+            }
+
+            egui::ComboBox::from_id_source("bisect-crossing")
+                .selected_text(match self.crossing {
+                    Crossing::Below => Text::DropsBelow.tr(lang),
+                    Crossing::Above => Text::RisesAbove.tr(lang)
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.crossing, Crossing::Below, Text::DropsBelow.tr(lang));
+                    ui.selectable_value(&mut self.crossing, Crossing::Above, Text::RisesAbove.tr(lang));
+                });
+
+            ui.add(egui::DragValue::new(&mut self.threshold).speed(0.01));
+        });
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(history.is_some(), egui::Button::new(Text::Start.tr(lang)))
+                .on_hover_text(Text::BisectHint.tr(lang))
+                .clicked()
+            {
+                if let Some(history) = history {
+                    self.start(history, lang);
+                }
+            }
+
+            if ui.add_enabled(self.run.is_some(), egui::Button::new(Text::Reset.tr(lang))).clicked() {
+                self.clear();
+            }
+        });
+
+        if let Some(err) = &self.error {
+            ui.label(RichText::new(err).color(Color32::RED));
+        }
+
+        let (crossing, threshold) = (self.crossing, self.threshold);
+        let Some(run) = &mut self.run else {
+            return restore;
+        };
+
+        let good = &run.samples[run.good];
+        let bad = &run.samples[run.bad];
+
+        ui.label(Text::GoodBad.fill(lang, &[&good.iteration, &bad.iteration, &run.steps]));
+
+        if run.done() {
+            ui.label(RichText::new(Text::FirstCrossed.fill(lang, &[
+                &good.iteration,
+                &format!("{:.6}", good.value),
+                &bad.iteration,
+                &format!("{:.6}", bad.value)
+            ])).strong());
+
+            if ui.button(Text::RestoreLastGood.tr(lang)).on_hover_text(Text::RestoreLastGoodHint.tr(lang)).clicked() {
+                restore = Some(good.state.clone());
+            }
+
+            return restore;
+        }
+
+        let candidate = &run.samples[run.candidate()];
+        let crossed = crossing.crossed(candidate.value, threshold);
+
+        ui.label(Text::Testing.fill(lang, &[
+            &candidate.iteration,
+            &format!("{:.6}", candidate.value),
+            &if crossed { Text::Crossed } else { Text::NotCrossedShort }.tr(lang)
+        ]));
+
+        ui.horizontal(|ui| {
+            if ui.button(Text::Step.tr(lang)).clicked() {
+                run.mark(crossed);
+            }
+
+            if ui.button(Text::RunToEnd.tr(lang)).clicked() {
+                while !run.done() {
+                    let value = run.samples[run.candidate()].value;
+                    run.mark(crossing.crossed(value, threshold));
+                }
+            }
+
+            // Overrides for judging the candidate by eye.
+            if ui.button(Text::MarkGood.tr(lang)).clicked() {
+                run.mark(false);
+            }
+
+            if ui.button(Text::MarkBad.tr(lang)).clicked() {
+                run.mark(true);
+            }
+        });
+
+        restore
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Frame;
+
+    const LANG: Language = Language::English;
+
+    /// A history of frames with the given qualities, ten iterations apart.
+    fn history(qualities: &[f64]) -> History {
+        let mut history = History::new();
+
+        for (i, quality) in qualities.iter().enumerate() {
+            history.record(&Frame {
+                revision: i + 1,
+                iteration: i * 10,
+                quality: *quality,
+                state: vec![i as f64],
+                ..Frame::default()
+            });
+        }
+
+        history
+    }
+
+    /// Bisect automatically, returning the last good and the first bad iteration.
+    fn bisect(bisect: &mut Bisect, history: &History) -> (usize, usize) {
+        bisect.start(history, LANG);
+        let (crossing, threshold) = (bisect.crossing, bisect.threshold);
+        let run = bisect.run.as_mut().expect("bisection failed to start");
+
+        while !run.done() {
+            let value = run.samples[run.candidate()].value;
+            run.mark(crossing.crossed(value, threshold));
+        }
+
+        (run.samples[run.good].iteration, run.samples[run.bad].iteration)
+    }
+
+    #[test]
+    fn crossings() {
+        assert!(Crossing::Below.crossed(0.5, 0.9));
+        assert!(!Crossing::Below.crossed(0.9, 0.9));
+        assert!(Crossing::Above.crossed(1.5, 1.0));
+        assert!(!Crossing::Above.crossed(0.5, 1.0));
+        assert!(Crossing::Above.crossed(f64::NAN, 1.0));
+        assert!(Crossing::Below.crossed(f64::NAN, 1.0));
+    }
+
+    #[test]
+    fn finds_the_first_crossed_frame() {
+        let history = history(&[1.0, 0.99, 0.95, 0.92, 0.8, 0.7, 0.5, 0.4]);
+        let mut tool = Bisect::new();
+
+        assert_eq!(bisect(&mut tool, &history), (30, 40));
+        assert!(tool.run.as_ref().is_some_and(|run| run.steps <= 3));
+    }
+
+    #[test]
+    fn watches_adjustables() {
+        let history = history(&[0.0; 6]);
+        let mut tool = Bisect {
+            subject: Subject::Adjustable(0),
+            threshold: 1.5,
+            crossing: Crossing::Above,
+            ..Bisect::default()
+        };
+
+        assert_eq!(bisect(&mut tool, &history), (10, 20));
+    }
+
+    #[test]
+    fn needs_a_crossing_in_the_history() {
+        let mut tool = Bisect::new();
+
+        tool.start(&history(&[0.95]), LANG);
+        assert_eq!(tool.error.as_deref(), Some(Text::RecordTwoFrames.tr(LANG)));
+
+        tool.start(&history(&[0.5, 0.4]), LANG);
+        assert_eq!(tool.error, Some(Text::AlreadyCrossed.fill(LANG, &[&0])));
+
+        tool.start(&history(&[0.95, 0.96, 0.97]), LANG);
+        assert_eq!(tool.error, Some(Text::NotCrossed.fill(LANG, &[&20])));
+        assert!(tool.run.is_none());
+
+        tool.start(&history(&[0.95, 0.5]), LANG);
+        assert!(tool.error.is_none());
+        assert!(tool.run.as_ref().is_some_and(Run::done));
+    }
+}
@@ -0,0 +1,96 @@
+use egui::{Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use crate::i18n::{Language, Text};
+use crate::runtime::Frame;
+
+/// Warns when adjustables leave `[-bound, bound]`, which usually means the engine is diverging.
+///
+/// Only published frames are checked, so unattended runs are checked every few cycles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoundsAlarm {
+    pub enabled: bool,
+    pub bound: f64,
+    /// Pause the engine when the alarm goes off.
+    pub pause: bool,
+    /// Indices of adjustables out of bounds in the last checked frame.
+    #[serde(skip)]
+    pub escaped: Vec<usize>,
+    #[serde(skip)]
+    last_revision: Option<usize>
+}
+
+impl Default for BoundsAlarm {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bound: 1000.0,
+            pause: true,
+            escaped: Vec::new(),
+            last_revision: None
+        }
+    }
+}
+
+impl BoundsAlarm {
+    pub fn clear(&mut self) {
+        self.escaped.clear();
+        self.last_revision = None;
+    }
+
+    /// Check a frame. Returns whether adjustables went out of bounds in it, having been in bounds before.
+    pub fn check(&mut self, frame: &Frame) -> bool {
+        if self.last_revision == Some(frame.revision) {
+            return false;
+        }
+
+        self.last_revision = Some(frame.revision);
+
+        let was_escaped = !self.escaped.is_empty();
+
+        self.escaped = if self.enabled {
+            frame.state.iter()
+                .enumerate()
+                .filter(|(_, v)| v.abs() > self.bound)
+                .map(|(i, _)| i)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let alarm = !was_escaped && !self.escaped.is_empty();
+
+        if alarm {
+            warn!(iteration = frame.iteration, "{} adjustable(s) left [-{}, {}]", self.escaped.len(), self.bound, self.bound);
+        }
+
+        alarm
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, lang: Language) {
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.enabled, Text::WarnWhenExceeds.tr(lang)).changed() {
+                self.last_revision = None;
+            }
+
+            if ui.add(egui::DragValue::new(&mut self.bound).speed(10.0).range(0.0..=f64::MAX))
+                .on_hover_text(Text::InAbsoluteValue.tr(lang))
+                .changed()
+            {
+                self.last_revision = None;
+            }
+        });
+
+        ui.add_enabled(self.enabled, egui::Checkbox::new(&mut self.pause, Text::PauseWhenItDoes.tr(lang)));
+
+        if !self.escaped.is_empty() {
+            let list = self.escaped.iter()
+                .map(|i| format!("#{i}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            ui.label(RichText::new(Text::OutOfBounds.fill(lang, &[&list])).color(Color32::RED));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! A treemap of the current error, split up by rule.
+
+use egui::{pos2, Color32, FontId, Rect, Sense, Stroke, Ui, Vec2};
+use crate::engine::Rule;
+use crate::i18n::{Language, Text};
+use crate::workers;
+
+/// Height of the treemap.
+const HEIGHT: f32 = 160.0;
+const FONT_SIZE: f32 = 11.0;
+/// Colors of the smallest and the largest error.
+const COLD_COLOR: Color32 = Color32::from_rgb(120, 160, 230);
+const HOT_COLOR: Color32 = Color32::from_rgb(230, 70, 50);
+const BORDER_COLOR: Color32 = Color32::WHITE;
+/// Outline of the picked rule.
+const OUTLINE_COLOR: Color32 = Color32::from_rgb(30, 120, 255);
+
+/// Split `rect` into one tile per weight, with areas proportional to the weights. The weights are split in two
+/// at half their total and the longer side of `rect` along with them, until every tile has one weight.
+/// Splitting at half the total, rather than one weight off at a time, keeps the tiles from getting thin.
+#[must_use]
+pub fn layout(weights: &[f64], rect: Rect) -> Vec<Rect> {
+    let mut tiles = vec![Rect::NOTHING; weights.len()];
+    split(weights, rect, &mut tiles);
+    tiles
+}
+
+fn split(weights: &[f64], rect: Rect, tiles: &mut [Rect]) {
+    if weights.len() < 2 {
+        if let Some(tile) = tiles.first_mut() {
+            *tile = rect;
+        }
+
+        return;
+    }
+
+    let total = weights.iter().sum::<f64>();
+    let mut at = 1;
+    let mut before = weights[0];
+
+    while at + 1 < weights.len() && before < total / 2.0 {
+        before += weights[at];
+        at += 1;
+    }
+
+    let t = if total > 0.0 { (before / total) as f32 } else { at as f32 / weights.len() as f32 };
+    let (first, second) = if rect.width() >= rect.height() {
+        let x = rect.min.x + rect.width() * t;
+        (Rect::from_min_max(rect.min, pos2(x, rect.max.y)), Rect::from_min_max(pos2(x, rect.min.y), rect.max))
+    } else {
+        let y = rect.min.y + rect.height() * t;
+        (Rect::from_min_max(rect.min, pos2(rect.max.x, y)), Rect::from_min_max(pos2(rect.min.x, y), rect.max))
+    };
+
+    let (first_tiles, second_tiles) = tiles.split_at_mut(at);
+    split(&weights[..at], first, first_tiles);
+    split(&weights[at..], second, second_tiles);
+}
+
+/// Show the current `errors` of `rules` as a treemap, largest first, outlining the `picked` rule.
+/// Satisfied rules are left out. Returns the rule clicked.
+pub fn ui(
+    ui: &mut Ui,
+    rules: Option<&[Rule]>,
+    errors: Option<&[f64]>,
+    picked: Option<usize>,
+    lang: Language
+) -> Option<usize> {
+    let (Some(rules), Some(errors)) = (rules, errors) else {
+        ui.label(Text::NotSupportedByEngine.tr(lang));
+        return None;
+    };
+
+    let mut order = (0..errors.len().min(rules.len())).filter(|&i| errors[i] > 0.0).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| errors[b].total_cmp(&errors[a]));
+
+    if order.is_empty() {
+        ui.label(Text::AllRulesSatisfied.tr(lang));
+        return None;
+    }
+
+    ui.label(Text::ErrorBreakdownHint.tr(lang));
+
+    let weights = order.iter().map(|&i| errors[i]).collect::<Vec<_>>();
+    let total = weights.iter().sum::<f64>();
+    let largest = weights[0];
+
+    let (rect, response) = ui.allocate_exact_size(Vec2::new(ui.available_width(), HEIGHT), Sense::click());
+    let painter = ui.painter_at(rect);
+    let tiles = layout(&weights, rect);
+    let font = FontId::proportional(FONT_SIZE);
+
+    for (&i, tile) in order.iter().zip(&tiles) {
+        let heat = (errors[i] / largest) as f32;
+        painter.rect(*tile, 0.0, workers::mix(COLD_COLOR, HOT_COLOR, heat), Stroke::new(1.0, BORDER_COLOR));
+
+        let text = format!("{}\n{:.1}%", rules[i].name, 100.0 * errors[i] / total);
+        let galley = painter.layout_no_wrap(text, font.clone(), Color32::BLACK);
+
+        // Names that don't fit are left to the tooltip.
+        if galley.size().x + 4.0 <= tile.width() && galley.size().y + 4.0 <= tile.height() {
+            painter.galley(tile.min + Vec2::splat(2.0), galley, Color32::BLACK);
+        }
+
+        if picked == Some(i) {
+            painter.rect_stroke(tile.shrink(1.0), 0.0, Stroke::new(2.0, OUTLINE_COLOR));
+        }
+    }
+
+    let hovered = response.hover_pos()
+        .and_then(|pos| tiles.iter().position(|tile| tile.contains(pos)))
+        .map(|n| order[n]);
+    let clicked = hovered.filter(|_| response.clicked());
+
+    if let Some(i) = hovered {
+        let error = format!("{:.6}", errors[i]);
+        let share = format!("{:.1}", 100.0 * errors[i] / total);
+        response.on_hover_text(Text::ErrorShare.fill(lang, &[&rules[i].name, &error, &share]));
+    }
+
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_cover_the_rect_in_proportion() {
+        let rect = Rect::from_min_size(pos2(0.0, 0.0), Vec2::new(200.0, 100.0));
+        let weights = [4.0, 2.0, 1.0, 1.0];
+        let tiles = layout(&weights, rect);
+
+        for (tile, weight) in tiles.iter().zip(weights) {
+            let share = tile.area() / rect.area();
+            assert!((f64::from(share) - weight / 8.0).abs() < 1e-6);
+            assert!(rect.contains_rect(*tile));
+        }
+
+        assert_eq!(layout(&[3.0], rect), [rect]);
+        assert!(layout(&[], rect).is_empty());
+    }
+}
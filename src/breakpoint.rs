@@ -0,0 +1,267 @@
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "ui")]
+use egui::{Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ui")]
+use crate::i18n::{Language, Text};
+
+/// The value a breakpoint watches.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Subject {
+    Quality,
+    Adjustable(usize)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    /// The value becomes NaN or infinite.
+    NotFinite,
+    /// The value leaves the closed range.
+    Outside {
+        min: f64,
+        max: f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Breakpoint {
+    pub subject: Subject,
+    pub condition: Condition,
+    pub enabled: bool
+}
+
+impl Breakpoint {
+    /// Whether the breakpoint triggers for the given engine state.
+    #[must_use]
+    pub fn check(&self, state: &[f64], quality: f64) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let value = match self.subject {
+            Subject::Quality => quality,
+            Subject::Adjustable(i) => match state.get(i) {
+                Some(v) => *v,
+                None => return false
+            }
+        };
+
+        match self.condition {
+            Condition::NotFinite => !value.is_finite(),
+            Condition::Outside { min, max } => !(min..=max).contains(&value)
+        }
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.subject {
+            Subject::Quality => write!(f, "quality")?,
+            Subject::Adjustable(i) => write!(f, "adjustable #{i}")?
+        }
+
+        match self.condition {
+            Condition::NotFinite => write!(f, " becomes NaN/Inf"),
+            Condition::Outside { min, max } => write!(f, " leaves [{min}, {max}]")
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl Breakpoint {
+    /// The breakpoint as shown in the editor.
+    #[must_use]
+    pub fn describe(&self, lang: Language) -> String {
+        let subject = match self.subject {
+            Subject::Quality => Text::BpQuality.tr(lang).to_owned(),
+            Subject::Adjustable(i) => Text::BpAdjustable.fill(lang, &[&i])
+        };
+
+        match self.condition {
+            Condition::NotFinite => Text::BpBecomesNonFinite.fill(lang, &[&subject]),
+            Condition::Outside { min, max } => Text::BpLeavesRange.fill(lang, &[&subject, &min, &max])
+        }
+    }
+}
+
+/// Index of the first triggered breakpoint.
+#[must_use]
+pub fn first_hit(breakpoints: &[Breakpoint], state: &[f64], quality: f64) -> Option<usize> {
+    breakpoints.iter().position(|bp| bp.check(state, quality))
+}
+
+/// The breakpoint list along with the one being composed.
+#[cfg(feature = "ui")]
+pub struct Breakpoints {
+    pub list: Vec<Breakpoint>,
+    draft: Breakpoint
+}
+
+#[cfg(feature = "ui")]
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Self {
+            list: Vec::new(),
+            draft: Breakpoint {
+                subject: Subject::Adjustable(0),
+                condition: Condition::NotFinite,
+                enabled: true
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl Breakpoints {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the breakpoint editor, highlighting the `hit` breakpoint. Returns whether the list changed.
+    pub fn ui(&mut self, ui: &mut Ui, hit: Option<usize>, lang: Language) -> bool {
+        let mut changed = false;
+        let mut remove = None;
+
+        egui::Grid::new("breakpoints")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, bp) in self.list.iter_mut().enumerate() {
+                    changed |= ui.checkbox(&mut bp.enabled, "").changed();
+
+                    if hit == Some(i) {
+                        ui.label(RichText::new(bp.describe(lang)).color(Color32::RED).strong());
+                    } else {
+                        ui.label(bp.describe(lang));
+                    }
+
+                    if ui.small_button(Text::Remove.tr(lang)).clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(i) = remove {
+            self.list.remove(i);
+            changed = true;
+        }
+
+        ui.horizontal(|ui| {
+            let mut quality = self.draft.subject == Subject::Quality;
+
+            egui::ComboBox::from_id_source("breakpoint-subject")
+                .selected_text(if quality { Text::Quality.tr(lang) } else { Text::Adjustable.tr(lang) })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut quality, true, Text::Quality.tr(lang));
+                    ui.selectable_value(&mut quality, false, Text::Adjustable.tr(lang));
+                });
+
+            self.draft.subject = match (quality, self.draft.subject) {
+                (true, _) => Subject::Quality,
+                (false, Subject::Adjustable(i)) => Subject::Adjustable(i),
+                (false, Subject::Quality) => Subject::Adjustable(0)
+            };
+
+            if let Subject::Adjustable(i) = &mut self.draft.subject {
+                ui.label("#");
+                ui.add(egui::DragValue::new(i));
+            }
+
+            let mut range = matches!(self.draft.condition, Condition::Outside { .. });
+
+            egui::ComboBox::from_id_source("breakpoint-condition")
+                .selected_text(if range { Text::LeavesRange.tr(lang) } else { Text::BecomesNonFinite.tr(lang) })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut range, false, Text::BecomesNonFinite.tr(lang));
+                    ui.selectable_value(&mut range, true, Text::LeavesRange.tr(lang));
+                });
+
+            self.draft.condition = match (range, self.draft.condition) {
+                (false, _) => Condition::NotFinite,
+                (true, Condition::Outside { min, max }) => Condition::Outside { min, max },
+                (true, Condition::NotFinite) => Condition::Outside { min: -10.0, max: 10.0 }
+            };
+
+            if let Condition::Outside { min, max } = &mut self.draft.condition {
+                ui.add(egui::DragValue::new(min).speed(0.1));
+                ui.add(egui::DragValue::new(max).speed(0.1));
+            }
+
+            if ui.button(Text::Add.tr(lang)).clicked() {
+                self.list.push(self.draft);
+                changed = true;
+            }
+        });
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakpoint(subject: Subject, condition: Condition) -> Breakpoint {
+        Breakpoint {
+            subject,
+            condition,
+            enabled: true
+        }
+    }
+
+    #[test]
+    fn range_is_closed() {
+        let bp = breakpoint(Subject::Quality, Condition::Outside { min: 0.0, max: 1.0 });
+
+        assert!(!bp.check(&[], 0.0));
+        assert!(!bp.check(&[], 1.0));
+        assert!(bp.check(&[], 1.5));
+        assert!(bp.check(&[], -0.1));
+        // NaN is outside of every range.
+        assert!(bp.check(&[], f64::NAN));
+    }
+
+    #[test]
+    fn watches_adjustables() {
+        let bp = breakpoint(Subject::Adjustable(1), Condition::NotFinite);
+
+        assert!(!bp.check(&[f64::NAN, 0.0], 0.5));
+        assert!(bp.check(&[0.0, f64::INFINITY], 0.5));
+        // Missing adjustables never trigger.
+        assert!(!bp.check(&[f64::NAN], 0.5));
+    }
+
+    #[test]
+    fn disabled_breakpoints_never_trigger() {
+        let bp = Breakpoint {
+            enabled: false,
+            ..breakpoint(Subject::Quality, Condition::NotFinite)
+        };
+
+        assert!(!bp.check(&[], f64::NAN));
+    }
+
+    #[test]
+    fn first_hit_is_in_order() {
+        let breakpoints = [
+            breakpoint(Subject::Quality, Condition::NotFinite),
+            breakpoint(Subject::Adjustable(0), Condition::Outside { min: -1.0, max: 1.0 }),
+            breakpoint(Subject::Quality, Condition::Outside { min: 0.0, max: 0.5 })
+        ];
+
+        assert_eq!(first_hit(&breakpoints, &[0.0], 0.25), None);
+        assert_eq!(first_hit(&breakpoints, &[2.0], 0.75), Some(1));
+        assert_eq!(first_hit(&breakpoints, &[0.0], 0.75), Some(2));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            breakpoint(Subject::Adjustable(3), Condition::Outside { min: -1.0, max: 2.5 }).to_string(),
+            "adjustable #3 leaves [-1, 2.5]"
+        );
+        assert_eq!(breakpoint(Subject::Quality, Condition::NotFinite).to_string(), "quality becomes NaN/Inf");
+    }
+}
@@ -0,0 +1,40 @@
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// Extension of checkpoint files.
+pub const EXTENSION: &str = "gchk";
+
+/// A saved engine state, restorable into a runtime of the same script.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The script the state was generated for.
+    pub script: Option<PathBuf>,
+    pub iteration: usize,
+    pub quality: f64,
+    /// Seconds spent generating the state. Zero in checkpoints saved before it was recorded.
+    #[serde(default)]
+    pub elapsed: f64,
+    /// Values of all adjustables.
+    pub state: Vec<f64>
+}
+
+impl Checkpoint {
+    /// # Errors
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(path, content)
+    }
+
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't a valid checkpoint.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+
+        ron::from_str(&content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(not(target_arch = "wasm32"))]
+use arboard::{Clipboard, ImageData};
+use geo_aid_internal::projector::figure::{Item, Position};
+#[cfg(not(target_arch = "wasm32"))]
+use macroquad::texture::Image;
+use crate::i18n::Language;
+use crate::render;
+
+fn format_position(position: &Position) -> String {
+    format!("({:.3}, {:.3})", position.x, position.y)
+}
+
+/// An item with its canvas coordinates, as copied to the clipboard. Always in English, like exports.
+#[must_use]
+pub fn describe_with_coordinates(item: &Item) -> String {
+    let name = render::describe(item, Language::English);
+
+    match item {
+        Item::Point(pt) => format!("{name} at {}", format_position(&pt.position)),
+        Item::Line(ln) => format!(
+            "{name} through {} and {}",
+            format_position(&ln.points.0),
+            format_position(&ln.points.1)
+        ),
+        Item::Segment(x) => format!(
+            "{name} from {} to {}",
+            format_position(&x.points.0),
+            format_position(&x.points.1)
+        ),
+        Item::Ray(x) => format!(
+            "{name} from {} through {}",
+            format_position(&x.points.0),
+            format_position(&x.points.1)
+        ),
+        Item::Circle(circle) => format!(
+            "{name} centered at {} with radius {:.3}",
+            format_position(&circle.center),
+            circle.radius
+        )
+    }
+}
+
+/// Copy screen data to the clipboard. Screen data is stored bottom-up, the clipboard expects rows top-down.
+///
+/// # Errors
+/// Returns an error if the system clipboard is unavailable.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn copy_image(image: &Image) -> Result<(), arboard::Error> {
+    let row = usize::from(image.width) * 4;
+    let bytes = image.bytes.chunks_exact(row)
+        .rev()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+
+    Clipboard::new()?.set_image(ImageData {
+        width: usize::from(image.width),
+        height: usize::from(image.height),
+        bytes: bytes.into()
+    })
+}
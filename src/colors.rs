@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use geo_aid_internal::projector::figure::Item;
+use macroquad::color::Color;
+use serde::{Deserialize, Serialize};
+use crate::i18n::Text;
+
+/// An sRGB color with unmultiplied alpha.
+pub type Rgba = [u8; 4];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Kind {
+    Point,
+    Line,
+    Segment,
+    Ray,
+    Circle
+}
+
+impl Kind {
+    pub const ALL: [Self; 5] = [Self::Point, Self::Line, Self::Segment, Self::Ray, Self::Circle];
+
+    #[must_use]
+    pub fn of(item: &Item) -> Self {
+        match item {
+            Item::Point(_) => Self::Point,
+            Item::Line(_) => Self::Line,
+            Item::Segment(_) => Self::Segment,
+            Item::Ray(_) => Self::Ray,
+            Item::Circle(_) => Self::Circle
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::Point => Text::Points,
+            Self::Line => Text::Lines,
+            Self::Segment => Text::Segments,
+            Self::Ray => Text::Rays,
+            Self::Circle => Text::Circles
+        }
+    }
+}
+
+/// Default colors of item kinds. All but monochrome are safe for common color vision deficiencies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Monochrome,
+    /// Okabe and Ito's palette.
+    OkabeIto,
+    /// Paul Tol's bright scheme.
+    TolBright
+}
+
+impl Palette {
+    pub const ALL: [Self; 3] = [Self::Monochrome, Self::OkabeIto, Self::TolBright];
+
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::Monochrome => Text::Monochrome,
+            Self::OkabeIto => Text::OkabeIto,
+            Self::TolBright => Text::TolBright
+        }
+    }
+
+    /// The color of items of a kind. Points stay black in every palette for contrast with the lines through them.
+    #[must_use]
+    pub fn color(self, kind: Kind) -> Rgba {
+        match (self, kind) {
+            (_, Kind::Point)
+            | (Self::Monochrome, _) => [0, 0, 0, 255],
+            (Self::OkabeIto, Kind::Line) => [0, 114, 178, 255],
+            (Self::OkabeIto, Kind::Segment) => [213, 94, 0, 255],
+            (Self::OkabeIto, Kind::Ray) => [0, 158, 115, 255],
+            (Self::OkabeIto, Kind::Circle) => [204, 121, 167, 255],
+            (Self::TolBright, Kind::Line) => [68, 119, 170, 255],
+            (Self::TolBright, Kind::Segment) => [238, 102, 119, 255],
+            (Self::TolBright, Kind::Ray) => [34, 136, 51, 255],
+            (Self::TolBright, Kind::Circle) => [170, 51, 119, 255]
+        }
+    }
+}
+
+/// User-assigned item colors. Anything without a color takes its kind's color from the palette.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemColors {
+    pub palette: Palette,
+    /// Draw thicker strokes and larger labels.
+    pub high_contrast: bool,
+    /// Colors of all items of a kind.
+    pub kinds: BTreeMap<Kind, Rgba>,
+    /// Colors of single items by their index in the figure, taking precedence over kinds.
+    pub items: BTreeMap<usize, Rgba>
+}
+
+impl ItemColors {
+    /// The color of items of a kind.
+    #[must_use]
+    pub fn kind(&self, kind: Kind) -> Rgba {
+        self.kinds.get(&kind).copied().unwrap_or_else(|| self.palette.color(kind))
+    }
+
+    /// The color of the item at `index`.
+    #[must_use]
+    pub fn get(&self, index: usize, item: &Item) -> Color {
+        let [r, g, b, a] = self.rgba(index, item);
+        Color::from_rgba(r, g, b, a)
+    }
+
+    /// The color of the item at `index`, as exported.
+    #[must_use]
+    pub fn rgba(&self, index: usize, item: &Item) -> Rgba {
+        self.items.get(&index)
+            .copied()
+            .unwrap_or_else(|| self.kind(Kind::of(item)))
+    }
+}
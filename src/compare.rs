@@ -0,0 +1,92 @@
+use egui::{Color32, RichText, Ui};
+use crate::i18n::{Language, Text};
+use crate::runtime::Frame;
+
+/// Two marked frames and the differences between them.
+#[derive(Default)]
+pub struct Comparison {
+    pub a: Option<Frame>,
+    pub b: Option<Frame>,
+    /// Draw A and B instead of the latest figure.
+    pub overlay: bool
+}
+
+impl Comparison {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.a = None;
+        self.b = None;
+    }
+
+    /// Both frames, if marked and drawing them is enabled.
+    #[must_use]
+    pub fn overlaid(&self) -> Option<(&Frame, &Frame)> {
+        self.a.as_ref().zip(self.b.as_ref()).filter(|_| self.overlay)
+    }
+
+    /// Show the mark buttons and, once both frames are marked, the adjustable deltas.
+    pub fn ui(&mut self, ui: &mut Ui, latest: Option<&Frame>, lang: Language) {
+        ui.horizontal(|ui| {
+            if ui.add_enabled(latest.is_some(), egui::Button::new(Text::MarkA.tr(lang))).clicked() {
+                self.a = latest.cloned();
+            }
+
+            if ui.add_enabled(latest.is_some(), egui::Button::new(Text::MarkB.tr(lang))).clicked() {
+                self.b = latest.cloned();
+            }
+
+            if ui.button(Text::Clear.tr(lang)).clicked() {
+                self.clear();
+            }
+        });
+
+        for (name, frame) in [("A", &self.a), ("B", &self.b)] {
+            if let Some(frame) = frame {
+                ui.label(Text::MarkedFrame.fill(lang, &[&name, &frame.iteration, &format!("{:.4}", frame.quality)]));
+            }
+        }
+
+        let (Some(a), Some(b)) = (&self.a, &self.b) else {
+            return;
+        };
+
+        ui.checkbox(&mut self.overlay, Text::ShowAAndB.tr(lang));
+        ui.label(Text::QualityDelta.fill(lang, &[&format!("{:+.6}", b.quality - a.quality)]));
+
+        if a.state.len() != b.state.len() {
+            ui.label(RichText::new(Text::DifferentScripts.tr(lang)).color(Color32::RED));
+            return;
+        }
+
+        // Largest changes first.
+        let mut deltas = a.state.iter()
+            .zip(&b.state)
+            .enumerate()
+            .map(|(i, (a, b))| (i, *a, *b, b - a))
+            .collect::<Vec<_>>();
+        deltas.sort_by(|x, y| y.3.abs().total_cmp(&x.3.abs()));
+
+        egui::Grid::new("comparison")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("");
+                ui.label("A");
+                ui.label("B");
+                ui.label(Text::Delta.tr(lang));
+                ui.end_row();
+
+                for (i, a, b, delta) in deltas {
+                    ui.label(format!("#{i}"));
+                    ui.label(format!("{a:.6}"));
+                    ui.label(format!("{b:.6}"));
+                    ui.label(format!("{delta:+.6}"));
+                    ui.end_row();
+                }
+            });
+    }
+}
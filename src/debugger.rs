@@ -0,0 +1,1676 @@
+use std::{fs, io, mem};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use egui::{Color32, Context, Key, RichText};
+use egui_dock::{DockArea, DockState, Style};
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::projector::figure::Item;
+use geo_aid_internal::script::math;
+use geo_aid_internal::script::math::Intermediate;
+use macroquad::miniquad::window::clipboard_get;
+use macroquad::prelude::{get_fps, request_new_screen_size, screen_dpi_scale, screen_height, screen_width};
+use tracing::{info, warn};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+use crate::annotation::Annotations;
+use crate::archive::TimeTravel;
+use crate::auto_export;
+use crate::auto_export::AutoExport;
+#[cfg(feature = "automation")]
+use crate::automation::{Automation, AutomationTool};
+use crate::benchmark;
+use crate::benchmark::{Benchmark, BenchmarkTool};
+use crate::bisect::Bisect;
+use crate::bounds::BoundsAlarm;
+use crate::breakpoint::Breakpoints;
+use crate::checkpoint;
+use crate::clipboard;
+use crate::checkpoint::Checkpoint;
+use crate::colors::ItemColors;
+use crate::compare::Comparison;
+use crate::degeneracy::Degeneracies;
+use crate::dialog;
+use crate::engine::{DebuggableEngine, EngineKind};
+use crate::error;
+use crate::error::{CompileError, DebuggerError};
+use crate::diff;
+use crate::diff::{Diff, DiffTool};
+use crate::export;
+use crate::export::{Appearance, ExportTool, Format, Metrics};
+use crate::font::{Fonts, LabelFont};
+use crate::golden;
+use crate::golden::{Golden, GoldenComparison};
+use crate::help::Help;
+use crate::i18n::{Language, Text};
+use crate::legend;
+use crate::logging::LogView;
+use crate::measure::Measure;
+use crate::mock::MockEngine;
+use crate::memory;
+use crate::memory::Bytes;
+use crate::pacing;
+use crate::pacing::FrameRate;
+use crate::performance::PerformanceTool;
+use crate::overlay::{Overlay, OverlayStack};
+use crate::profiler::Profiler;
+use crate::projection::Projection;
+use crate::proposals::Proposals;
+use crate::rules::Rules;
+use crate::runtime;
+use crate::runtime::{Batching, Message, Runtime, Threading};
+#[cfg(feature = "remote")]
+use crate::remote;
+use crate::session;
+use crate::selection::Selection;
+use crate::session::Session;
+use crate::spatial::SpatialIndex;
+use crate::statistics::{MultiRun, StatisticsTool};
+use crate::stop::StopForm;
+use crate::style::ItemStyle;
+use crate::trace;
+use crate::sweep;
+use crate::sweep::{Parameter, Sweep, SweepTool};
+use crate::trajectory::TrajectoryTool;
+use crate::undo::UndoStack;
+use crate::view::{Bookmarks, View};
+use layout::Tabs;
+
+pub use layout::Tab;
+
+mod canvas;
+mod layout;
+mod panels;
+
+/// What the user asked to quit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quit {
+    /// Discard the engine.
+    Runtime,
+    /// Close the debugger.
+    Window
+}
+
+/// Where a capture of the canvas goes.
+pub enum Capture {
+    /// A PNG file, `scale` times the canvas size.
+    Png {
+        path: PathBuf,
+        scale: f32
+    },
+    Clipboard
+}
+
+/// Window size in compact mode.
+const COMPACT_SIZE: (f32, f32) = (480.0, 420.0);
+/// How long toast notifications stay up.
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+/// Canvas size used when the canvas tab isn't visible.
+const FALLBACK_CANVAS: (f32, f32) = (800.0, 600.0);
+
+pub struct Debugger {
+    file: Option<PathBuf>,
+    last_dir: Option<PathBuf>,
+    /// Why the script couldn't be read, saved or compiled.
+    file_error: Option<DebuggerError>,
+    /// Source of the script, as shown in the script editor.
+    script: String,
+    script_dirty: bool,
+    /// Errors of the last compilation.
+    script_errors: Vec<CompileError>,
+    worker_count: String,
+    worker_count_error: Option<DebuggerError>,
+    max_adjustment: String,
+    max_adjustment_error: Option<DebuggerError>,
+    stop: StopForm,
+    remote: String,
+    /// Why the engine couldn't be started, locally or on the server.
+    engine_error: Option<DebuggerError>,
+    /// Seconds a busy worker may go without reporting before it's shown as stuck.
+    watchdog_timeout: f64,
+    pub runtime: Option<Runtime>,
+    /// The compiled script the runtime was started with.
+    pub intermediate: Option<Intermediate>,
+    run: bool,
+    /// How often the worker publishes in Run mode.
+    batching: Batching,
+    /// Seconds of generation after which Run stops, if set.
+    time_budget: Option<f64>,
+    /// The runtime's generation time when Run was started, while it's on.
+    run_started: Option<Duration>,
+    /// Engine used for local generation.
+    engine: EngineKind,
+    /// Scheduling of the generation and benchmark threads.
+    threading: Threading,
+    /// MiB the runtime's recordings may take before old entries are thinned out.
+    memory_budget: usize,
+    benchmark: BenchmarkTool,
+    pub overlays: OverlayStack,
+    pub projection: Projection,
+    pub item_colors: ItemColors,
+    pub item_style: ItemStyle,
+    pub label_font: LabelFont,
+    /// Fonts loaded for `label_font`.
+    fonts: Fonts,
+    breakpoints: Breakpoints,
+    bounds: BoundsAlarm,
+    bisect: Bisect,
+    time_travel: TimeTravel,
+    /// Descriptions of items that were projected to non-finite coordinates in the last frame.
+    pub non_finite: Vec<String>,
+    session_error: Option<String>,
+    export: ExportTool,
+    statistics: StatisticsTool,
+    sweep: SweepTool,
+    diff: DiffTool,
+    #[cfg(feature = "automation")]
+    automation: AutomationTool,
+    auto_export: AutoExport,
+    trajectory: TrajectoryTool,
+    performance: PerformanceTool,
+    degeneracies: Degeneracies,
+    rules: Rules,
+    profiler: Profiler,
+    proposals: Proposals,
+    help: Help,
+    /// Where to capture the canvas to on the next frame.
+    pub pending_capture: Option<Capture>,
+    #[cfg(target_arch = "wasm32")]
+    script_picker: dialog::ScriptPicker,
+    layout: DockState<Tab>,
+    /// Screen area of the canvas tab in the last frame, if it was visible.
+    canvas: Option<egui::Rect>,
+    /// Hide all panels and give the whole window to the canvas.
+    zen: bool,
+    /// A short notification and when it was raised.
+    toast: Option<(String, Instant)>,
+    /// Window size from before compact mode, if it's on.
+    compact: Option<(f32, f32)>,
+    /// egui's scale in the last frame.
+    pixels_per_point: f32,
+    /// The latest figure as drawn on the canvas.
+    pub items: Vec<Item>,
+    /// Hit-testing index over `items`.
+    pub index: SpatialIndex,
+    pub selection: Selection,
+    /// Zoom and pan of the canvas.
+    pub view: View,
+    pub bookmarks: Bookmarks,
+    pub annotations: Annotations,
+    /// Cursor position in canvas coordinates, if it's over the canvas.
+    cursor: Option<egui::Pos2>,
+    measure: Measure,
+    log: LogView,
+    frame_rate: FrameRate,
+    /// Language of the UI.
+    pub language: Language,
+    /// Whether anything happened in the last frame. Otherwise, power saving may kick in.
+    active: bool,
+    /// Revision of the latest frame when it was last drawn.
+    drawn_revision: usize,
+    undo: UndoStack,
+    pub comparison: Comparison,
+    golden: GoldenComparison,
+    /// Plot the quality as log10(1 - quality).
+    quality_log_scale: bool,
+    /// Show the target quality in the quality plot.
+    quality_target_line: bool,
+    /// Plot adjustment magnitudes under the quality.
+    magnitude_plot: bool,
+    rule_error_plot: bool,
+    /// A quit waiting for confirmation.
+    quit_requested: Option<Quit>,
+    /// Whether the debugger should be closed.
+    pub exit: bool
+}
+
+impl Debugger {
+    #[must_use]
+    pub fn new(log: LogView) -> Self {
+        let session = Session::default();
+
+        Self {
+            file: session.script,
+            last_dir: None,
+            file_error: None,
+            script: String::new(),
+            script_dirty: false,
+            script_errors: Vec::new(),
+            worker_count: session.worker_count,
+            worker_count_error: None,
+            max_adjustment: session.max_adjustment,
+            max_adjustment_error: None,
+            stop: session.stop,
+            remote: session.remote,
+            engine_error: None,
+            watchdog_timeout: session.watchdog_timeout,
+            runtime: None,
+            intermediate: None,
+            run: false,
+            batching: session.batching,
+            time_budget: session.time_budget,
+            run_started: None,
+            engine: session.engine,
+            threading: session.threading,
+            memory_budget: session.memory_budget,
+            benchmark: BenchmarkTool::new(),
+            overlays: OverlayStack::new(session.overlays),
+            projection: session.projection,
+            item_colors: session.item_colors,
+            item_style: session.item_style,
+            fonts: Fonts::load(session.label_font.path.as_deref()),
+            label_font: session.label_font,
+            breakpoints: Breakpoints::new(),
+            bounds: session.bounds,
+            bisect: Bisect::new(),
+            time_travel: TimeTravel::new(),
+            non_finite: Vec::new(),
+            session_error: None,
+            export: ExportTool::new(),
+            statistics: StatisticsTool::new(),
+            sweep: SweepTool::new(),
+            diff: DiffTool::new(),
+            #[cfg(feature = "automation")]
+            automation: AutomationTool::new(),
+            auto_export: AutoExport::new(),
+            trajectory: TrajectoryTool::new(),
+            performance: PerformanceTool::new(),
+            degeneracies: Degeneracies::new(),
+            rules: Rules::new(),
+            profiler: Profiler::new(),
+            proposals: Proposals::new(),
+            help: Help::new(),
+            pending_capture: None,
+            #[cfg(target_arch = "wasm32")]
+            script_picker: dialog::ScriptPicker::default(),
+            layout: session.layout.unwrap_or_else(layout::default_layout),
+            canvas: None,
+            zen: false,
+            compact: None,
+            toast: None,
+            pixels_per_point: 1.0,
+            items: Vec::new(),
+            index: SpatialIndex::new(),
+            selection: Selection::new(),
+            view: session.view,
+            bookmarks: Bookmarks { list: session.bookmarks, ..Bookmarks::new() },
+            annotations: Annotations {
+                list: session.annotations,
+                ..Annotations::new()
+            },
+            cursor: None,
+            measure: Measure::new(),
+            log,
+            frame_rate: session.frame_rate,
+            language: session.language,
+            active: true,
+            drawn_revision: 0,
+            undo: UndoStack::new(),
+            comparison: Comparison::new(),
+            golden: GoldenComparison::new(),
+            quality_log_scale: false,
+            quality_target_line: true,
+            magnitude_plot: false,
+            rule_error_plot: false,
+            quit_requested: None,
+            exit: false
+        }
+    }
+
+    /// The canvas area in macroquad's screen coordinates, if the canvas tab was visible in the last frame.
+    ///
+    /// egui measures in points, which differ from macroquad's units when egui is zoomed.
+    #[must_use]
+    pub fn canvas(&self) -> Option<macroquad::math::Rect> {
+        let scale = self.pixels_per_point / screen_dpi_scale();
+
+        self.canvas.map(|rect| macroquad::math::Rect::new(
+            rect.min.x * scale,
+            rect.min.y * scale,
+            rect.width() * scale,
+            rect.height() * scale
+        ))
+    }
+
+    /// The frame rate to pace the next frame at, if any.
+    #[must_use]
+    pub fn target_fps(&self) -> Option<u32> {
+        if self.frame_rate.power_saving && !self.active {
+            Some(pacing::IDLE_FPS)
+        } else {
+            Some(self.frame_rate.cap).filter(|cap| *cap > 0)
+        }
+    }
+
+    /// Add a custom canvas overlay. It's listed in the inspector after the built-in ones.
+    pub fn register_overlay(&mut self, overlay: impl Overlay + 'static) {
+        self.overlays.register(Box::new(overlay));
+    }
+
+    fn canvas_size(&self) -> (f32, f32) {
+        self.canvas().map_or_else(|| self.projection.canvas(FALLBACK_CANVAS), |rect| (rect.w, rect.h))
+    }
+
+    /// The part of `area` the canvas takes up: all of it, or the fixed canvas size centered in it.
+    fn fit_canvas(&self, area: egui::Rect) -> egui::Rect {
+        let scale = self.pixels_per_point / screen_dpi_scale();
+        let (width, height) = self.projection.canvas((area.width() * scale, area.height() * scale));
+        let size = egui::vec2(width, height) / scale;
+        let offset = ((area.size() - size) / 2.0).max(egui::Vec2::ZERO);
+
+        egui::Rect::from_min_size(area.min + offset, size)
+    }
+
+    fn session(&self) -> Session {
+        Session {
+            script: self.file.clone(),
+            worker_count: self.worker_count.clone(),
+            max_adjustment: self.max_adjustment.clone(),
+            stop: self.stop.clone(),
+            remote: self.remote.clone(),
+            watchdog_timeout: self.watchdog_timeout,
+            batching: self.batching,
+            time_budget: self.time_budget,
+            engine: self.engine,
+            threading: self.threading,
+            memory_budget: self.memory_budget,
+            overlays: self.overlays.settings(),
+            projection: self.projection,
+            view: self.view,
+            bookmarks: self.bookmarks.list.clone(),
+            item_colors: self.item_colors.clone(),
+            item_style: self.item_style,
+            label_font: self.label_font.clone(),
+            breakpoints: self.breakpoints.list.clone(),
+            bounds: self.bounds.clone(),
+            frame_rate: self.frame_rate,
+            annotations: self.annotations.list.clone(),
+            language: self.language,
+            layout: Some(self.layout.clone())
+        }
+    }
+
+    fn apply_session(&mut self, session: Session) {
+        if let Some(script) = &session.script {
+            self.open_script(script);
+        }
+
+        self.file = session.script;
+        self.worker_count = session.worker_count;
+        self.max_adjustment = session.max_adjustment;
+        self.stop = session.stop;
+        self.remote = session.remote;
+        self.watchdog_timeout = session.watchdog_timeout;
+        self.batching = session.batching;
+        self.time_budget = session.time_budget;
+        self.engine = session.engine;
+        self.threading = session.threading;
+        self.memory_budget = session.memory_budget;
+        self.overlays.apply(session.overlays);
+        self.projection = session.projection;
+        self.view = session.view;
+        self.bookmarks.list = session.bookmarks;
+        self.item_colors = session.item_colors;
+        self.item_style = session.item_style;
+        self.label_font = session.label_font;
+        self.breakpoints.list = session.breakpoints;
+        self.bounds = session.bounds;
+        self.frame_rate = session.frame_rate;
+        self.annotations.list = session.annotations;
+        self.language = session.language;
+
+        if let Some(layout) = session.layout {
+            self.layout = layout;
+        }
+
+        if let Some(runtime) = &mut self.runtime {
+            runtime.send(Message::SetBreakpoints(self.breakpoints.list.clone()));
+        }
+    }
+
+    /// A file dialog titled `title`, starting in the directory of the last picked file.
+    fn file_dialog(&self, title: Text) -> dialog::FileDialog {
+        let dialog = dialog::FileDialog::new().set_title(title.tr(self.language));
+
+        match &self.last_dir {
+            Some(dir) => dialog.set_directory(dir),
+            None => dialog
+        }
+    }
+
+    /// Ask for a file to open, showing the files with the given extensions.
+    fn open_path(&self, title: Text, filter: &str, extensions: &[&str]) -> Option<PathBuf> {
+        self.file_dialog(title).add_filter(filter, extensions).pick_file()
+    }
+
+    /// Ask where to save a file, showing the files with the given extensions.
+    fn save_path(&self, title: Text, filter: &str, extensions: &[&str]) -> Option<PathBuf> {
+        self.file_dialog(title).add_filter(filter, extensions).save_file()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_session(&mut self) {
+        if let Some(path) = self.save_path(
+            Text::SaveSessionTitle,
+            Text::DebuggerSession.tr(self.language),
+            &[session::EXTENSION]
+        ) {
+            self.session_error = self.session().save(&path)
+                .err()
+                .map(|err| Text::CouldNotSaveSession.fill(self.language, &[&err]));
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_session(&mut self) {
+        if let Some(path) = self.open_path(
+            Text::LoadSessionTitle,
+            Text::DebuggerSession.tr(self.language),
+            &[session::EXTENSION]
+        ) {
+            match Session::load(&path) {
+                Ok(session) => {
+                    self.session_error = None;
+                    self.apply_session(session);
+                }
+                Err(err) => self.session_error = Some(Text::CouldNotLoadSession.fill(self.language, &[&err]))
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_checkpoint(&mut self) {
+        let Some(frame) = self.runtime.as_ref().and_then(|rt| rt.history.latest()) else {
+            return;
+        };
+
+        let checkpoint = Checkpoint {
+            script: self.file.clone(),
+            iteration: frame.iteration,
+            quality: frame.quality,
+            elapsed: self.runtime.as_ref().map_or(0.0, |rt| rt.elapsed.as_secs_f64()),
+            state: frame.state.clone()
+        };
+
+        if let Some(path) = self.save_path(
+            Text::SaveCheckpointTitle,
+            Text::EngineCheckpoint.tr(self.language),
+            &[checkpoint::EXTENSION]
+        ) {
+            self.session_error = checkpoint.save(&path)
+                .err()
+                .map(|err| Text::CouldNotSaveCheckpoint.fill(self.language, &[&err]));
+        }
+    }
+
+    /// Restore a checkpoint into the running engine. The adjustable count must match.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_checkpoint(&mut self) {
+        let Some(path) = self.open_path(
+            Text::LoadCheckpointTitle,
+            Text::EngineCheckpoint.tr(self.language),
+            &[checkpoint::EXTENSION]
+        ) else {
+            return;
+        };
+
+        let Some(runtime) = &mut self.runtime else {
+            return;
+        };
+
+        let checkpoint = match Checkpoint::load(&path) {
+            Ok(checkpoint) => checkpoint,
+            Err(err) => {
+                self.session_error = Some(Text::CouldNotLoadCheckpoint.fill(self.language, &[&err]));
+                return;
+            }
+        };
+
+        let expected = runtime.history.latest().map_or(0, |frame| frame.state.len());
+
+        if checkpoint.state.len() == expected {
+            self.session_error = None;
+
+            if let Some(snapshot) = runtime.snapshot() {
+                self.undo.push(snapshot);
+            }
+
+            runtime.send(Message::Restore(checkpoint.state));
+        } else {
+            self.session_error = Some(Text::CheckpointMismatch.fill(
+                self.language,
+                &[&checkpoint.state.len(), &expected]
+            ));
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_trace(&mut self) {
+        let Some(trace) = self.runtime.as_ref().and_then(|rt| rt.trace.as_ref()) else {
+            return;
+        };
+
+        if let Some(path) = self.save_path(
+            Text::SaveTraceTitle,
+            Text::AdjustableTrace.tr(self.language),
+            &[trace::EXTENSION]
+        ) {
+            self.session_error = trace.save(&path)
+                .err()
+                .map(|err| Text::CouldNotSaveTrace.fill(self.language, &[&err]));
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_golden(&mut self) {
+        let Some(golden) = self.golden.capture(self.file.clone()) else {
+            return;
+        };
+
+        if let Some(path) = self.save_path(
+            Text::SaveGoldenTitle,
+            Text::Golden.tr(self.language),
+            &[golden::EXTENSION]
+        ) {
+            self.session_error = golden.save(&path)
+                .err()
+                .map(|err| Text::CouldNotSaveGolden.fill(self.language, &[&err]));
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pick_label_font(&mut self) {
+        if let Some(path) = self.open_path(
+            Text::PickLabelFontTitle,
+            Text::TrueTypeFont.tr(self.language),
+            &["ttf", "otf"]
+        ) {
+            self.label_font.path = Some(path);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_golden(&mut self) {
+        let Some(path) = self.open_path(
+            Text::LoadGoldenTitle,
+            Text::Golden.tr(self.language),
+            &[golden::EXTENSION]
+        ) else {
+            return;
+        };
+
+        match Golden::load(&path) {
+            Ok(golden) => {
+                self.session_error = None;
+                self.golden.golden = Some(golden);
+            }
+            Err(err) => self.session_error = Some(Text::CouldNotLoadGolden.fill(self.language, &[&err]))
+        }
+    }
+
+    fn undo(&mut self) {
+        let Some(runtime) = &mut self.runtime else {
+            return;
+        };
+
+        if let Some(snapshot) = runtime.snapshot().and_then(|current| self.undo.undo(current)) {
+            self.max_adjustment = snapshot.max_adjustment.to_string();
+            runtime.restore(snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        let Some(runtime) = &mut self.runtime else {
+            return;
+        };
+
+        if let Some(snapshot) = runtime.snapshot().and_then(|current| self.undo.redo(current)) {
+            self.max_adjustment = snapshot.max_adjustment.to_string();
+            runtime.restore(snapshot);
+        }
+    }
+
+    fn show_menu(&mut self, ctx: &Context) {
+        let lang = self.language;
+
+        egui::TopBottomPanel::top("menu")
+            .show(ctx, |ui| {
+                egui::menu::bar(ui, |ui| {
+                    ui.menu_button(Text::File.tr(lang), |ui| {
+                        if ui.button(Text::NewFromClipboard.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.new_from_clipboard();
+                        }
+
+                        // Browsers have no paths to save to or load from.
+                        #[cfg(not(target_arch = "wasm32"))]
+                        {
+                            let save_as = egui::Button::new(Text::SaveScriptAs.tr(lang));
+                            if ui.add_enabled(!self.script.is_empty(), save_as).clicked() {
+                                ui.close_menu();
+                                self.save_script_as();
+                            }
+
+                            ui.separator();
+
+                            if ui.button(Text::SaveSession.tr(lang)).clicked() {
+                                ui.close_menu();
+                                self.save_session();
+                            }
+
+                            if ui.button(Text::LoadSession.tr(lang)).clicked() {
+                                ui.close_menu();
+                                self.load_session();
+                            }
+
+                            ui.separator();
+
+                            let idle = self.runtime.as_ref().is_some_and(|rt| !rt.progress.unattended);
+
+                            if ui.add_enabled(idle, egui::Button::new(Text::SaveCheckpoint.tr(lang))).clicked() {
+                                ui.close_menu();
+                                self.save_checkpoint();
+                            }
+
+                            if ui.add_enabled(idle, egui::Button::new(Text::LoadCheckpoint.tr(lang))).clicked() {
+                                ui.close_menu();
+                                self.load_checkpoint();
+                            }
+
+                            ui.separator();
+
+                            if let Some(runtime) = &mut self.runtime {
+                                let mut tracing = runtime.trace.is_some();
+
+                                if ui.checkbox(&mut tracing, Text::RecordTrace.tr(lang))
+                                    .on_hover_text(Text::RecordTraceHint.tr(lang))
+                                    .changed()
+                                {
+                                    runtime.set_tracing(tracing);
+                                }
+                            }
+
+                            let traced = self.runtime.as_ref().is_some_and(|rt| rt.trace.is_some());
+
+                            if ui.add_enabled(traced, egui::Button::new(Text::SaveTrace.tr(lang))).clicked() {
+                                ui.close_menu();
+                                self.save_trace();
+                            }
+
+                            ui.separator();
+
+                            if ui.button(Text::Export.tr(lang)).clicked() {
+                                ui.close_menu();
+                                self.export.open = true;
+                            }
+                        }
+                    });
+
+                    ui.menu_button(Text::Edit.tr(lang), |ui| {
+                        let idle = self.runtime.as_ref().is_some_and(|rt| !rt.progress.unattended);
+
+                        if ui.add_enabled(idle && self.undo.can_undo(), egui::Button::new(Text::Undo.tr(lang))).clicked() {
+                            ui.close_menu();
+                            self.undo();
+                        }
+
+                        if ui.add_enabled(idle && self.undo.can_redo(), egui::Button::new(Text::Redo.tr(lang))).clicked() {
+                            ui.close_menu();
+                            self.redo();
+                        }
+                    });
+
+                    ui.menu_button(Text::View.tr(lang), |ui| {
+                        if ui.button(Text::ZenMode.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.zen = true;
+                        }
+
+                        if ui.button(Text::CompactMode.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.compact = Some((screen_width(), screen_height()));
+                            request_new_screen_size(COMPACT_SIZE.0, COMPACT_SIZE.1);
+                        }
+
+                        if ui.checkbox(&mut self.measure.active, Text::Measure.tr(lang)).changed() {
+                            self.measure.clear();
+                        }
+
+                        if ui.add_enabled(self.view.is_zoomed(), egui::Button::new(Text::ResetView.tr(lang))).clicked() {
+                            ui.close_menu();
+                            self.view = View::default();
+                        }
+
+                        if ui.button(Text::ResetLayout.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.layout = layout::default_layout();
+                        }
+
+                        ui.menu_button(Text::FloatPanel.tr(lang), |ui| {
+                            for tab in Tab::FLOATABLE {
+                                if ui.button(tab.title().tr(lang)).clicked() {
+                                    ui.close_menu();
+                                    layout::float(&mut self.layout, tab);
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(Text::FloatPanelHint.tr(lang));
+
+                        ui.separator();
+
+                        ui.horizontal(|ui| {
+                            ui.label(Text::FpsCap.tr(lang));
+                            ui.add(egui::DragValue::new(&mut self.frame_rate.cap).range(0..=240))
+                                .on_hover_text(Text::NoLimit.tr(lang));
+                        });
+                        ui.checkbox(&mut self.frame_rate.power_saving, Text::PowerSaving.tr(lang));
+
+                        ui.separator();
+
+                        ui.menu_button(Text::Language.tr(lang), |ui| {
+                            for language in Language::ALL {
+                                if ui.radio_value(&mut self.language, language, language.name()).clicked() {
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+
+                    ui.menu_button(Text::Tools.tr(lang), |ui| {
+                        // These run engines on threads of their own.
+                        #[cfg(not(target_arch = "wasm32"))]
+                        {
+                            if ui.button(Text::Benchmark.tr(lang)).clicked() {
+                                ui.close_menu();
+                                self.benchmark.open = true;
+                            }
+
+                            if ui.button(Text::MultiRunStatistics.tr(lang)).clicked() {
+                                ui.close_menu();
+                                self.statistics.open = true;
+                            }
+
+                            if ui.button(Text::ParameterSweep.tr(lang)).clicked() {
+                                ui.close_menu();
+                                self.sweep.open = true;
+                            }
+
+                            if ui.button(Text::ScriptDiff.tr(lang)).clicked() {
+                                ui.close_menu();
+                                self.diff.open = true;
+                            }
+                        }
+
+                        #[cfg(feature = "automation")]
+                        if ui.button(Text::Automation.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.automation.open = true;
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if ui.button(Text::AutoExport.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.auto_export.open = true;
+                        }
+
+                        if ui.button(Text::PointTrajectory.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.trajectory.open = true;
+                        }
+
+                        if ui.button(Text::Performance.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.performance.open = true;
+                        }
+                    });
+
+                    ui.menu_button(Text::Help.tr(lang), |ui| {
+                        if ui.button(Text::Keybindings.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.help.open = true;
+                        }
+
+                        if ui.button(Text::Tour.tr(lang)).clicked() {
+                            ui.close_menu();
+                            self.help.start_tour();
+                        }
+                    });
+
+                    if let Some(err) = &self.session_error {
+                        ui.label(RichText::new(err).color(Color32::RED));
+                    }
+                });
+            });
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        // Scripts picked in the browser have no path to be saved back to.
+        #[cfg(target_arch = "wasm32")]
+        if let Some((name, script)) = self.script_picker.poll() {
+            info!("opened {name}");
+            self.open(String::from_utf8_lossy(&script).into_owned(), None);
+        }
+
+        if let Some(runtime) = &mut self.runtime {
+            if runtime.poll() || runtime.crash.is_some() || !self.non_finite.is_empty() {
+                self.run = false;
+            }
+
+            runtime.enforce_budget(self.memory_budget * memory::MIB);
+
+            let escaped = runtime.history.latest().is_some_and(|frame| self.bounds.check(frame));
+
+            if escaped && self.bounds.pause {
+                self.run = false;
+
+                if runtime.progress.unattended && !runtime.progress.suspended {
+                    runtime.send(Message::Suspend);
+                }
+            }
+
+            if self.run {
+                // The budget counts from the generation time at which Run started.
+                let started = *self.run_started.get_or_insert(runtime.elapsed);
+                let run_time = runtime.elapsed.saturating_sub(started);
+
+                if self.time_budget.is_some_and(|budget| run_time.as_secs_f64() >= budget) {
+                    info!(run_time = run_time.as_secs_f64(), "time budget spent, stopping");
+                    self.run = false;
+                    self.toast = Some((Text::TimeBudgetSpent.tr(self.language).to_string(), Instant::now()));
+                }
+            }
+
+            runtime.set_running(self.run.then_some(self.batching));
+        }
+
+        if !self.run {
+            self.run_started = None;
+        }
+
+        let due = self.runtime.as_ref()
+            .and_then(|rt| rt.history.latest())
+            .and_then(|frame| self.auto_export.due(frame.iteration, frame.quality));
+
+        if let Some((format, path)) = due {
+            self.auto_export.error = self.export_to(format, path, false)
+                .err()
+                .map(|err| Text::CouldNotExport.fill(self.language, &[&err]));
+        }
+
+        self.show_quit_dialog(ctx);
+
+        if ctx.input(|input| input.key_pressed(Key::F11)) {
+            self.zen = !self.zen;
+        }
+
+        if ctx.input(|input| input.key_pressed(Key::F12)) {
+            self.screenshot();
+        }
+
+        self.show_toast(ctx);
+
+        let unattended = self.runtime.as_ref().is_some_and(|rt| rt.progress.unattended);
+
+        // Text fields have their own copy. Ctrl+C arrives as a copy event rather than a key press.
+        if !ctx.wants_keyboard_input() {
+            let (copy, image) = ctx.input(|input| (
+                input.events.contains(&egui::Event::Copy),
+                input.modifiers.shift
+            ));
+
+            if copy && image {
+                self.copy_canvas();
+            } else if copy {
+                self.copy_selection(ctx);
+            }
+        }
+
+        // Text fields have their own undo.
+        if !ctx.wants_keyboard_input() && !unattended {
+            let (undo, redo) = ctx.input(|input| (
+                input.modifiers.command && !input.modifiers.shift && input.key_pressed(Key::Z),
+                input.modifiers.command && (input.key_pressed(Key::Y) || (input.modifiers.shift && input.key_pressed(Key::Z)))
+            ));
+
+            if undo {
+                self.undo();
+            } else if redo {
+                self.redo();
+            }
+        }
+
+        let revision = self.runtime.as_ref()
+            .and_then(|rt| rt.history.latest())
+            .map_or(0, |frame| frame.revision);
+
+        self.active = self.run
+            || self.runtime.as_ref().is_some_and(|rt| rt.progress.unattended)
+            || revision != self.drawn_revision
+            || ctx.input(|input| !input.events.is_empty() || input.pointer.is_moving());
+        self.drawn_revision = revision;
+        self.pixels_per_point = ctx.pixels_per_point();
+
+        if self.zen {
+            if ctx.input(|input| input.key_pressed(Key::Escape)) {
+                self.zen = false;
+            }
+
+            self.canvas = Some(self.fit_canvas(ctx.screen_rect()));
+            return;
+        }
+
+        if self.compact.is_some() {
+            self.show_compact(ctx);
+            return;
+        }
+
+        self.show_menu(ctx);
+        self.show_status_bar(ctx);
+
+        self.canvas = None;
+
+        // The dock area is transparent so that the figure drawn underneath shows through the canvas tab.
+        let mut layout = std::mem::replace(&mut self.layout, DockState::new(Vec::new()));
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none())
+            .show(ctx, |ui| {
+                DockArea::new(&mut layout)
+                    .style(Style::from_egui(ui.style()))
+                    .show_inside(ui, &mut Tabs(self));
+            });
+        self.layout = layout;
+
+        let available = self.runtime.as_ref().is_some_and(|rt| rt.history.latest().is_some());
+        if let Some(format) = self.export.show(ctx, available, self.selection.items.len(), self.language) {
+            self.export_figure(format);
+        }
+
+        self.show_tools(ctx);
+        self.help.show(ctx, self.language);
+    }
+
+    /// Queue a canvas capture to the next numbered file in [`export::SCREENSHOT_DIR`].
+    fn screenshot(&mut self) {
+        if self.runtime.as_ref().and_then(|rt| rt.history.latest()).is_none() {
+            return;
+        }
+
+        let message = match export::next_screenshot_path() {
+            Ok(path) => {
+                let message = Text::Saved.fill(self.language, &[&path.display()]);
+                self.pending_capture = Some(Capture::Png {
+                    path,
+                    scale: screen_dpi_scale()
+                });
+                message
+            }
+            Err(err) => Text::CouldNotScreenshot.fill(self.language, &[&err])
+        };
+
+        self.toast = Some((message, Instant::now()));
+    }
+
+    /// Queue a capture of the canvas to the clipboard.
+    fn copy_canvas(&mut self) {
+        if self.runtime.as_ref().and_then(|rt| rt.history.latest()).is_none() {
+            return;
+        }
+
+        self.pending_capture = Some(Capture::Clipboard);
+        self.toast = Some((Text::CopiedCanvas.tr(self.language).to_string(), Instant::now()));
+    }
+
+    /// Copy the selected items with their coordinates as text, one per line.
+    fn copy_selection(&mut self, ctx: &Context) {
+        if self.selection.items.is_empty() {
+            return;
+        }
+
+        let text = self.selection.items.iter()
+            .filter_map(|i| self.items.get(*i))
+            .map(clipboard::describe_with_coordinates)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        ctx.copy_text(text);
+        self.toast = Some((Text::CopiedItems.fill(self.language, &[&self.selection.items.len()]), Instant::now()));
+    }
+
+    fn show_toast(&mut self, ctx: &Context) {
+        let Some((message, raised)) = &self.toast else {
+            return;
+        };
+
+        if raised.elapsed() > TOAST_DURATION {
+            self.toast = None;
+            return;
+        }
+
+        egui::Area::new(egui::Id::new("toast"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -40.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| ui.label(message));
+            });
+
+        ctx.request_repaint();
+    }
+
+    /// Just the canvas and a toolbar for stepping.
+    fn show_compact(&mut self, ctx: &Context) {
+        let lang = self.language;
+
+        egui::TopBottomPanel::top("compact-toolbar")
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(runtime) = &mut self.runtime {
+                        let stepping = !runtime.progress.unattended && runtime.crash.is_none();
+
+                        if self.run {
+                            if ui.button(Text::Pause.tr(lang)).clicked() {
+                                self.run = false;
+                            }
+                        } else if ui.add_enabled(stepping, egui::Button::new(Text::Run.tr(lang))).clicked() {
+                            self.run = true;
+                        }
+
+                        if ui.add_enabled(stepping && !self.run, egui::Button::new(Text::Next.tr(lang))).clicked() {
+                            runtime.send(Message::Next);
+                        }
+
+                        ui.label(format!("#{} q={:.4}", runtime.progress.iteration, runtime.progress.quality));
+                    }
+
+                    if ui.button(Text::ExitCompact.tr(lang)).clicked() {
+                        if let Some((width, height)) = self.compact.take() {
+                            request_new_screen_size(width, height);
+                        }
+                    }
+                });
+            });
+
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none())
+            .show(ctx, |ui| self.canvas_ui(ui));
+    }
+
+    fn show_tools(&mut self, ctx: &Context) {
+        if let Some(auto_export::Action::PickDirectory) = self.auto_export.show(ctx, self.language) {
+            let mut dialog = self.file_dialog(Text::PickExportDirTitle);
+
+            if let Some(dir) = &self.auto_export.directory {
+                dialog = dialog.set_directory(dir);
+            }
+
+            if let Some(path) = dialog.pick_folder() {
+                self.auto_export.directory = Some(path);
+            }
+        }
+
+        match self.benchmark.show(ctx, self.language) {
+            Some(benchmark::Action::Start { worker_counts, cycles }) => {
+                let ma = error::parse_max_adjustment(&self.max_adjustment);
+                let file = self.load_script();
+
+                self.file_error = file.as_ref().err().cloned();
+                self.max_adjustment_error = ma.as_ref().err().cloned();
+
+                if let (Ok(ma), Ok(file)) = (ma, file) {
+                    self.benchmark.start(Benchmark::spawn(file, worker_counts, cycles, ma, self.threading));
+                }
+            }
+            Some(benchmark::Action::Use(worker_count)) => {
+                self.worker_count = worker_count.to_string();
+                self.worker_count_error = None;
+            }
+            None => ()
+        }
+
+        if let Some(start) = self.statistics.show(ctx, self.engine, self.language) {
+            let wc = error::parse_worker_count(&self.worker_count);
+            let ma = error::parse_max_adjustment(&self.max_adjustment);
+            let file = self.load_script();
+
+            self.file_error = file.as_ref().err().cloned();
+            self.worker_count_error = wc.as_ref().err().cloned();
+            self.max_adjustment_error = ma.as_ref().err().cloned();
+
+            if let (Ok(wc), Ok(ma), Ok(file)) = (wc, ma, file) {
+                self.statistics.start(match self.engine {
+                    EngineKind::Rage => MultiRun::spawn::<Rage>(file, wc, ma, start),
+                    EngineKind::Mock => MultiRun::spawn::<MockEngine>(file, wc, ma, start)
+                });
+            }
+        }
+
+        match self.sweep.show(ctx, self.language) {
+            Some(sweep::Action::Start(start)) => {
+                // The swept parameter's setup field isn't used, so it needn't be valid.
+                let wc = error::parse_worker_count(&self.worker_count)
+                    .or_else(|err| if start.parameter == Parameter::WorkerCount { Ok(0) } else { Err(err) });
+                let ma = error::parse_max_adjustment(&self.max_adjustment)
+                    .or_else(|err| if start.parameter == Parameter::MaxAdjustment { Ok(0.0) } else { Err(err) });
+                let file = self.load_script();
+
+                self.file_error = file.as_ref().err().cloned();
+                self.worker_count_error = wc.as_ref().err().cloned();
+                self.max_adjustment_error = ma.as_ref().err().cloned();
+
+                if let (Ok(wc), Ok(ma), Ok(file)) = (wc, ma, file) {
+                    self.sweep.start(Sweep::spawn(file, start, wc, ma));
+                }
+            }
+            Some(sweep::Action::Use(Parameter::MaxAdjustment, value)) => {
+                self.max_adjustment = value.to_string();
+                self.max_adjustment_error = None;
+            }
+            Some(sweep::Action::Use(Parameter::WorkerCount, value)) => {
+                self.worker_count = (value as usize).to_string();
+                self.worker_count_error = None;
+            }
+            None => ()
+        }
+
+        match self.diff.show(ctx, self.language) {
+            Some(diff::Action::Pick) => {
+                if let Some(path) = self.open_path(Text::PickOldScriptTitle, "GeoScript", &["geo"]) {
+                    self.diff.other = Some(path);
+                }
+            }
+            Some(diff::Action::Start { other, iterations }) => {
+                let wc = error::parse_worker_count(&self.worker_count);
+                let ma = error::parse_max_adjustment(&self.max_adjustment);
+                let new = self.load_script();
+
+                self.file_error = new.as_ref().err().cloned();
+                self.worker_count_error = wc.as_ref().err().cloned();
+                self.max_adjustment_error = ma.as_ref().err().cloned();
+
+                let lang = self.language;
+                let old = fs::read_to_string(&other)
+                    .map_err(|err| Text::CouldNotReadFile.fill(lang, &[&other.display(), &err]))
+                    .and_then(|script| math::load_script(&script)
+                        .map_err(|err| {
+                            let errors = error::compile_errors(err).iter().map(ToString::to_string).collect::<Vec<_>>();
+                            Text::OldVersionFailed.fill(lang, &[&errors.join("; ")])
+                        }));
+
+                match (old, new, wc, ma) {
+                    (Err(err), ..) => self.diff.error = Some(err),
+                    (Ok(old), Ok(new), Ok(wc), Ok(ma)) => {
+                        self.diff.start(Diff::spawn(old, new, wc, ma, iterations));
+                    }
+                    _ => self.diff.error = Some(Text::FixSetupFirst.tr(lang).to_string())
+                }
+            }
+            None => ()
+        }
+
+        #[cfg(feature = "automation")]
+        if let Some(source) = self.automation.show(ctx, self.language) {
+            // The script loads its own figure; the setup's parameters are only defaults.
+            let wc = error::parse_worker_count(&self.worker_count).unwrap_or(runtime::DEFAULT_WORKER_COUNT);
+            let ma = error::parse_max_adjustment(&self.max_adjustment).unwrap_or(runtime::DEFAULT_MAX_ADJUSTMENT);
+
+            self.automation.start(match self.engine {
+                EngineKind::Rage => Automation::spawn::<Rage>(source, wc, ma),
+                EngineKind::Mock => Automation::spawn::<MockEngine>(source, wc, ma)
+            });
+        }
+
+        self.trajectory.show(ctx, self.selected_point(), self.language);
+        let no_timings = VecDeque::new();
+        self.performance.show(ctx, self.runtime.as_ref().map_or(&no_timings, |rt| &rt.timings), self.language);
+    }
+
+    /// The selected item if it's the only one selected and it's a point.
+    fn selected_point(&self) -> Option<usize> {
+        let mut selected = self.selection.items.iter();
+
+        match (selected.next(), selected.next()) {
+            (Some(&i), None) => matches!(self.items.get(i), Some(Item::Point(_))).then_some(i),
+            _ => None
+        }
+    }
+
+    fn generate(&mut self) {
+        let wc = error::parse_worker_count(&self.worker_count);
+        let ma = error::parse_max_adjustment(&self.max_adjustment);
+        let file = self.load_script();
+
+        self.file_error = file.as_ref().err().cloned();
+        self.worker_count_error = wc.as_ref().err().cloned();
+        self.max_adjustment_error = ma.as_ref().err().cloned();
+
+        if let (Ok(wc), Ok(ma), Ok(file)) = (wc, ma, file) {
+            let intermediate = file.clone();
+            let runtime = if self.remote.is_empty() {
+                info!(worker_count = wc, max_adjustment = ma, engine = self.engine.name(), "starting generation");
+
+                match self.engine {
+                    EngineKind::Rage => start_engine::<Rage>(wc, &file)
+                        .map(|rage| Runtime::new(rage, ma, file.figure, file.flags, self.threading)),
+                    EngineKind::Mock => start_engine::<MockEngine>(wc, &file).map(|mock| {
+                        let flags = mock.flags();
+                        Runtime::new(mock, ma, (), flags, self.threading)
+                    })
+                }
+            } else {
+                self.connect(file, ma)
+            };
+
+            let mut runtime = match runtime {
+                Ok(runtime) => runtime,
+                Err(err) => {
+                    warn!("{err}");
+                    self.engine_error = Some(err);
+                    return;
+                }
+            };
+
+            self.engine_error = None;
+            runtime.send(Message::SetBreakpoints(self.breakpoints.list.clone()));
+            self.runtime = Some(runtime);
+            self.intermediate = Some(intermediate);
+            self.undo.clear();
+        }
+    }
+
+    /// Connect to the debugging server set in the engine settings.
+    #[cfg(feature = "remote")]
+    fn connect(&self, file: Intermediate, max_adjustment: f64) -> Result<Runtime, DebuggerError> {
+        info!(max_adjustment, "connecting to {}", self.remote);
+
+        remote::connect(&self.remote, &self.script, file, max_adjustment).map_err(|err| {
+            DebuggerError::EngineInit(Text::CouldNotConnect.fill(self.language, &[&self.remote, &err]))
+        })
+    }
+
+    /// Without the `remote` feature the server can't be set, but sessions saved by other builds can still have it.
+    #[cfg(not(feature = "remote"))]
+    fn connect(&self, _file: Intermediate, _max_adjustment: f64) -> Result<Runtime, DebuggerError> {
+        Err(DebuggerError::EngineInit(Text::CouldNotConnect.fill(
+            self.language,
+            &[&self.remote, &Text::BuiltWithoutRemote.tr(self.language)]
+        )))
+    }
+
+    /// Pick a worker count from the number of cores and the number of adjustables the selected engine finds.
+    fn pick_worker_count(&mut self) {
+        let count = self.load_script()
+            .and_then(|file| match self.engine {
+                EngineKind::Rage => count_adjustables::<Rage>(&file),
+                EngineKind::Mock => count_adjustables::<MockEngine>(&file)
+            })
+            .map(runtime::auto_worker_count);
+
+        match count {
+            Ok(count) => {
+                info!(worker_count = count, "picked a worker count");
+                self.worker_count = count.to_string();
+                self.file_error = None;
+            }
+            Err(err) => self.file_error = Some(err)
+        }
+    }
+
+    /// Close the debugger, asking first if there's an engine to lose.
+    pub fn request_exit(&mut self) {
+        if self.runtime.is_some() {
+            self.quit_requested = Some(Quit::Window);
+        } else {
+            self.exit = true;
+        }
+    }
+
+    fn show_quit_dialog(&mut self, ctx: &Context) {
+        let Some(quit) = self.quit_requested else {
+            return;
+        };
+
+        let Some(runtime) = &self.runtime else {
+            self.quit_requested = None;
+            self.exit |= quit == Quit::Window;
+            return;
+        };
+
+        let lang = self.language;
+        let idle = !runtime.progress.unattended;
+        let iteration = runtime.progress.iteration;
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new(Text::QuitTitle.tr(lang))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(Text::QuitLoses.fill(lang, &[&iteration]));
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    if ui.button(Text::SaveSession.tr(lang)).clicked() {
+                        self.save_session();
+                    }
+
+                    if ui.add_enabled(idle, egui::Button::new(Text::SaveCheckpoint.tr(lang)))
+                        .on_disabled_hover_text(Text::StopUnattendedFirst.tr(lang))
+                        .clicked()
+                    {
+                        self.save_checkpoint();
+                    }
+                });
+
+                if let Some(err) = &self.session_error {
+                    ui.label(RichText::new(err).color(Color32::RED));
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    confirmed = ui.button(Text::Quit.tr(lang)).clicked();
+                    cancelled = ui.button(Text::Cancel.tr(lang)).clicked();
+                });
+            });
+
+        if confirmed {
+            self.quit_requested = None;
+
+            match quit {
+                Quit::Runtime => self.quit(),
+                Quit::Window => self.exit = true
+            }
+        } else if cancelled {
+            self.quit_requested = None;
+        }
+    }
+
+    fn quit(&mut self) {
+        info!("runtime discarded");
+        self.run = false;
+        self.runtime = None;
+        self.intermediate = None;
+        self.non_finite.clear();
+        self.items.clear();
+        self.index = SpatialIndex::new();
+        self.selection.clear();
+        self.measure.clear();
+        self.undo.clear();
+        self.comparison.clear();
+        self.golden.clear();
+        self.trajectory.clear();
+        self.degeneracies.clear();
+        self.rules.clear();
+        self.bounds.clear();
+        self.bisect.clear();
+        self.auto_export.clear();
+    }
+
+    fn export_figure(&mut self, format: Format) {
+        let Some(path) = self.save_path(Text::ExportFigureTitle, format.name(), &[format.extension()]) else {
+            return;
+        };
+
+        self.export.error = self.export_to(format, path, self.export.selection_only)
+            .err()
+            .map(|err| Text::CouldNotExport.fill(self.language, &[&err]));
+    }
+
+    /// Export the latest frame with the export tool's settings. PNGs are captured when the canvas is next drawn.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be written.
+    fn export_to(&mut self, format: Format, path: PathBuf, selection_only: bool) -> io::Result<()> {
+        if format == Format::Png {
+            self.pending_capture = Some(Capture::Png {
+                path,
+                scale: self.export.png_scale
+            });
+            return Ok(());
+        }
+
+        let Some(runtime) = &self.runtime else {
+            return Ok(());
+        };
+
+        let Some(frame) = runtime.history.latest() else {
+            return Ok(());
+        };
+
+        let canvas = self.canvas_size();
+        let reference = runtime.reference.as_ref().map(|frame| &frame.generated);
+        let items = self.projection.project(frame.generated.clone(), reference, &runtime.flags, canvas);
+
+        let (items, colors): (Vec<_>, Vec<_>) = items.into_iter()
+            .enumerate()
+            .filter(|(i, _)| !selection_only || self.selection.items.contains(i))
+            .map(|(i, item)| {
+                let color = self.item_colors.rgba(i, &item);
+                (item, color)
+            })
+            .unzip();
+        let appearance = Appearance::new(colors, &self.item_colors, &self.item_style);
+
+        let legend = if self.export.legend {
+            legend::legend(&items)
+        } else {
+            Vec::new()
+        };
+
+        let metrics = Metrics {
+            iteration: frame.iteration,
+            quality: frame.quality,
+            elapsed: runtime.elapsed.as_secs_f64()
+        };
+
+        export::write(format, &items, &appearance, &self.annotations.list, &legend, Some(&metrics), canvas, &path)
+    }
+
+    /// Compile the script editor's contents.
+    fn load_script(&mut self) -> Result<Intermediate, DebuggerError> {
+        match math::load_script(&self.script) {
+            Ok(intermediate) => {
+                info!("script compiled");
+                self.script_errors.clear();
+                Ok(intermediate)
+            }
+            Err(err) => {
+                warn!("script failed to compile: {err:?}");
+                self.script_errors = error::compile_errors(err);
+                Err(DebuggerError::Compile(self.script_errors.clone()))
+            }
+        }
+    }
+
+    /// Read a script file into the editor.
+    fn open_script(&mut self, path: &Path) {
+        match fs::read_to_string(path) {
+            Ok(script) => {
+                self.script = script;
+                self.script_dirty = false;
+                self.file_error = None;
+            }
+            Err(err) => {
+                warn!("could not read {}: {err}", path.display());
+                self.script.clear();
+                self.file_error = Some(DebuggerError::io(path, &err));
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_script(&mut self) {
+        if let Some(file) = &self.file {
+            self.file_error = fs::write(file, &self.script).err().map(|err| DebuggerError::io(file, &err));
+            self.script_dirty = self.file_error.is_some();
+        }
+    }
+
+    /// Load a script into the editor. Without a file, it's treated as not yet saved anywhere.
+    pub fn open(&mut self, script: String, file: Option<PathBuf>) {
+        self.script_dirty = file.is_none();
+        self.last_dir = file.as_deref().and_then(Path::parent).map(PathBuf::from);
+        self.file = file;
+        self.script = script;
+        self.file_error = self.load_script().err();
+    }
+
+    /// Replace the editor's contents with the clipboard's, as a script not yet saved anywhere.
+    fn new_from_clipboard(&mut self) {
+        let Some(script) = clipboard_get().filter(|text| !text.trim().is_empty()) else {
+            self.session_error = Some(Text::ClipboardEmpty.tr(self.language).to_string());
+            return;
+        };
+
+        self.session_error = None;
+        self.script = script;
+        self.script_dirty = true;
+        self.file = None;
+
+        // Compile right away, so that errors show up before generating.
+        self.file_error = self.load_script().err();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_script_as(&mut self) {
+        if let Some(path) = self.save_path(Text::SaveScriptTitle, "GeoScript", &["geo"]) {
+            self.last_dir = path.parent().map(PathBuf::from);
+            self.file = Some(path);
+            self.save_script();
+        }
+    }
+
+    /// How long the worker has gone without answering, if longer than the watchdog timeout.
+    fn stuck(&self) -> Option<Duration> {
+        let runtime = self.runtime.as_ref().filter(|runtime| runtime.crash.is_none())?;
+        runtime.unanswered().filter(|silent| silent.as_secs_f64() > self.watchdog_timeout)
+    }
+
+    fn show_status_bar(&self, ctx: &Context) {
+        let lang = self.language;
+
+        egui::TopBottomPanel::bottom("status-bar")
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(runtime) = &self.runtime {
+                        ui.label(Text::StatusIteration.fill(lang, &[&runtime.progress.iteration]));
+                        ui.separator();
+                        ui.label(Text::StatusQuality.fill(lang, &[&format!("{:.4}", runtime.progress.quality)]));
+                        ui.separator();
+                        let steps = format!("{:.1}", runtime.steps_per_second());
+                        ui.label(Text::StatusStepsPerSecond.fill(lang, &[&steps]));
+                        ui.separator();
+                        ui.label(Text::GeneratingFor.fill(lang, &[&format_duration(runtime.elapsed)]))
+                            .on_hover_text(Text::GeneratingForHint.tr(lang));
+
+                        if let Some(eta) = runtime.eta() {
+                            ui.separator();
+                            ui.label(Text::Eta.fill(lang, &[&format_duration(eta)]));
+                        }
+
+                        let figure = self.items.capacity() * mem::size_of::<Item>();
+                        let usage = runtime.memory_usage();
+                        ui.separator();
+                        ui.label(Text::StatusMemory.fill(lang, &[&Bytes(usage.total() + figure)]))
+                            .on_hover_text(Text::MemoryBreakdown.fill(lang, &[
+                                &Bytes(usage.frames),
+                                &Bytes(usage.archive),
+                                &Bytes(usage.logs),
+                                &Bytes(usage.trace),
+                                &Bytes(figure),
+                                &self.memory_budget
+                            ]));
+
+                        if let Some(silent) = self.stuck() {
+                            ui.separator();
+                            ui.label(RichText::new(Text::WorkerBusy.fill(lang, &[&format_duration(silent)]))
+                                .color(Color32::YELLOW));
+                        }
+
+                        if let Some(reason) = runtime.progress.stopped {
+                            ui.separator();
+                            ui.label(Text::RunEnded.fill(lang, &[&reason.name().tr(lang)]));
+                        }
+                    } else {
+                        ui.label(Text::NoRuntime.tr(lang));
+                    }
+
+                    if let Some(cursor) = self.cursor {
+                        ui.separator();
+                        ui.label(Text::Cursor.fill(lang, &[&format!("{:.1}", cursor.x), &format!("{:.1}", cursor.y)]));
+                    }
+
+                    if let Some(result) = self.measure.result(lang) {
+                        ui.separator();
+                        ui.label(Text::Measured.fill(lang, &[&result]));
+                    }
+
+                    ui.separator();
+                    ui.label(Text::Fps.fill(lang, &[&get_fps()]));
+                });
+            });
+    }
+
+    /// Show the native file dialog, starting in the directory of the last picked file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pick_file(&mut self) {
+        let dialog = self.file_dialog(Text::OpenScriptTitle)
+            .add_filter("GeoScript", &["geo"])
+            .add_filter(Text::AllFiles.tr(self.language), &["*"]);
+
+        if let Some(path) = dialog.pick_file() {
+            self.last_dir = path.parent().map(PathBuf::from);
+            self.open_script(&path);
+            self.file = Some(path);
+        }
+    }
+
+    /// Show the browser's file picker. The script is opened by [`Self::show`] once it's been read.
+    #[cfg(target_arch = "wasm32")]
+    fn pick_file(&mut self) {
+        self.script_picker.open(Text::OpenScriptTitle.tr(self.language));
+    }
+}
+
+/// Create an engine, catching it panicking on a script it can't handle.
+fn start_engine<E: DebuggableEngine>(worker_count: usize, intermediate: &Intermediate) -> Result<E, DebuggerError> {
+    panic::catch_unwind(AssertUnwindSafe(|| E::compile(worker_count, intermediate)))
+        .map_err(|payload| DebuggerError::EngineInit(runtime::panic_message(payload.as_ref())))
+}
+
+/// Count the adjustables of a script, catching the engine panicking on a script it can't handle.
+fn count_adjustables<E: DebuggableEngine>(intermediate: &Intermediate) -> Result<usize, DebuggerError> {
+    panic::catch_unwind(AssertUnwindSafe(|| E::adjustables(intermediate)))
+        .map_err(|payload| DebuggerError::EngineInit(runtime::panic_message(payload.as_ref())))
+}
+
+/// A duration as hours, minutes and seconds, leaving out leading zero units.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s:02}s"),
+        (h, m, s) => format!("{h}h {m:02}m {s:02}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_leave_out_leading_zero_units() {
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(Duration::from_millis(59_999)), "59s");
+        assert_eq!(format_duration(Duration::from_secs(60)), "1m 00s");
+        assert_eq!(format_duration(Duration::from_secs(754)), "12m 34s");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1h 00m 00s");
+        assert_eq!(format_duration(Duration::from_secs(100 * 3600 + 61)), "100h 01m 01s");
+    }
+}
@@ -0,0 +1,146 @@
+use geo_aid_internal::projector::figure::Item;
+use geo_aid_internal::script::figure::Generated;
+use macroquad::prelude::*;
+use tracing::warn;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::clipboard;
+use crate::font::Fonts;
+use crate::i18n::Text;
+use crate::offscreen;
+use crate::overlay::OverlayContext;
+use crate::render;
+use crate::render::Renderer;
+use crate::spatial::SpatialIndex;
+use super::{Capture, Debugger};
+
+impl Debugger {
+    /// Draw the figure and enabled overlays into the canvas area.
+    pub fn draw_canvas(&mut self) {
+        if !self.fonts.is_for(self.label_font.path.as_deref()) {
+            self.fonts = Fonts::load(self.label_font.path.as_deref());
+        }
+
+        if let (Some(dbg), Some(area)) = (&self.runtime, self.canvas()) {
+            let canvas = (area.w, area.h);
+            let projection = self.projection;
+            let view = self.view;
+            let reference = dbg.reference.as_ref().map(|frame| &frame.generated);
+            let project = |generated: &Generated| {
+                let mut items = projection.project(generated.clone(), reference, &dbg.flags, canvas);
+                view.apply(&mut items, canvas);
+                items
+            };
+
+            // Draw in canvas coordinates, with the origin at the top-left corner of the canvas tab.
+            let camera = Camera2D::from_display_rect(Rect::new(
+                -area.x,
+                -area.y,
+                screen_width(),
+                screen_height()
+            ));
+            set_camera(&camera);
+
+            if let Some(latest) = dbg.history.latest() {
+                let items = project(&latest.generated);
+                let overlay_ctx = self.intermediate.as_ref().map(|intermediate| OverlayContext {
+                    generated: &latest.generated,
+                    items: &items,
+                    intermediate,
+                    history: &dbg.history,
+                    rules: dbg.rules.as_deref(),
+                    camera: &camera,
+                    canvas,
+                    project: &project,
+                    language: self.language
+                });
+
+                if let Some(ctx) = &overlay_ctx {
+                    self.overlays.draw(ctx, true);
+                }
+
+                if self.draw_figure(&items, &project, canvas) {
+                    render::draw_notice(canvas, Text::LabelsHidden.tr(self.language), &self.fonts);
+                }
+
+                if let Some(capture) = self.pending_capture.take() {
+                    let scale = match &capture {
+                        Capture::Png { scale, .. } => *scale,
+                        Capture::Clipboard => screen_dpi_scale()
+                    };
+
+                    let image = offscreen::render(canvas, scale, || {
+                        self.draw_figure(&items, &project, canvas);
+                    });
+                    set_camera(&camera);
+
+                    match capture {
+                        Capture::Png { path, .. } => image.export_png(&path.to_string_lossy()),
+                        #[cfg(not(target_arch = "wasm32"))]
+                        Capture::Clipboard => {
+                            if let Err(err) = clipboard::copy_image(&image) {
+                                warn!("could not copy the canvas: {err}");
+                            }
+                        }
+                        #[cfg(target_arch = "wasm32")]
+                        Capture::Clipboard => warn!("could not copy the canvas: no image clipboard in the browser")
+                    }
+                }
+
+                let non_finite = render::non_finite_items(&items)
+                    .into_iter()
+                    .map(|i| render::describe(&items[i], self.language))
+                    .collect::<Vec<_>>();
+
+                if !non_finite.is_empty() && non_finite != self.non_finite {
+                    warn!("projected to NaN/Inf: {}", non_finite.join(", "));
+                }
+
+                self.non_finite = non_finite;
+
+                if !self.non_finite.is_empty() {
+                    draw_rectangle_lines(0.0, 0.0, canvas.0, canvas.1, 4.0, RED);
+                    render::draw_runs(
+                        &Text::NonFiniteItems.fill(self.language, &[&self.non_finite.len()]),
+                        &self.fonts,
+                        10.0,
+                        24.0,
+                        20,
+                        RED
+                    );
+                }
+
+                if let Some(ctx) = &overlay_ctx {
+                    self.overlays.draw(ctx, false);
+                }
+
+                self.trajectory.record(latest.revision, latest.iteration, &items);
+                self.degeneracies.update(latest.revision, &items);
+                self.golden.update(latest, &dbg.flags);
+                self.index = SpatialIndex::build(&items);
+                self.items = items;
+            }
+
+            set_default_camera();
+        }
+    }
+
+    /// Draw the figure and annotations, as captured and exported.
+    /// Returns whether labels were left out for level of detail.
+    fn draw_figure(&self, items: &[Item], project: impl Fn(&Generated) -> Vec<Item>, canvas: (f32, f32)) -> bool {
+        let labels_left_out = if let Some((a, b)) = self.comparison.overlaid() {
+            render::draw_comparison(&project(&a.generated), &project(&b.generated));
+            false
+        } else {
+            Renderer {
+                colors: &self.item_colors,
+                hidden: &self.selection.hidden,
+                fonts: &self.fonts,
+                label_size: self.label_font.size,
+                style: &self.item_style
+            }.draw(items, canvas)
+        };
+
+        render::draw_annotations(self.annotations.list.iter().chain(&self.annotations.draft), canvas);
+        labels_left_out
+    }
+}
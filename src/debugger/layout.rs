@@ -0,0 +1,130 @@
+use egui::{Ui, WidgetText};
+use egui_dock::{DockState, NodeIndex, TabViewer};
+use serde::{Deserialize, Serialize};
+use crate::i18n::Text;
+use super::Debugger;
+
+/// A dockable panel of the debugger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tab {
+    /// The figure itself, drawn by macroquad underneath the UI.
+    Canvas,
+    /// Script file, engine parameters and run controls.
+    Controls,
+    /// Pause information, adjustables, breakpoints and overlays.
+    Inspector,
+    /// Quality over iterations.
+    Metrics,
+    /// The script editor.
+    Script,
+    /// Log viewer.
+    Log,
+    /// What the script's entities are derived from and which rules bind them.
+    Dependencies
+}
+
+impl Tab {
+    /// Tabs that can float in their own windows. The canvas is drawn underneath the UI, so it stays docked.
+    pub const FLOATABLE: [Self; 6] = [
+        Self::Controls,
+        Self::Inspector,
+        Self::Metrics,
+        Self::Script,
+        Self::Log,
+        Self::Dependencies
+    ];
+
+    #[must_use]
+    pub fn title(self) -> Text {
+        match self {
+            Self::Canvas => Text::Canvas,
+            Self::Controls => Text::Controls,
+            Self::Inspector => Text::Inspector,
+            Self::Metrics => Text::Metrics,
+            Self::Script => Text::Script,
+            Self::Log => Text::Log,
+            Self::Dependencies => Text::Dependencies
+        }
+    }
+}
+
+/// Canvas in the center, controls and inspector on the right, metrics, the log and dependencies below
+/// and the script on the left.
+#[must_use]
+pub fn default_layout() -> DockState<Tab> {
+    let mut dock = DockState::new(vec![Tab::Canvas]);
+    let surface = dock.main_surface_mut();
+
+    let [canvas, right] = surface.split_right(NodeIndex::root(), 0.75, vec![Tab::Controls]);
+    surface.split_below(right, 0.4, vec![Tab::Inspector]);
+    let [canvas, _] = surface.split_below(canvas, 0.75, vec![Tab::Metrics, Tab::Log, Tab::Dependencies]);
+    surface.split_left(canvas, 0.25, vec![Tab::Script]);
+
+    dock
+}
+
+/// Move a docked tab into a floating window of its own. Tabs already floating are left alone.
+///
+/// The window floats over the debugger rather than being a separate OS window: macroquad draws to a single window
+/// and the egui integration has no viewports, so panels can't be moved to another monitor.
+pub fn float(dock: &mut DockState<Tab>, tab: Tab) {
+    let Some(position) = dock.find_tab(&tab).filter(|(surface, ..)| surface.is_main()) else {
+        return;
+    };
+
+    if let Some(tab) = dock.remove_tab(position) {
+        dock.add_window(vec![tab]);
+    }
+}
+
+/// Shows the debugger's tabs.
+pub struct Tabs<'a>(pub &'a mut Debugger);
+
+impl TabViewer for Tabs<'_> {
+    type Tab = Tab;
+
+    fn title(&mut self, tab: &mut Tab) -> WidgetText {
+        let title = tab.title().tr(self.0.language);
+
+        if *tab == Tab::Script && self.0.script_dirty {
+            format!("{title}*").into()
+        } else {
+            title.into()
+        }
+    }
+
+    fn id(&mut self, tab: &mut Tab) -> egui::Id {
+        egui::Id::new(*tab)
+    }
+
+    fn ui(&mut self, ui: &mut Ui, tab: &mut Tab) {
+        match tab {
+            Tab::Canvas => self.0.canvas_ui(ui),
+            Tab::Controls => self.0.controls_ui(ui),
+            Tab::Inspector => self.0.inspector_ui(ui),
+            Tab::Metrics => self.0.metrics_ui(ui),
+            Tab::Script => self.0.script_ui(ui),
+            Tab::Log => self.0.log.ui(ui, self.0.language),
+            Tab::Dependencies => self.0.dependencies_ui(ui)
+        }
+    }
+
+    fn closeable(&mut self, _tab: &mut Tab) -> bool {
+        false
+    }
+
+    fn allowed_in_windows(&self, tab: &mut Tab) -> bool {
+        *tab != Tab::Canvas
+    }
+
+    fn clear_background(&self, tab: &Tab) -> bool {
+        *tab != Tab::Canvas
+    }
+
+    fn scroll_bars(&self, tab: &Tab) -> [bool; 2] {
+        match tab {
+            Tab::Canvas | Tab::Metrics | Tab::Log => [false, false],
+            _ => [true, true]
+        }
+    }
+}
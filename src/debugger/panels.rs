@@ -0,0 +1,1177 @@
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+use egui::{Color32, RichText, Sense, Ui};
+use egui::text::{LayoutJob, TextFormat};
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
+use crate::breakdown;
+use crate::colors::{Kind, Palette};
+use crate::dependencies;
+use crate::dependencies::Pick;
+use crate::engine::EngineKind;
+use crate::error;
+use crate::error::DebuggerError;
+use crate::font;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::golden;
+use crate::help::Target;
+use crate::i18n::{Language, Text};
+use crate::legend;
+use crate::measure;
+use crate::minimap;
+use crate::profiler::Action;
+use crate::projection::Aspect;
+use crate::proposals;
+use crate::render;
+use crate::rules;
+use crate::rules::Edit;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::runtime;
+use crate::runtime::{Batching, Message, PauseReason};
+use crate::style;
+use crate::style::{DotStyle, ItemStyle};
+use crate::view::View;
+use crate::workers;
+use super::{format_duration, Debugger, Quit};
+
+/// Fill of the canvas tab outside a fixed-size or aspect-locked canvas.
+const LETTERBOX_COLOR: Color32 = Color32::from_gray(200);
+/// Range of the live max adjustment slider.
+const MAX_ADJUSTMENT_RANGE: RangeInclusive<f64> = 0.0001..=10.0;
+/// Time budget offered when enabling one, in seconds.
+const DEFAULT_TIME_BUDGET: f64 = 60.0;
+/// Worker counts offered next to the worker count field.
+const WORKER_PRESETS: [usize; 3] = [64, 256, 1024];
+/// Most adjustables whose magnitudes are plotted.
+const MAX_PLOTTED_MAGNITUDES: usize = 16;
+/// Zoom factor per scrolled point, as an exponent.
+const ZOOM_SPEED: f64 = 0.002;
+/// Smallest 1 - quality shown on the log scale, so that a perfect quality doesn't go to minus infinity.
+const MIN_PLOTTED_ERROR: f64 = 1e-12;
+
+impl Debugger {
+    /// Reserve the canvas area so the figure drawn underneath stays visible.
+    pub(super) fn canvas_ui(&mut self, ui: &mut Ui) {
+        let tab = ui.available_rect_before_wrap();
+        let response = ui.allocate_rect(tab, Sense::click_and_drag());
+        let rect = self.fit_canvas(tab);
+        self.canvas = Some(rect);
+
+        if rect != tab {
+            let bars = [
+                egui::Rect::from_x_y_ranges(tab.x_range(), tab.top()..=rect.top()),
+                egui::Rect::from_x_y_ranges(tab.x_range(), rect.bottom()..=tab.bottom()),
+                egui::Rect::from_x_y_ranges(tab.left()..=rect.left(), rect.y_range()),
+                egui::Rect::from_x_y_ranges(rect.right()..=tab.right(), rect.y_range())
+            ];
+
+            for bar in bars.into_iter().filter(|bar| bar.is_positive()) {
+                ui.painter().rect_filled(bar, 0.0, LETTERBOX_COLOR);
+            }
+        }
+
+        let origin = rect.min.to_vec2();
+        self.cursor = response.hover_pos().map(|pos| pos - origin);
+        self.view_input(ui, &response, rect.size());
+        self.degeneracies.paint(ui.painter(), origin, &self.items);
+        let rules = self.runtime.as_ref().and_then(|rt| rt.rules.as_deref());
+        self.rules.paint(ui.painter(), origin, &self.items, rules);
+        self.golden.paint(ui.painter(), origin, &self.items);
+        minimap::ui(ui, rect, &mut self.view, &self.items);
+
+        if self.annotations.active && !self.measure.active {
+            self.annotations.interact(&response, origin, rect.size());
+            return;
+        }
+
+        if !self.measure.active {
+            let add = ui.input(|input| input.modifiers.shift);
+            self.selection.interact(&response, origin, &self.items, &self.index, add);
+            self.selection.paint(ui.painter(), origin, &self.items);
+            return;
+        }
+
+        if ui.input(|input| input.key_pressed(egui::Key::Escape)) {
+            self.measure.clear();
+        }
+
+        if let Some(cursor) = self.cursor {
+            let snap = measure::snap(&self.items, &self.index, cursor, |i| self.selection.pickable(i));
+
+            if let (true, Some(snap)) = (response.clicked(), snap) {
+                self.measure.click(snap.pick());
+            }
+
+            measure::paint_crosshair(ui.painter(), rect, cursor, snap);
+        }
+
+        self.measure.paint(ui.painter(), origin);
+    }
+
+    /// Zoom with the scroll wheel, pan by dragging with the middle button, reset with Home
+    /// and jump to bookmarks with number keys.
+    fn view_input(&mut self, ui: &Ui, response: &egui::Response, size: egui::Vec2) {
+        let canvas = (size.x, size.y);
+
+        if let Some(cursor) = self.cursor {
+            let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+
+            if scroll != 0.0 {
+                let factor = (f64::from(scroll) * ZOOM_SPEED).exp();
+                self.view.zoom_at(factor, (f64::from(cursor.x), f64::from(cursor.y)), canvas);
+            }
+        }
+
+        if response.dragged_by(egui::PointerButton::Middle) {
+            let delta = response.drag_delta();
+            self.view.pan((f64::from(delta.x), f64::from(delta.y)), canvas);
+        }
+
+        // Keys typed into text fields aren't shortcuts.
+        if !response.hovered() || ui.ctx().wants_keyboard_input() {
+            return;
+        }
+
+        if ui.input(|input| input.key_pressed(egui::Key::Home)) {
+            self.view = View::default();
+        }
+
+        if let Some(view) = self.bookmarks.pressed(ui) {
+            self.view = view;
+        }
+    }
+
+    pub(super) fn controls_ui(&mut self, ui: &mut Ui) {
+        if self.runtime.is_some() {
+            self.run_controls_ui(ui);
+        } else {
+            self.setup_ui(ui);
+        }
+    }
+
+    fn run_controls_ui(&mut self, ui: &mut Ui) {
+        let lang = self.language;
+        let mut quit = false;
+        let mut abort = false;
+        let stuck = self.stuck();
+
+        let Some(runtime) = &mut self.runtime else {
+            return;
+        };
+
+        if ui.button(Text::Quit.tr(lang)).on_hover_text(Text::QuitHint.tr(lang)).clicked() {
+            quit = true;
+        }
+
+        if let Some(silent) = stuck {
+            egui::Frame::group(ui.style())
+                .stroke(egui::Stroke::new(1.0, Color32::YELLOW))
+                .show(ui, |ui| {
+                    ui.label(RichText::new(Text::WorkerSilent.fill(lang, &[&format_duration(silent)]))
+                        .color(Color32::YELLOW)
+                        .strong());
+                    ui.label(Text::WorkerStuckHint.tr(lang));
+
+                    abort = ui.button(Text::Abort.tr(lang))
+                        .on_hover_text(Text::AbortHint.tr(lang))
+                        .clicked();
+                });
+        }
+
+        if let Some(crash) = &runtime.crash {
+            egui::Frame::group(ui.style())
+                .stroke(egui::Stroke::new(1.0, Color32::RED))
+                .show(ui, |ui| {
+                    ui.label(RichText::new(Text::WorkerPanicked.tr(lang)).color(Color32::RED).strong());
+                    ui.label(Text::AtIteration.fill(lang, &[&crash.iteration]));
+                    ui.label(RichText::new(&crash.message).color(Color32::RED).monospace());
+                });
+        } else if runtime.progress.unattended {
+            let state = if runtime.progress.suspended { Text::PausedUnattended } else { Text::RunningUnattended };
+            ui.label(state.fill(lang, &[
+                &runtime.progress.iteration,
+                &format!("{:.4}", runtime.progress.quality),
+                &format!("{:.0}", runtime.steps_per_second())
+            ]));
+
+            if let Some(eta) = runtime.eta() {
+                ui.label(Text::EstimatedTimeLeft.fill(lang, &[&format_duration(eta)]));
+            }
+
+            ui.horizontal(|ui| {
+                if runtime.progress.suspended {
+                    if ui.button(Text::Resume.tr(lang)).clicked() {
+                        runtime.send(Message::Resume);
+                    }
+                } else if ui.button(Text::Pause.tr(lang)).clicked() {
+                    runtime.send(Message::Suspend);
+                }
+
+                if ui.button(Text::Stop.tr(lang)).clicked() {
+                    runtime.send(Message::Stop);
+                }
+            });
+        } else if self.run {
+            let pause = ui.button(Text::Pause.tr(lang));
+            self.help.target(Target::Stepping, pause.rect);
+
+            if pause.clicked() {
+                self.run = false;
+            }
+
+            batching_ui(ui, &mut self.batching, lang);
+            time_budget_ui(ui, &mut self.time_budget, lang);
+        } else {
+            let run = ui.button(Text::Run.tr(lang));
+            let next = ui.button(Text::NextStep.tr(lang));
+            self.help.target(Target::Stepping, run.rect.union(next.rect));
+
+            if run.clicked() {
+                self.run = true;
+            }
+
+            if next.clicked() {
+                runtime.send(Message::Next);
+            }
+
+            batching_ui(ui, &mut self.batching, lang);
+            time_budget_ui(ui, &mut self.time_budget, lang);
+
+            ui.separator();
+
+            ui.label(Text::StopCriteria.tr(lang));
+            self.stop.ui(ui, lang);
+
+            if ui.button(Text::RunUntilStopped.tr(lang)).clicked() {
+                if let Some(criteria) = self.stop.parse() {
+                    runtime.run_until(criteria);
+                }
+            }
+
+            if let Some(reason) = runtime.progress.stopped {
+                ui.label(Text::LastRunEnded.fill(lang, &[&reason.name().tr(lang)]));
+            }
+        }
+
+        ui.label(Text::IterationQuality.fill(lang, &[
+            &runtime.progress.iteration,
+            &format!("{:.4}", runtime.progress.quality)
+        ]));
+
+        let mut max_adjustment = runtime.max_adjustment;
+        let slider = egui::Slider::new(&mut max_adjustment, MAX_ADJUSTMENT_RANGE)
+            .logarithmic(true)
+            .text(Text::MaxAdjustment.tr(lang));
+
+        let response = ui.add_enabled(runtime.crash.is_none(), slider);
+
+        // A whole drag is a single change.
+        if response.drag_started() || (response.changed() && !response.dragged()) {
+            if let Some(snapshot) = runtime.snapshot() {
+                self.undo.push(snapshot);
+            }
+        }
+
+        if response.changed() {
+            runtime.set_max_adjustment(max_adjustment);
+            self.max_adjustment = max_adjustment.to_string();
+        }
+
+        if abort {
+            self.quit();
+        } else if quit {
+            self.quit_requested = Some(Quit::Runtime);
+        }
+    }
+
+    fn setup_ui(&mut self, ui: &mut Ui) {
+        let lang = self.language;
+        let mut pick_file = false;
+        let mut generate = false;
+
+        egui::Grid::new("file-data")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(Text::FileLabel.tr(lang));
+                let picker = if let Some(file) = &self.file {
+                    ui.horizontal(|ui| {
+                        ui.label(file.to_string_lossy());
+                        ui.button(Text::Change.tr(lang))
+                    }).inner
+                } else if !self.script.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(Text::UnsavedScript.tr(lang));
+                        ui.button(Text::Open.tr(lang))
+                    }).inner
+                } else {
+                    ui.button(Text::Open.tr(lang))
+                };
+                self.help.target(Target::FilePicker, picker.rect);
+
+                if picker.clicked() {
+                    pick_file = true;
+                }
+                ui.end_row();
+
+                if let Some(err) = &self.file_error {
+                    error_row(ui, err, lang);
+                }
+
+                ui.label(Text::WorkerCount.tr(lang));
+                let (parameters, preset) = ui.horizontal(|ui| {
+                    let field = ui.text_edit_singleline(&mut self.worker_count).rect;
+                    let mut preset = None;
+
+                    if ui.button(Text::Auto.tr(lang))
+                        .on_hover_text(Text::AutoWorkersHint.tr(lang))
+                        .clicked()
+                    {
+                        preset = Some(None);
+                    }
+
+                    for count in WORKER_PRESETS {
+                        if ui.button(count.to_string()).clicked() {
+                            preset = Some(Some(count));
+                        }
+                    }
+
+                    (field, preset)
+                }).inner;
+
+                match preset {
+                    Some(Some(count)) => self.worker_count = count.to_string(),
+                    Some(None) => self.pick_worker_count(),
+                    None => ()
+                }
+
+                self.worker_count_error = error::parse_worker_count(&self.worker_count).err();
+                outline_invalid(ui, parameters, self.worker_count_error.is_some());
+                ui.end_row();
+
+                if let Some(err) = &self.worker_count_error {
+                    error_row(ui, err, lang);
+                }
+
+                ui.label(Text::MaximumAdjustment.tr(lang));
+                let max_adjustment = ui.text_edit_singleline(&mut self.max_adjustment).rect;
+                self.max_adjustment_error = error::parse_max_adjustment(&self.max_adjustment).err();
+                outline_invalid(ui, max_adjustment, self.max_adjustment_error.is_some());
+                let parameters = parameters.union(max_adjustment);
+                self.help.target(Target::Parameters, parameters);
+                ui.end_row();
+
+                if let Some(err) = &self.max_adjustment_error {
+                    error_row(ui, err, lang);
+                }
+
+                ui.label(Text::Engine.tr(lang));
+                egui::ComboBox::from_id_source("engine")
+                    .selected_text(self.engine.name())
+                    .show_ui(ui, |ui| {
+                        for engine in EngineKind::ALL {
+                            ui.selectable_value(&mut self.engine, engine, engine.name());
+                        }
+                    })
+                    .response
+                    .on_hover_text(Text::MockHint.tr(lang));
+                ui.end_row();
+
+                // The worker is stepped on the UI thread on the web.
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.label(Text::GenerationThread.tr(lang));
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.threading.low_priority, Text::LowPriority.tr(lang))
+                            .on_hover_text(Text::LowPriorityHint.tr(lang));
+
+                        let core_name = |core: Option<usize>| core.map_or_else(
+                            || Text::AnyCore.tr(lang).to_string(),
+                            |core| Text::Core.fill(lang, &[&core])
+                        );
+                        egui::ComboBox::from_id_source("pin-core")
+                            .selected_text(core_name(self.threading.core))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.threading.core, None, core_name(None));
+
+                                for core in 0..runtime::core_count() {
+                                    ui.selectable_value(&mut self.threading.core, Some(core), core_name(Some(core)));
+                                }
+                            })
+                            .response
+                            .on_hover_text(Text::PinCoreHint.tr(lang));
+                    });
+                    ui.end_row();
+                }
+
+                ui.label(Text::HistoryBudget.tr(lang));
+                ui.add(egui::DragValue::new(&mut self.memory_budget).range(16..=65_536).suffix(" MiB"))
+                    .on_hover_text(Text::HistoryBudgetHint.tr(lang));
+                ui.end_row();
+
+                ui.label(Text::WatchdogTimeout.tr(lang));
+                ui.add(egui::DragValue::new(&mut self.watchdog_timeout).range(1.0..=3600.0).suffix(" s"))
+                    .on_hover_text(Text::WatchdogTimeoutHint.tr(lang));
+                ui.end_row();
+
+                #[cfg(feature = "remote")]
+                {
+                    ui.label(Text::Server.tr(lang));
+                    ui.add(egui::TextEdit::singleline(&mut self.remote).hint_text(Text::Local.tr(lang)))
+                        .on_hover_text(Text::ServerHint.tr(lang));
+                    ui.end_row();
+                }
+
+                if let Some(err) = &self.engine_error {
+                    error_row(ui, err, lang);
+                }
+
+                ui.label("");
+                let valid = self.worker_count_error.is_none() && self.max_adjustment_error.is_none();
+                if ui.add_enabled(valid, egui::Button::new(Text::Generate.tr(lang)))
+                    .on_disabled_hover_text(Text::FixParameters.tr(lang))
+                    .clicked()
+                {
+                    generate = true;
+                }
+                ui.end_row();
+            });
+
+        if generate {
+            self.generate();
+        }
+
+        if pick_file {
+            self.pick_file();
+        }
+    }
+
+    pub(super) fn inspector_ui(&mut self, ui: &mut Ui) {
+        let lang = self.language;
+
+        if let Some(runtime) = &mut self.runtime {
+            match runtime.progress.pause.as_ref().map(|pause| (&pause.reason, pause.iteration)) {
+                Some((PauseReason::Breakpoint(index), iteration)) => {
+                    if let Some(bp) = self.breakpoints.list.get(*index) {
+                        ui.label(
+                            RichText::new(Text::PausedAtBreakpoint.fill(lang, &[&iteration, &bp.describe(lang)]))
+                                .color(Color32::RED)
+                        );
+                    }
+                }
+                Some((PauseReason::NonFinite(adjustables), iteration)) => {
+                    ui.label(
+                        RichText::new(Text::PausedAtNonFinite.fill(lang, &[&iteration, &adjustables.len()]))
+                            .color(Color32::RED)
+                    );
+                }
+                None => ()
+            }
+
+            if !self.non_finite.is_empty() {
+                ui.label(
+                    RichText::new(Text::ProjectedNonFinite.fill(lang, &[&self.non_finite.join(", ")]))
+                        .color(Color32::RED)
+                );
+            }
+
+            ui.collapsing(Text::Adjustables.tr(lang), |ui| {
+                let Some(frame) = runtime.history.latest() else {
+                    return;
+                };
+
+                egui::Grid::new("adjustables")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, value) in frame.state.iter().enumerate() {
+                            let text = format!("{value:.6}");
+
+                            ui.label(format!("#{i}"));
+                            if value.is_finite() {
+                                ui.label(text);
+                            } else {
+                                ui.label(RichText::new(text).color(Color32::RED).strong());
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            ui.collapsing(Text::Magnitudes.tr(lang), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(Text::BakedFor.fill(lang, &[&runtime.max_adjustment]));
+
+                    if ui.button(Text::ReHeat.tr(lang)).on_hover_text(Text::ReHeatHint.tr(lang)).clicked() {
+                        let max_adjustment = runtime.max_adjustment;
+                        runtime.set_max_adjustment(max_adjustment);
+                    }
+                });
+
+                let mut magnitudes = runtime.magnitudes.clone();
+                let mut changed = false;
+
+                egui::Grid::new("magnitudes")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, magnitude) in magnitudes.iter_mut().enumerate() {
+                            ui.label(format!("#{i}"));
+                            changed |= ui.add(
+                                egui::DragValue::new(magnitude)
+                                    .speed(0.001)
+                                    .range(0.0..=f64::MAX)
+                                    .max_decimals(6)
+                            ).changed();
+                            ui.end_row();
+                        }
+                    });
+
+                if changed {
+                    runtime.send(Message::SetMagnitudes(magnitudes));
+                }
+            });
+
+            ui.collapsing(Text::Workers.tr(lang), |ui| {
+                let workers = runtime.history.latest().and_then(|frame| frame.workers.as_deref());
+                workers::ui(ui, workers, lang);
+            });
+
+            ui.collapsing(Text::Proposals.tr(lang), |ui| {
+                let dissecting = runtime.dissecting();
+                let unsupported = runtime.dissecting_unsupported;
+
+                match self.proposals.ui(ui, dissecting, unsupported, runtime.proposals.as_ref(), lang) {
+                    Some(proposals::Action::Dissect(dissecting)) => runtime.set_dissecting(dissecting),
+                    // A free run steps on its own.
+                    Some(proposals::Action::NextCycle) if !self.run => runtime.send(Message::Next),
+                    Some(proposals::Action::NextCycle) | None => ()
+                }
+            });
+
+            ui.collapsing(Text::Rules.tr(lang), |ui| {
+                let errors = runtime.history.latest().and_then(|frame| frame.rule_errors.as_deref());
+                let rules = runtime.rules.as_deref();
+
+                match self.rules.ui(ui, rules, errors, &self.selection.items, &self.items, lang) {
+                    Some(Edit::Enabled(rule, enabled)) => runtime.set_rule_enabled(rule, enabled),
+                    Some(Edit::Weight(rule, weight)) => runtime.set_rule_weight(rule, weight),
+                    None => ()
+                }
+            });
+
+            ui.collapsing(Text::ErrorBreakdown.tr(lang), |ui| {
+                let errors = runtime.history.latest().and_then(|frame| frame.rule_errors.as_deref());
+                let rules = runtime.rules.as_deref();
+
+                let Some((i, rule)) = breakdown::ui(ui, rules, errors, self.rules.picked, lang)
+                    .and_then(|i| rules.and_then(|rules| rules.get(i)).map(|rule| (i, rule)))
+                else {
+                    return;
+                };
+
+                let items = rule.items.iter().copied().filter(|&item| self.selection.pickable(item)).collect();
+                self.rules.picked = Some(i);
+                self.selection.items = items;
+            });
+
+            ui.collapsing(Text::Profiler.tr(lang), |ui| {
+                let action = self.profiler.ui(
+                    ui,
+                    runtime.profiling(),
+                    runtime.profiling_unsupported,
+                    runtime.profile.as_ref(),
+                    runtime.rules.as_deref(),
+                    lang
+                );
+
+                match action {
+                    Some(Action::Profile(window)) => runtime.set_profiling(window),
+                    Some(Action::PickRule(rule)) => self.rules.picked = Some(rule),
+                    None => ()
+                }
+            });
+        }
+
+        ui.collapsing(Text::FigureStatistics.tr(lang), |ui| {
+            let counts = render::count_items(&self.items);
+            let adjustables = self.runtime.as_ref()
+                .and_then(|rt| rt.history.latest())
+                .map(|frame| frame.state.len());
+
+            egui::Grid::new("figure-statistics")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    let rows = [
+                        (Text::Points, counts.points),
+                        (Text::Lines, counts.lines),
+                        (Text::Segments, counts.segments),
+                        (Text::Rays, counts.rays),
+                        (Text::Circles, counts.circles),
+                        (Text::Labels, counts.labels)
+                    ];
+
+                    for (name, count) in rows {
+                        ui.label(name.tr(lang));
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+
+                    ui.label(Text::Adjustables.tr(lang));
+                    ui.label(adjustables.map_or_else(|| String::from("-"), |count| count.to_string()));
+                    ui.end_row();
+                });
+        });
+
+        ui.collapsing(Text::Degeneracies.tr(lang), |ui| self.degeneracies.ui(ui, &self.items, lang));
+
+        ui.collapsing(Text::Legend.tr(lang), |ui| {
+            let legend = legend::legend(&self.items);
+
+            if legend.is_empty() {
+                ui.label(Text::NoLabelledItems.tr(lang));
+            }
+
+            egui::Grid::new("legend")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for entry in legend {
+                        ui.strong(&entry.label);
+                        ui.label(entry.describe(lang));
+                        ui.end_row();
+                    }
+                });
+        });
+
+        ui.collapsing(Text::ItemColors.tr(lang), |ui| {
+            egui::ComboBox::from_label(Text::Palette.tr(lang))
+                .selected_text(self.item_colors.palette.name().tr(lang))
+                .show_ui(ui, |ui| {
+                    for palette in Palette::ALL {
+                        ui.selectable_value(&mut self.item_colors.palette, palette, palette.name().tr(lang));
+                    }
+                });
+
+            ui.checkbox(&mut self.item_colors.high_contrast, Text::HighContrast.tr(lang))
+                .on_hover_text(Text::HighContrastHint.tr(lang));
+
+            egui::Grid::new("kind-colors")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for kind in Kind::ALL {
+                        let mut color = self.item_colors.kind(kind);
+
+                        ui.label(kind.name().tr(lang));
+                        if ui.color_edit_button_srgba_unmultiplied(&mut color).changed() {
+                            self.item_colors.kinds.insert(kind, color);
+                        }
+
+                        let custom = self.item_colors.kinds.contains_key(&kind);
+                        if custom && ui.small_button(Text::Reset.tr(lang)).clicked() {
+                            self.item_colors.kinds.remove(&kind);
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+
+            egui::Grid::new("item-colors")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (i, item) in self.items.iter().enumerate() {
+                        let custom = self.item_colors.items.get(&i).copied();
+                        let mut color = custom.unwrap_or_else(|| self.item_colors.get(i, item).into());
+
+                        ui.label(render::describe(item, lang));
+                        if ui.color_edit_button_srgba_unmultiplied(&mut color).changed() {
+                            self.item_colors.items.insert(i, color);
+                        }
+
+                        if custom.is_some() && ui.small_button(Text::Reset.tr(lang)).clicked() {
+                            self.item_colors.items.remove(&i);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        ui.collapsing(Text::ItemStyle.tr(lang), |ui| {
+            let item_style = &mut self.item_style;
+
+            ui.add(egui::Slider::new(&mut item_style.stroke_width, style::MIN_STROKE_WIDTH..=style::MAX_STROKE_WIDTH)
+                .text(Text::StrokeWidth.tr(lang)));
+            ui.add(egui::Slider::new(&mut item_style.point_radius, style::MIN_POINT_RADIUS..=style::MAX_POINT_RADIUS)
+                .text(Text::PointRadius.tr(lang)));
+
+            egui::ComboBox::from_label(Text::Dots.tr(lang))
+                .selected_text(item_style.dot.name().tr(lang))
+                .show_ui(ui, |ui| {
+                    for dot in DotStyle::ALL {
+                        ui.selectable_value(&mut item_style.dot, dot, dot.name().tr(lang));
+                    }
+                });
+
+            ui.checkbox(&mut item_style.scale_with_canvas, Text::ScaleWithCanvas.tr(lang))
+                .on_hover_text(Text::ScaleWithCanvasHint.tr(lang));
+            ui.checkbox(&mut item_style.level_of_detail, Text::LevelOfDetail.tr(lang))
+                .on_hover_text(Text::LevelOfDetailHint.tr(lang));
+
+            if ui.button(Text::Reset.tr(lang)).clicked() {
+                *item_style = ItemStyle::default();
+            }
+        });
+
+        ui.collapsing(Text::LabelFont.tr(lang), |ui| {
+            ui.horizontal(|ui| {
+                match &self.label_font.path {
+                    Some(path) => ui.label(path.to_string_lossy()),
+                    None => ui.label(Text::SystemFonts.tr(lang))
+                };
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button(Text::Pick.tr(lang)).clicked() {
+                    self.pick_label_font();
+                }
+
+                if self.label_font.path.is_some() && ui.small_button(Text::Reset.tr(lang)).clicked() {
+                    self.label_font.path = None;
+                }
+            });
+
+            if let Some(err) = &self.fonts.error {
+                ui.label(RichText::new(Text::CouldNotLoadFont.fill(lang, &[err])).color(Color32::RED));
+            }
+
+            if self.fonts.is_empty() {
+                ui.label(Text::NoUnicodeFont.tr(lang))
+                    .on_hover_text(Text::NoUnicodeFontHint.tr(lang));
+            }
+
+            let size = egui::Slider::new(&mut self.label_font.size, font::MIN_SIZE..=font::MAX_SIZE);
+            ui.add(size.text(Text::Size.tr(lang)));
+        });
+
+        ui.collapsing(Text::Selection.tr(lang), |ui| {
+            if self.selection.ui(ui, &mut self.item_colors, lang) {
+                self.export.selection_only = true;
+                self.export.open = true;
+            }
+        });
+
+        ui.collapsing(Text::Annotations.tr(lang), |ui| self.annotations.ui(ui, lang));
+
+        ui.collapsing(Text::Bookmarks.tr(lang), |ui| {
+            if let Some(view) = self.bookmarks.ui(ui, &self.view, lang) {
+                self.view = view;
+            }
+        });
+
+        ui.collapsing(Text::Comparison.tr(lang), |ui| {
+            let latest = self.runtime.as_ref().and_then(|rt| rt.history.latest());
+            self.comparison.ui(ui, latest, lang);
+        });
+
+        // Goldens are only ever saved to and loaded from files.
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.collapsing(Text::Golden.tr(lang), |ui| match self.golden.ui(ui, lang) {
+            Some(golden::Action::Save) => self.save_golden(),
+            Some(golden::Action::Load) => self.load_golden(),
+            None => ()
+        });
+
+        ui.collapsing(Text::Breakpoints.tr(lang), |ui| {
+            let hit = match self.runtime.as_ref().and_then(|rt| rt.progress.pause.as_ref()).map(|pause| &pause.reason) {
+                Some(PauseReason::Breakpoint(index)) => Some(*index),
+                _ => None
+            };
+
+            if self.breakpoints.ui(ui, hit, lang) {
+                if let Some(runtime) = &mut self.runtime {
+                    runtime.send(Message::SetBreakpoints(self.breakpoints.list.clone()));
+                }
+            }
+
+            ui.separator();
+            self.bounds.ui(ui, lang);
+        });
+
+        ui.collapsing(Text::Bisect.tr(lang), |ui| {
+            let history = self.runtime.as_ref().map(|rt| &rt.history);
+
+            if let Some(state) = self.bisect.ui(ui, history, lang) {
+                if let Some(runtime) = &mut self.runtime {
+                    if let Some(snapshot) = runtime.snapshot() {
+                        self.undo.push(snapshot);
+                    }
+
+                    runtime.send(Message::Restore(state));
+                }
+            }
+        });
+
+        ui.collapsing(Text::TimeTravel.tr(lang), |ui| {
+            let archive = self.runtime.as_ref().map(|rt| &rt.archive);
+
+            if let Some(state) = self.time_travel.ui(ui, archive, lang) {
+                if let Some(runtime) = &mut self.runtime {
+                    if let Some(snapshot) = runtime.snapshot() {
+                        self.undo.push(snapshot);
+                    }
+
+                    runtime.send(Message::Restore(state));
+                }
+            }
+        });
+
+        ui.collapsing(Text::Overlays.tr(lang), |ui| self.overlays.ui(ui, lang));
+
+        ui.collapsing(Text::Projection.tr(lang), |ui| {
+            let mut fixed = self.projection.size.is_some();
+
+            if ui.checkbox(&mut fixed, Text::FixedCanvasSize.tr(lang))
+                .on_hover_text(Text::FixedCanvasSizeHint.tr(lang))
+                .changed()
+            {
+                self.projection.size = fixed.then(|| {
+                    let (width, height) = self.canvas_size();
+                    (width as u32, height as u32)
+                });
+            }
+
+            if let Some((width, height)) = &mut self.projection.size {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(width).range(1..=10_000));
+                    ui.label("×");
+                    ui.add(egui::DragValue::new(height).range(1..=10_000));
+                });
+            } else {
+                egui::ComboBox::from_label(Text::AspectRatio.tr(lang))
+                    .selected_text(self.projection.aspect.map_or(Text::Free, Aspect::name).tr(lang))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.projection.aspect, None, Text::Free.tr(lang));
+
+                        for aspect in Aspect::ALL {
+                            ui.selectable_value(&mut self.projection.aspect, Some(aspect), aspect.name().tr(lang));
+                        }
+                    });
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(Text::Margin.tr(lang));
+                ui.add(egui::DragValue::new(&mut self.projection.margin).range(0..=1000));
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.projection.freeze_fit, Text::FreezeFit.tr(lang))
+                    .on_hover_text(Text::FreezeFitHint.tr(lang));
+
+                if let Some(runtime) = self.runtime.as_mut().filter(|_| self.projection.freeze_fit) {
+                    if ui.button(Text::FreezeHere.tr(lang)).clicked() {
+                        runtime.reference = runtime.history.latest().cloned();
+                    }
+                }
+            });
+        });
+    }
+
+    pub(super) fn metrics_ui(&mut self, ui: &mut Ui) {
+        let lang = self.language;
+
+        if self.runtime.is_none() {
+            ui.label(Text::NothingGenerated.tr(lang));
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.quality_log_scale, Text::LogScale.tr(lang))
+                .on_hover_text(Text::LogScaleHint.tr(lang));
+            ui.checkbox(&mut self.quality_target_line, Text::TargetLine.tr(lang));
+            ui.checkbox(&mut self.magnitude_plot, Text::Magnitudes.tr(lang));
+            ui.checkbox(&mut self.rule_error_plot, Text::RuleErrors.tr(lang))
+                .on_hover_text(Text::RuleErrorsHint.tr(lang));
+
+            if let Some(runtime) = &mut self.runtime {
+                if ui.button(Text::ReHeat.tr(lang))
+                    .on_hover_text(Text::ReHeatOverridesHint.tr(lang))
+                    .clicked()
+                {
+                    let max_adjustment = runtime.max_adjustment;
+                    runtime.set_max_adjustment(max_adjustment);
+                }
+            }
+        });
+        ui.label(Text::PlotHint.tr(lang));
+
+        let Some(runtime) = &self.runtime else {
+            return;
+        };
+
+        let log_scale = self.quality_log_scale;
+        let scale = |quality: f64| if log_scale {
+            (1.0 - quality).max(MIN_PLOTTED_ERROR).log10()
+        } else {
+            quality
+        };
+
+        let target = self.stop.target().filter(|_| self.quality_target_line);
+        let points = runtime.quality_log.iter()
+            .map(|[iteration, quality]| [*iteration, scale(*quality)])
+            .collect::<Vec<_>>();
+
+        let plots = 2 + usize::from(self.magnitude_plot) + usize::from(self.rule_error_plot);
+        let height = ui.available_height() / plots as f32;
+        let mut plot = Plot::new("metrics-quality")
+            .height(height)
+            .link_axis("metrics", true, false)
+            .allow_drag(true)
+            .allow_zoom(true)
+            .allow_scroll(true)
+            .x_axis_label(Text::Iteration.tr(lang))
+            .y_axis_label(if log_scale { Text::LogError } else { Text::Quality }.tr(lang));
+
+        if !log_scale {
+            plot = plot.include_y(0.0).include_y(1.0);
+        }
+
+        plot.show(ui, |plot| {
+            plot.line(Line::new(PlotPoints::from(points)).name(Text::Quality.tr(lang)));
+
+            if let Some(target) = target {
+                plot.hline(HLine::new(scale(target)).name(Text::Target.tr(lang)).color(Color32::RED));
+            }
+        });
+
+        if self.magnitude_plot {
+            // Magnitudes only change at logged points, so they're drawn as steps up to the current iteration.
+            let current = runtime.progress.iteration as f64;
+            let count = runtime.magnitudes.len().min(MAX_PLOTTED_MAGNITUDES);
+            let series = |i: usize| {
+                let mut points: Vec<[f64; 2]> = Vec::new();
+
+                for (iteration, magnitudes) in &runtime.magnitude_log {
+                    let Some(magnitude) = magnitudes.get(i) else {
+                        continue;
+                    };
+
+                    if let Some(&[_, previous]) = points.last() {
+                        points.push([*iteration as f64, previous]);
+                    }
+
+                    points.push([*iteration as f64, *magnitude]);
+                }
+
+                if let Some(&[_, last]) = points.last() {
+                    points.push([current, last]);
+                }
+
+                points
+            };
+
+            Plot::new("metrics-magnitudes")
+                .height(height)
+                .link_axis("metrics", true, false)
+                .include_y(0.0)
+                .x_axis_label(Text::Iteration.tr(lang))
+                .y_axis_label(Text::Magnitude.tr(lang))
+                .show(ui, |plot| {
+                    for i in 0..count {
+                        plot.line(Line::new(PlotPoints::from(series(i))).name(format!("#{i}")));
+                    }
+                });
+        }
+
+        if self.rule_error_plot {
+            let series = rules::stacked_errors(&runtime.rule_error_log);
+
+            match runtime.rules.as_deref() {
+                Some(names) => {
+                    Plot::new("metrics-rule-errors")
+                        .height(height)
+                        .link_axis("metrics", true, false)
+                        .include_y(0.0)
+                        .legend(Legend::default())
+                        .x_axis_label(Text::Iteration.tr(lang))
+                        .y_axis_label(Text::RuleErrors.tr(lang))
+                        .show(ui, |plot| {
+                            // Lower layers are drawn over the ones stacked on them, so that each band shows.
+                            for (i, points) in series.into_iter().enumerate().rev() {
+                                let name = names.get(i).map_or_else(|| format!("#{i}"), |rule| rule.name.clone());
+                                plot.line(Line::new(PlotPoints::from(points)).name(name).fill(0.0));
+                            }
+                        });
+                }
+                None => {
+                    ui.label(Text::NotSupportedByEngine.tr(lang));
+                }
+            }
+        }
+
+        // Near zero suggests the max adjustment is too large, near one that it's too small.
+        Plot::new("metrics-acceptance")
+            .link_axis("metrics", true, false)
+            .include_y(0.0)
+            .include_y(1.0)
+            .x_axis_label(Text::Iteration.tr(lang))
+            .y_axis_label(Text::AcceptanceRate.tr(lang))
+            .show(ui, |plot| {
+                let acceptance = Line::new(PlotPoints::from(runtime.acceptance_log.clone()));
+                plot.line(acceptance.name(Text::AcceptanceRate.tr(lang)));
+            });
+    }
+
+    pub(super) fn dependencies_ui(&mut self, ui: &mut Ui) {
+        let lang = self.language;
+
+        let Some(runtime) = &self.runtime else {
+            ui.label(Text::NothingGenerated.tr(lang));
+            return;
+        };
+
+        let entities = runtime.entities.as_deref();
+        let rules = runtime.rules.as_deref();
+
+        match dependencies::ui(ui, entities, rules, &self.selection.items, self.rules.picked, lang) {
+            Some(Pick::Item(item)) if self.selection.pickable(item) => self.selection.items = BTreeSet::from([item]),
+            Some(Pick::Rule(rule)) => self.rules.picked = Some(rule),
+            Some(Pick::Item(_)) | None => ()
+        }
+    }
+
+    pub(super) fn script_ui(&mut self, ui: &mut Ui) {
+        let lang = self.language;
+
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let save = egui::Button::new(Text::Save.tr(lang));
+                if ui.add_enabled(self.file.is_some() && self.script_dirty, save).clicked() {
+                    self.save_script();
+                }
+
+                if ui.button(Text::SaveAs.tr(lang)).clicked() {
+                    self.save_script_as();
+                }
+            }
+
+            if self.runtime.is_some() {
+                ui.label(Text::ChangesApplyNext.tr(lang));
+            }
+        });
+
+        let mut jump = None;
+
+        if !self.script_errors.is_empty() {
+            egui::Frame::group(ui.style())
+                .stroke(egui::Stroke::new(1.0, Color32::RED))
+                .show(ui, |ui| {
+                    ui.label(RichText::new(Text::ScriptFailedToCompile.tr(lang)).color(Color32::RED).strong());
+
+                    egui::ScrollArea::vertical()
+                        .id_source("script-error")
+                        .max_height(150.0)
+                        .show(ui, |ui| {
+                            for err in &self.script_errors {
+                                let text = RichText::new(err.to_string()).color(Color32::RED).monospace();
+                                let index = err.char_index(&self.script);
+                                let response = if index.is_some() {
+                                    ui.link(text).on_hover_text(Text::GoToError.tr(lang))
+                                } else {
+                                    ui.label(text)
+                                };
+
+                                if response.clicked() {
+                                    jump = index;
+                                }
+                            }
+                        });
+                });
+        }
+
+        let id = egui::Id::new("script-editor");
+
+        if let Some(index) = jump {
+            let mut state = egui::TextEdit::load_state(ui.ctx(), id).unwrap_or_default();
+            let cursor = egui::text::CCursor::new(index);
+            state.cursor.set_char_range(Some(egui::text::CCursorRange::one(cursor)));
+            egui::TextEdit::store_state(ui.ctx(), id, state);
+            ui.memory_mut(|memory| memory.request_focus(id));
+        }
+
+        let error_lines = self.script_errors.iter()
+            .filter_map(|err| err.position.map(|(line, _)| line))
+            .collect::<BTreeSet<_>>();
+
+        let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+            let font = egui::TextStyle::Monospace.resolve(ui.style());
+            let mut job = LayoutJob::default();
+
+            for (i, line) in text.split_inclusive('\n').enumerate() {
+                let mut format = TextFormat::simple(font.clone(), ui.visuals().text_color());
+
+                if error_lines.contains(&(i + 1)) {
+                    format.background = ui.visuals().error_fg_color.gamma_multiply(0.2);
+                }
+
+                job.append(line, 0.0, format);
+            }
+
+            job.wrap.max_width = wrap_width;
+            ui.fonts(|fonts| fonts.layout_job(job))
+        };
+
+        let editor = egui::TextEdit::multiline(&mut self.script)
+            .id(id)
+            .code_editor()
+            .desired_width(f32::INFINITY)
+            .layouter(&mut layouter);
+
+        let response = ui.add_sized(ui.available_size(), editor);
+
+        if jump.is_some() {
+            response.scroll_to_me(Some(egui::Align::Center));
+        }
+
+        if response.changed() {
+            self.script_dirty = true;
+        }
+    }
+}
+
+/// Settings of how often the worker publishes in Run mode.
+fn batching_ui(ui: &mut Ui, batching: &mut Batching, lang: Language) {
+    ui.horizontal(|ui| {
+        ui.label(Text::RunPublishesEvery.tr(lang));
+        ui.add(egui::DragValue::new(&mut batching.cycles).range(1..=100_000));
+        ui.label(Text::CyclesOr.tr(lang));
+        ui.add(egui::DragValue::new(&mut batching.interval_ms).range(1..=10_000).suffix(" ms"));
+    }).response.on_hover_text(Text::BatchingHint.tr(lang));
+}
+
+/// Setting of how long Run may generate.
+fn time_budget_ui(ui: &mut Ui, budget: &mut Option<f64>, lang: Language) {
+    ui.horizontal(|ui| {
+        let mut enabled = budget.is_some();
+        let mut seconds = budget.unwrap_or(DEFAULT_TIME_BUDGET);
+        ui.checkbox(&mut enabled, Text::StopRunAfter.tr(lang));
+        ui.add_enabled(enabled, egui::DragValue::new(&mut seconds).range(1.0..=86_400.0).suffix(" s"));
+        *budget = enabled.then_some(seconds);
+    }).response.on_hover_text(Text::TimeBudgetHint.tr(lang));
+}
+
+/// A setup grid row explaining an error and how to fix it.
+fn error_row(ui: &mut Ui, err: &DebuggerError, lang: Language) {
+    ui.label(RichText::new(err.message(lang)).color(Color32::RED));
+    ui.label(err.hint().tr(lang));
+    ui.end_row();
+}
+
+/// Outline a field in red if its contents are invalid.
+fn outline_invalid(ui: &Ui, rect: egui::Rect, invalid: bool) {
+    if invalid {
+        ui.painter().rect_stroke(rect.expand(1.0), 2.0, egui::Stroke::new(1.5, Color32::RED));
+    }
+}
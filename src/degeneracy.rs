@@ -0,0 +1,210 @@
+use std::collections::BTreeSet;
+use egui::{Color32, Painter, Stroke, Ui, Vec2};
+use geo_aid_internal::projector::figure::{Item, Position};
+use crate::i18n::{Language, Text};
+use crate::render;
+use crate::selection;
+
+const HIGHLIGHT_COLOR: Color32 = Color32::from_rgb(255, 140, 0);
+
+/// A near-degenerate part of the figure. Indices are of items in the figure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Degeneracy {
+    CoincidentPoints(usize, usize),
+    ZeroRadius(usize),
+    ZeroLength(usize),
+    /// Three segments forming a triangle with (nearly) collinear vertices.
+    CollinearTriangle([usize; 3])
+}
+
+impl Degeneracy {
+    #[must_use]
+    pub fn items(&self) -> Vec<usize> {
+        match self {
+            Self::CoincidentPoints(a, b) => vec![*a, *b],
+            Self::ZeroRadius(i)
+            | Self::ZeroLength(i) => vec![*i],
+            Self::CollinearTriangle(sides) => sides.to_vec()
+        }
+    }
+
+    /// Describes the degeneracy with the descriptions of the `items` involved.
+    #[must_use]
+    pub fn describe(&self, items: &[Item], lang: Language) -> String {
+        let describe = |i: &usize| items.get(*i)
+            .map_or_else(|| String::from("?"), |item| render::describe(item, lang));
+
+        match self {
+            Self::CoincidentPoints(a, b) => Text::Coincide.fill(lang, &[&describe(a), &describe(b)]),
+            Self::ZeroRadius(i) => Text::ZeroRadius.fill(lang, &[&describe(i)]),
+            Self::ZeroLength(i) => Text::ZeroLength.fill(lang, &[&describe(i)]),
+            Self::CollinearTriangle(sides) => Text::FlatTriangle.fill(
+                lang,
+                &[&describe(&sides[0]), &describe(&sides[1]), &describe(&sides[2])]
+            )
+        }
+    }
+}
+
+fn distance(a: &Position, b: &Position) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// The endpoint of `a` that `b` also ends at, if any.
+fn shared_end<'a>(a: &'a (Position, Position), b: &(Position, Position), tolerance: f64) -> Option<&'a Position> {
+    [&a.0, &a.1].into_iter().find(|p| distance(p, &b.0) < tolerance || distance(p, &b.1) < tolerance)
+}
+
+/// Find near-degenerate configurations. Lengths under `tolerance` canvas units count as zero.
+#[must_use]
+pub fn detect(items: &[Item], tolerance: f64) -> Vec<Degeneracy> {
+    let mut found = Vec::new();
+
+    let points = items.iter()
+        .enumerate()
+        .filter_map(|(i, item)| match item {
+            Item::Point(pt) => Some((i, &pt.position)),
+            _ => None
+        })
+        .collect::<Vec<_>>();
+
+    for (n, (a, pa)) in points.iter().enumerate() {
+        for (b, pb) in &points[n + 1..] {
+            if distance(pa, pb) < tolerance {
+                found.push(Degeneracy::CoincidentPoints(*a, *b));
+            }
+        }
+    }
+
+    let mut segments = Vec::new();
+
+    for (i, item) in items.iter().enumerate() {
+        match item {
+            Item::Circle(circle) if circle.radius.abs() < tolerance => found.push(Degeneracy::ZeroRadius(i)),
+            Item::Segment(x) => {
+                if distance(&x.points.0, &x.points.1) < tolerance {
+                    found.push(Degeneracy::ZeroLength(i));
+                } else {
+                    segments.push((i, &x.points));
+                }
+            }
+            _ => ()
+        }
+    }
+
+    // Segments sharing endpoints pairwise form a triangle. It's flat if its height is under the tolerance.
+    for (n, (i, a)) in segments.iter().enumerate() {
+        for (m, (j, b)) in segments.iter().enumerate().skip(n + 1) {
+            let Some(ab) = shared_end(a, b, tolerance) else {
+                continue;
+            };
+
+            for (k, c) in segments.iter().skip(m + 1) {
+                let (Some(bc), Some(ca)) = (shared_end(b, c, tolerance), shared_end(c, a, tolerance)) else {
+                    continue;
+                };
+
+                let sides = [distance(ab, bc), distance(bc, ca), distance(ca, ab)];
+                let longest = sides.into_iter().fold(0.0, f64::max);
+                let doubled_area = ((bc.x - ab.x) * (ca.y - ab.y) - (bc.y - ab.y) * (ca.x - ab.x)).abs();
+
+                if longest >= tolerance && doubled_area / longest < tolerance {
+                    found.push(Degeneracy::CollinearTriangle([*i, *j, *k]));
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// The degeneracy detector, run on every new frame.
+pub struct Degeneracies {
+    pub enabled: bool,
+    pub tolerance: f64,
+    pub found: Vec<Degeneracy>,
+    /// Revision of the last checked frame.
+    last_revision: Option<usize>,
+    /// The degeneracy hovered in the list, highlighted alone.
+    hovered: Option<usize>
+}
+
+impl Default for Degeneracies {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tolerance: 0.5,
+            found: Vec::new(),
+            last_revision: None,
+            hovered: None
+        }
+    }
+}
+
+impl Degeneracies {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.found.clear();
+        self.last_revision = None;
+        self.hovered = None;
+    }
+
+    /// Check a newly projected frame.
+    pub fn update(&mut self, revision: usize, items: &[Item]) {
+        if !self.enabled {
+            self.found.clear();
+            return;
+        }
+
+        if self.last_revision != Some(revision) {
+            self.found = detect(items, self.tolerance);
+            self.last_revision = Some(revision);
+        }
+    }
+
+    /// Highlight the items involved. `origin` is the top-left corner of the canvas.
+    pub fn paint(&self, painter: &Painter, origin: Vec2, items: &[Item]) {
+        let involved: BTreeSet<usize> = match self.hovered.and_then(|i| self.found.get(i)) {
+            Some(degeneracy) => degeneracy.items().into_iter().collect(),
+            None => self.found.iter().flat_map(Degeneracy::items).collect()
+        };
+
+        let stroke = Stroke::new(3.0, HIGHLIGHT_COLOR.gamma_multiply(0.7));
+
+        for item in involved.iter().filter_map(|i| items.get(*i)) {
+            selection::highlight(painter, origin, item, stroke);
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, items: &[Item], lang: Language) {
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.enabled, Text::Detect.tr(lang)).changed() {
+                self.last_revision = None;
+            }
+
+            ui.label(Text::Tolerance.tr(lang));
+            if ui.add(egui::DragValue::new(&mut self.tolerance).speed(0.05).range(0.0..=100.0))
+                .on_hover_text(Text::ToleranceHint.tr(lang))
+                .changed()
+            {
+                self.last_revision = None;
+            }
+        });
+
+        self.hovered = None;
+
+        if self.found.is_empty() {
+            ui.label(Text::NothingDegenerate.tr(lang));
+        }
+
+        for (i, degeneracy) in self.found.iter().enumerate() {
+            if ui.colored_label(HIGHLIGHT_COLOR, degeneracy.describe(items, lang)).hovered() {
+                self.hovered = Some(i);
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! The graph of what a script's entities are derived from and which rules bind them.
+
+use std::collections::BTreeSet;
+use egui::{Color32, FontId, Rect, Sense, Stroke, Ui, Vec2};
+use crate::engine::{Entity, Rule};
+use crate::i18n::{Language, Text};
+
+/// Height of a node, space around its name and space between layers and rows of nodes.
+const NODE_HEIGHT: f32 = 22.0;
+const PADDING: f32 = 8.0;
+const LAYER_GAP: f32 = 48.0;
+const ROW_GAP: f32 = 10.0;
+const FONT_SIZE: f32 = 12.0;
+const ENTITY_FILL: Color32 = Color32::from_rgb(220, 232, 250);
+const RULE_FILL: Color32 = Color32::from_rgb(250, 225, 240);
+const EDGE_COLOR: Color32 = Color32::from_gray(150);
+/// Edges of the hovered node.
+const LIT_EDGE_COLOR: Color32 = Color32::from_rgb(230, 120, 0);
+/// Outline of nodes of selected items and of the picked rule.
+const OUTLINE_COLOR: Color32 = Color32::from_rgb(30, 120, 255);
+
+/// A node clicked in the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+    /// An entity, by the index of the item showing it.
+    Item(usize),
+    Rule(usize)
+}
+
+struct Node<'a> {
+    name: &'a str,
+    layer: usize,
+    fill: Color32,
+    outlined: bool
+}
+
+/// The layer of each entity: zero for those derived from nothing, otherwise one past the deepest parent.
+/// Parents that don't exist are ignored. Scripts can't define cycles, if an engine reports one, it only stretches
+/// the graph.
+#[must_use]
+pub fn depths(entities: &[Entity]) -> Vec<usize> {
+    let mut depths = vec![0; entities.len()];
+
+    // A path can't have more edges than there are entities, so further rounds would only go around cycles.
+    for _ in 0..entities.len() {
+        let mut changed = false;
+
+        for (i, entity) in entities.iter().enumerate() {
+            let depth = entity.parents.iter()
+                .filter_map(|parent| depths.get(*parent))
+                .map(|depth| depth + 1)
+                .max()
+                .unwrap_or(0);
+
+            if depth > depths[i] {
+                depths[i] = depth;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    depths
+}
+
+/// Show `entities` laid out by depth, with the `rules` binding them after them. Entities showing `selected` items
+/// and the `picked` rule are outlined, hovering a node lights up its edges. Returns what was clicked.
+pub fn ui(
+    ui: &mut Ui,
+    entities: Option<&[Entity]>,
+    rules: Option<&[Rule]>,
+    selected: &BTreeSet<usize>,
+    picked: Option<usize>,
+    lang: Language
+) -> Option<Pick> {
+    let Some(entities) = entities else {
+        ui.label(Text::NotSupportedByEngine.tr(lang));
+        return None;
+    };
+
+    ui.label(Text::DependenciesHint.tr(lang));
+
+    let rules = rules.unwrap_or_default();
+    let depths = depths(entities);
+    let rule_layer = depths.iter().max().map_or(0, |depth| depth + 1);
+
+    let mut nodes = entities.iter()
+        .zip(&depths)
+        .map(|(entity, &layer)| Node {
+            name: &entity.name,
+            layer,
+            fill: ENTITY_FILL,
+            outlined: entity.item.is_some_and(|item| selected.contains(&item))
+        })
+        .collect::<Vec<_>>();
+
+    nodes.extend(rules.iter().enumerate().map(|(i, rule)| Node {
+        name: &rule.name,
+        layer: rule_layer,
+        fill: RULE_FILL,
+        outlined: picked == Some(i)
+    }));
+
+    let mut edges = Vec::new();
+
+    for (i, entity) in entities.iter().enumerate() {
+        edges.extend(entity.parents.iter().filter(|parent| **parent < entities.len()).map(|parent| (*parent, i)));
+    }
+
+    for (i, rule) in rules.iter().enumerate() {
+        for (j, entity) in entities.iter().enumerate() {
+            if entity.item.is_some_and(|item| rule.items.contains(&item)) {
+                edges.push((j, entities.len() + i));
+            }
+        }
+    }
+
+    let font = FontId::monospace(FONT_SIZE);
+    let galleys = nodes.iter()
+        .map(|node| ui.painter().layout_no_wrap(node.name.to_owned(), font.clone(), Color32::BLACK))
+        .collect::<Vec<_>>();
+
+    let layers = rule_layer + 1;
+    let mut widths = vec![0.0_f32; layers];
+    let mut rows = vec![0_usize; layers];
+    let mut places = Vec::with_capacity(nodes.len());
+
+    for (node, galley) in nodes.iter().zip(&galleys) {
+        widths[node.layer] = widths[node.layer].max(galley.size().x + 2.0 * PADDING);
+        places.push(rows[node.layer]);
+        rows[node.layer] += 1;
+    }
+
+    let lefts = widths.iter()
+        .scan(0.0, |left, width| {
+            let this = *left;
+            *left += width + LAYER_GAP;
+            Some(this)
+        })
+        .collect::<Vec<_>>();
+
+    let size = Vec2::new(
+        widths.iter().sum::<f32>() + LAYER_GAP * (layers - 1) as f32,
+        rows.iter().max().copied().unwrap_or(0) as f32 * (NODE_HEIGHT + ROW_GAP)
+    );
+    let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+    let painter = ui.painter_at(rect);
+    let bounds = |n: usize| {
+        let layer = nodes[n].layer;
+        let min = rect.min + Vec2::new(lefts[layer], places[n] as f32 * (NODE_HEIGHT + ROW_GAP));
+        Rect::from_min_size(min, Vec2::new(widths[layer], NODE_HEIGHT))
+    };
+
+    let hovered = response.hover_pos().and_then(|pos| (0..nodes.len()).find(|&n| bounds(n).contains(pos)));
+
+    for &(from, to) in &edges {
+        let stroke = if hovered.is_some_and(|n| n == from || n == to) {
+            Stroke::new(2.0, LIT_EDGE_COLOR)
+        } else {
+            Stroke::new(1.0, EDGE_COLOR)
+        };
+
+        painter.line_segment([bounds(from).right_center(), bounds(to).left_center()], stroke);
+    }
+
+    for (n, (node, galley)) in nodes.iter().zip(galleys).enumerate() {
+        let area = bounds(n);
+        let stroke = if node.outlined { Stroke::new(2.0, OUTLINE_COLOR) } else { Stroke::new(1.0, EDGE_COLOR) };
+
+        painter.rect(area, 4.0, node.fill, stroke);
+        painter.galley(area.center() - galley.size() / 2.0, galley, Color32::BLACK);
+    }
+
+    let clicked = hovered.filter(|_| response.clicked())?;
+
+    match clicked.checked_sub(entities.len()) {
+        Some(rule) => Some(Pick::Rule(rule)),
+        None => entities[clicked].item.map(Pick::Item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(parents: &[usize]) -> Entity {
+        Entity {
+            name: String::new(),
+            item: None,
+            parents: parents.to_vec()
+        }
+    }
+
+    #[test]
+    fn entities_come_after_their_parents() {
+        let entities = [entity(&[]), entity(&[]), entity(&[0, 1]), entity(&[2, 0]), entity(&[9])];
+
+        assert_eq!(depths(&entities), [0, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn cycles_end() {
+        let entities = [entity(&[1]), entity(&[0])];
+
+        assert_eq!(depths(&entities).len(), 2);
+    }
+}
@@ -0,0 +1,96 @@
+//! File dialogs.
+//!
+//! Natively, this is rfd's blocking [`FileDialog`]. Browsers have no paths and no blocking dialogs, so on the web
+//! [`FileDialog`] is a stand-in that never picks anything, and scripts are opened with `ScriptPicker` instead.
+//! Whatever else needs a path (sessions, checkpoints, traces, goldens, exports and fonts) is left out of the web UI.
+//! rfd's web backend goes through `wasm-bindgen`, so the page has to load the debugger through `wasm-bindgen`'s
+//! glue rather than macroquad's plain loader.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use rfd::FileDialog;
+
+#[cfg(target_arch = "wasm32")]
+pub use web::{FileDialog, ScriptPicker};
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::future::Future;
+    use std::path::{Path, PathBuf};
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    /// A file name and the file's contents.
+    type Picked = Option<(String, Vec<u8>)>;
+
+    /// The native dialog's builder, picking nothing.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct FileDialog;
+
+    impl FileDialog {
+        #[must_use]
+        pub fn new() -> Self {
+            Self
+        }
+
+        #[must_use]
+        pub fn set_title(self, _title: impl Into<String>) -> Self {
+            self
+        }
+
+        #[must_use]
+        pub fn add_filter(self, _name: impl Into<String>, _extensions: &[impl ToString]) -> Self {
+            self
+        }
+
+        #[must_use]
+        pub fn set_directory<P: AsRef<Path>>(self, _path: P) -> Self {
+            self
+        }
+
+        #[must_use]
+        pub fn pick_file(self) -> Option<PathBuf> {
+            None
+        }
+
+        #[must_use]
+        pub fn pick_folder(self) -> Option<PathBuf> {
+            None
+        }
+
+        #[must_use]
+        pub fn save_file(self) -> Option<PathBuf> {
+            None
+        }
+    }
+
+    /// The browser's file picker, read once the user picks a file. Polled every frame.
+    #[derive(Default)]
+    pub struct ScriptPicker(Option<Pin<Box<dyn Future<Output = Picked>>>>);
+
+    impl ScriptPicker {
+        /// Show the picker, replacing one still open.
+        pub fn open(&mut self, title: &str) {
+            let dialog = rfd::AsyncFileDialog::new()
+                .set_title(title)
+                .add_filter("GeoScript", &["geo"]);
+
+            self.0 = Some(Box::pin(async move {
+                let file = dialog.pick_file().await?;
+                Some((file.file_name(), file.read().await))
+            }));
+        }
+
+        /// The picked file's name and contents, once they've been read.
+        pub fn poll(&mut self) -> Picked {
+            let future = self.0.as_mut()?;
+
+            match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+                Poll::Ready(picked) => {
+                    self.0 = None;
+                    picked
+                }
+                Poll::Pending => None
+            }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use egui::{Color32, Context, RichText, Sense, Stroke, Vec2};
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::projector;
+use geo_aid_internal::projector::figure::{Item, Position};
+use geo_aid_internal::script::figure::Generated;
+use geo_aid_internal::script::math::{Flags, Intermediate};
+use crate::i18n::{Language, Text};
+use crate::selection;
+
+const OLD_COLOR: Color32 = Color32::from_gray(150);
+const NEW_COLOR: Color32 = Color32::from_rgb(30, 110, 230);
+const CORRESPONDENCE_COLOR: Color32 = Color32::from_rgb(220, 60, 60);
+/// Height of the figure area in the window.
+const FIGURE_HEIGHT: f32 = 400.0;
+
+/// A script generated to convergence.
+pub struct Version {
+    pub generated: Generated,
+    pub flags: Flags,
+    pub quality: f64
+}
+
+fn converge(intermediate: &Intermediate, worker_count: usize, max_adjustment: f64, iterations: usize) -> Version {
+    let mut rage = Rage::new(worker_count, intermediate);
+    let magnitudes = rage.gen().bake_magnitudes(max_adjustment);
+
+    for _ in 0..iterations {
+        rage.gen_mut().cycle_prebaked(&magnitudes);
+    }
+
+    Version {
+        generated: rage.get_figure(intermediate.figure.clone()),
+        flags: intermediate.flags.clone(),
+        quality: rage.gen().get_total_quality()
+    }
+}
+
+/// Two versions of a script generated on a background thread.
+pub struct Diff {
+    result: mpsc::Receiver<(Version, Version)>,
+    pub versions: Option<(Version, Version)>,
+    pub finished: bool
+}
+
+impl Diff {
+    #[must_use]
+    pub fn spawn(
+        old: Intermediate,
+        new: Intermediate,
+        worker_count: usize,
+        max_adjustment: f64,
+        iterations: usize
+    ) -> Self {
+        let (send, result) = mpsc::channel();
+
+        thread::spawn(move || {
+            let old = converge(&old, worker_count, max_adjustment, iterations);
+            let new = converge(&new, worker_count, max_adjustment, iterations);
+            let _ = send.send((old, new));
+        });
+
+        Self {
+            result,
+            versions: None,
+            finished: false
+        }
+    }
+
+    pub fn poll(&mut self) {
+        match self.result.try_recv() {
+            Ok(versions) => {
+                self.versions = Some(versions);
+                self.finished = true;
+            }
+            Err(TryRecvError::Empty) => (),
+            Err(TryRecvError::Disconnected) => self.finished = true
+        }
+    }
+}
+
+/// Labelled points of a figure by label.
+fn named_points(items: &[Item]) -> HashMap<String, &Position> {
+    items.iter()
+        .filter_map(|item| match item {
+            Item::Point(pt) => pt.label.as_ref().map(|label| (label.content.to_string(), &pt.position)),
+            _ => None
+        })
+        .collect()
+}
+
+pub enum Action {
+    /// Pick the other version of the script.
+    Pick,
+    Start {
+        other: PathBuf,
+        iterations: usize
+    }
+}
+
+/// The "Script diff" window, comparing the figure of the edited script with another version of it.
+pub struct DiffTool {
+    pub open: bool,
+    /// The other version of the script.
+    pub other: Option<PathBuf>,
+    iterations: String,
+    iterations_valid: bool,
+    /// Why the last diff couldn't start.
+    pub error: Option<String>,
+    diff: Option<Diff>,
+    /// Both figures projected for the last figure area size.
+    projected: Option<((usize, usize), Vec<Item>, Vec<Item>)>
+}
+
+impl Default for DiffTool {
+    fn default() -> Self {
+        Self {
+            open: false,
+            other: None,
+            iterations: String::from("5000"),
+            iterations_valid: true,
+            error: None,
+            diff: None,
+            projected: None
+        }
+    }
+}
+
+impl DiffTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, diff: Diff) {
+        self.error = None;
+        self.projected = None;
+        self.diff = Some(diff);
+    }
+
+    #[must_use]
+    pub fn show(&mut self, ctx: &Context, lang: Language) -> Option<Action> {
+        let mut action = None;
+
+        if let Some(diff) = &mut self.diff {
+            diff.poll();
+        }
+
+        egui::Window::new(Text::ScriptDiff.tr(lang))
+            .open(&mut self.open)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                let running = self.diff.as_ref().is_some_and(|d| !d.finished);
+
+                egui::Grid::new("diff-params")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(Text::OldVersion.tr(lang));
+                        ui.horizontal(|ui| {
+                            if let Some(other) = &self.other {
+                                ui.label(other.to_string_lossy());
+                            }
+
+                            if ui.button(Text::Pick.tr(lang)).clicked() {
+                                action = Some(Action::Pick);
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label(Text::NewVersion.tr(lang));
+                        ui.label(Text::ScriptInEditor.tr(lang));
+                        ui.end_row();
+
+                        ui.label(Text::IterationsField.tr(lang));
+                        ui.text_edit_singleline(&mut self.iterations);
+                        ui.end_row();
+
+                        if !self.iterations_valid {
+                            ui.label(RichText::new(Text::InvalidIterationCount.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBePositiveInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label("");
+                        let compare = egui::Button::new(Text::Compare.tr(lang));
+                        if ui.add_enabled(!running && self.other.is_some(), compare).clicked() {
+                            let iterations = usize::from_str(&self.iterations).ok().filter(|i| *i > 0);
+                            self.iterations_valid = iterations.is_some();
+
+                            if let (Some(other), Some(iterations)) = (&self.other, iterations) {
+                                action = Some(Action::Start {
+                                    other: other.clone(),
+                                    iterations
+                                });
+                            }
+                        }
+                        ui.end_row();
+                    });
+
+                if let Some(err) = &self.error {
+                    ui.label(RichText::new(err).color(Color32::RED));
+                }
+
+                let Some(diff) = &self.diff else {
+                    return;
+                };
+
+                if running {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(Text::GeneratingBoth.tr(lang));
+                    });
+                    return;
+                }
+
+                let Some((old, new)) = &diff.versions else {
+                    ui.label(RichText::new(Text::GenerationFailed.tr(lang)).color(Color32::RED));
+                    return;
+                };
+
+                ui.separator();
+                let (old_quality, new_quality) = (format!("{:.4}", old.quality), format!("{:.4}", new.quality));
+                ui.label(Text::QualityOldNew.fill(lang, &[&old_quality, &new_quality]));
+                ui.horizontal(|ui| {
+                    ui.colored_label(OLD_COLOR, Text::OldKey.tr(lang));
+                    ui.colored_label(NEW_COLOR, Text::NewKey.tr(lang));
+                    ui.colored_label(CORRESPONDENCE_COLOR, Text::SameNamedKey.tr(lang));
+                });
+
+                let (response, painter) = ui.allocate_painter(
+                    Vec2::new(ui.available_width(), FIGURE_HEIGHT),
+                    Sense::hover()
+                );
+                let rect = response.rect;
+                let size = (rect.width() as usize, rect.height() as usize);
+
+                // Both figures are projected onto the same area, each fitted on its own.
+                if self.projected.as_ref().map_or(true, |(projected_size, ..)| *projected_size != size) {
+                    self.projected = Some((
+                        size,
+                        projector::project(old.generated.clone(), &old.flags, size).items,
+                        projector::project(new.generated.clone(), &new.flags, size).items
+                    ));
+                }
+
+                let Some((_, old_items, new_items)) = &self.projected else {
+                    return;
+                };
+
+                let origin = rect.min.to_vec2();
+
+                for item in old_items {
+                    selection::highlight(&painter, origin, item, Stroke::new(1.5, OLD_COLOR));
+                }
+
+                for item in new_items {
+                    selection::highlight(&painter, origin, item, Stroke::new(1.0, NEW_COLOR));
+                }
+
+                let old_points = named_points(old_items);
+                let new_points = named_points(new_items);
+                let mut moved = Vec::new();
+
+                for (name, a) in &old_points {
+                    let Some(b) = new_points.get(name) else {
+                        continue;
+                    };
+
+                    let at = |p: &Position| rect.min + Vec2::new(p.x as f32, p.y as f32);
+                    painter.line_segment([at(a), at(b)], Stroke::new(1.0, CORRESPONDENCE_COLOR));
+                    moved.push((name, (a.x - b.x).hypot(a.y - b.y)));
+                }
+
+                // Largest moves first.
+                moved.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+                egui::Grid::new("diff-points")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (name, distance) in moved {
+                            ui.label(format!("{} {name}", Text::ItemPoint.tr(lang)));
+                            ui.label(Text::Moved.fill(lang, &[&format!("{distance:.2}")]));
+                            ui.end_row();
+                        }
+                    });
+
+                let mut only_old = old_points.keys().filter(|name| !new_points.contains_key(*name)).collect::<Vec<_>>();
+                let mut only_new = new_points.keys().filter(|name| !old_points.contains_key(*name)).collect::<Vec<_>>();
+                only_old.sort();
+                only_new.sort();
+
+                for (which, names) in [(Text::OnlyInOld, only_old), (Text::OnlyInNew, only_new)] {
+                    if !names.is_empty() {
+                        let names = names.into_iter().cloned().collect::<Vec<_>>().join(", ");
+                        ui.label(which.fill(lang, &[&names]));
+                    }
+                }
+            });
+
+        action
+    }
+}
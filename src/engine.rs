@@ -0,0 +1,237 @@
+//! What the debugger needs from a generation engine.
+//!
+//! The runtime drives engines through [`DebuggableEngine`], so that other engines than Rage can be debugged
+//! the same way, local or behind a server.
+//!
+//! Beyond stepping, engines may let the debugger look inside them, e.g. at the error of each rule. Those methods
+//! return `None` by default, and the UI says the engine doesn't support them. Rage keeps its rules to itself,
+//! so only [`MockEngine`] implements them for now.
+
+use std::time::Duration;
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::script::figure::{Figure, Generated};
+use geo_aid_internal::script::math::Intermediate;
+use serde::{Deserialize, Serialize};
+use crate::mock::MockEngine;
+
+/// Engines available for local generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EngineKind {
+    #[default]
+    Rage,
+    /// [`crate::mock::MockEngine`], for working on the UI.
+    Mock
+}
+
+impl EngineKind {
+    pub const ALL: [Self; 2] = [Self::Rage, Self::Mock];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rage => "Rage",
+            Self::Mock => "Mock"
+        }
+    }
+
+    /// Whether runs of the engine can be reproduced from a seed.
+    #[must_use]
+    pub fn seeded(self) -> bool {
+        match self {
+            Self::Rage => Rage::SEEDED,
+            Self::Mock => MockEngine::SEEDED
+        }
+    }
+}
+
+/// A rule of a compiled script, as far as the debugger needs to know it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// How the rule reads in the script.
+    pub name: String,
+    /// Indices of the projected items the rule refers to.
+    pub items: Vec<usize>,
+    /// Whether the rule counts toward the quality.
+    pub enabled: bool,
+    /// What the rule's error is multiplied by, `None` if the engine doesn't weight rules.
+    pub weight: Option<f64>
+}
+
+/// Something a compiled script defines, e.g. a point or a distance, and what it's derived from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entity {
+    /// How the entity reads in the script.
+    pub name: String,
+    /// Index of the projected item showing the entity, if it's drawn.
+    pub item: Option<usize>,
+    /// Indices of the entities it's derived from.
+    pub parents: Vec<usize>
+}
+
+/// What a worker of the engine has been doing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkerStats {
+    /// Quality of the worker's latest proposal.
+    pub quality: f64,
+    /// Number of the worker's proposals taken.
+    pub accepted: usize,
+    /// Number of the worker's proposals dropped for a better one or the state before the cycle.
+    pub rejected: usize
+}
+
+/// What a worker proposed in a cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Proposal {
+    /// Index of the worker.
+    pub worker: usize,
+    /// Change to each adjustable.
+    pub adjustment: Vec<f64>,
+    /// Quality of the state with the adjustment made.
+    pub quality: f64,
+    /// Whether the cycle took the proposal.
+    pub accepted: bool
+}
+
+/// How often an expression of a compiled script was evaluated, and for how long.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpressionStats {
+    /// How the expression reads in the script.
+    pub name: String,
+    /// Index of the rule the expression is part of, if any.
+    pub rule: Option<usize>,
+    pub evaluations: u64,
+    pub time: Duration
+}
+
+/// A generation engine the debugger can step through.
+pub trait DebuggableEngine: Send + 'static {
+    /// What figures are generated from, along with the engine's state.
+    type Figure: Clone + Send + 'static;
+
+    /// Create an engine for a compiled script.
+    fn compile(worker_count: usize, intermediate: &Intermediate) -> Self
+    where
+        Self: Sized;
+
+    /// Number of adjustables of a compiled script.
+    ///
+    /// By default, a single-worker engine is compiled to count them, as Rage doesn't expose the count otherwise.
+    fn adjustables(intermediate: &Intermediate) -> usize
+    where
+        Self: Sized
+    {
+        Self::compile(1, intermediate).state().len()
+    }
+
+    /// What figures are generated from, taken from a compiled script.
+    fn figure_of(intermediate: &Intermediate) -> Self::Figure
+    where
+        Self: Sized;
+
+    /// Whether [`Self::reseed`] makes runs reproducible.
+    const SEEDED: bool = false;
+
+    /// Start over from the state a `seed` leads to. Engines without a seed keep their own random state.
+    fn reseed(&mut self, _seed: u64) {}
+
+    /// Adjustment magnitudes of all adjustables for a max adjustment.
+    fn bake(&self, max_adjustment: f64) -> Vec<f64>;
+
+    /// Perform a cycle with previously baked magnitudes.
+    fn cycle(&mut self, magnitudes: &[f64]);
+
+    /// Perform a cycle like [`Self::cycle`] and return what each worker proposed in it,
+    /// `None` if the engine doesn't keep the proposals.
+    fn cycle_recorded(&mut self, magnitudes: &[f64]) -> Option<Vec<Proposal>> {
+        self.cycle(magnitudes);
+        None
+    }
+
+    /// Values of all adjustables.
+    fn state(&self) -> &[f64];
+
+    /// Replace the values of all adjustables.
+    fn set_state(&mut self, state: &[f64]);
+
+    /// Total quality of the current state.
+    fn quality(&self) -> f64;
+
+    /// The figure as generated from the current state.
+    fn figure(&self, figure: Self::Figure) -> Generated;
+
+    /// Rules of the compiled script, `None` if the engine doesn't tell them apart.
+    fn rules(&self) -> Option<Vec<Rule>> {
+        None
+    }
+
+    /// Entities of the compiled script, `None` if the engine doesn't keep track of what derives from what.
+    fn entities(&self) -> Option<Vec<Entity>> {
+        None
+    }
+
+    /// Error of each of [`Self::rules`] in the current state, zero for satisfied rules.
+    fn rule_errors(&self) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// What each worker has been doing, `None` if the engine doesn't tell its workers apart.
+    fn workers(&self) -> Option<Vec<WorkerStats>> {
+        None
+    }
+
+    /// Count one of [`Self::rules`] toward the quality or leave it out. Returns whether the engine did it.
+    fn set_rule_enabled(&mut self, _rule: usize, _enabled: bool) -> bool {
+        false
+    }
+
+    /// Change the weight of one of [`Self::rules`]. Returns whether the engine took it.
+    fn set_rule_weight(&mut self, _rule: usize, _weight: f64) -> bool {
+        false
+    }
+
+    /// Start or stop counting evaluations of each expression. Returns whether the engine can.
+    fn set_profiling(&mut self, _profiling: bool) -> bool {
+        false
+    }
+
+    /// Evaluations of each expression since profiling started or since the last call, which starts them over.
+    fn take_profile(&mut self) -> Vec<ExpressionStats> {
+        Vec::new()
+    }
+}
+
+impl DebuggableEngine for Rage {
+    type Figure = Figure;
+
+    fn compile(worker_count: usize, intermediate: &Intermediate) -> Self {
+        Rage::new(worker_count, intermediate)
+    }
+
+    fn figure_of(intermediate: &Intermediate) -> Figure {
+        intermediate.figure.clone()
+    }
+
+    fn bake(&self, max_adjustment: f64) -> Vec<f64> {
+        self.gen().bake_magnitudes(max_adjustment)
+    }
+
+    fn cycle(&mut self, magnitudes: &[f64]) {
+        self.gen_mut().cycle_prebaked(magnitudes);
+    }
+
+    fn state(&self) -> &[f64] {
+        self.gen().get_state()
+    }
+
+    fn set_state(&mut self, state: &[f64]) {
+        self.gen_mut().set_state(state);
+    }
+
+    fn quality(&self) -> f64 {
+        self.gen().get_total_quality()
+    }
+
+    fn figure(&self, figure: Figure) -> Generated {
+        self.get_figure(figure)
+    }
+}
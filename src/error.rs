@@ -0,0 +1,237 @@
+//! Why setting up a run failed, with hints on fixing it.
+
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use geo_aid_internal::script;
+use crate::i18n::{Language, Text};
+
+/// A setup parameter typed in by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Param {
+    WorkerCount,
+    MaxAdjustment
+}
+
+impl Param {
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::WorkerCount => Text::ParamWorkerCount,
+            Self::MaxAdjustment => Text::ParamMaxAdjustment
+        }
+    }
+}
+
+/// A compiler error, located in the script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    pub message: String,
+    /// 1-based (line, column) the error starts at, if the compiler pointed at a place.
+    pub position: Option<(usize, usize)>
+}
+
+impl CompileError {
+    #[must_use]
+    pub fn new(err: script::Error) -> Self {
+        let diagnostic = err.diagnostic();
+        let position = diagnostic.spans.first().map(|span| (span.span.start.line, span.span.start.column));
+
+        Self {
+            message: diagnostic.message,
+            position
+        }
+    }
+
+    /// Character index of the error's position in `script`.
+    #[must_use]
+    pub fn char_index(&self, script: &str) -> Option<usize> {
+        let (line, column) = self.position?;
+        let before = script.split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(|line| line.chars().count())
+            .sum::<usize>();
+
+        Some((before + column.saturating_sub(1)).min(script.chars().count()))
+    }
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "{line}:{column}: {}", self.message),
+            None => write!(f, "{}", self.message)
+        }
+    }
+}
+
+/// Locate all errors of a failed compilation.
+#[must_use]
+pub fn compile_errors(errors: Vec<script::Error>) -> Vec<CompileError> {
+    errors.into_iter().map(CompileError::new).collect()
+}
+
+#[derive(Debug, Clone)]
+pub enum DebuggerError {
+    /// A file couldn't be read or written.
+    Io {
+        path: PathBuf,
+        message: String
+    },
+    /// A parameter isn't a number.
+    Parse {
+        param: Param,
+        value: String
+    },
+    /// The script failed to compile.
+    Compile(Vec<CompileError>),
+    /// The engine couldn't be started, locally or on a server.
+    EngineInit(String),
+    /// A parameter is a number, but not a usable one.
+    InvalidParam {
+        param: Param,
+        reason: Text
+    }
+}
+
+impl DebuggerError {
+    #[must_use]
+    pub fn io(path: &Path, err: &io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            message: err.to_string()
+        }
+    }
+
+    /// What went wrong, in the given language.
+    #[must_use]
+    pub fn message(&self, lang: Language) -> String {
+        match self {
+            Self::Io { path, message } => Text::ErrorIo.fill(lang, &[&path.display(), message]),
+            Self::Parse { param, value } => Text::ErrorParse.fill(lang, &[&param.name().tr(lang), value]),
+            Self::Compile(errors) => {
+                let failed = Text::ScriptFailedToCompile.tr(lang);
+
+                match errors.as_slice() {
+                    [] => failed.to_string(),
+                    [err] => format!("{failed}: {err}"),
+                    [err, rest @ ..] => format!("{failed}: {err} {}", Text::AndMore.fill(lang, &[&rest.len()]))
+                }
+            }
+            Self::EngineInit(message) => Text::ErrorEngineInit.fill(lang, &[message]),
+            Self::InvalidParam { param, reason } => {
+                Text::ErrorInvalidParam.fill(lang, &[&param.name().tr(lang), &reason.tr(lang)])
+            }
+        }
+    }
+
+    /// What the user can do about it.
+    #[must_use]
+    pub fn hint(&self) -> Text {
+        match self {
+            Self::Io { .. } => Text::HintIo,
+            Self::Parse { param: Param::WorkerCount, .. } => Text::HintWholeNumber,
+            Self::Parse { param: Param::MaxAdjustment, .. } => Text::HintNumber,
+            Self::Compile(_) => Text::HintCompile,
+            Self::EngineInit(_) => Text::HintEngineInit,
+            Self::InvalidParam { param: Param::WorkerCount, .. } => Text::HintAtLeastOneWorker,
+            Self::InvalidParam { param: Param::MaxAdjustment, .. } => Text::HintPositive
+        }
+    }
+}
+
+impl Display for DebuggerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message(Language::English))
+    }
+}
+
+/// # Errors
+/// Returns an error if `text` isn't a positive whole number.
+pub fn parse_worker_count(text: &str) -> Result<usize, DebuggerError> {
+    let count = usize::from_str(text.trim()).map_err(|_| DebuggerError::Parse {
+        param: Param::WorkerCount,
+        value: text.to_string()
+    })?;
+
+    if count == 0 {
+        return Err(DebuggerError::InvalidParam {
+            param: Param::WorkerCount,
+            reason: Text::MustBeAtLeastOne
+        });
+    }
+
+    Ok(count)
+}
+
+/// # Errors
+/// Returns an error if `text` isn't a positive, finite number.
+pub fn parse_max_adjustment(text: &str) -> Result<f64, DebuggerError> {
+    let value = f64::from_str(text.trim()).map_err(|_| DebuggerError::Parse {
+        param: Param::MaxAdjustment,
+        value: text.to_string()
+    })?;
+
+    check_max_adjustment(value)
+}
+
+/// # Errors
+/// Returns an error if `value` isn't positive and finite.
+pub fn check_max_adjustment(value: f64) -> Result<f64, DebuggerError> {
+    if !(value.is_finite() && value > 0.0) {
+        return Err(DebuggerError::InvalidParam {
+            param: Param::MaxAdjustment,
+            reason: Text::MustBePositive
+        });
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: usize, column: usize) -> CompileError {
+        CompileError {
+            message: String::from("unexpected token"),
+            position: Some((line, column))
+        }
+    }
+
+    #[test]
+    fn char_index_counts_lines_and_columns() {
+        let script = "let A = Point();\nlet ł = Point();\nAB = 3;";
+
+        assert_eq!(at(1, 1).char_index(script), Some(0));
+        assert_eq!(at(2, 5).char_index(script), Some(21));
+        assert_eq!(at(3, 4).char_index(script), Some(37));
+    }
+
+    #[test]
+    fn char_index_stays_in_the_script() {
+        assert_eq!(at(9, 9).char_index("A = B;"), Some(6));
+        assert_eq!(CompileError { position: None, ..at(1, 1) }.char_index("A = B;"), None);
+    }
+
+    #[test]
+    fn compile_errors_show_their_position() {
+        assert_eq!(at(2, 5).to_string(), "2:5: unexpected token");
+
+        let err = DebuggerError::Compile(vec![at(2, 5), at(3, 1)]);
+        assert_eq!(err.to_string(), "The script failed to compile: 2:5: unexpected token (and 1 more)");
+    }
+
+    #[test]
+    fn parameters_are_validated() {
+        assert_eq!(parse_worker_count(" 512 ").ok(), Some(512));
+        assert!(matches!(parse_worker_count("0"), Err(DebuggerError::InvalidParam { .. })));
+        assert!(matches!(parse_worker_count("many"), Err(DebuggerError::Parse { .. })));
+        assert_eq!(parse_max_adjustment("0.5").ok(), Some(0.5));
+        assert!(matches!(parse_max_adjustment("-1"), Err(DebuggerError::InvalidParam { .. })));
+        assert!(matches!(parse_max_adjustment("inf"), Err(DebuggerError::InvalidParam { .. })));
+        assert_eq!(check_max_adjustment(0.1).ok(), Some(0.1));
+        assert!(matches!(check_max_adjustment(f64::NAN), Err(DebuggerError::InvalidParam { .. })));
+    }
+}
@@ -0,0 +1,670 @@
+//! Exporting the projected figure.
+//!
+//! geo-aid-internal 0.4.2 doesn't expose the CLI's drawers, so SVG, TikZ and JSON are written here from the
+//! projected items and may differ in detail from what the CLI writes. GeoGebra isn't supported: its output is
+//! built from the figure's construction, which only the CLI's drawer knows how to translate.
+
+use std::{fs, io};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use egui::Context;
+use geo_aid_internal::projector::figure::{Item, Label, Position};
+use serde_json::{json, Value};
+use crate::annotation::Annotation;
+use crate::colors::{ItemColors, Rgba};
+use crate::i18n::{Language, Text};
+use crate::legend::Entry;
+use crate::math_label;
+use crate::render;
+use crate::style::{DotStyle, ItemStyle};
+
+/// Color of annotations, as understood by both SVG and TikZ.
+const ANNOTATION_COLOR: &str = "magenta";
+/// Length of the arrowhead sides of annotation arrows.
+const ARROWHEAD_SIZE: f32 = 6.0;
+/// Font size and line height of the legend block.
+const LEGEND_FONT_SIZE: f32 = 14.0;
+/// Distance of the legend block from the top-left corner.
+const LEGEND_MARGIN: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Latex,
+    Json,
+    Png
+}
+
+impl Format {
+    pub const ALL: [Self; 4] = [Self::Svg, Self::Latex, Self::Json, Self::Png];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Svg => "SVG",
+            Self::Latex => "LaTeX (TikZ)",
+            Self::Json => "JSON",
+            Self::Png => "PNG"
+        }
+    }
+
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Latex => "tex",
+            Self::Json => "json",
+            Self::Png => "png"
+        }
+    }
+}
+
+/// Progress of the run a figure was exported from.
+#[derive(Debug, Clone, Copy)]
+pub struct Metrics {
+    pub iteration: usize,
+    pub quality: f64,
+    /// Seconds spent generating.
+    pub elapsed: f64
+}
+
+/// Colors and sizes items are exported with, as set up for the canvas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Appearance {
+    /// Color of each exported item, in the order of the items.
+    pub colors: Vec<Rgba>,
+    pub stroke_width: f32,
+    pub point_radius: f32,
+    pub dot: DotStyle
+}
+
+impl Appearance {
+    /// Items of the given colors drawn with the canvas' settings. Sizes don't grow with the canvas.
+    #[must_use]
+    pub fn new(colors: Vec<Rgba>, item_colors: &ItemColors, style: &ItemStyle) -> Self {
+        let (stroke_scale, dot_scale) = if item_colors.high_contrast {
+            (render::HIGH_CONTRAST_STROKE_SCALE, render::HIGH_CONTRAST_DOT_SCALE)
+        } else {
+            (1.0, 1.0)
+        };
+
+        Self {
+            colors,
+            stroke_width: style.stroke_width * stroke_scale,
+            point_radius: style.point_radius * dot_scale,
+            dot: style.dot
+        }
+    }
+
+    /// The color of the item at `index`. Items without one are black.
+    fn color(&self, index: usize) -> Rgba {
+        self.colors.get(index).copied().unwrap_or([0, 0, 0, 255])
+    }
+}
+
+/// Write projected items in a vector or data format. PNG is captured from the canvas instead.
+/// An empty `legend` is left out. `metrics` are only included in JSON.
+///
+/// # Errors
+/// Returns an error if the file can't be written or `format` is PNG.
+#[allow(clippy::too_many_arguments)]
+pub fn write(
+    format: Format,
+    items: &[Item],
+    appearance: &Appearance,
+    annotations: &[Annotation],
+    legend: &[Entry],
+    metrics: Option<&Metrics>,
+    size: (f32, f32),
+    path: &Path
+) -> io::Result<()> {
+    let content = match format {
+        Format::Svg => svg(items, appearance, annotations, legend, size),
+        Format::Latex => latex(items, appearance, annotations, legend, size),
+        Format::Json => json(items, appearance, annotations, legend, metrics, size).to_string(),
+        Format::Png => return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "PNG exports are captured from the canvas"
+        ))
+    };
+
+    fs::write(path, content)
+}
+
+/// Directory screenshots taken with the hotkey go to.
+pub const SCREENSHOT_DIR: &str = "shots";
+
+/// The first unused `shots/figure-NNNN.png`, creating the directory if needed.
+///
+/// # Errors
+/// Returns an error if the directory can't be created or read.
+pub fn next_screenshot_path() -> io::Result<PathBuf> {
+    fs::create_dir_all(SCREENSHOT_DIR)?;
+
+    let mut last = 0;
+
+    for entry in fs::read_dir(SCREENSHOT_DIR)? {
+        let name = entry?.file_name();
+        let number = name.to_str()
+            .and_then(|name| name.strip_prefix("figure-"))
+            .and_then(|name| name.strip_suffix(".png"))
+            .and_then(|number| number.parse::<u32>().ok());
+
+        if let Some(number) = number {
+            last = last.max(number);
+        }
+    }
+
+    Ok(Path::new(SCREENSHOT_DIR).join(format!("figure-{:04}.png", last + 1)))
+}
+
+fn svg(
+    items: &[Item],
+    appearance: &Appearance,
+    annotations: &[Annotation],
+    legend: &[Entry],
+    size: (f32, f32)
+) -> String {
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+        <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+        w = size.0,
+        h = size.1
+    );
+
+    let mut labels = Vec::new();
+
+    let width = appearance.stroke_width;
+    let r = f64::from(appearance.point_radius);
+
+    for (i, item) in items.iter().enumerate() {
+        let color = appearance.color(i);
+        let stroke = format!("{} stroke-width=\"{width}\"", svg_paint("stroke", color));
+
+        match item {
+            Item::Point(pt) => {
+                let (x, y) = (pt.position.x, pt.position.y);
+
+                if pt.display_dot {
+                    match appearance.dot {
+                        DotStyle::Filled => {
+                            let fill = svg_paint("fill", color);
+                            let _ = writeln!(out, "<circle cx=\"{x}\" cy=\"{y}\" r=\"{r}\" {fill}/>");
+                        }
+                        DotStyle::Hollow => {
+                            let _ = writeln!(out, "<circle cx=\"{x}\" cy=\"{y}\" r=\"{r}\" fill=\"none\" {stroke}/>");
+                        }
+                        DotStyle::Cross => {
+                            svg_line(&mut out, (x - r, y - r), (x + r, y + r), &stroke);
+                            svg_line(&mut out, (x - r, y + r), (x + r, y - r), &stroke);
+                        }
+                    }
+                }
+                labels.extend(&pt.label);
+            }
+            Item::Line(ln) => {
+                svg_line(&mut out, (ln.points.0.x, ln.points.0.y), (ln.points.1.x, ln.points.1.y), &stroke);
+                labels.extend(&ln.label);
+            }
+            Item::Segment(x)
+            | Item::Ray(x) => {
+                svg_line(&mut out, (x.points.0.x, x.points.0.y), (x.points.1.x, x.points.1.y), &stroke);
+                labels.extend(&x.label);
+            }
+            Item::Circle(circle) => {
+                let _ = writeln!(
+                    out,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" {stroke}/>",
+                    circle.center.x, circle.center.y, circle.radius
+                );
+                labels.extend(&circle.label);
+            }
+        }
+    }
+
+    for label in labels {
+        let _ = writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"middle\" font-size=\"18\">{}</text>",
+            label.position.x,
+            label.position.y,
+            escape_xml(&label.content.to_string())
+        );
+    }
+
+    for annotation in annotations {
+        let points = annotation.points(size);
+
+        match annotation {
+            Annotation::Text { text, .. } => {
+                let _ = writeln!(
+                    out,
+                    "<text x=\"{}\" y=\"{}\" font-size=\"18\" fill=\"{ANNOTATION_COLOR}\">{}</text>",
+                    points[0].0, points[0].1, escape_xml(text)
+                );
+            }
+            Annotation::Arrow { .. } => {
+                let (x1, y1) = points[0];
+                let (x2, y2) = points[1];
+                let length = (x2 - x1).hypot(y2 - y1).max(f32::EPSILON);
+                let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+                let back = (x2 - dx * ARROWHEAD_SIZE, y2 - dy * ARROWHEAD_SIZE);
+                let side = (-dy * ARROWHEAD_SIZE / 2.0, dx * ARROWHEAD_SIZE / 2.0);
+
+                let _ = writeln!(
+                    out,
+                    "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{ANNOTATION_COLOR}\"/>"
+                );
+                let _ = writeln!(
+                    out,
+                    "<polygon points=\"{x2},{y2} {},{} {},{}\" fill=\"{ANNOTATION_COLOR}\"/>",
+                    back.0 + side.0, back.1 + side.1, back.0 - side.0, back.1 - side.1
+                );
+            }
+            Annotation::Freehand(_) => {
+                let points = points.iter()
+                    .map(|(x, y)| format!("{x},{y}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                let _ = writeln!(
+                    out,
+                    "<polyline points=\"{points}\" fill=\"none\" stroke=\"{ANNOTATION_COLOR}\" stroke-width=\"2\"/>"
+                );
+            }
+        }
+    }
+
+    for (i, entry) in legend.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "<text x=\"{LEGEND_MARGIN}\" y=\"{}\" font-size=\"{LEGEND_FONT_SIZE}\">{} \u{2014} {}</text>",
+            LEGEND_MARGIN + LEGEND_FONT_SIZE * (i + 1) as f32,
+            escape_xml(&entry.label),
+            escape_xml(&entry.description())
+        );
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn svg_line(out: &mut String, (x1, y1): (f64, f64), (x2, y2): (f64, f64), stroke: &str) {
+    let _ = writeln!(out, "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" {stroke}/>");
+}
+
+/// A `fill` or `stroke` attribute, with its opacity if the color isn't opaque.
+fn svg_paint(attribute: &str, [r, g, b, a]: Rgba) -> String {
+    let paint = format!("{attribute}=\"#{r:02x}{g:02x}{b:02x}\"");
+
+    if a == u8::MAX {
+        paint
+    } else {
+        format!("{paint} {attribute}-opacity=\"{:.3}\"", f32::from(a) / 255.0)
+    }
+}
+
+/// A TikZ color option, with its opacity if the color isn't opaque.
+fn tikz_color([r, g, b, a]: Rgba) -> String {
+    let color = format!("color={{rgb,255:red,{r}; green,{g}; blue,{b}}}");
+
+    if a == u8::MAX {
+        color
+    } else {
+        format!("{color}, opacity={:.3}", f32::from(a) / 255.0)
+    }
+}
+
+/// Escape characters special in LaTeX text mode.
+fn escape_latex(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => String::from("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{c}"),
+            '~' => String::from("\\textasciitilde{}"),
+            '^' => String::from("\\textasciicircum{}"),
+            c => c.to_string()
+        })
+        .collect()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// TikZ has its y axis pointing up, so all y coordinates are flipped.
+fn latex(
+    items: &[Item],
+    appearance: &Appearance,
+    annotations: &[Annotation],
+    legend: &[Entry],
+    size: (f32, f32)
+) -> String {
+    let height = f64::from(size.1);
+    let at = |x: f64, y: f64| format!("({x:.3}, {:.3})", height - y);
+    let pos = |p: &Position| at(p.x, p.y);
+    let annotation_pos = |(x, y): &(f32, f32)| format!("({x:.3}, {:.3})", size.1 - y);
+
+    let mut out = String::from("\\begin{tikzpicture}[x=1pt, y=1pt]\n");
+    let mut labels = Vec::new();
+
+    let r = f64::from(appearance.point_radius);
+
+    for (i, item) in items.iter().enumerate() {
+        let color = tikz_color(appearance.color(i));
+        let stroke = format!("{color}, line width={:.3}pt", appearance.stroke_width);
+
+        match item {
+            Item::Point(pt) => {
+                let (x, y) = (pt.position.x, pt.position.y);
+
+                if pt.display_dot {
+                    let _ = match appearance.dot {
+                        DotStyle::Filled => writeln!(out, "    \\fill[{color}] {} circle ({r:.3});", at(x, y)),
+                        DotStyle::Hollow => writeln!(out, "    \\draw[{stroke}] {} circle ({r:.3});", at(x, y)),
+                        DotStyle::Cross => writeln!(
+                            out,
+                            "    \\draw[{stroke}] {} -- {} {} -- {};",
+                            at(x - r, y - r), at(x + r, y + r), at(x - r, y + r), at(x + r, y - r)
+                        )
+                    };
+                }
+                labels.extend(&pt.label);
+            }
+            Item::Line(ln) => {
+                let _ = writeln!(out, "    \\draw[{stroke}] {} -- {};", pos(&ln.points.0), pos(&ln.points.1));
+                labels.extend(&ln.label);
+            }
+            Item::Segment(x)
+            | Item::Ray(x) => {
+                let _ = writeln!(out, "    \\draw[{stroke}] {} -- {};", pos(&x.points.0), pos(&x.points.1));
+                labels.extend(&x.label);
+            }
+            Item::Circle(circle) => {
+                let _ = writeln!(out, "    \\draw[{stroke}] {} circle ({:.3});", pos(&circle.center), circle.radius);
+                labels.extend(&circle.label);
+            }
+        }
+    }
+
+    for label in labels {
+        let content = math_label::to_latex(&math_label::parse(&label.content.to_string()));
+        let _ = writeln!(out, "    \\node at {} {{${content}$}};", pos(&label.position));
+    }
+
+    for annotation in annotations {
+        let points = annotation.points(size);
+
+        match annotation {
+            Annotation::Text { text, .. } => {
+                let _ = writeln!(
+                    out,
+                    "    \\node[{ANNOTATION_COLOR}, anchor=south west] at {} {{{}}};",
+                    annotation_pos(&points[0]),
+                    escape_latex(text)
+                );
+            }
+            Annotation::Arrow { .. } => {
+                let _ = writeln!(
+                    out,
+                    "    \\draw[->, {ANNOTATION_COLOR}] {} -- {};",
+                    annotation_pos(&points[0]),
+                    annotation_pos(&points[1])
+                );
+            }
+            Annotation::Freehand(_) => {
+                let path = points.iter().map(annotation_pos).collect::<Vec<_>>().join(" -- ");
+                let _ = writeln!(out, "    \\draw[{ANNOTATION_COLOR}, thick] {path};");
+            }
+        }
+    }
+
+    if !legend.is_empty() {
+        let lines = legend.iter()
+            .map(|entry| format!("${}$ --- {}", entry.label, escape_latex(&entry.description())))
+            .collect::<Vec<_>>()
+            .join(" \\\\ ");
+
+        let _ = writeln!(
+            out,
+            "    \\node[anchor=north west, align=left, font=\\small] at ({LEGEND_MARGIN:.3}, {:.3}) {{{lines}}};",
+            size.1 - LEGEND_MARGIN
+        );
+    }
+
+    out.push_str("\\end{tikzpicture}\n");
+    out
+}
+
+fn json(
+    items: &[Item],
+    appearance: &Appearance,
+    annotations: &[Annotation],
+    legend: &[Entry],
+    metrics: Option<&Metrics>,
+    size: (f32, f32)
+) -> Value {
+    let pos = |p: &Position| json!({ "x": p.x, "y": p.y });
+    let label = |l: &Option<Label>| l.as_ref().map(|l| json!({
+        "content": l.content.to_string(),
+        "position": pos(&l.position)
+    }));
+
+    let items = items.iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let mut value = match item {
+                Item::Point(pt) => json!({
+                    "type": "point",
+                    "position": pos(&pt.position),
+                    "displayDot": pt.display_dot,
+                    "label": label(&pt.label)
+                }),
+                Item::Line(ln) => json!({
+                    "type": "line",
+                    "points": [pos(&ln.points.0), pos(&ln.points.1)],
+                    "label": label(&ln.label)
+                }),
+                Item::Segment(x) => json!({
+                    "type": "segment",
+                    "points": [pos(&x.points.0), pos(&x.points.1)],
+                    "label": label(&x.label)
+                }),
+                Item::Ray(x) => json!({
+                    "type": "ray",
+                    "points": [pos(&x.points.0), pos(&x.points.1)],
+                    "label": label(&x.label)
+                }),
+                Item::Circle(circle) => json!({
+                    "type": "circle",
+                    "center": pos(&circle.center),
+                    "radius": circle.radius,
+                    "label": label(&circle.label)
+                })
+            };
+
+            let [r, g, b, a] = appearance.color(i);
+            value["color"] = json!(format!("#{r:02x}{g:02x}{b:02x}{a:02x}"));
+            value
+        })
+        .collect::<Vec<_>>();
+
+    let annotations = annotations.iter()
+        .map(|annotation| {
+            let points = annotation.points(size)
+                .into_iter()
+                .map(|(x, y)| json!({ "x": x, "y": y }))
+                .collect::<Vec<_>>();
+
+            match annotation {
+                Annotation::Text { text, .. } => json!({
+                    "type": "text",
+                    "position": points[0],
+                    "text": text
+                }),
+                Annotation::Arrow { .. } => json!({
+                    "type": "arrow",
+                    "points": points
+                }),
+                Annotation::Freehand(_) => json!({
+                    "type": "freehand",
+                    "points": points
+                })
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let legend = legend.iter()
+        .map(|entry| json!({
+            "label": entry.label,
+            "description": entry.description()
+        }))
+        .collect::<Vec<_>>();
+
+    let mut value = json!({
+        "width": size.0,
+        "height": size.1,
+        "items": items,
+        "style": {
+            "strokeWidth": appearance.stroke_width,
+            "pointRadius": appearance.point_radius,
+            "dot": appearance.dot
+        },
+        "annotations": annotations,
+        "legend": legend
+    });
+
+    if let Some(metrics) = metrics {
+        value["metrics"] = json!({
+            "iteration": metrics.iteration,
+            "quality": metrics.quality,
+            "elapsed": metrics.elapsed
+        });
+    }
+
+    value
+}
+
+/// The "Export" window.
+pub struct ExportTool {
+    pub open: bool,
+    format: Format,
+    /// Export only the box-selected items. Doesn't apply to PNG.
+    pub selection_only: bool,
+    /// Add a legend of labelled items. Doesn't apply to PNG.
+    pub legend: bool,
+    /// PNG resolution relative to the canvas size.
+    pub png_scale: f32,
+    pub error: Option<String>
+}
+
+impl Default for ExportTool {
+    fn default() -> Self {
+        Self {
+            open: false,
+            format: Format::Svg,
+            selection_only: false,
+            legend: false,
+            png_scale: 2.0,
+            error: None
+        }
+    }
+}
+
+impl ExportTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the format to export to when the user asks for an export.
+    /// `selected` is the number of box-selected items.
+    #[must_use]
+    pub fn show(&mut self, ctx: &Context, available: bool, selected: usize, lang: Language) -> Option<Format> {
+        let mut export = None;
+
+        egui::Window::new(Text::ExportWindow.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                egui::ComboBox::from_label(Text::Format.tr(lang))
+                    .selected_text(self.format.name())
+                    .show_ui(ui, |ui| {
+                        for format in Format::ALL {
+                            ui.selectable_value(&mut self.format, format, format.name());
+                        }
+                    });
+
+                self.selection_only &= selected > 0;
+                ui.add_enabled(
+                    selected > 0 && self.format != Format::Png,
+                    egui::Checkbox::new(&mut self.selection_only, Text::SelectedItemsOnly.fill(lang, &[&selected]))
+                );
+
+                ui.add_enabled(
+                    self.format != Format::Png,
+                    egui::Checkbox::new(&mut self.legend, Text::IncludeLegend.tr(lang))
+                );
+
+                if self.format == Format::Png {
+                    ui.add(
+                        egui::Slider::new(&mut self.png_scale, 1.0..=8.0)
+                            .text(Text::Resolution.tr(lang))
+                            .suffix("×")
+                    );
+                }
+
+                if ui.add_enabled(available, egui::Button::new(Text::Export.tr(lang))).clicked() {
+                    export = Some(self.format);
+                }
+
+                if !available {
+                    ui.label(Text::NothingGenerated.tr(lang));
+                }
+
+                ui.weak(Text::GeoGebraCliOnly.tr(lang));
+
+                if let Some(err) = &self.error {
+                    ui.colored_label(egui::Color32::RED, err);
+                }
+            });
+
+        export
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_is_not_written() {
+        let path = std::env::temp_dir().join("geo-aid-dbg-export.png");
+        let appearance = Appearance::new(Vec::new(), &ItemColors::default(), &ItemStyle::default());
+        let err = write(Format::Png, &[], &appearance, &[], &[], None, (100.0, 100.0), &path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn colors_keep_their_opacity() {
+        assert_eq!(svg_paint("fill", [0, 114, 178, 255]), "fill=\"#0072b2\"");
+        assert_eq!(svg_paint("stroke", [255, 0, 0, 51]), "stroke=\"#ff0000\" stroke-opacity=\"0.200\"");
+        assert_eq!(tikz_color([0, 114, 178, 255]), "color={rgb,255:red,0; green,114; blue,178}");
+        assert_eq!(tikz_color([0, 0, 0, 51]), "color={rgb,255:red,0; green,0; blue,0}, opacity=0.200");
+    }
+
+    #[test]
+    fn high_contrast_scales_strokes_and_dots() {
+        let colors = ItemColors {
+            high_contrast: true,
+            ..ItemColors::default()
+        };
+        let appearance = Appearance::new(Vec::new(), &colors, &ItemStyle::default());
+
+        assert_eq!(appearance.stroke_width, ItemStyle::default().stroke_width * render::HIGH_CONTRAST_STROKE_SCALE);
+        assert_eq!(appearance.color(3), [0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,161 @@
+//! Fonts for figure labels.
+//!
+//! The built-in font only covers ASCII, so labels are drawn with a chain of fonts: the one picked by the user,
+//! then whichever of [`SYSTEM_FALLBACKS`] are installed, then the built-in one. Every character is drawn
+//! with the first font of the chain that has a glyph for it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use macroquad::text::{load_ttf_font_from_bytes, Font};
+use serde::{Deserialize, Serialize};
+
+/// Label font size on a reference-sized canvas.
+pub const DEFAULT_SIZE: f32 = 18.0;
+pub const MIN_SIZE: f32 = 8.0;
+pub const MAX_SIZE: f32 = 48.0;
+
+/// Common fonts with broad Unicode coverage, tried in order.
+const SYSTEM_FALLBACKS: [&str; 8] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf"
+];
+/// At most this many system fonts are loaded.
+const MAX_FALLBACKS: usize = 2;
+
+/// Label font settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LabelFont {
+    /// A TrueType font to draw labels with before any fallback.
+    pub path: Option<PathBuf>,
+    pub size: f32
+}
+
+impl Default for LabelFont {
+    fn default() -> Self {
+        Self {
+            path: None,
+            size: DEFAULT_SIZE
+        }
+    }
+}
+
+struct Face {
+    font: Font,
+    /// The same font, parsed for glyph lookups.
+    glyphs: fontdue::Font
+}
+
+impl Face {
+    fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|err| err.to_string())?;
+        let glyphs = fontdue::Font::from_bytes(bytes.as_slice(), fontdue::FontSettings::default())
+            .map_err(String::from)?;
+        let font = load_ttf_font_from_bytes(&bytes).map_err(|err| format!("{err:?}"))?;
+
+        Ok(Self { font, glyphs })
+    }
+
+    fn has(&self, c: char) -> bool {
+        self.glyphs.lookup_glyph_index(c) != 0
+    }
+}
+
+/// The loaded font chain.
+#[derive(Default)]
+pub struct Fonts {
+    /// The user font the chain was loaded for.
+    path: Option<PathBuf>,
+    faces: Vec<Face>,
+    /// The user font that couldn't be loaded and why.
+    pub error: Option<String>
+}
+
+impl Fonts {
+    #[must_use]
+    pub fn load(path: Option<&Path>) -> Self {
+        let mut faces = Vec::new();
+        let mut error = None;
+
+        if let Some(path) = path {
+            match Face::load(path) {
+                Ok(face) => faces.push(face),
+                Err(err) => error = Some(format!("{}: {err}", path.display()))
+            }
+        }
+
+        faces.extend(
+            SYSTEM_FALLBACKS.iter()
+                .map(Path::new)
+                .filter(|path| path.exists())
+                .filter_map(|path| Face::load(path).ok())
+                .take(MAX_FALLBACKS)
+        );
+
+        Self {
+            path: path.map(PathBuf::from),
+            faces,
+            error
+        }
+    }
+
+    /// Whether the chain was loaded for this user font.
+    #[must_use]
+    pub fn is_for(&self, path: Option<&Path>) -> bool {
+        self.path.as_deref() == path
+    }
+
+    /// Whether only the built-in font is available.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// The font to draw `c` with, `None` for the built-in one.
+    #[must_use]
+    pub fn font_for(&self, c: char) -> Option<&Font> {
+        self.faces.iter()
+            .find(|face| face.has(c))
+            .or_else(|| if c.is_ascii() { None } else { self.faces.first() })
+            .map(|face| &face.font)
+    }
+
+    /// Split `text` into runs drawn with the same font.
+    #[must_use]
+    pub fn runs<'a>(&self, text: &'a str) -> Vec<(&'a str, Option<&Font>)> {
+        let mut runs: Vec<(&str, Option<&Font>)> = Vec::new();
+        let mut start = 0;
+        let mut current = None;
+
+        for (i, c) in text.char_indices() {
+            let font = self.font_for(c);
+
+            if i > 0 && !same(font, current) {
+                runs.push((&text[start..i], current));
+                start = i;
+            }
+
+            current = font;
+        }
+
+        if start < text.len() {
+            runs.push((&text[start..], current));
+        }
+
+        runs
+    }
+}
+
+fn same(a: Option<&Font>, b: Option<&Font>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => std::ptr::eq(a, b),
+        (None, None) => true,
+        _ => false
+    }
+}
@@ -0,0 +1,257 @@
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use egui::{Color32, Painter, RichText, Stroke, Ui, Vec2};
+use geo_aid_internal::projector;
+use geo_aid_internal::projector::figure::Item;
+use geo_aid_internal::script::math::Flags;
+use serde::{Deserialize, Serialize};
+use crate::i18n::{Language, Text};
+use crate::runtime::Frame;
+
+/// Extension of golden state files.
+pub const EXTENSION: &str = "ggold";
+
+/// Size of the canvas goldens are projected to, so deviations don't depend on the window size.
+const SIZE: (usize, usize) = (1000, 1000);
+
+const GOLDEN_COLOR: Color32 = Color32::from_rgb(212, 175, 55);
+
+/// A point of a golden figure, in golden canvas units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldenPoint {
+    pub label: Option<String>,
+    pub x: f64,
+    pub y: f64
+}
+
+/// Points of a figure, in order, as they're compared against a golden.
+fn points(items: &[Item]) -> Vec<GoldenPoint> {
+    items.iter()
+        .filter_map(|item| match item {
+            Item::Point(pt) => Some(GoldenPoint {
+                label: pt.label.as_ref().map(|label| label.content.to_string()),
+                x: pt.position.x,
+                y: pt.position.y
+            }),
+            _ => None
+        })
+        .collect()
+}
+
+/// A known-good final state of a script, for checking that later runs converge to the same figure.
+///
+/// Points are stored projected, so a golden stays comparable when the engine's adjustables change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Golden {
+    /// The script the state was generated for.
+    pub script: Option<PathBuf>,
+    pub iteration: usize,
+    pub quality: f64,
+    pub state: Vec<f64>,
+    pub points: Vec<GoldenPoint>
+}
+
+impl Golden {
+    /// # Errors
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(path, content)
+    }
+
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't a valid golden state.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+
+        ron::from_str(&content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+pub enum Action {
+    Save,
+    Load
+}
+
+/// Compares the latest frame against a loaded golden state.
+pub struct GoldenComparison {
+    pub golden: Option<Golden>,
+    /// Points of the latest frame, projected like the golden's.
+    current: Vec<GoldenPoint>,
+    /// The latest frame, for saving it as a golden.
+    frame: Option<Frame>,
+    /// Draw the golden points over the figure.
+    pub overlay: bool
+}
+
+impl Default for GoldenComparison {
+    fn default() -> Self {
+        Self {
+            golden: None,
+            current: Vec::new(),
+            frame: None,
+            overlay: true
+        }
+    }
+}
+
+impl GoldenComparison {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the latest frame. The golden is kept for the next run.
+    pub fn clear(&mut self) {
+        self.current.clear();
+        self.frame = None;
+    }
+
+    /// Project a new frame for comparison.
+    pub fn update(&mut self, frame: &Frame, flags: &Flags) {
+        if self.frame.as_ref().is_some_and(|f| f.revision == frame.revision) {
+            return;
+        }
+
+        self.current = points(&projector::project(frame.generated.clone(), flags, SIZE).items);
+        self.frame = Some(frame.clone());
+    }
+
+    /// The latest frame as a golden state.
+    #[must_use]
+    pub fn capture(&self, script: Option<PathBuf>) -> Option<Golden> {
+        self.frame.as_ref().map(|frame| Golden {
+            script,
+            iteration: frame.iteration,
+            quality: frame.quality,
+            state: frame.state.clone(),
+            points: self.current.clone()
+        })
+    }
+
+    /// (point index, label, distance from the golden) for all points, if both figures have the same points.
+    fn deviations(&self) -> Option<Vec<(usize, Option<&str>, f64)>> {
+        let golden = self.golden.as_ref()?;
+
+        (golden.points.len() == self.current.len()).then(|| golden.points.iter()
+            .zip(&self.current)
+            .enumerate()
+            .map(|(i, (g, c))| (i, g.label.as_deref(), (g.x - c.x).hypot(g.y - c.y)))
+            .collect())
+    }
+
+    /// Draw the golden points over the canvas. `origin` is the top-left corner of the canvas.
+    ///
+    /// The canvas may be projected at any size, so golden positions are carried over
+    /// with the scale and offset between the two projections of the latest frame.
+    pub fn paint(&self, painter: &Painter, origin: Vec2, items: &[Item]) {
+        let Some(golden) = self.golden.as_ref().filter(|_| self.overlay) else {
+            return;
+        };
+
+        let live = points(items);
+
+        if live.is_empty() || live.len() != self.current.len() || golden.points.len() != live.len() {
+            return;
+        }
+
+        let centroid = |points: &[GoldenPoint]| {
+            let n = points.len() as f64;
+            (points.iter().map(|p| p.x).sum::<f64>() / n, points.iter().map(|p| p.y).sum::<f64>() / n)
+        };
+        let spread = |points: &[GoldenPoint], (cx, cy): (f64, f64)| {
+            points.iter().map(|p| (p.x - cx).hypot(p.y - cy)).sum::<f64>()
+        };
+
+        let live_center = centroid(&live);
+        let current_center = centroid(&self.current);
+        let current_spread = spread(&self.current, current_center);
+        let scale = if current_spread > 0.0 {
+            spread(&live, live_center) / current_spread
+        } else {
+            1.0
+        };
+
+        let to_canvas = |p: &GoldenPoint| origin.to_pos2() + Vec2::new(
+            (live_center.0 + (p.x - current_center.0) * scale) as f32,
+            (live_center.1 + (p.y - current_center.1) * scale) as f32
+        );
+
+        for (g, l) in golden.points.iter().zip(&live) {
+            let target = to_canvas(g);
+            let position = origin.to_pos2() + Vec2::new(l.x as f32, l.y as f32);
+
+            painter.line_segment([position, target], Stroke::new(1.0, GOLDEN_COLOR.gamma_multiply(0.6)));
+            painter.circle_stroke(target, 4.0, Stroke::new(1.5, GOLDEN_COLOR));
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, lang: Language) -> Option<Action> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.frame.is_some(), egui::Button::new(Text::SaveAsGolden.tr(lang))).clicked() {
+                action = Some(Action::Save);
+            }
+
+            if ui.button(Text::LoadGolden.tr(lang)).clicked() {
+                action = Some(Action::Load);
+            }
+
+            if ui.add_enabled(self.golden.is_some(), egui::Button::new(Text::Clear.tr(lang))).clicked() {
+                self.golden = None;
+            }
+        });
+
+        let Some(golden) = &self.golden else {
+            ui.label(Text::NoGolden.tr(lang));
+            return action;
+        };
+
+        if let Some(script) = &golden.script {
+            ui.label(Text::GoldenOf.fill(lang, &[&script.display()]));
+        }
+
+        ui.label(Text::GoldenFrame.fill(lang, &[&golden.iteration, &format!("{:.4}", golden.quality)]));
+
+        let Some(frame) = &self.frame else {
+            return action;
+        };
+
+        ui.label(Text::QualityDifference.fill(lang, &[&format!("{:+.6}", frame.quality - golden.quality)]));
+        ui.checkbox(&mut self.overlay, Text::ShowGoldenPoints.tr(lang));
+
+        let Some(mut deviations) = self.deviations() else {
+            ui.label(RichText::new(Text::GoldenPointCount.fill(lang, &[&golden.points.len(), &self.current.len()]))
+                .color(Color32::RED));
+            return action;
+        };
+
+        let max = deviations.iter().map(|d| d.2).fold(0.0, f64::max);
+        let mean = deviations.iter().map(|d| d.2).sum::<f64>() / deviations.len().max(1) as f64;
+        ui.label(Text::PointDeviation.fill(lang, &[&format!("{mean:.2}"), &format!("{max:.2}"), &SIZE.0]));
+
+        // Largest deviations first.
+        deviations.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        egui::Grid::new("golden-deviations")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, label, distance) in deviations {
+                    ui.label(label.map_or_else(
+                        || Text::ItemPointNumber.fill(lang, &[&i]),
+                        |label| format!("{} {label}", Text::ItemPoint.tr(lang))
+                    ));
+                    ui.label(format!("{distance:.2}"));
+                    ui.end_row();
+                }
+            });
+
+        action
+    }
+}
+
@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::Path;
+use egui::{Align2, Color32, Context, Key, Rect, Stroke};
+use crate::i18n::{Language, Text};
+
+/// Created once the tour is finished or skipped, so that it's only shown on the first run.
+const TOUR_MARKER: &str = ".geo-aid-dbg-tour";
+const HIGHLIGHT_COLOR: Color32 = Color32::from_rgb(255, 170, 0);
+
+const KEYBINDINGS: [(Text, Text); 15] = [
+    (Text::KeyF1, Text::ToggleHelp),
+    (Text::KeyF11, Text::ToggleZen),
+    (Text::KeyEsc, Text::LeaveZen),
+    (Text::KeyF12, Text::SaveScreenshot),
+    (Text::KeyCtrlC, Text::CopySelected),
+    (Text::KeyCtrlShiftC, Text::CopyCanvasImage),
+    (Text::KeyCtrlZ, Text::UndoEdit),
+    (Text::KeyRedo, Text::RedoEdit),
+    (Text::KeyDrag, Text::SelectRectangle),
+    (Text::KeyShiftDrag, Text::AddToSelection),
+    (Text::KeyScroll, Text::ZoomInOut),
+    (Text::KeyMiddleDrag, Text::PanZoomed),
+    (Text::KeyHome, Text::ResetZoom),
+    (Text::KeyDigits, Text::JumpToBookmark),
+    (Text::KeyMeasureClick, Text::PickForMeasure)
+];
+
+const PANELS: [(Text, Text); 6] = [
+    (Text::Canvas, Text::CanvasPanelHelp),
+    (Text::Controls, Text::ControlsPanelHelp),
+    (Text::Inspector, Text::InspectorPanelHelp),
+    (Text::Metrics, Text::MetricsPanelHelp),
+    (Text::Script, Text::ScriptPanelHelp),
+    (Text::Log, Text::LogPanelHelp)
+];
+
+/// A UI element the tour points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    FilePicker,
+    Parameters,
+    Stepping
+}
+
+/// Tour steps in order, with what they say.
+const TOUR: [(Target, Text); 3] = [
+    (Target::FilePicker, Text::TourFilePicker),
+    (Target::Parameters, Text::TourParameters),
+    (Target::Stepping, Text::TourStepping)
+];
+
+/// The F1 help window and the first-run tour.
+pub struct Help {
+    pub open: bool,
+    /// Index of the current tour step, if the tour is on.
+    tour: Option<usize>,
+    /// Where the tour targets were drawn in the last frame.
+    targets: Vec<(Target, Rect)>
+}
+
+impl Default for Help {
+    fn default() -> Self {
+        Self {
+            open: false,
+            tour: (!Path::new(TOUR_MARKER).exists()).then_some(0),
+            targets: Vec::new()
+        }
+    }
+}
+
+impl Help {
+    /// The tour starts if it wasn't done before.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start_tour(&mut self) {
+        self.tour = Some(0);
+    }
+
+    /// Record where a tour target was drawn. Only needed while the tour is on.
+    pub fn target(&mut self, target: Target, rect: Rect) {
+        if self.tour.is_some() {
+            self.targets.push((target, rect));
+        }
+    }
+
+    fn end_tour(&mut self) {
+        self.tour = None;
+        let _ = fs::write(TOUR_MARKER, "");
+    }
+
+    pub fn show(&mut self, ctx: &Context, lang: Language) {
+        if ctx.input(|input| input.key_pressed(Key::F1)) {
+            self.open = !self.open;
+        }
+
+        egui::Window::new(Text::HelpTitle.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                ui.heading(Text::KeybindingsHeading.tr(lang));
+                egui::Grid::new("help-keybindings")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (keys, action) in KEYBINDINGS {
+                            ui.strong(keys.tr(lang));
+                            ui.label(action.tr(lang));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.heading(Text::Panels.tr(lang));
+                egui::Grid::new("help-panels")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (panel, description) in PANELS {
+                            ui.strong(panel.tr(lang));
+                            ui.label(description.tr(lang));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        self.show_tour(ctx, lang);
+    }
+
+    fn show_tour(&mut self, ctx: &Context, lang: Language) {
+        let targets = std::mem::take(&mut self.targets);
+
+        let Some(step) = self.tour else {
+            return;
+        };
+
+        let (target, text) = TOUR[step];
+        let rect = targets.iter().find(|(t, _)| *t == target).map(|(_, rect)| *rect);
+
+        if let Some(rect) = rect {
+            ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("tour-highlight")))
+                .rect_stroke(rect.expand(4.0), 4.0, Stroke::new(3.0, HIGHLIGHT_COLOR));
+        }
+
+        let mut area = egui::Area::new(egui::Id::new("tour")).order(egui::Order::Foreground);
+
+        area = match rect {
+            Some(rect) => area.fixed_pos(rect.left_bottom() + egui::vec2(0.0, 8.0)),
+            None => area.anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        };
+
+        area.show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(Text::TourStep.fill(lang, &[&(step + 1), &TOUR.len()]));
+                ui.label(text.tr(lang));
+
+                ui.horizontal(|ui| {
+                    let last = step + 1 == TOUR.len();
+
+                    if ui.button(if last { Text::Done } else { Text::Next }.tr(lang)).clicked() {
+                        if last {
+                            self.end_tour();
+                        } else {
+                            self.tour = Some(step + 1);
+                        }
+                    }
+
+                    if !last && ui.button(Text::SkipTour.tr(lang)).clicked() {
+                        self.end_tour();
+                    }
+                });
+            });
+        });
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::VecDeque;
+use crate::runtime::Frame;
+
+/// How many frames are kept by default.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Recently generated frames, oldest first.
+pub struct History {
+    frames: VecDeque<Frame>,
+    capacity: usize
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            frames: VecDeque::with_capacity(DEFAULT_CAPACITY),
+            capacity: DEFAULT_CAPACITY
+        }
+    }
+}
+
+impl History {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `frame` unless it is the same revision as the latest recorded one.
+    pub fn record(&mut self, frame: &Frame) {
+        if self.latest().is_some_and(|latest| latest.revision == frame.revision) {
+            return;
+        }
+
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back(frame.clone());
+    }
+
+    #[must_use]
+    pub fn latest(&self) -> Option<&Frame> {
+        self.frames.back()
+    }
+
+    /// All recorded frames, oldest first.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Frame> {
+        self.frames.iter()
+    }
+
+    /// The frame recorded right before the latest one.
+    #[must_use]
+    pub fn previous(&self) -> Option<&Frame> {
+        self.frames.len().checked_sub(2).and_then(|i| self.frames.get(i))
+    }
+
+    /// Up to `count` frames recorded before the latest one, oldest first.
+    pub fn before_latest(&self, count: usize) -> impl ExactSizeIterator<Item = &Frame> {
+        let end = self.frames.len().saturating_sub(1);
+        let start = end.saturating_sub(count);
+
+        self.frames.range(start..end)
+    }
+}
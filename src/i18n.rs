@@ -0,0 +1,1474 @@
+use std::fmt::{Display, Write};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Polish
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::Polish];
+
+    /// The language's name in itself.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Polish => "Polski"
+        }
+    }
+}
+
+/// A translated UI string.
+///
+/// To translate a new string, add a variant here and its translations in [`Text::tr`].
+/// Strings with values in them mark each place with `{}`, see [`Text::fill`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    // Menu bar
+    File,
+    NewFromClipboard,
+    SaveScriptAs,
+    SaveSession,
+    LoadSession,
+    SaveCheckpoint,
+    LoadCheckpoint,
+    RecordTrace,
+    SaveTrace,
+    Export,
+    Edit,
+    Undo,
+    Redo,
+    View,
+    ZenMode,
+    CompactMode,
+    Measure,
+    ResetLayout,
+    ResetView,
+    FloatPanel,
+    FpsCap,
+    NoLimit,
+    PowerSaving,
+    Language,
+    Tools,
+    Benchmark,
+    MultiRunStatistics,
+    ParameterSweep,
+    ScriptDiff,
+    Automation,
+    AutoExport,
+    PointTrajectory,
+    Performance,
+    Help,
+    Keybindings,
+    Tour,
+    // Tabs
+    Canvas,
+    Controls,
+    Inspector,
+    Metrics,
+    Script,
+    Log,
+    Dependencies,
+    // Controls
+    Generate,
+    Quit,
+    QuitHint,
+    Run,
+    Pause,
+    Resume,
+    Stop,
+    Abort,
+    NextStep,
+    StopCriteria,
+    RunUntilStopped,
+    // Inspector
+    Adjustables,
+    Magnitudes,
+    FigureStatistics,
+    Degeneracies,
+    Legend,
+    ItemColors,
+    ItemStyle,
+    LabelFont,
+    Selection,
+    Annotations,
+    Bookmarks,
+    Comparison,
+    Golden,
+    Breakpoints,
+    Bisect,
+    TimeTravel,
+    Overlays,
+    Projection,
+    // Run controls
+    WorkerSilent,
+    WorkerStuckHint,
+    AbortHint,
+    WorkerPanicked,
+    AtIteration,
+    PausedUnattended,
+    RunningUnattended,
+    EstimatedTimeLeft,
+    LastRunEnded,
+    IterationQuality,
+    MaxAdjustment,
+    RunPublishesEvery,
+    CyclesOr,
+    BatchingHint,
+    StopRunAfter,
+    TimeBudgetHint,
+    // Setup
+    FileLabel,
+    Change,
+    UnsavedScript,
+    Open,
+    WorkerCount,
+    Auto,
+    AutoWorkersHint,
+    MaximumAdjustment,
+    Engine,
+    MockHint,
+    GenerationThread,
+    LowPriority,
+    LowPriorityHint,
+    AnyCore,
+    Core,
+    PinCoreHint,
+    HistoryBudget,
+    HistoryBudgetHint,
+    WatchdogTimeout,
+    WatchdogTimeoutHint,
+    Server,
+    Local,
+    ServerHint,
+    FixParameters,
+    // Setup errors
+    ParamWorkerCount,
+    ParamMaxAdjustment,
+    ErrorIo,
+    ErrorParse,
+    ScriptFailedToCompile,
+    AndMore,
+    ErrorEngineInit,
+    CouldNotConnect,
+    BuiltWithoutRemote,
+    ErrorInvalidParam,
+    MustBeAtLeastOne,
+    MustBePositive,
+    HintIo,
+    HintWholeNumber,
+    HintNumber,
+    HintCompile,
+    HintEngineInit,
+    HintAtLeastOneWorker,
+    HintPositive,
+    // Inspector
+    PausedAtBreakpoint,
+    PausedAtNonFinite,
+    ProjectedNonFinite,
+    BakedFor,
+    ReHeat,
+    ReHeatHint,
+    ReHeatOverridesHint,
+    Points,
+    Lines,
+    Segments,
+    Rays,
+    Circles,
+    Labels,
+    NoLabelledItems,
+    Palette,
+    Monochrome,
+    OkabeIto,
+    TolBright,
+    HighContrast,
+    HighContrastHint,
+    Reset,
+    StrokeWidth,
+    PointRadius,
+    Dots,
+    DotFilled,
+    DotHollow,
+    DotCross,
+    ScaleWithCanvas,
+    ScaleWithCanvasHint,
+    LevelOfDetail,
+    LevelOfDetailHint,
+    SystemFonts,
+    Pick,
+    NoUnicodeFont,
+    NoUnicodeFontHint,
+    Size,
+    FixedCanvasSize,
+    FixedCanvasSizeHint,
+    AspectRatio,
+    Free,
+    Square,
+    A4Portrait,
+    A4Landscape,
+    Margin,
+    ContributingWorkers,
+    WorkerDetails,
+    Proposals,
+    StepThroughProposals,
+    NoProposalsYet,
+    PreviousProposal,
+    NextProposal,
+    TakenProposal,
+    ProposalOf,
+    ProposalDetails,
+    ProposalTaken,
+    ProposalDropped,
+    // Metrics
+    NothingGenerated,
+    LogScale,
+    LogScaleHint,
+    TargetLine,
+    PlotHint,
+    Iteration,
+    Quality,
+    LogError,
+    Target,
+    Magnitude,
+    AcceptanceRate,
+    // Script tab
+    Save,
+    SaveAs,
+    ChangesApplyNext,
+    GoToError,
+    // Values
+    AspectFourThree,
+    AspectSixteenNine,
+    WorkerCountParam,
+    // Dialogs and messages
+    DebuggerSession,
+    EngineCheckpoint,
+    AdjustableTrace,
+    TrueTypeFont,
+    AllFiles,
+    SaveSessionTitle,
+    LoadSessionTitle,
+    SaveCheckpointTitle,
+    LoadCheckpointTitle,
+    SaveTraceTitle,
+    SaveGoldenTitle,
+    LoadGoldenTitle,
+    PickLabelFontTitle,
+    PickExportDirTitle,
+    PickOldScriptTitle,
+    ExportFigureTitle,
+    SaveScriptTitle,
+    OpenScriptTitle,
+    CouldNotSaveSession,
+    CouldNotLoadSession,
+    CouldNotSaveCheckpoint,
+    CouldNotLoadCheckpoint,
+    CheckpointMismatch,
+    CouldNotSaveTrace,
+    CouldNotSaveGolden,
+    CouldNotLoadGolden,
+    CouldNotExport,
+    CouldNotReadFile,
+    OldVersionFailed,
+    FixSetupFirst,
+    ClipboardEmpty,
+    RecordTraceHint,
+    FloatPanelHint,
+    // Toasts
+    TimeBudgetSpent,
+    Saved,
+    CouldNotScreenshot,
+    CopiedCanvas,
+    CopiedItems,
+    // Compact mode
+    Next,
+    ExitCompact,
+    // Quit dialog
+    QuitTitle,
+    QuitLoses,
+    StopUnattendedFirst,
+    Cancel,
+    // Status bar
+    StatusIteration,
+    StatusQuality,
+    StatusStepsPerSecond,
+    GeneratingFor,
+    GeneratingForHint,
+    Eta,
+    StatusMemory,
+    MemoryBreakdown,
+    WorkerBusy,
+    RunEnded,
+    NoRuntime,
+    Cursor,
+    Measured,
+    Fps,
+    // Canvas
+    LabelsHidden,
+    NonFiniteItems,
+    // Stop criteria
+    TargetReached,
+    MaxIterationsDone,
+    TimeLimitExceeded,
+    QualityStagnated,
+    PausedByBreakpoint,
+    StoppedByUser,
+    TargetQualityField,
+    MaxIterationsField,
+    TimeLimitField,
+    StagnationWindowField,
+    StagnationWindowHint,
+    MinimumImprovementField,
+    InvalidTargetQuality,
+    InvalidMaxIterations,
+    InvalidTimeLimit,
+    InvalidStagnationWindow,
+    InvalidMinimumImprovement,
+    // Common
+    Remove,
+    Clear,
+    ClearAll,
+    Add,
+    Adjustable,
+    Step,
+    Restore,
+    NotSupportedByEngine,
+    // Items
+    ItemPoint,
+    ItemLine,
+    ItemSegment,
+    ItemRay,
+    ItemCircle,
+    ItemPointNumber,
+    // Breakpoints
+    BpQuality,
+    BpAdjustable,
+    BpBecomesNonFinite,
+    BpLeavesRange,
+    BecomesNonFinite,
+    LeavesRange,
+    // Degeneracies
+    Detect,
+    Tolerance,
+    ToleranceHint,
+    NothingDegenerate,
+    Coincide,
+    ZeroRadius,
+    ZeroLength,
+    FlatTriangle,
+    // Rules
+    Rules,
+    Rule,
+    RuleError,
+    RuleWeight,
+    RuleErrors,
+    RuleErrorsHint,
+    ErrorBreakdown,
+    ErrorBreakdownHint,
+    AllRulesSatisfied,
+    ErrorShare,
+    RulesHint,
+    RefersTo,
+    RuleEnabledHint,
+    DependenciesHint,
+    // Profiler
+    Profiler,
+    ProfileExpressions,
+    ProfileWindow,
+    NoProfileYet,
+    ProfiledCycles,
+    Expression,
+    Evaluations,
+    ShareOfTime,
+    // Selection
+    ItemsSelected,
+    Hide,
+    Lock,
+    LockHint,
+    Deselect,
+    ApplyColor,
+    ShowHidden,
+    UnlockAll,
+    // Annotations
+    AnnotateOnCanvas,
+    AnnotationText,
+    Arrow,
+    Freehand,
+    Note,
+    TextNote,
+    FreehandMark,
+    // Comparison
+    MarkA,
+    MarkB,
+    MarkedFrame,
+    ShowAAndB,
+    QualityDelta,
+    DifferentScripts,
+    Delta,
+    // Golden
+    SaveAsGolden,
+    LoadGolden,
+    NoGolden,
+    GoldenOf,
+    GoldenFrame,
+    QualityDifference,
+    ShowGoldenPoints,
+    GoldenPointCount,
+    PointDeviation,
+    // Bounds alarm
+    WarnWhenExceeds,
+    InAbsoluteValue,
+    PauseWhenItDoes,
+    OutOfBounds,
+    // Bisect
+    DropsBelow,
+    RisesAbove,
+    Start,
+    BisectHint,
+    AlreadyCrossed,
+    NotCrossed,
+    RecordTwoFrames,
+    GoodBad,
+    FirstCrossed,
+    RestoreLastGood,
+    RestoreLastGoodHint,
+    Testing,
+    Crossed,
+    NotCrossedShort,
+    RunToEnd,
+    MarkGood,
+    MarkBad,
+    // Time travel
+    NoStatesArchived,
+    State,
+    StatesArchived,
+    RestoreStateHint,
+    NoArchivedState,
+    NoKeyframe,
+    CorruptState,
+    // Overlays
+    GhostsOverlay,
+    GhostCount,
+    MovementOverlay,
+    VectorScale,
+    GridOverlay,
+    GridSpacing,
+    Units,
+    HeatmapOverlay,
+    // Bookmarks
+    Go,
+    Update,
+    UpdateBookmarkHint,
+    SaveCurrentView,
+    ViewNumber,
+    BookmarkKeysHint,
+    // Help
+    HelpTitle,
+    Panels,
+    KeyF1,
+    KeyF11,
+    KeyEsc,
+    KeyF12,
+    KeyCtrlC,
+    KeyCtrlShiftC,
+    KeyCtrlZ,
+    KeyRedo,
+    KeyDrag,
+    KeyShiftDrag,
+    KeyScroll,
+    KeyMiddleDrag,
+    KeyHome,
+    KeyDigits,
+    KeyMeasureClick,
+    ToggleHelp,
+    ToggleZen,
+    LeaveZen,
+    SaveScreenshot,
+    CopySelected,
+    CopyCanvasImage,
+    UndoEdit,
+    RedoEdit,
+    SelectRectangle,
+    AddToSelection,
+    ZoomInOut,
+    PanZoomed,
+    ResetZoom,
+    JumpToBookmark,
+    PickForMeasure,
+    CanvasPanelHelp,
+    ControlsPanelHelp,
+    InspectorPanelHelp,
+    MetricsPanelHelp,
+    ScriptPanelHelp,
+    LogPanelHelp,
+    TourFilePicker,
+    TourParameters,
+    TourStepping,
+    TourStep,
+    Done,
+    SkipTour,
+    KeybindingsHeading,
+    // Tool windows
+    Use,
+    Mean,
+    Best,
+    Worst,
+    MustBePositiveInteger,
+    InvalidRunCount,
+    InvalidIterationCount,
+    IterationsPerRun,
+    FinalQuality,
+    CouldNotLoadFont,
+    // Parameter sweep
+    ParameterField,
+    From,
+    To,
+    InvalidRange,
+    InvalidRangeHint,
+    Steps,
+    InvalidStepCount,
+    RunsPerValue,
+    ValueProgress,
+    BestMeanQualityAt,
+    // Multi-run statistics
+    RunsField,
+    RunProgress,
+    FinalQualitySummary,
+    FinalQualityDistribution,
+    Convergence,
+    RunWithSeed,
+    FirstSeed,
+    InvalidSeed,
+    MustBeNonNegativeInteger,
+    EngineTakesNoSeed,
+    // Benchmark
+    WorkerCounts,
+    InvalidWorkerCounts,
+    InvalidWorkerCountsHint,
+    Cycles,
+    InvalidCycleCount,
+    RunBenchmark,
+    Workers,
+    CyclesPerSecond,
+    GainPerSecond,
+    RecommendedWorkerCount,
+    MeasuringProgress,
+    // Script diff
+    OldVersion,
+    NewVersion,
+    ScriptInEditor,
+    IterationsField,
+    Compare,
+    GeneratingBoth,
+    GenerationFailed,
+    QualityOldNew,
+    OldKey,
+    NewKey,
+    SameNamedKey,
+    Moved,
+    OnlyInOld,
+    OnlyInNew,
+    // Performance
+    NoCyclesTimed,
+    EngineCycle,
+    BreakpointChecks,
+    FigureBuild,
+    MicrosecondsPerCycle,
+    EngineCycleNote,
+    MicrosecondsPerCycleAxis,
+    // Export
+    Format,
+    FormatField,
+    SelectedItemsOnly,
+    IncludeLegend,
+    Resolution,
+    GeoGebraCliOnly,
+    // Auto-export
+    ExportWhileRunning,
+    Directory,
+    Every,
+    Iterations,
+    WhenQualityImproves,
+    FilesNamedAfterIteration,
+    ExportedFiles,
+    // Point trajectory
+    TrackSelectedPoint,
+    SelectSinglePoint,
+    XAndY,
+    Path,
+    NoPointTracked,
+    TrackedPoint,
+    // Automation
+    AutomationHelp,
+    // Log
+    Module,
+    Search,
+    // Legend
+    CenteredAt,
+    Through,
+    // Measure
+    MeasureDistanceDirection,
+    MeasureDistance,
+    MeasureAngle,
+    // Parameter sweep notes
+    NoSeedNote,
+    // Export window
+    ExportWindow,
+    // Frozen fit
+    FreezeFit,
+    FreezeFitHint,
+    FreezeHere
+}
+
+impl Text {
+    /// The string in the given language.
+    #[must_use]
+    pub fn tr(self, language: Language) -> &'static str {
+        let [english, polish] = match self {
+            Self::File => ["File", "Plik"],
+            Self::NewFromClipboard => ["New script from clipboard", "Nowy skrypt ze schowka"],
+            Self::SaveScriptAs => ["Save script as...", "Zapisz skrypt jako..."],
+            Self::SaveSession => ["Save session...", "Zapisz sesję..."],
+            Self::LoadSession => ["Load session...", "Wczytaj sesję..."],
+            Self::SaveCheckpoint => ["Save checkpoint...", "Zapisz punkt kontrolny..."],
+            Self::LoadCheckpoint => ["Load checkpoint...", "Wczytaj punkt kontrolny..."],
+            Self::RecordTrace => ["Record trace", "Nagrywaj przebieg"],
+            Self::SaveTrace => ["Save trace...", "Zapisz przebieg..."],
+            Self::Export => ["Export...", "Eksportuj..."],
+            Self::Edit => ["Edit", "Edycja"],
+            Self::Undo => ["Undo (Ctrl+Z)", "Cofnij (Ctrl+Z)"],
+            Self::Redo => ["Redo (Ctrl+Y)", "Ponów (Ctrl+Y)"],
+            Self::View => ["View", "Widok"],
+            Self::ZenMode => ["Zen mode (F11)", "Tryb zen (F11)"],
+            Self::CompactMode => ["Compact mode", "Tryb kompaktowy"],
+            Self::Measure => ["Measure", "Pomiar"],
+            Self::ResetLayout => ["Reset layout", "Przywróć układ"],
+            Self::ResetView => ["Reset zoom", "Przywróć powiększenie"],
+            Self::FloatPanel => ["Float panel", "Panel pływający"],
+            Self::FpsCap => ["FPS cap:", "Limit FPS:"],
+            Self::NoLimit => ["0 for no limit", "0 oznacza brak limitu"],
+            Self::PowerSaving => ["Power saving when idle", "Oszczędzanie energii w bezczynności"],
+            Self::Language => ["Language", "Język"],
+            Self::Tools => ["Tools", "Narzędzia"],
+            Self::Benchmark => ["Benchmark...", "Test wydajności..."],
+            Self::MultiRunStatistics => ["Multi-run statistics...", "Statystyki wielu przebiegów..."],
+            Self::ParameterSweep => ["Parameter sweep...", "Przegląd parametrów..."],
+            Self::ScriptDiff => ["Script diff...", "Porównanie skryptów..."],
+            Self::Automation => ["Automation...", "Automatyzacja..."],
+            Self::AutoExport => ["Auto-export...", "Automatyczny eksport..."],
+            Self::PointTrajectory => ["Point trajectory...", "Trajektoria punktu..."],
+            Self::Performance => ["Performance...", "Wydajność..."],
+            Self::Help => ["Help", "Pomoc"],
+            Self::Keybindings => ["Keybindings (F1)", "Skróty klawiszowe (F1)"],
+            Self::Tour => ["Tour", "Przewodnik"],
+            Self::Canvas => ["Canvas", "Płótno"],
+            Self::Controls => ["Controls", "Sterowanie"],
+            Self::Inspector => ["Inspector", "Inspektor"],
+            Self::Metrics => ["Metrics", "Metryki"],
+            Self::Script => ["Script", "Skrypt"],
+            Self::Log => ["Log", "Dziennik"],
+            Self::Dependencies => ["Dependencies", "Zależności"],
+            Self::Generate => ["Generate", "Generuj"],
+            Self::Quit => ["Quit", "Zakończ"],
+            Self::QuitHint => ["Discard the engine and its history", "Odrzuć silnik i jego historię"],
+            Self::Run => ["Run", "Uruchom"],
+            Self::Pause => ["Pause", "Wstrzymaj"],
+            Self::Resume => ["Resume", "Wznów"],
+            Self::Stop => ["Stop", "Zatrzymaj"],
+            Self::Abort => ["Abort", "Przerwij"],
+            Self::NextStep => ["Next step", "Następny krok"],
+            Self::StopCriteria => ["Stop criteria", "Kryteria zatrzymania"],
+            Self::RunUntilStopped => ["Run until stopped", "Uruchom do zatrzymania"],
+            Self::Adjustables => ["Adjustables", "Parametry dostosowywane"],
+            Self::Magnitudes => ["Magnitudes", "Wielkości kroków"],
+            Self::FigureStatistics => ["Figure statistics", "Statystyki figury"],
+            Self::Degeneracies => ["Degeneracies", "Zdegenerowania"],
+            Self::Legend => ["Legend", "Legenda"],
+            Self::ItemColors => ["Item colors", "Kolory elementów"],
+            Self::ItemStyle => ["Item style", "Styl elementów"],
+            Self::LabelFont => ["Label font", "Czcionka etykiet"],
+            Self::Selection => ["Selection", "Zaznaczenie"],
+            Self::Annotations => ["Annotations", "Adnotacje"],
+            Self::Bookmarks => ["View bookmarks", "Zakładki widoku"],
+            Self::Comparison => ["A/B comparison", "Porównanie A/B"],
+            Self::Golden => ["Golden state", "Stan wzorcowy"],
+            Self::Breakpoints => ["Breakpoints", "Punkty przerwania"],
+            Self::Bisect => ["Bisect", "Bisekcja"],
+            Self::TimeTravel => ["Time travel", "Podróż w czasie"],
+            Self::Overlays => ["Overlays", "Nakładki"],
+            Self::Projection => ["Projection", "Rzutowanie"],
+            Self::WorkerSilent => ["The worker hasn't responded for {}", "Wątek roboczy nie odpowiada od {}"],
+            Self::WorkerStuckHint => [
+                "A cycle may be taking very long, or the worker may be stuck.",
+                "Cykl może trwać bardzo długo albo wątek roboczy mógł się zawiesić."
+            ],
+            Self::AbortHint => [
+                "Discard the runtime without waiting for the worker",
+                "Odrzuć środowisko wykonawcze bez czekania na wątek roboczy"
+            ],
+            Self::WorkerPanicked => ["The generation worker panicked", "Wątek generowania uległ awarii"],
+            Self::AtIteration => ["At iteration {}:", "W iteracji {}:"],
+            Self::PausedUnattended => [
+                "Paused unattended: iteration {}, quality {}, {} steps/s",
+                "Wstrzymano bez nadzoru: iteracja {}, jakość {}, {} kroków/s"
+            ],
+            Self::RunningUnattended => [
+                "Running unattended: iteration {}, quality {}, {} steps/s",
+                "Działa bez nadzoru: iteracja {}, jakość {}, {} kroków/s"
+            ],
+            Self::EstimatedTimeLeft => ["Estimated time left: {}", "Szacowany pozostały czas: {}"],
+            Self::LastRunEnded => ["Last run ended: {}.", "Ostatni przebieg zakończył się: {}."],
+            Self::IterationQuality => ["Iteration {}, quality {}", "Iteracja {}, jakość {}"],
+            Self::MaxAdjustment => ["Max adjustment", "Maks. dostosowanie"],
+            Self::RunPublishesEvery => ["Run publishes every", "Uruchomienie publikuje co"],
+            Self::CyclesOr => ["cycles or", "cykli lub"],
+            Self::BatchingHint => [
+                "Run mode cycles independently of the frame rate and shows the latest state at this rate.",
+                "Tryb uruchomienia wykonuje cykle niezależnie od liczby klatek \
+                i pokazuje najnowszy stan z tą częstotliwością."
+            ],
+            Self::StopRunAfter => ["Stop Run after", "Zatrzymaj po"],
+            Self::TimeBudgetHint => [
+                "Generation time, not counting pauses, after which Run stops on its own.",
+                "Czas generowania bez przerw, po którym uruchomienie zatrzyma się samo."
+            ],
+            Self::FileLabel => ["File:", "Plik:"],
+            Self::Change => ["Change", "Zmień"],
+            Self::UnsavedScript => ["Unsaved script", "Niezapisany skrypt"],
+            Self::Open => ["Open", "Otwórz"],
+            Self::WorkerCount => ["Worker count:", "Liczba wątków roboczych:"],
+            Self::Auto => ["Auto", "Auto"],
+            Self::AutoWorkersHint => [
+                "Pick a count from the number of cores and the size of the figure.",
+                "Dobierz liczbę na podstawie liczby rdzeni i rozmiaru figury."
+            ],
+            Self::MaximumAdjustment => ["Maximum adjustment:", "Maksymalne dostosowanie:"],
+            Self::Engine => ["Engine:", "Silnik:"],
+            Self::MockHint => [
+                "The mock ignores the script and draws nothing, but runs the same way every time.",
+                "Atrapa ignoruje skrypt i niczego nie rysuje, ale za każdym razem działa tak samo."
+            ],
+            Self::GenerationThread => ["Generation thread:", "Wątek generowania:"],
+            Self::LowPriority => ["Low priority", "Niski priorytet"],
+            Self::LowPriorityHint => [
+                "Keep the UI responsive while generating.",
+                "Zachowaj płynność interfejsu podczas generowania."
+            ],
+            Self::AnyCore => ["Any core", "Dowolny rdzeń"],
+            Self::Core => ["Core {}", "Rdzeń {}"],
+            Self::PinCoreHint => [
+                "Pin the thread to a core, for more stable benchmarks.",
+                "Przypnij wątek do rdzenia, aby testy wydajności były stabilniejsze."
+            ],
+            Self::HistoryBudget => ["History budget:", "Budżet historii:"],
+            Self::HistoryBudgetHint => [
+                "Past this, older quality, acceptance, magnitude and timing reports are thinned out.",
+                "Powyżej tego starsze raporty jakości, akceptacji, wielkości kroków i czasów są przerzedzane."
+            ],
+            Self::WatchdogTimeout => ["Watchdog timeout:", "Limit czasu nadzorcy:"],
+            Self::WatchdogTimeoutHint => [
+                "How long a busy worker may go without reporting before it's shown as stuck.",
+                "Jak długo zajęty wątek roboczy może nie raportować, zanim zostanie uznany za zawieszony."
+            ],
+            Self::Server => ["Server:", "Serwer:"],
+            Self::Local => ["local", "lokalnie"],
+            Self::ServerHint => [
+                "Address of a geo-aid-dbg --serve instance. The worker count is set by the server.",
+                "Adres instancji geo-aid-dbg --serve. Liczbę wątków roboczych ustala serwer."
+            ],
+            Self::FixParameters => [
+                "Fix the parameters marked in red first.",
+                "Najpierw popraw parametry zaznaczone na czerwono."
+            ],
+            Self::ParamWorkerCount => ["worker count", "Liczba wątków roboczych"],
+            Self::ParamMaxAdjustment => ["max adjustment", "Maksymalne dostosowanie"],
+            Self::ErrorIo => ["Could not access {}: {}", "Nie można uzyskać dostępu do {}: {}"],
+            Self::ErrorParse => ["The {} \"{}\" isn't a number", "{}: „{}” nie jest liczbą"],
+            Self::ScriptFailedToCompile => ["The script failed to compile", "Nie udało się skompilować skryptu"],
+            Self::AndMore => ["(and {} more)", "(i {} więcej)"],
+            Self::ErrorEngineInit => ["The engine could not start: {}", "Nie udało się uruchomić silnika: {}"],
+            Self::CouldNotConnect => ["could not connect to {}: {}", "nie udało się połączyć z {}: {}"],
+            Self::BuiltWithoutRemote => ["built without the remote feature", "zbudowano bez funkcji zdalnej"],
+            Self::ErrorInvalidParam => ["The {} {}", "{} {}"],
+            Self::MustBeAtLeastOne => ["must be at least 1", "musi wynosić co najmniej 1"],
+            Self::MustBePositive => ["must be positive and finite", "musi być dodatnie i skończone"],
+            Self::HintIo => [
+                "Check that the file exists and can be accessed.",
+                "Sprawdź, czy plik istnieje i jest dostępny."
+            ],
+            Self::HintWholeNumber => ["Enter a whole number, like 512.", "Wpisz liczbę całkowitą, np. 512."],
+            Self::HintNumber => ["Enter a number, like 0.5.", "Wpisz liczbę, np. 0.5."],
+            Self::HintCompile => [
+                "See the script tab for the compiler's errors.",
+                "Błędy kompilatora są widoczne w karcie skryptu."
+            ],
+            Self::HintEngineInit => [
+                "Check the server address, or the script if generating locally.",
+                "Sprawdź adres serwera albo skrypt, jeśli generujesz lokalnie."
+            ],
+            Self::HintAtLeastOneWorker => ["Use at least one worker.", "Użyj co najmniej jednego wątku roboczego."],
+            Self::HintPositive => ["Use a positive number, like 0.5.", "Użyj liczby dodatniej, np. 0.5."],
+            Self::PausedAtBreakpoint => ["Paused at iteration {}: {}", "Wstrzymano w iteracji {}: {}"],
+            Self::PausedAtNonFinite => [
+                "Paused at iteration {}: {} adjustable(s) became NaN/Inf",
+                "Wstrzymano w iteracji {}: parametry dostosowywane z wartością NaN/Inf: {}"
+            ],
+            Self::ProjectedNonFinite => ["Projected to NaN/Inf: {}", "Rzutowane na NaN/Inf: {}"],
+            Self::BakedFor => [
+                "Baked for max adjustment {}. Edits last until it's re-baked.",
+                "Wyliczone dla maks. dostosowania {}. Zmiany obowiązują do ponownego wyliczenia."
+            ],
+            Self::ReHeat => ["Re-heat", "Podgrzej ponownie"],
+            Self::ReHeatHint => ["Re-bake from the max adjustment", "Wylicz ponownie z maks. dostosowania"],
+            Self::ReHeatOverridesHint => [
+                "Re-bake the magnitudes from the max adjustment, dropping overrides",
+                "Wylicz ponownie wielkości kroków z maks. dostosowania, odrzucając ręczne zmiany"
+            ],
+            Self::Points => ["Points", "Punkty"],
+            Self::Lines => ["Lines", "Proste"],
+            Self::Segments => ["Segments", "Odcinki"],
+            Self::Rays => ["Rays", "Półproste"],
+            Self::Circles => ["Circles", "Okręgi"],
+            Self::Labels => ["Labels", "Etykiety"],
+            Self::NoLabelledItems => ["No labelled items.", "Brak elementów z etykietami."],
+            Self::Palette => ["Palette", "Paleta"],
+            Self::Monochrome => ["Monochrome", "Monochromatyczna"],
+            Self::OkabeIto => ["Okabe-Ito", "Okabe-Ito"],
+            Self::TolBright => ["Tol bright", "Tol jasna"],
+            Self::HighContrast => ["High contrast", "Wysoki kontrast"],
+            Self::HighContrastHint => ["Thicker strokes and larger labels", "Grubsze linie i większe etykiety"],
+            Self::Reset => ["Reset", "Przywróć"],
+            Self::StrokeWidth => ["Stroke width", "Grubość linii"],
+            Self::PointRadius => ["Point radius", "Promień punktu"],
+            Self::Dots => ["Dots", "Kropki"],
+            Self::DotFilled => ["Filled", "Wypełnione"],
+            Self::DotHollow => ["Hollow", "Puste"],
+            Self::DotCross => ["Cross", "Krzyżyki"],
+            Self::ScaleWithCanvas => ["Scale with canvas", "Skaluj z płótnem"],
+            Self::ScaleWithCanvasHint => [
+                "Grow strokes and dots with the canvas size, like labels",
+                "Powiększaj linie i kropki razem z płótnem, tak jak etykiety"
+            ],
+            Self::LevelOfDetail => ["Level of detail", "Poziom szczegółów"],
+            Self::LevelOfDetailHint => [
+                "Leave out labels when there are too many of them or they'd be too small to read",
+                "Pomijaj etykiety, gdy jest ich za dużo lub byłyby za małe, by je odczytać"
+            ],
+            Self::SystemFonts => ["System fonts", "Czcionki systemowe"],
+            Self::Pick => ["Pick...", "Wybierz..."],
+            Self::NoUnicodeFont => [
+                "No Unicode font found, labels outside ASCII may not render.",
+                "Nie znaleziono czcionki Unicode, etykiety spoza ASCII mogą się nie wyświetlić."
+            ],
+            Self::NoUnicodeFontHint => [
+                "Pick a TrueType font covering the characters you need",
+                "Wybierz czcionkę TrueType zawierającą potrzebne znaki"
+            ],
+            Self::Size => ["Size", "Rozmiar"],
+            Self::FixedCanvasSize => ["Fixed canvas size", "Stały rozmiar płótna"],
+            Self::FixedCanvasSizeHint => [
+                "Project to the size of the final output instead of the canvas tab",
+                "Rzutuj na rozmiar docelowego wyniku zamiast karty płótna"
+            ],
+            Self::AspectRatio => ["Aspect ratio", "Proporcje"],
+            Self::Free => ["Free", "Dowolne"],
+            Self::Square => ["Square", "Kwadrat"],
+            Self::A4Portrait => ["A4 portrait", "A4 pionowo"],
+            Self::A4Landscape => ["A4 landscape", "A4 poziomo"],
+            Self::Margin => ["Margin:", "Margines:"],
+            Self::ContributingWorkers => [
+                "{} of {} workers had a proposal taken",
+                "{} z {} wątków miało przyjętą propozycję"
+            ],
+            Self::WorkerDetails => [
+                "Worker #{}\nLatest proposal quality: {}\nTaken: {}\nRejected: {}",
+                "Wątek #{}\nJakość ostatniej propozycji: {}\nPrzyjęte: {}\nOdrzucone: {}"
+            ],
+            Self::Proposals => ["Proposals", "Propozycje"],
+            Self::StepThroughProposals => ["Step through proposals", "Krokuj po propozycjach"],
+            Self::NoProposalsYet => [
+                "Step to see what the workers propose in the next cycle.",
+                "Wykonaj krok, aby zobaczyć, co wątki zaproponują w następnym cyklu."
+            ],
+            Self::PreviousProposal => ["Previous proposal", "Poprzednia propozycja"],
+            Self::NextProposal => ["Next proposal", "Następna propozycja"],
+            Self::TakenProposal => ["Taken proposal", "Przyjęta propozycja"],
+            Self::ProposalOf => ["Proposal {} of {} in iteration {}", "Propozycja {} z {} w iteracji {}"],
+            Self::ProposalDetails => ["Worker #{}, quality {}", "Wątek #{}, jakość {}"],
+            Self::ProposalTaken => ["Taken", "Przyjęta"],
+            Self::ProposalDropped => ["Dropped", "Odrzucona"],
+            Self::NothingGenerated => ["Nothing generated yet.", "Jeszcze niczego nie wygenerowano."],
+            Self::LogScale => ["Log scale", "Skala logarytmiczna"],
+            Self::LogScaleHint => [
+                "Plot log10(1 - quality), so that the tail of convergence stays visible",
+                "Rysuj log10(1 - jakość), aby końcówka zbieżności pozostała widoczna"
+            ],
+            Self::TargetLine => ["Target line", "Linia celu"],
+            Self::PlotHint => [
+                "Drag to pan, scroll to zoom, double-click to reset.",
+                "Przeciągnij, aby przesunąć, przewiń, aby powiększyć, kliknij dwukrotnie, aby przywrócić."
+            ],
+            Self::Iteration => ["Iteration", "Iteracja"],
+            Self::Quality => ["Quality", "Jakość"],
+            Self::LogError => ["log10(1 - quality)", "log10(1 - jakość)"],
+            Self::Target => ["Target", "Cel"],
+            Self::Magnitude => ["Magnitude", "Wielkość kroku"],
+            Self::AcceptanceRate => ["Acceptance rate", "Współczynnik akceptacji"],
+            Self::Save => ["Save", "Zapisz"],
+            Self::SaveAs => ["Save as...", "Zapisz jako..."],
+            Self::ChangesApplyNext => [
+                "Changes apply on the next generation.",
+                "Zmiany zostaną zastosowane przy następnym generowaniu."
+            ],
+            Self::GoToError => ["Go to the error", "Przejdź do błędu"],
+            Self::AspectFourThree => ["4:3", "4:3"],
+            Self::AspectSixteenNine => ["16:9", "16:9"],
+            Self::WorkerCountParam => ["Worker count", "Liczba wątków roboczych"],
+            Self::DebuggerSession => ["Debugger session", "Sesja debugera"],
+            Self::EngineCheckpoint => ["Engine checkpoint", "Punkt kontrolny silnika"],
+            Self::AdjustableTrace => ["Adjustable trace", "Przebieg parametrów dostosowywanych"],
+            Self::TrueTypeFont => ["TrueType font", "Czcionka TrueType"],
+            Self::AllFiles => ["All files", "Wszystkie pliki"],
+            Self::SaveSessionTitle => ["Save session", "Zapisz sesję"],
+            Self::LoadSessionTitle => ["Load session", "Wczytaj sesję"],
+            Self::SaveCheckpointTitle => ["Save checkpoint", "Zapisz punkt kontrolny"],
+            Self::LoadCheckpointTitle => ["Load checkpoint", "Wczytaj punkt kontrolny"],
+            Self::SaveTraceTitle => ["Save trace", "Zapisz przebieg"],
+            Self::SaveGoldenTitle => ["Save golden state", "Zapisz stan wzorcowy"],
+            Self::LoadGoldenTitle => ["Load golden state", "Wczytaj stan wzorcowy"],
+            Self::PickLabelFontTitle => ["Pick label font", "Wybierz czcionkę etykiet"],
+            Self::PickExportDirTitle => ["Pick a directory to export into", "Wybierz katalog eksportu"],
+            Self::PickOldScriptTitle => ["Pick the old version of the script", "Wybierz starą wersję skryptu"],
+            Self::ExportFigureTitle => ["Export figure", "Eksportuj figurę"],
+            Self::SaveScriptTitle => ["Save script", "Zapisz skrypt"],
+            Self::OpenScriptTitle => ["Open a GeoScript file", "Otwórz plik GeoScript"],
+            Self::CouldNotSaveSession => ["Could not save session: {}", "Nie udało się zapisać sesji: {}"],
+            Self::CouldNotLoadSession => ["Could not load session: {}", "Nie udało się wczytać sesji: {}"],
+            Self::CouldNotSaveCheckpoint => [
+                "Could not save checkpoint: {}",
+                "Nie udało się zapisać punktu kontrolnego: {}"
+            ],
+            Self::CouldNotLoadCheckpoint => [
+                "Could not load checkpoint: {}",
+                "Nie udało się wczytać punktu kontrolnego: {}"
+            ],
+            Self::CheckpointMismatch => [
+                "Could not load checkpoint: it has {} adjustables, the script has {}",
+                "Nie udało się wczytać punktu kontrolnego: ma {} parametrów dostosowywanych, a skrypt {}"
+            ],
+            Self::CouldNotSaveTrace => ["Could not save trace: {}", "Nie udało się zapisać przebiegu: {}"],
+            Self::CouldNotSaveGolden => [
+                "Could not save golden state: {}",
+                "Nie udało się zapisać stanu wzorcowego: {}"
+            ],
+            Self::CouldNotLoadGolden => [
+                "Could not load golden state: {}",
+                "Nie udało się wczytać stanu wzorcowego: {}"
+            ],
+            Self::CouldNotExport => ["Could not export: {}", "Nie udało się wyeksportować: {}"],
+            Self::CouldNotReadFile => ["Could not read {}: {}", "Nie udało się odczytać {}: {}"],
+            Self::OldVersionFailed => [
+                "The old version failed to compile: {}",
+                "Nie udało się skompilować starej wersji: {}"
+            ],
+            Self::FixSetupFirst => ["Fix the setup first.", "Najpierw popraw ustawienia."],
+            Self::ClipboardEmpty => ["The clipboard holds no text", "Schowek nie zawiera tekstu"],
+            Self::RecordTraceHint => [
+                "Record every state change for replaying with --replay",
+                "Zapisuj każdą zmianę stanu do odtworzenia z --replay"
+            ],
+            Self::FloatPanelHint => [
+                "Float a panel in a movable window over the debugger. Tabs can also be dragged out of the dock. \
+                Panels stay inside the debugger's window, so they can't be moved to another monitor.",
+                "Przenieś panel do ruchomego okna nad debugerem. Karty można też wyciągnąć z doku. \
+                Panele pozostają w oknie debugera, więc nie można ich przenieść na inny monitor."
+            ],
+            Self::TimeBudgetSpent => [
+                "Time budget spent, Run stopped",
+                "Limit czasu wyczerpany, uruchomienie zatrzymane"
+            ],
+            Self::Saved => ["Saved {}", "Zapisano {}"],
+            Self::CouldNotScreenshot => ["Could not take a screenshot: {}", "Nie udało się zrobić zrzutu ekranu: {}"],
+            Self::CopiedCanvas => ["Copied the canvas", "Skopiowano płótno"],
+            Self::CopiedItems => ["Copied {} item(s)", "Skopiowane elementy: {}"],
+            Self::Next => ["Next", "Dalej"],
+            Self::ExitCompact => ["Exit compact", "Zakończ tryb kompaktowy"],
+            Self::QuitTitle => ["Quit?", "Zakończyć?"],
+            Self::QuitLoses => [
+                "The engine at iteration {} and its history will be lost.",
+                "Silnik w iteracji {} i jego historia zostaną utracone."
+            ],
+            Self::StopUnattendedFirst => ["Stop the unattended run first", "Najpierw zatrzymaj przebieg bez nadzoru"],
+            Self::Cancel => ["Cancel", "Anuluj"],
+            Self::StatusIteration => ["Iteration: {}", "Iteracja: {}"],
+            Self::StatusQuality => ["Quality: {}", "Jakość: {}"],
+            Self::StatusStepsPerSecond => ["Steps/s: {}", "Kroki/s: {}"],
+            Self::GeneratingFor => ["Generating for {}", "Generowanie od {}"],
+            Self::GeneratingForHint => [
+                "Time the worker spent generating, not counting pauses.",
+                "Czas generowania przez wątek roboczy bez przerw."
+            ],
+            Self::Eta => ["ETA: {}", "Pozostało: {}"],
+            Self::StatusMemory => ["Memory: {}", "Pamięć: {}"],
+            Self::MemoryBreakdown => [
+                "Frames: {}\nArchive: {}\nLogs: {}\nTrace: {}\nFigure: {}\nBudget: {} MiB",
+                "Klatki: {}\nArchiwum: {}\nDzienniki: {}\nPrzebieg: {}\nFigura: {}\nBudżet: {} MiB"
+            ],
+            Self::WorkerBusy => ["Worker busy for {}", "Wątek roboczy zajęty od {}"],
+            Self::RunEnded => ["Run ended: {}", "Przebieg zakończony: {}"],
+            Self::NoRuntime => ["No runtime", "Brak środowiska wykonawczego"],
+            Self::Cursor => ["Cursor: ({}, {})", "Kursor: ({}, {})"],
+            Self::Measured => ["Measured {}", "Zmierzono {}"],
+            Self::Fps => ["FPS: {}", "FPS: {}"],
+            Self::LabelsHidden => ["Labels hidden (level of detail)", "Etykiety ukryte (poziom szczegółów)"],
+            Self::NonFiniteItems => ["{} item(s) projected to NaN/Inf", "Elementy rzutowane na NaN/Inf: {}"],
+            Self::TargetReached => ["target quality reached", "osiągnięto docelową jakość"],
+            Self::MaxIterationsDone => ["max iterations done", "wykonano maksymalną liczbę iteracji"],
+            Self::TimeLimitExceeded => ["time limit exceeded", "przekroczono limit czasu"],
+            Self::QualityStagnated => ["quality stagnated", "jakość przestała rosnąć"],
+            Self::PausedByBreakpoint => ["paused by a breakpoint", "wstrzymano przez punkt przerwania"],
+            Self::StoppedByUser => ["stopped by the user", "zatrzymano przez użytkownika"],
+            Self::TargetQualityField => ["Target quality:", "Docelowa jakość:"],
+            Self::MaxIterationsField => ["Max iterations:", "Maks. liczba iteracji:"],
+            Self::TimeLimitField => ["Time limit (s):", "Limit czasu (s):"],
+            Self::StagnationWindowField => ["Stagnation window:", "Okno stagnacji:"],
+            Self::StagnationWindowHint => [
+                "Stop if quality improves by less than the minimum over this many iterations",
+                "Zatrzymaj, jeśli jakość wzrośnie o mniej niż minimum w ciągu tylu iteracji"
+            ],
+            Self::MinimumImprovementField => ["Minimum improvement:", "Minimalna poprawa:"],
+            Self::InvalidTargetQuality => ["Invalid target quality", "Nieprawidłowa docelowa jakość"],
+            Self::InvalidMaxIterations => ["Invalid max iterations", "Nieprawidłowa maks. liczba iteracji"],
+            Self::InvalidTimeLimit => ["Invalid time limit", "Nieprawidłowy limit czasu"],
+            Self::InvalidStagnationWindow => ["Invalid stagnation window", "Nieprawidłowe okno stagnacji"],
+            Self::InvalidMinimumImprovement => ["Invalid minimum improvement", "Nieprawidłowa minimalna poprawa"],
+            Self::Remove => ["Remove", "Usuń"],
+            Self::Clear => ["Clear", "Wyczyść"],
+            Self::ClearAll => ["Clear all", "Wyczyść wszystko"],
+            Self::Add => ["Add", "Dodaj"],
+            Self::Adjustable => ["Adjustable", "Parametr dostosowywany"],
+            Self::Step => ["Step", "Krok"],
+            Self::Restore => ["Restore", "Przywróć"],
+            Self::NotSupportedByEngine => ["Not supported by this engine", "Nieobsługiwane przez ten silnik"],
+            Self::ItemPoint => ["point", "punkt"],
+            Self::ItemLine => ["line", "prosta"],
+            Self::ItemSegment => ["segment", "odcinek"],
+            Self::ItemRay => ["ray", "półprosta"],
+            Self::ItemCircle => ["circle", "okrąg"],
+            Self::ItemPointNumber => ["point #{}", "punkt #{}"],
+            Self::BpQuality => ["quality", "jakość"],
+            Self::BpAdjustable => ["adjustable #{}", "parametr #{}"],
+            Self::BpBecomesNonFinite => ["{} becomes NaN/Inf", "{} staje się NaN/Inf"],
+            Self::BpLeavesRange => ["{} leaves [{}, {}]", "{} opuszcza [{}, {}]"],
+            Self::BecomesNonFinite => ["becomes NaN/Inf", "staje się NaN/Inf"],
+            Self::LeavesRange => ["leaves range", "opuszcza zakres"],
+            Self::Detect => ["Detect", "Wykrywaj"],
+            Self::Tolerance => ["Tolerance:", "Tolerancja:"],
+            Self::ToleranceHint => [
+                "Lengths below this many canvas units count as zero",
+                "Długości poniżej tylu jednostek płótna liczą się jako zero"
+            ],
+            Self::NothingDegenerate => ["Nothing degenerate.", "Brak zdegenerowanych elementów."],
+            Self::Coincide => ["{} and {} coincide", "{} i {} się pokrywają"],
+            Self::ZeroRadius => ["{} has zero radius", "{} ma zerowy promień"],
+            Self::ZeroLength => ["{} has zero length", "{} ma zerową długość"],
+            Self::FlatTriangle => ["triangle of {}, {} and {} is flat", "trójkąt z {}, {} i {} jest płaski"],
+            Self::Rules => ["Rules", "Reguły"],
+            Self::Rule => ["Rule", "Reguła"],
+            Self::RuleError => ["Error", "Błąd"],
+            Self::RuleWeight => ["Weight", "Waga"],
+            Self::RuleErrors => ["Rule errors", "Błędy reguł"],
+            Self::RuleErrorsHint => [
+                "Plot the error of each rule stacked over iterations",
+                "Wykreśl błędy poszczególnych reguł, ułożone jedne na drugich"
+            ],
+            Self::ErrorBreakdown => ["Error breakdown", "Podział błędu"],
+            Self::ErrorBreakdownHint => [
+                "The current error by rule. Click a rule to pick it and select its items.",
+                "Obecny błąd według reguł. Kliknij regułę, aby ją wybrać i zaznaczyć jej elementy."
+            ],
+            Self::AllRulesSatisfied => ["All rules are satisfied.", "Wszystkie reguły są spełnione."],
+            Self::ErrorShare => ["{}\nError: {} ({}%)", "{}\nBłąd: {} ({}%)"],
+            Self::RulesHint => [
+                "Click a rule to highlight its items. Rules referring to selected items are marked.",
+                "Kliknij regułę, aby podświetlić jej elementy. Reguły zaznaczonych elementów są wyróżnione."
+            ],
+            Self::RefersTo => ["Refers to: {}", "Odnosi się do: {}"],
+            Self::RuleEnabledHint => ["Count the rule toward the quality", "Wliczaj regułę do jakości"],
+            Self::DependenciesHint => [
+                "Entities follow what they're derived from, rules come last. Click a node to select its item or rule.",
+                "Elementy stoją za swoimi źródłami, reguły na końcu. Kliknij węzeł, by zaznaczyć element lub regułę."
+            ],
+            Self::Profiler => ["Profiler", "Profiler"],
+            Self::ProfileExpressions => ["Profile expressions", "Profiluj wyrażenia"],
+            Self::ProfileWindow => ["Window (cycles):", "Okno (cykle):"],
+            Self::NoProfileYet => ["No window profiled yet.", "Żadne okno nie zostało jeszcze sprofilowane."],
+            Self::ProfiledCycles => [
+                "{} cycles up to iteration {}. Click a rule to highlight its items.",
+                "{} cykli do iteracji {}. Kliknij regułę, aby podświetlić jej elementy."
+            ],
+            Self::Expression => ["Expression", "Wyrażenie"],
+            Self::Evaluations => ["Evaluations", "Obliczenia"],
+            Self::ShareOfTime => ["Share of time", "Udział w czasie"],
+            Self::ItemsSelected => [
+                "{} item(s) selected. Drag on the canvas to select, with Shift to add.",
+                "Zaznaczone elementy: {}. Przeciągnij po płótnie, aby zaznaczyć, z Shift, aby dodać."
+            ],
+            Self::Hide => ["Hide", "Ukryj"],
+            Self::Lock => ["Lock", "Zablokuj"],
+            Self::LockHint => ["Exclude from selection and measuring", "Wyklucz z zaznaczania i pomiarów"],
+            Self::Deselect => ["Deselect", "Odznacz"],
+            Self::ApplyColor => ["Apply color", "Zastosuj kolor"],
+            Self::ShowHidden => ["Show hidden", "Pokaż ukryte"],
+            Self::UnlockAll => ["Unlock all", "Odblokuj wszystkie"],
+            Self::AnnotateOnCanvas => ["Annotate on canvas", "Adnotacje na płótnie"],
+            Self::AnnotationText => ["Text", "Tekst"],
+            Self::Arrow => ["Arrow", "Strzałka"],
+            Self::Freehand => ["Freehand", "Odręcznie"],
+            Self::Note => ["Note:", "Notatka:"],
+            Self::TextNote => ["Text \"{}\"", "Tekst „{}”"],
+            Self::FreehandMark => ["Freehand mark", "Odręczny znak"],
+            Self::MarkA => ["Mark A", "Oznacz A"],
+            Self::MarkB => ["Mark B", "Oznacz B"],
+            Self::MarkedFrame => ["{}: iteration {}, quality {}", "{}: iteracja {}, jakość {}"],
+            Self::ShowAAndB => ["Show A (grey) and B on the canvas", "Pokaż A (szare) i B na płótnie"],
+            Self::QualityDelta => ["Quality delta: {}", "Różnica jakości: {}"],
+            Self::DifferentScripts => [
+                "The frames come from different scripts.",
+                "Klatki pochodzą z różnych skryptów."
+            ],
+            Self::Delta => ["Delta", "Różnica"],
+            Self::SaveAsGolden => ["Save as golden...", "Zapisz jako wzorzec..."],
+            Self::LoadGolden => ["Load golden...", "Wczytaj wzorzec..."],
+            Self::NoGolden => ["No golden state loaded.", "Nie wczytano stanu wzorcowego."],
+            Self::GoldenOf => ["Golden of {}", "Wzorzec dla {}"],
+            Self::GoldenFrame => ["Golden: iteration {}, quality {}", "Wzorzec: iteracja {}, jakość {}"],
+            Self::QualityDifference => ["Quality difference: {}", "Różnica jakości: {}"],
+            Self::ShowGoldenPoints => ["Show golden points on the canvas", "Pokaż punkty wzorca na płótnie"],
+            Self::GoldenPointCount => [
+                "The golden has {} points, the figure has {}.",
+                "Wzorzec ma {} punktów, a rysunek {}."
+            ],
+            Self::PointDeviation => [
+                "Point deviation: mean {}, max {} (of {} units)",
+                "Odchylenie punktów: średnio {}, maks. {} (z {} jednostek)"
+            ],
+            Self::WarnWhenExceeds => ["Warn when an adjustable exceeds", "Ostrzegaj, gdy parametr przekroczy"],
+            Self::InAbsoluteValue => ["In absolute value", "Co do wartości bezwzględnej"],
+            Self::PauseWhenItDoes => ["Pause when it does", "Wtedy wstrzymaj"],
+            Self::OutOfBounds => ["Out of bounds: {}", "Poza zakresem: {}"],
+            Self::DropsBelow => ["drops below", "spada poniżej"],
+            Self::RisesAbove => ["rises above", "rośnie powyżej"],
+            Self::Start => ["Start", "Start"],
+            Self::BisectHint => ["Bisect over the recorded frames", "Bisekcja po nagranych klatkach"],
+            Self::AlreadyCrossed => [
+                "Already crossed at the oldest recorded iteration, #{}.",
+                "Przekroczono już w najstarszej nagranej iteracji, #{}."
+            ],
+            Self::NotCrossed => [
+                "Not crossed at the latest iteration, #{}.",
+                "Nie przekroczono w ostatniej iteracji, #{}."
+            ],
+            Self::RecordTwoFrames => ["Record at least two frames first.", "Najpierw nagraj co najmniej dwie klatki."],
+            Self::GoodBad => ["Good at #{}, bad at #{} after {} step(s).", "Dobra w #{}, zła w #{} po krokach: {}."],
+            Self::FirstCrossed => [
+                "First crossed between iterations #{} ({}) and #{} ({}).",
+                "Pierwsze przekroczenie między iteracjami #{} ({}) i #{} ({})."
+            ],
+            Self::RestoreLastGood => ["Restore last good", "Przywróć ostatnią dobrą"],
+            Self::RestoreLastGoodHint => [
+                "Resume the engine from the last good frame",
+                "Wznów silnik od ostatniej dobrej klatki"
+            ],
+            Self::Testing => ["Testing #{}: {}, {}.", "Sprawdzanie #{}: {}, {}."],
+            Self::Crossed => ["crossed", "przekroczono"],
+            Self::NotCrossedShort => ["not crossed", "nie przekroczono"],
+            Self::RunToEnd => ["Run to end", "Do końca"],
+            Self::MarkGood => ["Mark good", "Oznacz jako dobrą"],
+            Self::MarkBad => ["Mark bad", "Oznacz jako złą"],
+            Self::NoStatesArchived => ["No states archived yet.", "Nie zarchiwizowano jeszcze stanów."],
+            Self::State => ["State", "Stan"],
+            Self::StatesArchived => ["{} state(s) archived, taking {}.", "Zarchiwizowane stany: {}, zajmują {}."],
+            Self::RestoreStateHint => ["Resume the engine from this state", "Wznów silnik od tego stanu"],
+            Self::NoArchivedState => ["no archived state #{}", "brak zarchiwizowanego stanu #{}"],
+            Self::NoKeyframe => [
+                "no full state precedes archived state #{}",
+                "zarchiwizowany stan #{} nie ma poprzedzającego pełnego stanu"
+            ],
+            Self::CorruptState => [
+                "archived state #{} doesn't match its full state",
+                "zarchiwizowany stan #{} nie pasuje do swojego pełnego stanu"
+            ],
+            Self::GhostsOverlay => ["Ghosts of previous iterations", "Cienie poprzednich iteracji"],
+            Self::GhostCount => ["Ghost count", "Liczba cieni"],
+            Self::MovementOverlay => ["Movement vectors", "Wektory ruchu"],
+            Self::VectorScale => ["Vector scale", "Skala wektorów"],
+            Self::GridOverlay => ["Grid and scale bar", "Siatka i podziałka"],
+            Self::GridSpacing => ["Grid spacing", "Odstęp siatki"],
+            Self::Units => ["{} units", "{} jednostek"],
+            Self::HeatmapOverlay => ["Rule error heatmap", "Mapa ciepła błędów reguł"],
+            Self::Go => ["Go", "Idź"],
+            Self::Update => ["Update", "Aktualizuj"],
+            Self::UpdateBookmarkHint => ["Set to the current view", "Ustaw na bieżący widok"],
+            Self::SaveCurrentView => ["Save current view", "Zapisz bieżący widok"],
+            Self::ViewNumber => ["View {}", "Widok {}"],
+            Self::BookmarkKeysHint => [
+                "Press 1-9 over the canvas to jump to a bookmark.",
+                "Naciśnij 1-9 nad płótnem, aby przejść do zakładki."
+            ],
+            Self::HelpTitle => ["Help", "Pomoc"],
+            Self::Panels => ["Panels", "Panele"],
+            Self::KeyF1 => ["F1", "F1"],
+            Self::KeyF11 => ["F11", "F11"],
+            Self::KeyEsc => ["Esc", "Esc"],
+            Self::KeyF12 => ["F12", "F12"],
+            Self::KeyCtrlC => ["Ctrl+C", "Ctrl+C"],
+            Self::KeyCtrlShiftC => ["Ctrl+Shift+C", "Ctrl+Shift+C"],
+            Self::KeyCtrlZ => ["Ctrl+Z", "Ctrl+Z"],
+            Self::KeyRedo => ["Ctrl+Y, Ctrl+Shift+Z", "Ctrl+Y, Ctrl+Shift+Z"],
+            Self::KeyDrag => ["Drag on canvas", "Przeciąganie po płótnie"],
+            Self::KeyShiftDrag => ["Shift+drag", "Shift+przeciąganie"],
+            Self::KeyScroll => ["Scroll on canvas", "Przewijanie na płótnie"],
+            Self::KeyMiddleDrag => ["Middle-drag on canvas", "Przeciąganie środkowym przyciskiem"],
+            Self::KeyHome => ["Home", "Home"],
+            Self::KeyDigits => ["1-9 over canvas", "1-9 nad płótnem"],
+            Self::KeyMeasureClick => ["Click (measure mode)", "Kliknięcie (tryb pomiaru)"],
+            Self::ToggleHelp => ["Show or hide this help", "Pokaż lub ukryj tę pomoc"],
+            Self::ToggleZen => ["Toggle zen mode", "Przełącz tryb zen"],
+            Self::LeaveZen => ["Leave zen mode, clear measurements", "Wyjdź z trybu zen, wyczyść pomiary"],
+            Self::SaveScreenshot => ["Save a screenshot of the canvas", "Zapisz zrzut płótna"],
+            Self::CopySelected => [
+                "Copy the selected items with their coordinates",
+                "Kopiuj zaznaczone elementy z ich współrzędnymi"
+            ],
+            Self::CopyCanvasImage => ["Copy the canvas as an image", "Kopiuj płótno jako obraz"],
+            Self::UndoEdit => ["Undo a parameter edit", "Cofnij zmianę parametru"],
+            Self::RedoEdit => ["Redo", "Ponów"],
+            Self::SelectRectangle => ["Select items in a rectangle", "Zaznacz elementy w prostokącie"],
+            Self::AddToSelection => ["Add to the selection", "Dodaj do zaznaczenia"],
+            Self::ZoomInOut => ["Zoom in or out", "Przybliż lub oddal"],
+            Self::PanZoomed => ["Pan while zoomed in", "Przesuwaj widok po przybliżeniu"],
+            Self::ResetZoom => ["Reset the zoom", "Przywróć powiększenie"],
+            Self::JumpToBookmark => ["Jump to a view bookmark", "Przejdź do zakładki widoku"],
+            Self::PickForMeasure => ["Pick a point, line or intersection", "Wybierz punkt, prostą lub przecięcie"],
+            Self::CanvasPanelHelp => [
+                "The figure as projected in the latest frame, with overlays.",
+                "Rysunek rzutowany w ostatniej klatce, z nakładkami."
+            ],
+            Self::ControlsPanelHelp => [
+                "Script and engine parameters before generating; stepping and run criteria after.",
+                "Parametry skryptu i silnika przed generowaniem; potem krokowanie i kryteria przebiegu."
+            ],
+            Self::InspectorPanelHelp => [
+                "Adjustable values, statistics, colors, selection, breakpoints and overlays.",
+                "Wartości parametrów, statystyki, kolory, zaznaczenie, punkty przerwania i nakładki."
+            ],
+            Self::MetricsPanelHelp => ["Quality over iterations.", "Jakość w kolejnych iteracjach."],
+            Self::ScriptPanelHelp => [
+                "The script's source. Edits apply on the next generation.",
+                "Źródło skryptu. Zmiany obowiązują od następnego generowania."
+            ],
+            Self::LogPanelHelp => ["Messages from the debugger and the engine.", "Komunikaty debuggera i silnika."],
+            Self::TourFilePicker => [
+                "Start by opening a Geo-AID script here.",
+                "Zacznij od otwarcia tutaj skryptu Geo-AID."
+            ],
+            Self::TourParameters => [
+                "Set the engine parameters, then press Generate.",
+                "Ustaw parametry silnika, a następnie naciśnij Generuj."
+            ],
+            Self::TourStepping => [
+                "Once generated, step through cycles one by one or let them run.",
+                "Po wygenerowaniu przechodź przez cykle pojedynczo lub puść je w ruch."
+            ],
+            Self::TourStep => ["Tour {}/{}", "Przewodnik {}/{}"],
+            Self::Done => ["Done", "Gotowe"],
+            Self::SkipTour => ["Skip tour", "Pomiń przewodnik"],
+            Self::KeybindingsHeading => ["Keybindings", "Skróty klawiszowe"],
+            Self::Use => ["Use", "Użyj"],
+            Self::Mean => ["Mean", "Średnia"],
+            Self::Best => ["Best", "Najlepsza"],
+            Self::Worst => ["Worst", "Najgorsza"],
+            Self::MustBePositiveInteger => ["Must be positive integer.", "Musi być dodatnią liczbą całkowitą."],
+            Self::InvalidRunCount => ["Invalid run count", "Nieprawidłowa liczba przebiegów"],
+            Self::InvalidIterationCount => ["Invalid iteration count", "Nieprawidłowa liczba iteracji"],
+            Self::IterationsPerRun => ["Iterations per run:", "Iteracje na przebieg:"],
+            Self::FinalQuality => ["Final quality", "Końcowa jakość"],
+            Self::CouldNotLoadFont => ["Could not load {}", "Nie udało się wczytać {}"],
+            Self::ParameterField => ["Parameter:", "Parametr:"],
+            Self::From => ["From:", "Od:"],
+            Self::To => ["To:", "Do:"],
+            Self::InvalidRange => ["Invalid range", "Nieprawidłowy zakres"],
+            Self::InvalidRangeHint => [
+                "Max adjustments must be positive, worker counts at least 1.",
+                "Maksymalne zmiany muszą być dodatnie, liczby wątków co najmniej 1."
+            ],
+            Self::Steps => ["Steps:", "Kroki:"],
+            Self::InvalidStepCount => ["Invalid step count", "Nieprawidłowa liczba kroków"],
+            Self::RunsPerValue => ["Runs per value:", "Przebiegi na wartość:"],
+            Self::ValueProgress => ["Value {}/{}...", "Wartość {}/{}..."],
+            Self::BestMeanQualityAt => ["Best mean quality at {} = {}", "Najlepsza średnia jakość dla {} = {}"],
+            Self::RunsField => ["Runs:", "Przebiegi:"],
+            Self::RunProgress => ["Run {}/{}...", "Przebieg {}/{}..."],
+            Self::FinalQualitySummary => [
+                "Final quality: mean {}, best {}, worst {}",
+                "Końcowa jakość: średnio {}, najlepsza {}, najgorsza {}"
+            ],
+            Self::FinalQualityDistribution => ["Final quality distribution", "Rozkład końcowej jakości"],
+            Self::Convergence => ["Convergence", "Zbieżność"],
+            Self::RunWithSeed => ["Run {} (seed {})", "Przebieg {} (ziarno {})"],
+            Self::FirstSeed => ["First seed:", "Pierwsze ziarno:"],
+            Self::InvalidSeed => ["Invalid seed", "Nieprawidłowe ziarno"],
+            Self::MustBeNonNegativeInteger => [
+                "Must be a non-negative integer.",
+                "Musi być nieujemną liczbą całkowitą."
+            ],
+            Self::EngineTakesNoSeed => [
+                "{} takes no seed, so its runs can't be reproduced. Seeds are only recorded.",
+                "{} nie przyjmuje ziarna, więc jego przebiegów nie da się powtórzyć. Ziarna są tylko zapisywane."
+            ],
+            Self::WorkerCounts => ["Worker counts:", "Liczby wątków:"],
+            Self::InvalidWorkerCounts => ["Invalid worker counts", "Nieprawidłowe liczby wątków"],
+            Self::InvalidWorkerCountsHint => [
+                "Must be a comma-separated list of positive integers.",
+                "Musi być listą dodatnich liczb całkowitych rozdzielonych przecinkami."
+            ],
+            Self::Cycles => ["Cycles:", "Cykle:"],
+            Self::InvalidCycleCount => ["Invalid cycle count", "Nieprawidłowa liczba cykli"],
+            Self::RunBenchmark => ["Run benchmark", "Uruchom test wydajności"],
+            Self::Workers => ["Workers", "Wątki"],
+            Self::CyclesPerSecond => ["Cycles/s", "Cykle/s"],
+            Self::GainPerSecond => ["Gain/s", "Zysk/s"],
+            Self::RecommendedWorkerCount => ["Recommended worker count: {}", "Zalecana liczba wątków: {}"],
+            Self::MeasuringProgress => ["Measuring {}/{}...", "Pomiar {}/{}..."],
+            Self::OldVersion => ["Old version:", "Stara wersja:"],
+            Self::NewVersion => ["New version:", "Nowa wersja:"],
+            Self::ScriptInEditor => ["The script in the editor", "Skrypt w edytorze"],
+            Self::IterationsField => ["Iterations:", "Iteracje:"],
+            Self::Compare => ["Compare", "Porównaj"],
+            Self::GeneratingBoth => ["Generating both versions...", "Generowanie obu wersji..."],
+            Self::GenerationFailed => [
+                "Generation failed, see the log.",
+                "Generowanie nie powiodło się, zobacz dziennik."
+            ],
+            Self::QualityOldNew => ["Quality: old {}, new {}", "Jakość: stara {}, nowa {}"],
+            Self::OldKey => ["■ old", "■ stara"],
+            Self::NewKey => ["■ new", "■ nowa"],
+            Self::SameNamedKey => ["— same-named points", "— punkty o tych samych nazwach"],
+            Self::Moved => ["moved {}", "przesunięty o {}"],
+            Self::OnlyInOld => ["Only in the old version: {}", "Tylko w starej wersji: {}"],
+            Self::OnlyInNew => ["Only in the new version: {}", "Tylko w nowej wersji: {}"],
+            Self::NoCyclesTimed => ["No cycles timed yet.", "Nie zmierzono jeszcze żadnych cykli."],
+            Self::EngineCycle => ["Engine cycle", "Cykl silnika"],
+            Self::BreakpointChecks => ["Breakpoint checks", "Sprawdzanie punktów przerwania"],
+            Self::FigureBuild => ["Figure build", "Budowa rysunku"],
+            Self::MicrosecondsPerCycle => ["{} µs/cycle", "{} µs/cykl"],
+            Self::EngineCycleNote => [
+                "The engine cycle covers both adjustment and rule evaluation, \
+                which the engine doesn't time separately.",
+                "Cykl silnika obejmuje zarówno zmianę parametrów, jak i ocenę reguł, \
+                których silnik nie mierzy osobno."
+            ],
+            Self::MicrosecondsPerCycleAxis => ["µs per cycle", "µs na cykl"],
+            Self::Format => ["Format", "Format"],
+            Self::FormatField => ["Format:", "Format:"],
+            Self::SelectedItemsOnly => ["Selected items only ({})", "Tylko zaznaczone elementy ({})"],
+            Self::IncludeLegend => ["Include legend", "Dołącz legendę"],
+            Self::Resolution => ["Resolution", "Rozdzielczość"],
+            Self::GeoGebraCliOnly => [
+                "GeoGebra export is only available in the CLI.",
+                "Eksport do GeoGebry jest dostępny tylko w wierszu poleceń."
+            ],
+            Self::ExportWhileRunning => ["Export while running", "Eksportuj w trakcie działania"],
+            Self::Directory => ["Directory:", "Katalog:"],
+            Self::Every => ["Every", "Co"],
+            Self::Iterations => ["iterations", "iteracji"],
+            Self::WhenQualityImproves => ["When quality improves by", "Gdy jakość wzrośnie o"],
+            Self::FilesNamedAfterIteration => [
+                "Files are named after the iteration, e.g. iteration-00001000.svg.",
+                "Pliki są nazywane według iteracji, np. iteration-00001000.svg."
+            ],
+            Self::ExportedFiles => ["Exported {} file(s).", "Wyeksportowane pliki: {}."],
+            Self::TrackSelectedPoint => ["Track selected point", "Śledź zaznaczony punkt"],
+            Self::SelectSinglePoint => [
+                "Select a single point on the canvas first",
+                "Najpierw zaznacz jeden punkt na płótnie"
+            ],
+            Self::XAndY => ["x and y", "x i y"],
+            Self::Path => ["Path", "Ścieżka"],
+            Self::NoPointTracked => ["No point tracked.", "Nie śledzono punktu."],
+            Self::TrackedPoint => [
+                "Point #{}: {} sample(s), in canvas coordinates",
+                "Punkt #{}: próbki: {}, we współrzędnych płótna"
+            ],
+            Self::AutomationHelp => [
+                "Rhai script. Commands: load(path), workers(n), max_adjustment(x), restart(), step(n), \
+                quality(), iteration(), state(), export_svg/export_latex/export_json(path).",
+                "Skrypt Rhai. Polecenia: load(ścieżka), workers(n), max_adjustment(x), restart(), step(n), \
+                quality(), iteration(), state(), export_svg/export_latex/export_json(ścieżka)."
+            ],
+            Self::Module => ["Module:", "Moduł:"],
+            Self::Search => ["Search:", "Szukaj:"],
+            Self::CenteredAt => ["centered at", "o środku"],
+            Self::Through => ["through", "przez"],
+            Self::MeasureDistanceDirection => ["distance {}, direction {}°", "odległość {}, kierunek {}°"],
+            Self::MeasureDistance => ["distance {}", "odległość {}"],
+            Self::MeasureAngle => ["angle {}°", "kąt {}°"],
+            Self::NoSeedNote => [
+                "The engine takes no seed. Each run starts from a fresh random state instead.",
+                "Silnik nie przyjmuje ziarna. Zamiast tego każdy przebieg zaczyna od nowego losowego stanu."
+            ],
+            Self::ExportWindow => ["Export", "Eksport"],
+            Self::FreezeFit => ["Freeze the fit", "Zamroź dopasowanie"],
+            Self::FreezeFitHint => [
+                "Place every frame the way the first one was fitted, so that entities running off to infinity \
+                leave the canvas instead of shrinking the rest of the figure.",
+                "Umieszcza każdą klatkę tak, jak dopasowano pierwszą, więc obiekty uciekające \
+                do nieskończoności opuszczają płótno zamiast zmniejszać resztę figury."
+            ],
+            Self::FreezeHere => ["Freeze at the latest frame", "Zamroź na ostatniej klatce"]
+        };
+
+        match language {
+            Language::English => english,
+            Language::Polish => polish
+        }
+    }
+
+    /// The string in the given language, with each `{}` replaced by the next of `args`.
+    #[must_use]
+    pub fn fill(self, language: Language, args: &[&dyn Display]) -> String {
+        let mut parts = self.tr(language).split("{}");
+        let mut text = String::from(parts.next().unwrap_or_default());
+        let mut args = args.iter();
+
+        for part in parts {
+            if let Some(arg) = args.next() {
+                let _ = write!(text, "{arg}");
+            }
+
+            text.push_str(part);
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_replaces_placeholders_in_order() {
+        assert_eq!(Text::IterationQuality.fill(Language::English, &[&12, &"0.5000"]), "Iteration 12, quality 0.5000");
+        assert_eq!(Text::IterationQuality.fill(Language::Polish, &[&12, &"0.5000"]), "Iteracja 12, jakość 0.5000");
+    }
+
+    #[test]
+    fn fill_without_placeholders() {
+        assert_eq!(Text::Run.fill(Language::Polish, &[]), Text::Run.tr(Language::Polish));
+    }
+}
@@ -0,0 +1,172 @@
+use geo_aid_internal::projector::figure::{Item, Position};
+#[cfg(feature = "ui")]
+use crate::i18n::{Language, Text};
+
+/// How far (in canvas units) a labelled point may be from an item to count as lying on it.
+const INCIDENCE_TOLERANCE: f64 = 0.5;
+
+/// What a legend entry is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Point,
+    Line,
+    Segment,
+    Ray,
+    Circle
+}
+
+impl Kind {
+    fn english(self) -> &'static str {
+        match self {
+            Self::Point => "point",
+            Self::Line => "line",
+            Self::Segment => "segment",
+            Self::Ray => "ray",
+            Self::Circle => "circle"
+        }
+    }
+
+    #[cfg(feature = "ui")]
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::Point => Text::ItemPoint,
+            Self::Line => Text::ItemLine,
+            Self::Segment => Text::ItemSegment,
+            Self::Ray => Text::ItemRay,
+            Self::Circle => Text::ItemCircle
+        }
+    }
+}
+
+/// One labelled item and what it is.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub label: String,
+    pub kind: Kind,
+    /// The labelled point at the center of a circle.
+    pub center: Option<String>,
+    /// Labelled points lying on the item.
+    pub through: Vec<String>
+}
+
+impl Entry {
+    /// E.g. "circle centered at O, through A, B", from the words for the kind, "centered at" and "through".
+    fn compose(&self, kind: &str, centered_at: &str, through: &str) -> String {
+        let mut description = kind.to_owned();
+        let mut separator = " ";
+
+        if let Some(center) = &self.center {
+            description += &format!("{separator}{centered_at} {center}");
+            separator = ", ";
+        }
+
+        if !self.through.is_empty() {
+            description += &format!("{separator}{through} {}", self.through.join(", "));
+        }
+
+        description
+    }
+
+    /// The description in English, as exported.
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.compose(self.kind.english(), "centered at", "through")
+    }
+
+    /// The description in the UI language.
+    #[cfg(feature = "ui")]
+    #[must_use]
+    pub fn describe(&self, lang: Language) -> String {
+        self.compose(self.kind.name().tr(lang), Text::CenteredAt.tr(lang), Text::Through.tr(lang))
+    }
+}
+
+fn distance(a: &Position, b: &Position) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// Distance from `p` to the line through `a` and `b`, or to `a` if they coincide.
+fn line_distance(p: &Position, a: &Position, b: &Position) -> f64 {
+    let length = distance(a, b);
+
+    if length == 0.0 {
+        distance(p, a)
+    } else {
+        ((p.x - a.x) * (b.y - a.y) - (p.y - a.y) * (b.x - a.x)).abs() / length
+    }
+}
+
+/// Distance from `p` to the segment `ab`.
+fn segment_distance(p: &Position, a: &Position, b: &Position) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+
+    if t.is_finite() {
+        (p.x - (a.x + t * dx)).hypot(p.y - (a.y + t * dy))
+    } else {
+        distance(p, a)
+    }
+}
+
+/// Names of the labelled points `on` returns true for.
+fn through(points: &[(String, &Position)], on: impl Fn(&Position) -> bool) -> Vec<String> {
+    points.iter()
+        .filter(|(_, p)| on(p))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Describe every labelled item. Relations between items are recovered from the projected
+/// geometry: a labelled point lying on a line or circle is listed as one it goes through.
+#[must_use]
+pub fn legend(items: &[Item]) -> Vec<Entry> {
+    let points = items.iter()
+        .filter_map(|item| match item {
+            Item::Point(pt) => pt.label.as_ref().map(|label| (label.content.to_string(), &pt.position)),
+            _ => None
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect::<Vec<_>>();
+
+    items.iter()
+        .filter_map(|item| {
+            let (label, kind, center, on) = match item {
+                Item::Point(pt) => (&pt.label, Kind::Point, None, Vec::new()),
+                Item::Line(ln) => {
+                    let (a, b) = &ln.points;
+                    let on = through(&points, |p| line_distance(p, a, b) < INCIDENCE_TOLERANCE);
+                    (&ln.label, Kind::Line, None, on)
+                }
+                Item::Segment(x) => {
+                    let (a, b) = &x.points;
+                    let on = through(&points, |p| segment_distance(p, a, b) < INCIDENCE_TOLERANCE);
+                    (&x.label, Kind::Segment, None, on)
+                }
+                Item::Ray(x) => {
+                    let (a, b) = &x.points;
+                    let on = through(&points, |p| segment_distance(p, a, b) < INCIDENCE_TOLERANCE);
+                    (&x.label, Kind::Ray, None, on)
+                }
+                Item::Circle(circle) => {
+                    let center = points.iter()
+                        .find(|(_, p)| distance(p, &circle.center) < INCIDENCE_TOLERANCE)
+                        .map(|(name, _)| name.clone());
+                    let on = through(&points, |p| {
+                        (distance(p, &circle.center) - circle.radius).abs() < INCIDENCE_TOLERANCE
+                    });
+
+                    (&circle.label, Kind::Circle, center, on)
+                }
+            };
+
+            let label = label.as_ref()?.content.to_string();
+
+            if label.is_empty() {
+                return None;
+            }
+
+            Some(Entry { label, kind, center, through: on })
+        })
+        .collect()
+}
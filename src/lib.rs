@@ -0,0 +1,132 @@
+//! Debugger for Geo-AID's generation process.
+//!
+//! The engine-facing parts ([`engine`], [`mock`], [`runtime`], [`history`], [`archive`], [`memory`],
+//! [`breakpoint`], [`stop`], [`checkpoint`], [`undo`] and [`trace`]) don't depend on the UI
+//! and are available with `default-features = false`.
+//! Everything else is behind the `ui` feature. The `snapshot` module, a harness for renderer tests,
+//! is behind the `snapshot` feature. Debugging over TCP (`remote`) and Rhai scripting (`automation`)
+//! have features of their own, both on by default.
+//!
+//! # The web
+//! The debugger builds for `wasm32-unknown-unknown` with `--no-default-features --features ui`.
+//! There, the worker is stepped from the frame loop instead of running on a thread (see [`runtime::Stepper`]),
+//! scripts are opened with the browser's file picker (see [`dialog`]) and the tools running engines on threads
+//! of their own are hidden. The engine itself (`geo-aid-internal`) has to build for the target as well.
+
+pub mod archive;
+pub mod breakpoint;
+pub mod checkpoint;
+pub mod engine;
+pub mod history;
+pub mod legend;
+pub mod memory;
+pub mod mock;
+pub mod pacing;
+pub mod projection;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod runtime;
+pub mod stop;
+pub mod trace;
+pub mod undo;
+
+#[cfg(feature = "ui")]
+pub mod annotation;
+#[cfg(feature = "ui")]
+pub mod auto_export;
+#[cfg(feature = "automation")]
+pub mod automation;
+#[cfg(feature = "ui")]
+pub mod benchmark;
+#[cfg(feature = "ui")]
+pub mod bisect;
+#[cfg(feature = "ui")]
+pub mod bounds;
+#[cfg(feature = "ui")]
+pub mod breakdown;
+#[cfg(feature = "ui")]
+pub mod clipboard;
+#[cfg(feature = "ui")]
+pub mod colors;
+#[cfg(feature = "ui")]
+pub mod compare;
+#[cfg(feature = "ui")]
+pub mod debugger;
+#[cfg(feature = "ui")]
+pub mod degeneracy;
+#[cfg(feature = "ui")]
+pub mod dependencies;
+#[cfg(feature = "ui")]
+pub mod dialog;
+#[cfg(feature = "ui")]
+pub mod diff;
+#[cfg(feature = "ui")]
+pub mod egui_macroquad;
+#[cfg(feature = "ui")]
+pub mod egui_miniquad;
+#[cfg(feature = "ui")]
+pub mod error;
+#[cfg(feature = "ui")]
+pub mod export;
+#[cfg(feature = "ui")]
+pub mod font;
+#[cfg(feature = "ui")]
+pub mod golden;
+#[cfg(feature = "ui")]
+pub mod help;
+#[cfg(feature = "ui")]
+pub mod i18n;
+#[cfg(feature = "ui")]
+pub mod logging;
+#[cfg(feature = "ui")]
+pub mod math_label;
+#[cfg(feature = "ui")]
+pub mod measure;
+#[cfg(feature = "ui")]
+pub mod mesh;
+#[cfg(feature = "ui")]
+pub mod offscreen;
+#[cfg(feature = "ui")]
+pub mod minimap;
+#[cfg(feature = "ui")]
+pub mod overlay;
+#[cfg(feature = "ui")]
+pub mod performance;
+#[cfg(feature = "ui")]
+pub mod profiler;
+#[cfg(feature = "ui")]
+pub mod proposals;
+#[cfg(feature = "ui")]
+pub mod render;
+#[cfg(feature = "ui")]
+pub mod rules;
+#[cfg(feature = "ui")]
+pub mod selection;
+#[cfg(feature = "ui")]
+pub mod session;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+#[cfg(feature = "ui")]
+pub mod source;
+#[cfg(feature = "ui")]
+pub mod spatial;
+#[cfg(feature = "ui")]
+pub mod statistics;
+#[cfg(feature = "ui")]
+pub mod style;
+#[cfg(feature = "ui")]
+pub mod sweep;
+#[cfg(feature = "ui")]
+pub mod trajectory;
+#[cfg(feature = "ui")]
+pub mod view;
+#[cfg(feature = "ui")]
+pub mod workers;
+
+#[cfg(feature = "ui")]
+pub use debugger::Debugger;
+#[cfg(feature = "ui")]
+pub use overlay::Overlay;
+#[cfg(feature = "ui")]
+pub use render::Renderer;
+pub use runtime::Runtime;
@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+use std::fmt::{Debug, Write};
+use std::fs::File;
+use std::sync::{Arc, Mutex};
+use egui::{Color32, RichText, Ui};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+use crate::i18n::{Language, Text};
+
+/// Where the log is written to, relative to the working directory.
+const LOG_FILE: &str = "geo-aid-dbg.log";
+/// How many entries the in-app viewer keeps.
+const CAPACITY: usize = 10_000;
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+pub struct Entry {
+    pub level: Level,
+    /// Module the event was emitted from.
+    pub target: String,
+    pub message: String
+}
+
+type Entries = Arc<Mutex<VecDeque<Entry>>>;
+
+/// Collects events for the log viewer.
+struct Capture(Entries);
+
+impl<S: Subscriber> Layer<S> for Capture {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = Message(String::new());
+        event.record(&mut message);
+
+        let mut entries = self.0.lock().unwrap();
+
+        if entries.len() == CAPACITY {
+            entries.pop_front();
+        }
+
+        entries.push_back(Entry {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            message: message.0
+        });
+    }
+}
+
+/// Formats the message field followed by all other fields.
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+/// Install the global subscriber, logging to [`LOG_FILE`] and to the returned viewer.
+#[must_use]
+pub fn init() -> LogView {
+    let entries = Entries::default();
+
+    let file = File::create(LOG_FILE).ok().map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(Mutex::new(file))
+    });
+
+    tracing_subscriber::registry()
+        .with(Capture(Arc::clone(&entries)))
+        .with(file)
+        .init();
+
+    LogView {
+        entries,
+        max_level: Level::INFO,
+        target: String::new(),
+        search: String::new()
+    }
+}
+
+/// The filterable log panel.
+pub struct LogView {
+    entries: Entries,
+    max_level: Level,
+    /// Only show entries whose target contains this.
+    target: String,
+    /// Only show entries whose message contains this.
+    search: String
+}
+
+impl LogView {
+    pub fn ui(&mut self, ui: &mut Ui, lang: Language) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("log-level")
+                .selected_text(self.max_level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in LEVELS {
+                        ui.selectable_value(&mut self.max_level, level, level.as_str());
+                    }
+                });
+
+            ui.label(Text::Module.tr(lang));
+            ui.add(egui::TextEdit::singleline(&mut self.target).desired_width(120.0));
+            ui.label(Text::Search.tr(lang));
+            ui.add(egui::TextEdit::singleline(&mut self.search).desired_width(160.0));
+
+            if ui.button(Text::Clear.tr(lang)).clicked() {
+                self.entries.lock().unwrap().clear();
+            }
+        });
+
+        let entries = self.entries.lock().unwrap();
+        let shown = entries.iter()
+            .filter(|entry| entry.level <= self.max_level)
+            .filter(|entry| entry.target.contains(&self.target))
+            .filter(|entry| entry.message.contains(&self.search))
+            .collect::<Vec<_>>();
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink(false)
+            .show_rows(ui, row_height, shown.len(), |ui, rows| {
+                for entry in &shown[rows] {
+                    let color = match entry.level {
+                        Level::ERROR => Color32::RED,
+                        Level::WARN => Color32::YELLOW,
+                        Level::INFO => ui.visuals().text_color(),
+                        _ => Color32::GRAY
+                    };
+
+                    ui.label(
+                        RichText::new(format!("{:5} {}: {}", entry.level, entry.target, entry.message))
+                            .color(color)
+                            .monospace()
+                    );
+                }
+            });
+    }
+}
@@ -0,0 +1,259 @@
+//! Basic math notation in labels: `_` subscripts, `^` superscripts and `\name` Greek letters.
+//!
+//! Scripts and groups are either a single character (`A_1`) or braced (`A_{12}`). `$` delimiters are dropped.
+
+use macroquad::prelude::*;
+use crate::font::Fonts;
+
+/// Size of sub- and superscripts relative to the label.
+const SCRIPT_SCALE: f32 = 0.7;
+/// How far subscripts are lowered and superscripts raised, relative to the label size.
+const SUBSCRIPT_SHIFT: f32 = 0.25;
+const SUPERSCRIPT_SHIFT: f32 = 0.4;
+
+const GREEK: [(&str, char); 40] = [
+    ("alpha", 'α'), ("beta", 'β'), ("gamma", 'γ'), ("delta", 'δ'), ("epsilon", 'ε'),
+    ("varepsilon", 'ε'), ("zeta", 'ζ'), ("eta", 'η'), ("theta", 'θ'), ("vartheta", 'ϑ'),
+    ("iota", 'ι'), ("kappa", 'κ'), ("lambda", 'λ'), ("mu", 'μ'), ("nu", 'ν'),
+    ("xi", 'ξ'), ("pi", 'π'), ("rho", 'ρ'), ("sigma", 'σ'), ("tau", 'τ'),
+    ("upsilon", 'υ'), ("phi", 'φ'), ("varphi", 'φ'), ("chi", 'χ'), ("psi", 'ψ'),
+    ("omega", 'ω'), ("Gamma", 'Γ'), ("Delta", 'Δ'), ("Theta", 'Θ'), ("Lambda", 'Λ'),
+    ("Xi", 'Ξ'), ("Pi", 'Π'), ("Sigma", 'Σ'), ("Upsilon", 'Υ'), ("Phi", 'Φ'),
+    ("Psi", 'Ψ'), ("Omega", 'Ω'), ("prime", '′'), ("circ", '°'), ("infty", '∞')
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shift {
+    Normal,
+    Subscript,
+    Superscript
+}
+
+/// A run of label text drawn at one size and height.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub shift: Shift
+}
+
+/// Replace `\name` commands with the characters they stand for. Unknown commands are kept as written.
+fn replace_commands(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            name.push(letter);
+        }
+
+        match GREEK.iter().find(|(command, _)| *command == name) {
+            Some((_, symbol)) => out.push(*symbol),
+            None => {
+                out.push('\\');
+                out.push_str(&name);
+            }
+        }
+    }
+
+    out
+}
+
+/// Split a label into spans.
+#[must_use]
+pub fn parse(label: &str) -> Vec<Span> {
+    let text = replace_commands(&label.replace('$', ""));
+    let mut spans: Vec<Span> = Vec::new();
+    let mut push = |text: String, shift: Shift| {
+        match spans.last_mut() {
+            Some(last) if last.shift == shift => last.text.push_str(&text),
+            _ if !text.is_empty() => spans.push(Span { text, shift }),
+            _ => ()
+        }
+    };
+
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        let shift = match c {
+            '_' => Shift::Subscript,
+            '^' => Shift::Superscript,
+            _ => {
+                push(c.to_string(), Shift::Normal);
+                continue;
+            }
+        };
+
+        let script = match chars.next() {
+            Some('{') => chars.by_ref().take_while(|c| *c != '}').collect(),
+            Some(c) => c.to_string(),
+            None => String::new()
+        };
+
+        push(script, shift);
+    }
+
+    spans
+}
+
+/// Write spans as LaTeX math, with the Greek letters and symbols [`parse`] replaced as commands again.
+#[must_use]
+pub fn to_latex(spans: &[Span]) -> String {
+    let mut out = String::new();
+
+    for span in spans {
+        let text = span.text.chars()
+            .map(|c| match GREEK.iter().find(|(_, symbol)| *symbol == c) {
+                Some((command, _)) => format!("\\{command} "),
+                None => match c {
+                    '\\' => String::from("\\backslash "),
+                    '#' | '%' | '&' | '{' | '}' => format!("\\{c}"),
+                    '~' => String::from("\\sim "),
+                    c => c.to_string()
+                }
+            })
+            .collect::<String>();
+
+        match span.shift {
+            Shift::Normal => out.push_str(&text),
+            Shift::Subscript => out.push_str(&format!("_{{{text}}}")),
+            Shift::Superscript => out.push_str(&format!("^{{{text}}}"))
+        }
+    }
+
+    out
+}
+
+fn span_size(span: &Span, size: u16) -> u16 {
+    match span.shift {
+        Shift::Normal => size,
+        Shift::Subscript | Shift::Superscript => (f32::from(size) * SCRIPT_SCALE).round() as u16
+    }
+}
+
+fn text_width(text: &str, fonts: &Fonts, size: u16) -> f32 {
+    fonts.runs(text)
+        .into_iter()
+        .map(|(run, font)| measure_text(run, font, size, 1.0).width)
+        .sum()
+}
+
+/// Dimensions of the spans drawn at `size`, as [`measure_text`] would report for plain text.
+#[must_use]
+pub fn measure(spans: &[Span], fonts: &Fonts, size: u16) -> TextDimensions {
+    let normal = measure_text("A", fonts.font_for('A'), size, 1.0);
+    let width = spans.iter()
+        .map(|span| text_width(&span.text, fonts, span_size(span, size)))
+        .sum();
+
+    let raised = spans.iter().any(|span| span.shift == Shift::Superscript);
+    let lowered = spans.iter().any(|span| span.shift == Shift::Subscript);
+    let extra_top = if raised { f32::from(size) * SUPERSCRIPT_SHIFT * 0.5 } else { 0.0 };
+    let extra_bottom = if lowered { f32::from(size) * SUBSCRIPT_SHIFT } else { 0.0 };
+
+    TextDimensions {
+        width,
+        height: normal.height + extra_top + extra_bottom,
+        offset_y: normal.offset_y + extra_top
+    }
+}
+
+/// Draw spans with their baseline starting at (`x`, `y`).
+pub fn draw(spans: &[Span], fonts: &Fonts, x: f32, y: f32, size: u16, color: Color) {
+    let mut x = x;
+
+    for span in spans {
+        let span_size = span_size(span, size);
+        let shift = match span.shift {
+            Shift::Normal => 0.0,
+            Shift::Subscript => f32::from(size) * SUBSCRIPT_SHIFT,
+            Shift::Superscript => -f32::from(size) * SUPERSCRIPT_SHIFT
+        };
+
+        for (run, font) in fonts.runs(&span.text) {
+            let dims = draw_text_ex(run, x, y + shift, TextParams {
+                font,
+                font_size: span_size,
+                color,
+                ..TextParams::default()
+            });
+            x += dims.width;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, shift: Shift) -> Span {
+        Span {
+            text: String::from(text),
+            shift
+        }
+    }
+
+    #[test]
+    fn plain_text_is_one_span() {
+        assert_eq!(parse("ABC"), [span("ABC", Shift::Normal)]);
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn single_and_braced_scripts() {
+        assert_eq!(
+            parse("A_1B^{12}"),
+            [
+                span("A", Shift::Normal),
+                span("1", Shift::Subscript),
+                span("B", Shift::Normal),
+                span("12", Shift::Superscript)
+            ]
+        );
+        assert_eq!(
+            parse("x_{ab}c"),
+            [span("x", Shift::Normal), span("ab", Shift::Subscript), span("c", Shift::Normal)]
+        );
+    }
+
+    #[test]
+    fn latex_restores_commands_and_escapes_the_rest() {
+        assert_eq!(to_latex(&parse("\\alpha_1")), "\\alpha _{1}");
+        assert_eq!(to_latex(&parse("A^{B}")), "A^{B}");
+        assert_eq!(to_latex(&parse("50%&\\foo")), "50\\%\\&\\backslash foo");
+    }
+
+    #[test]
+    fn adjacent_spans_merge() {
+        assert_eq!(parse("a_1_2"), [span("a", Shift::Normal), span("12", Shift::Subscript)]);
+    }
+
+    #[test]
+    fn dangling_markers_are_dropped() {
+        assert_eq!(parse("A_"), [span("A", Shift::Normal)]);
+        assert_eq!(parse("A^{}B"), [span("AB", Shift::Normal)]);
+    }
+
+    #[test]
+    fn dollars_are_dropped() {
+        assert_eq!(parse("$A_1$"), [span("A", Shift::Normal), span("1", Shift::Subscript)]);
+    }
+
+    #[test]
+    fn greek_letters() {
+        assert_eq!(parse(r"\alpha\Omega"), [span("αΩ", Shift::Normal)]);
+        assert_eq!(parse(r"\alpha_\beta"), [span("α", Shift::Normal), span("β", Shift::Subscript)]);
+        assert_eq!(parse(r"A^\prime"), [span("A", Shift::Normal), span("′", Shift::Superscript)]);
+    }
+
+    #[test]
+    fn unknown_commands_are_kept() {
+        assert_eq!(parse(r"\foo x"), [span(r"\foo x", Shift::Normal)]);
+        assert_eq!(parse(r"a\\b"), [span(r"a\\b", Shift::Normal)]);
+    }
+}
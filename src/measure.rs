@@ -0,0 +1,253 @@
+use egui::{Color32, Painter, Pos2, Rect, Stroke, Vec2};
+use geo_aid_internal::projector::figure::{Item, Position};
+use crate::i18n::{Language, Text};
+use crate::spatial::SpatialIndex;
+
+/// How close (in pixels) the cursor must be to pick something.
+const PICK_RADIUS: f32 = 8.0;
+const MEASURE_COLOR: Color32 = Color32::from_rgb(0, 140, 0);
+const CROSSHAIR_COLOR: Color32 = Color32::from_rgba_premultiplied(0, 70, 0, 100);
+/// Half the size of the snap indicator.
+const SNAP_MARKER_SIZE: f32 = 5.0;
+
+/// Something the measure tool can be clicked on, in canvas coordinates.
+#[derive(Debug, Clone, Copy)]
+pub enum Pick {
+    Point(Pos2),
+    /// A line through the two points. Segments and rays are measured as lines too.
+    Line(Pos2, Pos2)
+}
+
+fn pos(position: &Position) -> Pos2 {
+    Pos2::new(position.x as f32, position.y as f32)
+}
+
+/// Where the cursor snaps to, in canvas coordinates.
+#[derive(Debug, Clone, Copy)]
+pub enum Snap {
+    Point(Pos2),
+    /// Where two lines cross.
+    Intersection(Pos2),
+    /// The closest position `at` on the line through `a` and `b`.
+    Line {
+        a: Pos2,
+        b: Pos2,
+        at: Pos2
+    }
+}
+
+impl Snap {
+    #[must_use]
+    pub fn position(self) -> Pos2 {
+        match self {
+            Self::Point(p)
+            | Self::Intersection(p)
+            | Self::Line { at: p, .. } => p
+        }
+    }
+
+    /// What clicking while snapped picks for measurement. Intersections are measured as points.
+    #[must_use]
+    pub fn pick(self) -> Pick {
+        match self {
+            Self::Point(p)
+            | Self::Intersection(p) => Pick::Point(p),
+            Self::Line { a, b, .. } => Pick::Line(a, b)
+        }
+    }
+}
+
+/// The point of the segment `ab` closest to `p`.
+fn closest_on_segment(p: Pos2, a: Pos2, b: Pos2) -> Pos2 {
+    let ab = b - a;
+    let t = ((p - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0);
+
+    if t.is_finite() {
+        a + t * ab
+    } else {
+        a
+    }
+}
+
+/// Distance from `p` to the line through `a` and `b`.
+fn line_distance(p: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    ((p - a).x * ab.y - (p - a).y * ab.x).abs() / ab.length()
+}
+
+/// Where the segments `ab` and `cd` cross, if they do.
+fn segment_intersection(a: Pos2, b: Pos2, c: Pos2, d: Pos2) -> Option<Pos2> {
+    let r = b - a;
+    let s = d - c;
+    let denom = r.x * s.y - r.y * s.x;
+
+    if denom.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let ac = c - a;
+    let t = (ac.x * s.y - ac.y * s.x) / denom;
+    let u = (ac.x * r.y - ac.y * r.x) / denom;
+
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a + t * r)
+}
+
+/// End points of all straight items. Projected lines and rays end at the canvas edges.
+fn straight_items(items: &[&Item]) -> Vec<(Pos2, Pos2)> {
+    items.iter()
+        .filter_map(|item| match item {
+            Item::Line(ln) => Some(&ln.points),
+            Item::Segment(x)
+            | Item::Ray(x) => Some(&x.points),
+            _ => None
+        })
+        .map(|(a, b)| (pos(a), pos(b)))
+        .collect()
+}
+
+/// Snap `cursor` to the closest point in range. Failing that, to the closest
+/// intersection of two lines and, failing that, to the closest line. Only items
+/// for which `pickable` returns true are considered.
+#[must_use]
+pub fn snap(items: &[Item], index: &SpatialIndex, cursor: Pos2, pickable: impl Fn(usize) -> bool) -> Option<Snap> {
+    let in_range = |p: Pos2| p.distance(cursor) <= PICK_RADIUS;
+    let closest = |a: &Pos2, b: &Pos2| a.distance(cursor).total_cmp(&b.distance(cursor));
+
+    let items = index.near(cursor, PICK_RADIUS)
+        .into_iter()
+        .filter(|i| pickable(*i))
+        .map(|i| &items[i])
+        .collect::<Vec<_>>();
+
+    let point = items.iter()
+        .filter_map(|item| match item {
+            Item::Point(pt) => Some(pos(&pt.position)),
+            _ => None
+        })
+        .filter(|p| in_range(*p))
+        .min_by(closest);
+
+    if let Some(p) = point {
+        return Some(Snap::Point(p));
+    }
+
+    let lines = straight_items(items);
+
+    let intersection = lines.iter()
+        .enumerate()
+        .flat_map(|(i, (a, b))| lines[i + 1..].iter()
+            .filter_map(move |(c, d)| segment_intersection(*a, *b, *c, *d)))
+        .filter(|p| in_range(*p))
+        .min_by(closest);
+
+    if let Some(p) = intersection {
+        return Some(Snap::Intersection(p));
+    }
+
+    lines.into_iter()
+        .map(|(a, b)| Snap::Line { a, b, at: closest_on_segment(cursor, a, b) })
+        .filter(|snap| in_range(snap.position()))
+        .min_by(|x, y| closest(&x.position(), &y.position()))
+}
+
+/// Angle between two directions, in degrees from 0 to 90.
+fn angle_between(u: Vec2, v: Vec2) -> f32 {
+    let cos = (u.dot(v) / (u.length() * v.length())).abs();
+    cos.clamp(0.0, 1.0).acos().to_degrees()
+}
+
+/// The two-click measure mode.
+#[derive(Default)]
+pub struct Measure {
+    pub active: bool,
+    first: Option<Pick>,
+    second: Option<Pick>
+}
+
+impl Measure {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pick the first or the second object. A third click starts a new measurement.
+    pub fn click(&mut self, pick: Pick) {
+        if self.first.is_none() || self.second.is_some() {
+            self.first = Some(pick);
+            self.second = None;
+        } else {
+            self.second = Some(pick);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.first = None;
+        self.second = None;
+    }
+
+    /// Description of the current measurement, in canvas units.
+    #[must_use]
+    pub fn result(&self, lang: Language) -> Option<String> {
+        let (Some(first), Some(second)) = (self.first, self.second) else {
+            return None;
+        };
+
+        Some(match (first, second) {
+            (Pick::Point(a), Pick::Point(b)) => {
+                // Canvas y points down, so it's flipped for a counter-clockwise angle.
+                let direction = (-(b.y - a.y)).atan2(b.x - a.x).to_degrees();
+                let distance = format!("{:.3}", a.distance(b));
+                Text::MeasureDistanceDirection.fill(lang, &[&distance, &format!("{direction:.2}")])
+            }
+            (Pick::Point(p), Pick::Line(a, b))
+            | (Pick::Line(a, b), Pick::Point(p)) => {
+                Text::MeasureDistance.fill(lang, &[&format!("{:.3}", line_distance(p, a, b))])
+            }
+            (Pick::Line(a, b), Pick::Line(c, d)) => {
+                Text::MeasureAngle.fill(lang, &[&format!("{:.2}", angle_between(b - a, d - c))])
+            }
+        })
+    }
+
+    /// Highlight the picked objects. `origin` is the top-left corner of the canvas.
+    pub fn paint(&self, painter: &Painter, origin: Vec2) {
+        let stroke = Stroke::new(2.0, MEASURE_COLOR);
+
+        for pick in [self.first, self.second].into_iter().flatten() {
+            match pick {
+                Pick::Point(p) => painter.circle_stroke(p + origin, PICK_RADIUS / 2.0, stroke),
+                Pick::Line(a, b) => painter.line_segment([a + origin, b + origin], stroke)
+            }
+        }
+
+        if let (Some(Pick::Point(a)), Some(Pick::Point(b))) = (self.first, self.second) {
+            painter.line_segment([a + origin, b + origin], Stroke::new(1.0, MEASURE_COLOR));
+        }
+    }
+}
+
+/// Draw a crosshair across `canvas` at the cursor, moved to and marked where it snapped.
+/// `cursor` is in canvas coordinates.
+pub fn paint_crosshair(painter: &Painter, canvas: Rect, cursor: Pos2, snap: Option<Snap>) {
+    let origin = canvas.min.to_vec2();
+    let at = snap.map_or(cursor, Snap::position) + origin;
+    let stroke = Stroke::new(1.0, CROSSHAIR_COLOR);
+
+    painter.hline(canvas.x_range(), at.y, stroke);
+    painter.vline(at.x, canvas.y_range(), stroke);
+
+    let marker = Stroke::new(2.0, MEASURE_COLOR);
+    let size = SNAP_MARKER_SIZE;
+
+    match snap {
+        Some(Snap::Point(_)) => painter.circle_stroke(at, size, marker),
+        Some(Snap::Intersection(_)) => {
+            painter.line_segment([at - Vec2::splat(size), at + Vec2::splat(size)], marker);
+            painter.line_segment([at + Vec2::new(-size, size), at + Vec2::new(size, -size)], marker);
+        }
+        Some(Snap::Line { .. }) => {
+            painter.rect_stroke(Rect::from_center_size(at, Vec2::splat(2.0 * size)), 0.0, marker);
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,147 @@
+//! Approximate memory accounting of what a runtime records.
+//!
+//! Sizes are estimated from lengths and element sizes. Heap data owned by engine types (the generated figure)
+//! isn't visible from here, so the totals are lower bounds.
+
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::mem;
+
+/// Bytes in a mebibyte.
+pub const MIB: usize = 1024 * 1024;
+
+/// Approximate bytes used by a runtime's recordings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryUsage {
+    /// Recent frames kept for the UI.
+    pub frames: usize,
+    /// Archived states of the whole run.
+    pub archive: usize,
+    /// Quality, acceptance, magnitude and timing logs.
+    pub logs: usize,
+    /// The state trace, if recording.
+    pub trace: usize
+}
+
+impl MemoryUsage {
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.frames + self.archive + self.logs + self.trace
+    }
+}
+
+/// A byte count with a binary unit.
+#[derive(Debug, Clone, Copy)]
+pub struct Bytes(pub usize);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let bytes = self.0 as f64;
+
+        if self.0 < 1024 {
+            write!(f, "{} B", self.0)
+        } else if self.0 < MIB {
+            write!(f, "{:.1} KiB", bytes / 1024.0)
+        } else if self.0 < 1024 * MIB {
+            write!(f, "{:.1} MiB", bytes / MIB as f64)
+        } else {
+            write!(f, "{:.2} GiB", bytes / (1024 * MIB) as f64)
+        }
+    }
+}
+
+/// Bytes used by a vector's elements, not counting their own heap data.
+#[must_use]
+pub fn vec_size<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * mem::size_of::<T>()
+}
+
+/// Bytes used by a deque's elements, not counting their own heap data.
+#[must_use]
+pub fn deque_size<T>(deque: &VecDeque<T>) -> usize {
+    deque.capacity() * mem::size_of::<T>()
+}
+
+/// Whether to keep each entry of a `len` entries long log, in order, when thinning it.
+fn thinning<T>(len: usize) -> impl FnMut(&T) -> bool {
+    let old = len / 2;
+    let mut i = 0;
+
+    move |_| {
+        let keep = i >= old || i % 2 == 0;
+        i += 1;
+        keep
+    }
+}
+
+/// Keep every other entry of the older half of `log`, leaving recent entries at full resolution.
+/// Repeated thinning keeps every k-th of the oldest entries, with k doubling every time.
+pub fn thin<T>(log: &mut Vec<T>) {
+    log.retain(thinning(log.len()));
+    log.shrink_to_fit();
+}
+
+/// [`thin`] for logs trimmed from the front.
+pub fn thin_deque<T>(log: &mut VecDeque<T>) {
+    log.retain(thinning(log.len()));
+    log.shrink_to_fit();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thinning_keeps_the_recent_half() {
+        let mut log = (0..10).collect::<Vec<_>>();
+
+        thin(&mut log);
+        assert_eq!(log, [0, 2, 4, 5, 6, 7, 8, 9]);
+
+        thin(&mut log);
+        assert_eq!(log, [0, 4, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn deques_thin_like_vectors() {
+        let mut log = (0..101).collect::<Vec<_>>();
+        let mut deque = log.iter().copied().collect::<VecDeque<_>>();
+
+        // A deque that wrapped around its buffer.
+        deque.pop_front();
+        deque.push_back(101);
+        log.remove(0);
+        log.push(101);
+
+        for _ in 0..4 {
+            thin(&mut log);
+            thin_deque(&mut deque);
+            assert!(deque.iter().eq(&log));
+        }
+    }
+
+    #[test]
+    fn short_logs_stay() {
+        let mut log = vec![1];
+        thin(&mut log);
+        assert_eq!(log, [1]);
+
+        let mut log: Vec<u8> = Vec::new();
+        thin(&mut log);
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn sizes_follow_capacity() {
+        assert_eq!(vec_size(&Vec::<u64>::with_capacity(10)), 80);
+        assert!(deque_size(&VecDeque::<u32>::with_capacity(10)) >= 40);
+    }
+
+    #[test]
+    fn byte_units() {
+        assert_eq!(Bytes(1023).to_string(), "1023 B");
+        assert_eq!(Bytes(1536).to_string(), "1.5 KiB");
+        assert_eq!(Bytes(3 * MIB).to_string(), "3.0 MiB");
+        assert_eq!(Bytes(1024 * MIB).to_string(), "1.00 GiB");
+    }
+}
@@ -0,0 +1,244 @@
+//! Figure geometry batched into a few large meshes.
+//!
+//! Drawing primitives one by one costs a call into macroquad's batcher per line, and circles are drawn as
+//! a line per side on top of that. Building the vertex and index buffers directly is much cheaper on large figures.
+
+use std::f32::consts::{PI, TAU};
+use macroquad::models::{draw_mesh, Mesh, Vertex};
+use macroquad::prelude::*;
+
+/// Vertex and index capacity of a draw call. The window has to be configured with these for meshes
+/// to be drawn in one call each.
+pub const MAX_VERTICES: usize = u16::MAX as usize;
+pub const MAX_INDICES: usize = 3 * MAX_VERTICES;
+/// Largest distance between a circle and its polygon, in canvas pixels.
+/// Small enough for facets to stay invisible in captures at 8x the canvas size.
+const CIRCLE_TOLERANCE: f32 = 0.05;
+const MIN_CIRCLE_SIDES: usize = 12;
+const MAX_CIRCLE_SIDES: usize = 1024;
+
+fn vertex(at: Vec2, color: Color) -> Vertex {
+    Vertex::new(at.x, at.y, 0.0, 0.0, 0.0, color)
+}
+
+/// Sides of a polygon no farther than [`CIRCLE_TOLERANCE`] from a circle of the given radius.
+fn circle_sides(radius: f32) -> usize {
+    if radius <= CIRCLE_TOLERANCE {
+        return MIN_CIRCLE_SIDES;
+    }
+
+    // A side spanning the angle 2a lies at most r(1 - cos a) away from the circle.
+    let half_angle = (1.0 - CIRCLE_TOLERANCE / radius).acos();
+    ((PI / half_angle).ceil() as usize).clamp(MIN_CIRCLE_SIDES, MAX_CIRCLE_SIDES)
+}
+
+/// Untextured triangles collected over a frame, drawn all at once.
+#[derive(Default)]
+pub struct Batch {
+    meshes: Vec<Mesh>
+}
+
+impl Batch {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The mesh to add to, with room for the given number of vertices and indices.
+    /// Returns it with the index of its first new vertex.
+    fn reserve(&mut self, vertices: usize, indices: usize) -> (&mut Mesh, u16) {
+        let fits = self.meshes.last().is_some_and(|mesh| {
+            mesh.vertices.len() + vertices < MAX_VERTICES && mesh.indices.len() + indices < MAX_INDICES
+        });
+
+        if !fits {
+            self.meshes.push(Mesh {
+                vertices: Vec::new(),
+                indices: Vec::new(),
+                texture: None
+            });
+        }
+
+        let mesh = self.meshes.last_mut().unwrap();
+        let first = mesh.vertices.len() as u16;
+        (mesh, first)
+    }
+
+    /// A line `width` wide, as two triangles.
+    pub fn line(&mut self, a: Vec2, b: Vec2, width: f32, color: Color) {
+        let Some(dir) = (b - a).try_normalize() else {
+            return;
+        };
+
+        let side = dir.perp() * (width / 2.0);
+        let (mesh, first) = self.reserve(4, 6);
+
+        mesh.vertices.extend([a + side, a - side, b - side, b + side].map(|at| vertex(at, color)));
+        mesh.indices.extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+    }
+
+    /// A circle outline `width` wide, as a ring of quads.
+    pub fn circle_lines(&mut self, center: Vec2, radius: f32, width: f32, color: Color) {
+        let sides = circle_sides(radius);
+        let (mesh, first) = self.reserve(2 * sides, 6 * sides);
+
+        for i in 0..sides {
+            let dir = Vec2::from_angle(TAU * i as f32 / sides as f32);
+            mesh.vertices.push(vertex(center + dir * (radius - width / 2.0), color));
+            mesh.vertices.push(vertex(center + dir * (radius + width / 2.0), color));
+
+            let (inner, outer) = (first + 2 * i as u16, first + 2 * i as u16 + 1);
+            let next = first + 2 * ((i + 1) % sides) as u16;
+            mesh.indices.extend([inner, outer, next + 1, inner, next + 1, next]);
+        }
+    }
+
+    /// A filled circle, as a triangle fan.
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        let sides = circle_sides(radius);
+        let (mesh, first) = self.reserve(sides + 1, 3 * sides);
+
+        mesh.vertices.push(vertex(center, color));
+
+        for i in 0..sides {
+            let dir = Vec2::from_angle(TAU * i as f32 / sides as f32);
+            mesh.vertices.push(vertex(center + dir * radius, color));
+
+            let next = (i + 1) % sides;
+            mesh.indices.extend([first, first + 1 + i as u16, first + 1 + next as u16]);
+        }
+    }
+
+    /// Issue one draw call per mesh.
+    pub fn draw(&self) {
+        for mesh in &self.meshes {
+            draw_mesh(mesh);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Largest distance between a circle and its `sides`-sided polygon.
+    fn error(radius: f32, sides: usize) -> f32 {
+        (f64::from(radius) * (1.0 - (std::f64::consts::PI / sides as f64).cos())) as f32
+    }
+
+    fn vertex_count(batch: &Batch) -> usize {
+        batch.meshes.iter().map(|mesh| mesh.vertices.len()).sum()
+    }
+
+    fn index_count(batch: &Batch) -> usize {
+        batch.meshes.iter().map(|mesh| mesh.indices.len()).sum()
+    }
+
+    /// Every index points at a vertex of its own mesh.
+    fn assert_indices_valid(batch: &Batch) {
+        for mesh in &batch.meshes {
+            assert!(mesh.indices.iter().all(|i| usize::from(*i) < mesh.vertices.len()));
+            assert_eq!(mesh.indices.len() % 3, 0);
+        }
+    }
+
+    #[test]
+    fn circles_stay_within_tolerance() {
+        for radius in [1.0, 5.0, 20.0, 100.0, 400.0] {
+            let sides = circle_sides(radius);
+
+            assert!(error(radius, sides) <= CIRCLE_TOLERANCE * 1.001, "{sides} sides for radius {radius}");
+
+            // No fewer sides would do, unless the minimum applies.
+            if sides > MIN_CIRCLE_SIDES {
+                assert!(error(radius, sides - 1) > CIRCLE_TOLERANCE, "{sides} sides for radius {radius}");
+            }
+        }
+    }
+
+    #[test]
+    fn circle_sides_are_clamped() {
+        assert_eq!(circle_sides(0.0), MIN_CIRCLE_SIDES);
+        assert_eq!(circle_sides(CIRCLE_TOLERANCE), MIN_CIRCLE_SIDES);
+        assert_eq!(circle_sides(0.5), MIN_CIRCLE_SIDES);
+        assert_eq!(circle_sides(1e6), MAX_CIRCLE_SIDES);
+    }
+
+    #[test]
+    fn larger_circles_get_more_sides() {
+        let sides = [1.0, 10.0, 100.0, 1000.0, 10_000.0].map(circle_sides);
+
+        assert!(sides.windows(2).all(|pair| pair[0] <= pair[1]), "{sides:?}");
+        assert!(sides[0] < sides[4]);
+    }
+
+    #[test]
+    fn lines_are_two_triangles() {
+        let mut batch = Batch::new();
+        batch.line(Vec2::ZERO, Vec2::new(10.0, 0.0), 2.0, WHITE);
+        batch.line(Vec2::ONE, Vec2::new(1.0, 5.0), 1.0, WHITE);
+
+        assert_eq!(vertex_count(&batch), 8);
+        assert_eq!(index_count(&batch), 12);
+        assert_indices_valid(&batch);
+
+        // The first line is 2 wide around the x axis.
+        let ys = batch.meshes[0].vertices[..4].iter().map(|v| v.position.y).collect::<Vec<_>>();
+        assert!(ys.iter().all(|y| (y.abs() - 1.0).abs() < 1e-6), "{ys:?}");
+    }
+
+    #[test]
+    fn degenerate_lines_are_skipped() {
+        let mut batch = Batch::new();
+        batch.line(Vec2::ONE, Vec2::ONE, 2.0, WHITE);
+
+        assert_eq!(vertex_count(&batch), 0);
+        assert_eq!(index_count(&batch), 0);
+    }
+
+    #[test]
+    fn circle_counts() {
+        let radius = 50.0;
+        let sides = circle_sides(radius);
+        let mut batch = Batch::new();
+
+        batch.circle(Vec2::ZERO, radius, WHITE);
+        assert_eq!(vertex_count(&batch), sides + 1);
+        assert_eq!(index_count(&batch), 3 * sides);
+
+        batch.circle_lines(Vec2::ZERO, radius, 1.0, WHITE);
+        assert_eq!(vertex_count(&batch), sides + 1 + 2 * sides);
+        assert_eq!(index_count(&batch), 3 * sides + 6 * sides);
+        assert_indices_valid(&batch);
+    }
+
+    #[test]
+    fn full_meshes_start_new_ones() {
+        let lines = MAX_VERTICES / 4 + 100;
+        let mut batch = Batch::new();
+
+        for i in 0..lines {
+            batch.line(Vec2::ZERO, Vec2::new(1.0, i as f32), 1.0, WHITE);
+        }
+
+        assert_eq!(batch.meshes.len(), 2);
+        assert!(batch.meshes.iter().all(|mesh| mesh.vertices.len() < MAX_VERTICES));
+        assert_eq!(vertex_count(&batch), 4 * lines);
+        assert_eq!(index_count(&batch), 6 * lines);
+        assert_indices_valid(&batch);
+    }
+
+    #[test]
+    fn reserve_reports_the_first_new_vertex() {
+        let mut batch = Batch::new();
+        batch.circle(Vec2::ZERO, 1.0, WHITE);
+
+        let (mesh, first) = batch.reserve(4, 6);
+        assert_eq!(usize::from(first), mesh.vertices.len());
+        assert_eq!(first, 13);
+
+        let (_, first) = batch.reserve(MAX_VERTICES, 6);
+        assert_eq!(first, 0);
+        assert_eq!(batch.meshes.len(), 2);
+    }
+}
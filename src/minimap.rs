@@ -0,0 +1,60 @@
+//! An overview of the whole figure while zoomed in.
+
+use egui::{Color32, Rect, Sense, Stroke, Ui, Vec2};
+use geo_aid_internal::projector::figure::{Item, Position};
+use crate::view::View;
+
+/// Length of the longer side of the minimap.
+const SIZE: f32 = 160.0;
+/// Distance from the bottom-right corner of the canvas.
+const MARGIN: f32 = 8.0;
+const BACKGROUND: Color32 = Color32::from_rgba_premultiplied(216, 216, 216, 230);
+const ITEM_COLOR: Color32 = Color32::from_gray(90);
+const VIEWPORT_COLOR: Color32 = Color32::from_rgb(30, 110, 230);
+
+/// Show the minimap in the bottom-right corner of the `canvas` rect, if zoomed in.
+/// `items` are as drawn in the view. Clicking or dragging on the minimap centers the view there.
+pub fn ui(ui: &mut Ui, canvas: Rect, view: &mut View, items: &[Item]) {
+    if !view.is_zoomed() {
+        return;
+    }
+
+    let size = (canvas.width(), canvas.height());
+    let scale = SIZE / size.0.max(size.1);
+    let mini_size = canvas.size() * scale;
+    let rect = Rect::from_min_size(canvas.max - mini_size - Vec2::splat(MARGIN), mini_size);
+
+    let response = ui.interact(rect, ui.id().with("minimap"), Sense::click_and_drag());
+    let painter = ui.painter_at(rect);
+    let to_mini = |(x, y): (f64, f64)| rect.min + Vec2::new(x as f32, y as f32) * scale;
+    let at = |position: &Position| to_mini(view.to_figure((position.x, position.y), size));
+
+    painter.rect(rect, 2.0, BACKGROUND, Stroke::new(1.0, ITEM_COLOR));
+
+    let stroke = Stroke::new(1.0, ITEM_COLOR);
+    for item in items {
+        match item {
+            Item::Point(pt) => painter.circle_filled(at(&pt.position), 1.0, ITEM_COLOR),
+            Item::Line(ln) => painter.line_segment([at(&ln.points.0), at(&ln.points.1)], stroke),
+            Item::Segment(x)
+            | Item::Ray(x) => painter.line_segment([at(&x.points.0), at(&x.points.1)], stroke),
+            Item::Circle(circle) => {
+                let radius = circle.radius / view.zoom;
+                painter.circle_stroke(at(&circle.center), radius as f32 * scale, stroke);
+            }
+        }
+    }
+
+    let viewport = Rect::from_two_pos(
+        to_mini(view.to_figure((0.0, 0.0), size)),
+        to_mini(view.to_figure((f64::from(size.0), f64::from(size.1)), size))
+    );
+    painter.rect_stroke(viewport, 0.0, Stroke::new(1.5, VIEWPORT_COLOR));
+
+    if response.clicked() || response.dragged() {
+        if let Some(pointer) = response.interact_pointer_pos() {
+            let relative = (pointer - rect.min) / rect.size();
+            view.center_on((f64::from(relative.x), f64::from(relative.y)));
+        }
+    }
+}
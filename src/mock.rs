@@ -0,0 +1,576 @@
+//! A deterministic stand-in for the real engine, for working on the UI.
+//!
+//! The mock ignores the script: it has a fixed number of adjustables, each drifting toward a fixed target,
+//! jittered by the adjustment magnitudes, and the quality follows the remaining distance. Runs are the same
+//! every time, so plots, the history and the archive can be checked against known curves.
+//!
+//! The mock shows a small figure of its own, [`FIGURE`], instead of the script's. Rage evaluates it with the first
+//! adjustables of the mock's state, so the figure moves along with the run.
+//!
+//! Unlike Rage, the mock lets the debugger look inside it. Its [`RULES`] don't constrain anything, they split
+//! the distance to the targets up, so that views of individual rules have something to show. Disabling a rule
+//! leaves its adjustables where they are and its error out of the quality, heavier rules pull their adjustables
+//! in faster.
+
+use std::mem;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::script::figure::{Figure, Generated};
+use geo_aid_internal::script::math;
+use geo_aid_internal::script::math::{Flags, Intermediate};
+use crate::engine::{DebuggableEngine, Entity, ExpressionStats, Proposal, Rule, WorkerStats};
+
+/// Adjustables of a mock created in place of a compiled script. Only the first few move [`FIGURE`].
+pub const ADJUSTABLES: usize = 16;
+/// Seed of the mock's random numbers.
+const SEED: u64 = 0x5EED_5EED_5EED_5EED;
+/// Largest distance of a target from the starting value.
+const TARGET_SPREAD: f64 = 0.5;
+/// Fraction of the remaining distance covered by an accepted cycle.
+const PULL: f64 = 0.05;
+/// Chance of a cycle being accepted.
+const ACCEPTANCE: f64 = 0.3;
+/// Largest fraction of the quality a rejected proposal loses.
+const REJECTED_LOSS: f64 = 0.1;
+/// The figure the mock shows: three points, a line through two of them and a circle around the third.
+pub const FIGURE: &str = "let A, B, C = Point();
+dst(A, B) = dst(A, C);
+let k = Line(A, B);
+let omega = Circle(C, dst(A, B));
+";
+/// Entities of [`FIGURE`] the mock reports: their names, the items showing them and the entities they're derived from.
+const ENTITIES: [(&str, Option<usize>, &[usize]); 6] = [
+    ("A", Some(0), &[]),
+    ("B", Some(1), &[]),
+    ("C", Some(2), &[]),
+    ("k", Some(3), &[0, 1]),
+    ("dst(A, B)", None, &[0, 1]),
+    ("omega", Some(4), &[2, 4])
+];
+/// Rules the mock reports, as they read in [`FIGURE`], with the items they refer to in the order they're declared.
+/// Adjustables go to the rules in turn, and a rule's error is their squared distance to their targets.
+pub const RULES: [(&str, &[usize]); 3] = [
+    ("dst(A, B) = dst(A, C)", &[0, 1, 2]),
+    ("let k = Line(A, B)", &[0, 1, 3]),
+    ("let omega = Circle(C, dst(A, B))", &[0, 1, 2, 4])
+];
+/// Expressions the mock pretends to evaluate, with the index of their rule in [`RULES`] and how many nanoseconds
+/// an evaluation takes. Every worker evaluates the expressions of every enabled rule once a cycle.
+const EXPRESSIONS: [(&str, usize, u64); 5] = [
+    ("dst(A, B)", 0, 40),
+    ("dst(A, C)", 0, 40),
+    ("dst(A, B) = dst(A, C)", 0, 15),
+    ("Line(A, B)", 1, 60),
+    ("Circle(C, dst(A, B))", 2, 90)
+];
+
+/// SplitMix64, enough for reproducible noise.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Uniform in `[-1, 1)`.
+    fn signed(&mut self) -> f64 {
+        2.0 * self.unit() - 1.0
+    }
+}
+
+/// [`FIGURE`], compiled, with an engine to evaluate it at the mock's state.
+struct Sketch {
+    figure: Figure,
+    flags: Flags,
+    /// Only ever given a state and asked for the figure, never cycled.
+    rage: Mutex<Rage>
+}
+
+impl Sketch {
+    fn new() -> Self {
+        let intermediate = math::load_script(FIGURE).expect("the mock's figure compiles");
+
+        Self {
+            rage: Mutex::new(Rage::new(1, &intermediate)),
+            figure: intermediate.figure,
+            flags: intermediate.flags
+        }
+    }
+
+    /// The figure with its adjustables taken from the start of `state`, repeated if it's too short.
+    fn generate(&self, state: &[f64]) -> Generated {
+        let mut rage = self.rage.lock().unwrap_or_else(PoisonError::into_inner);
+        let adjustables = rage.gen().get_state().len();
+        let state = state.iter().copied().cycle().take(adjustables).collect::<Vec<_>>();
+
+        if state.len() == adjustables {
+            rage.gen_mut().set_state(&state);
+        }
+
+        rage.get_figure(self.figure.clone())
+    }
+}
+
+/// An engine with synthetic, reproducible dynamics.
+pub struct MockEngine {
+    state: Vec<f64>,
+    target: Vec<f64>,
+    /// Distance to the target at the start, for normalizing the quality.
+    initial: f64,
+    random: Random,
+    sketch: Arc<Sketch>,
+    /// One entry per worker. Every worker proposes in every cycle, an accepted cycle takes one of the proposals.
+    workers: Vec<WorkerStats>,
+    cycles: u64,
+    /// Whether each of [`RULES`] is evaluated.
+    enabled: [bool; RULES.len()],
+    /// What the error of each of [`RULES`] is multiplied by.
+    weights: [f64; RULES.len()],
+    /// Evaluations of each of [`EXPRESSIONS`] since the profile was last taken, while profiling.
+    evaluations: Option<[u64; EXPRESSIONS.len()]>
+}
+
+impl MockEngine {
+    /// A mock with `adjustables` adjustables, starting in `[0, 1)`.
+    #[must_use]
+    pub fn new(adjustables: usize) -> Self {
+        Self::seeded(adjustables, SEED)
+    }
+
+    /// Like [`Self::new`], with the starting state, the targets and the noise drawn from `seed`.
+    #[must_use]
+    pub fn seeded(adjustables: usize, seed: u64) -> Self {
+        Self::with_sketch(adjustables, seed, Arc::new(Sketch::new()))
+    }
+
+    fn with_sketch(adjustables: usize, seed: u64, sketch: Arc<Sketch>) -> Self {
+        let mut random = Random(seed);
+        let state = (0..adjustables).map(|_| random.unit()).collect::<Vec<_>>();
+        let target = state.iter()
+            .map(|value| value + TARGET_SPREAD * random.signed())
+            .collect::<Vec<_>>();
+
+        let mut engine = Self {
+            state,
+            target,
+            initial: 0.0,
+            random,
+            sketch,
+            workers: vec![WorkerStats::default()],
+            cycles: 0,
+            enabled: [true; RULES.len()],
+            weights: [1.0; RULES.len()],
+            evaluations: None
+        };
+
+        engine.initial = engine.distance();
+        engine
+    }
+
+    /// The same mock with `count` workers, at least one.
+    #[must_use]
+    pub fn with_workers(mut self, count: usize) -> Self {
+        self.workers = vec![WorkerStats::default(); count.max(1)];
+        self
+    }
+
+    /// Flags of [`FIGURE`], to project the mock's figures with.
+    #[must_use]
+    pub fn flags(&self) -> Flags {
+        self.sketch.flags.clone()
+    }
+
+    /// Weighted error of each of [`RULES`]. Disabled rules have none.
+    fn errors(&self) -> Vec<f64> {
+        let mut errors = vec![0.0; RULES.len()];
+
+        for (i, (value, target)) in self.state.iter().zip(&self.target).enumerate() {
+            let rule = i % RULES.len();
+
+            if self.enabled[rule] {
+                errors[rule] += self.weights[rule] * (value - target).powi(2);
+            }
+        }
+
+        errors
+    }
+
+    /// Credit a cycle to the workers. Unless the one whose proposal was taken if the cycle was `accepted`,
+    /// they all proposed something worse than the quality `before` the cycle.
+    fn credit_workers(&mut self, before: f64, accepted: bool) {
+        // Drawn apart from the dynamics, so that runs don't depend on the worker count.
+        let mut noise = Random(self.cycles);
+        let taken = accepted.then(|| (noise.next() % self.workers.len() as u64) as usize);
+        let quality = self.quality();
+
+        for (i, worker) in self.workers.iter_mut().enumerate() {
+            if Some(i) == taken {
+                worker.accepted += 1;
+                worker.quality = quality;
+            } else {
+                worker.rejected += 1;
+                worker.quality = before * (1.0 - REJECTED_LOSS * noise.unit());
+            }
+        }
+    }
+
+    fn distance(&self) -> f64 {
+        self.errors().iter().sum::<f64>().sqrt()
+    }
+}
+
+impl DebuggableEngine for MockEngine {
+    type Figure = ();
+
+    const SEEDED: bool = true;
+
+    /// Ignores the script, see [`ADJUSTABLES`].
+    fn compile(worker_count: usize, _intermediate: &Intermediate) -> Self {
+        Self::new(ADJUSTABLES).with_workers(worker_count)
+    }
+
+    fn adjustables(_intermediate: &Intermediate) -> usize {
+        ADJUSTABLES
+    }
+
+    fn figure_of(_intermediate: &Intermediate) {}
+
+    fn reseed(&mut self, seed: u64) {
+        let reseeded = Self::with_sketch(self.state.len(), seed, Arc::clone(&self.sketch));
+
+        *self = Self {
+            enabled: self.enabled,
+            weights: self.weights,
+            evaluations: self.evaluations,
+            ..reseeded.with_workers(self.workers.len())
+        };
+    }
+
+    fn bake(&self, max_adjustment: f64) -> Vec<f64> {
+        vec![max_adjustment; self.state.len()]
+    }
+
+    fn cycle(&mut self, magnitudes: &[f64]) {
+        let before = self.quality();
+        let accepted = self.random.unit() < ACCEPTANCE;
+        self.cycles += 1;
+
+        if accepted {
+            let adjusted = self.state.iter_mut()
+                .zip(&self.target)
+                .zip(magnitudes)
+                .enumerate()
+                .filter(|(i, _)| self.enabled[i % RULES.len()]);
+
+            for (i, ((value, target), magnitude)) in adjusted {
+                let pull = (PULL * self.weights[i % RULES.len()]).min(1.0);
+                let jitter = magnitude * pull * self.random.signed();
+                *value += (target - *value) * pull + jitter;
+            }
+        }
+
+        if let Some(evaluations) = &mut self.evaluations {
+            for (count, (_, rule, _)) in evaluations.iter_mut().zip(EXPRESSIONS) {
+                if self.enabled[rule] {
+                    *count += self.workers.len() as u64;
+                }
+            }
+        }
+
+        self.credit_workers(before, accepted);
+    }
+
+    /// The taken proposal is what the cycle did, the others are made up from the magnitudes.
+    fn cycle_recorded(&mut self, magnitudes: &[f64]) -> Option<Vec<Proposal>> {
+        let state = self.state.clone();
+        let accepted = self.workers.iter().map(|stats| stats.accepted).collect::<Vec<_>>();
+        self.cycle(magnitudes);
+
+        let taken = self.state.iter().zip(&state).map(|(after, before)| after - before).collect::<Vec<_>>();
+        // Drawn apart from the dynamics like the workers' qualities, but not from the same numbers.
+        let mut noise = Random(!self.cycles);
+
+        let proposals = self.workers.iter()
+            .zip(accepted)
+            .enumerate()
+            .map(|(worker, (stats, accepted))| {
+                let accepted = stats.accepted > accepted;
+                let adjustment = if accepted {
+                    taken.clone()
+                } else {
+                    magnitudes.iter().map(|magnitude| magnitude * noise.signed()).collect()
+                };
+
+                Proposal {
+                    worker,
+                    adjustment,
+                    quality: stats.quality,
+                    accepted
+                }
+            })
+            .collect();
+
+        Some(proposals)
+    }
+
+    fn state(&self) -> &[f64] {
+        &self.state
+    }
+
+    fn set_state(&mut self, state: &[f64]) {
+        self.state.clear();
+        self.state.extend_from_slice(state);
+    }
+
+    fn quality(&self) -> f64 {
+        if self.initial == 0.0 {
+            1.0
+        } else {
+            (1.0 - self.distance() / self.initial).clamp(0.0, 1.0)
+        }
+    }
+
+    fn figure(&self, _figure: ()) -> Generated {
+        self.sketch.generate(&self.state)
+    }
+
+    fn rules(&self) -> Option<Vec<Rule>> {
+        let rules = RULES.iter()
+            .zip(self.enabled)
+            .zip(self.weights)
+            .map(|(((name, items), enabled), weight)| Rule {
+                name: (*name).to_string(),
+                items: items.to_vec(),
+                enabled,
+                weight: Some(weight)
+            })
+            .collect();
+
+        Some(rules)
+    }
+
+    fn entities(&self) -> Option<Vec<Entity>> {
+        let entities = ENTITIES.iter()
+            .map(|(name, item, parents)| Entity {
+                name: (*name).to_string(),
+                item: *item,
+                parents: parents.to_vec()
+            })
+            .collect();
+
+        Some(entities)
+    }
+
+    fn rule_errors(&self) -> Option<Vec<f64>> {
+        Some(self.errors())
+    }
+
+    fn workers(&self) -> Option<Vec<WorkerStats>> {
+        Some(self.workers.clone())
+    }
+
+    fn set_rule_enabled(&mut self, rule: usize, enabled: bool) -> bool {
+        let Some(rule) = self.enabled.get_mut(rule) else {
+            return false;
+        };
+
+        *rule = enabled;
+        true
+    }
+
+    fn set_profiling(&mut self, profiling: bool) -> bool {
+        self.evaluations = profiling.then_some([0; EXPRESSIONS.len()]);
+        true
+    }
+
+    /// The mock evaluates nothing, so the time is made up from [`EXPRESSIONS`].
+    fn take_profile(&mut self) -> Vec<ExpressionStats> {
+        let Some(evaluations) = &mut self.evaluations else {
+            return Vec::new();
+        };
+
+        EXPRESSIONS.iter()
+            .zip(mem::take(evaluations))
+            .map(|((name, rule, nanos), evaluations)| ExpressionStats {
+                name: (*name).to_string(),
+                rule: Some(*rule),
+                evaluations,
+                time: Duration::from_nanos(nanos * evaluations)
+            })
+            .collect()
+    }
+
+    fn set_rule_weight(&mut self, rule: usize, weight: f64) -> bool {
+        match self.weights.get_mut(rule) {
+            Some(current) if weight.is_finite() && weight >= 0.0 => {
+                *current = weight;
+                true
+            }
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_aid_internal::projector;
+    use super::*;
+
+    #[test]
+    fn runs_are_reproducible() {
+        let run = || {
+            let mut engine = MockEngine::new(ADJUSTABLES);
+            let magnitudes = engine.bake(0.5);
+
+            for _ in 0..100 {
+                engine.cycle(&magnitudes);
+            }
+
+            (engine.state().to_vec(), engine.quality())
+        };
+
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn seeds_pick_the_run() {
+        let run = |seed| {
+            let mut engine = MockEngine::new(ADJUSTABLES);
+            engine.reseed(seed);
+            let magnitudes = engine.bake(0.5);
+
+            for _ in 0..100 {
+                engine.cycle(&magnitudes);
+            }
+
+            engine.state().to_vec()
+        };
+
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1), run(2));
+        assert_eq!(MockEngine::seeded(ADJUSTABLES, SEED).state(), MockEngine::new(ADJUSTABLES).state());
+    }
+
+    #[test]
+    fn figure_is_drawn_from_the_state() {
+        let engine = MockEngine::new(ADJUSTABLES);
+        let figure = projector::project(engine.figure(()), &engine.flags(), (100, 100));
+
+        // Three points, the line and the circle.
+        assert_eq!(figure.items.len(), 5);
+    }
+
+    #[test]
+    fn rule_errors_add_up_to_the_distance() {
+        let engine = MockEngine::new(ADJUSTABLES);
+        let errors = engine.rule_errors().unwrap();
+
+        assert_eq!(errors.len(), engine.rules().unwrap().len());
+        assert!((errors.iter().sum::<f64>() - engine.distance().powi(2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn disabled_rules_are_left_alone() {
+        let mut engine = MockEngine::new(ADJUSTABLES);
+        let magnitudes = engine.bake(0.5);
+        let before = engine.state().to_vec();
+
+        assert!(engine.set_rule_enabled(1, false));
+        assert!(!engine.set_rule_enabled(RULES.len(), false));
+
+        for _ in 0..100 {
+            engine.cycle(&magnitudes);
+        }
+
+        let moved = |i: usize| engine.state()[i] != before[i];
+
+        assert_eq!(engine.rule_errors().unwrap()[1], 0.0);
+        assert!(!engine.rules().unwrap()[1].enabled);
+        assert!((0..ADJUSTABLES).all(|i| moved(i) != (i % RULES.len() == 1)));
+    }
+
+    #[test]
+    fn the_taken_proposal_is_what_the_cycle_did() {
+        let mut engine = MockEngine::new(ADJUSTABLES).with_workers(4);
+        let magnitudes = engine.bake(0.5);
+
+        for _ in 0..20 {
+            let before = engine.state().to_vec();
+            let proposals = engine.cycle_recorded(&magnitudes).unwrap();
+            let taken = proposals.iter().filter(|proposal| proposal.accepted).collect::<Vec<_>>();
+
+            assert_eq!(proposals.len(), 4);
+
+            match taken[..] {
+                [proposal] => {
+                    let after = before.iter().zip(&proposal.adjustment).map(|(value, change)| value + change);
+                    assert!(after.zip(engine.state()).all(|(expected, value)| (expected - value).abs() < 1e-12));
+                }
+                [] => assert_eq!(engine.state(), before),
+                _ => panic!("more than one proposal taken")
+            }
+        }
+    }
+
+    #[test]
+    fn profiles_start_over_once_taken() {
+        let mut engine = MockEngine::new(ADJUSTABLES).with_workers(4);
+        let magnitudes = engine.bake(0.5);
+
+        assert!(engine.take_profile().is_empty());
+        assert!(engine.set_profiling(true));
+        engine.set_rule_enabled(1, false);
+
+        for _ in 0..10 {
+            engine.cycle(&magnitudes);
+        }
+
+        let evaluations = |profile: Vec<ExpressionStats>| {
+            profile.iter().map(|stats| stats.evaluations).collect::<Vec<_>>()
+        };
+
+        assert_eq!(evaluations(engine.take_profile()), [40, 40, 40, 0, 40]);
+        assert_eq!(evaluations(engine.take_profile()), [0; EXPRESSIONS.len()]);
+    }
+
+    #[test]
+    fn workers_share_the_cycles() {
+        let mut engine = MockEngine::new(ADJUSTABLES).with_workers(8);
+        let magnitudes = engine.bake(0.5);
+
+        for _ in 0..100 {
+            engine.cycle(&magnitudes);
+        }
+
+        let workers = engine.workers().unwrap();
+        let accepted = workers.iter().map(|worker| worker.accepted).sum::<usize>();
+
+        assert_eq!(workers.len(), 8);
+        assert!(workers.iter().all(|worker| worker.accepted + worker.rejected == 100));
+        assert!(accepted > 0 && accepted < 100, "{accepted} accepted");
+    }
+
+    #[test]
+    fn converges() {
+        let mut engine = MockEngine::new(ADJUSTABLES);
+        let magnitudes = engine.bake(0.01);
+        let initial = engine.quality();
+
+        for _ in 0..2000 {
+            engine.cycle(&magnitudes);
+        }
+
+        assert!(engine.quality() > initial);
+        assert!(engine.quality() > 0.9, "quality {}", engine.quality());
+    }
+}
@@ -0,0 +1,58 @@
+//! Drawing into off-screen textures, independent of the window size.
+
+use macroquad::prelude::*;
+use macroquad::window::get_internal_gl;
+
+/// Largest side of an off-screen texture. Every GL 3 implementation supports at least this.
+pub const MAX_SIDE: u32 = 8192;
+
+/// Size of the texture `canvas` is drawn into at `scale`, keeping the aspect ratio within [`MAX_SIDE`].
+#[must_use]
+pub fn texture_size(canvas: (f32, f32), scale: f32) -> (u32, u32) {
+    let longer = canvas.0.max(canvas.1) * scale;
+    let scale = if longer > MAX_SIDE as f32 { scale * MAX_SIDE as f32 / longer } else { scale };
+
+    (
+        ((canvas.0 * scale).round() as u32).max(1),
+        ((canvas.1 * scale).round() as u32).max(1)
+    )
+}
+
+/// Draw into `target` on a white background. `draw` draws in coordinates of a `canvas`-sized area,
+/// which is stretched over the whole texture.
+///
+/// The draw is carried out right away and the default camera is set afterwards.
+pub fn draw_to(target: &RenderTarget, canvas: (f32, f32), draw: impl FnOnce()) {
+    // Flush what was drawn before, so that it ends up in its own pass.
+    // SAFETY: only macroquad's own draw calls are submitted, no raw GL state is touched.
+    unsafe {
+        get_internal_gl().flush();
+    }
+
+    set_camera(&Camera2D {
+        render_target: Some(target.clone()),
+        ..Camera2D::from_display_rect(Rect::new(0.0, 0.0, canvas.0, canvas.1))
+    });
+
+    draw_rectangle(0.0, 0.0, canvas.0, canvas.1, WHITE);
+    draw();
+
+    // SAFETY: as above.
+    unsafe {
+        get_internal_gl().flush();
+    }
+
+    set_default_camera();
+}
+
+/// Draw a `canvas`-sized area into a multisampled image `scale` times larger. See [`draw_to`].
+///
+/// Like screen data, the image is stored bottom-up.
+#[must_use]
+pub fn render(canvas: (f32, f32), scale: f32, draw: impl FnOnce()) -> Image {
+    let (width, height) = texture_size(canvas, scale);
+    let target = render_target_msaa(width, height);
+
+    draw_to(&target, canvas, draw);
+    target.texture.get_texture_data()
+}
@@ -0,0 +1,362 @@
+use egui::Ui;
+use geo_aid_internal::projector::figure::Item;
+use geo_aid_internal::script::figure::Generated;
+use geo_aid_internal::script::math::Intermediate;
+use macroquad::camera::Camera2D;
+use macroquad::math::vec2;
+use serde::{Deserialize, Serialize};
+use crate::engine::Rule;
+use crate::history;
+use crate::history::History;
+use crate::i18n::{Language, Text};
+use crate::render;
+
+/// Settings of the built-in overlays, as stored in sessions.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Overlays {
+    /// Show where points moved since the previous iteration.
+    pub movement: bool,
+    /// How much movement vectors are scaled by.
+    pub movement_scale: f32,
+    /// Show older iterations under the current one.
+    pub ghosts: bool,
+    /// How many older iterations to show.
+    pub ghost_count: usize,
+    /// Show a grid, axes and a scale bar.
+    pub grid: bool,
+    /// Minimum distance between grid lines.
+    pub grid_spacing: f32,
+    /// Color items by the error of the rules referring to them.
+    pub heatmap: bool
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Self {
+            movement: false,
+            movement_scale: 1.0,
+            ghosts: false,
+            ghost_count: 8,
+            grid: false,
+            grid_spacing: 40.0,
+            heatmap: false
+        }
+    }
+}
+
+/// Everything an overlay may draw from.
+pub struct OverlayContext<'a> {
+    /// The latest generated figure.
+    pub generated: &'a Generated,
+    /// `generated` as projected onto the canvas.
+    pub items: &'a [Item],
+    pub intermediate: &'a Intermediate,
+    /// Recently generated frames.
+    pub history: &'a History,
+    /// Rules of the engine, if it tells them apart.
+    pub rules: Option<&'a [Rule]>,
+    /// The camera mapping canvas coordinates onto the screen. It's already set when drawing.
+    pub camera: &'a Camera2D,
+    /// Canvas size in canvas coordinates.
+    pub canvas: (f32, f32),
+    /// Projects another figure (e.g. one from `history`) the same way `items` were.
+    pub project: &'a dyn Fn(&Generated) -> Vec<Item>,
+    /// The UI language, for text drawn on the canvas.
+    pub language: Language
+}
+
+/// A layer drawn on the canvas along with the figure.
+///
+/// Custom overlays are added with [`Debugger::register_overlay`](crate::Debugger::register_overlay)
+/// and listed in the inspector after the built-in ones.
+pub trait Overlay {
+    /// Name shown next to the overlay's checkbox, in the UI language if the overlay is translated.
+    fn name(&self, language: Language) -> &str;
+
+    /// Whether the overlay is shown.
+    fn enabled(&mut self) -> &mut bool;
+
+    /// Whether to draw under the figure instead of over it.
+    fn below(&self) -> bool {
+        false
+    }
+
+    /// Draw the overlay in canvas coordinates.
+    fn draw(&mut self, ctx: &OverlayContext);
+
+    /// Overlay-specific settings, shown under its checkbox.
+    fn ui(&mut self, _ui: &mut Ui, _language: Language) {}
+}
+
+/// Fading copies of older iterations.
+pub struct Ghosts {
+    pub enabled: bool,
+    pub count: usize
+}
+
+impl Overlay for Ghosts {
+    fn name(&self, language: Language) -> &str {
+        Text::GhostsOverlay.tr(language)
+    }
+
+    fn enabled(&mut self) -> &mut bool {
+        &mut self.enabled
+    }
+
+    fn below(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, ctx: &OverlayContext) {
+        let ghosts = ctx.history.before_latest(self.count)
+            .map(|frame| (ctx.project)(&frame.generated))
+            .collect::<Vec<_>>();
+
+        render::draw_ghosts(ghosts.iter().map(Vec::as_slice));
+    }
+
+    fn ui(&mut self, ui: &mut Ui, language: Language) {
+        ui.add(
+            egui::Slider::new(&mut self.count, 1..=history::DEFAULT_CAPACITY - 1)
+                .text(Text::GhostCount.tr(language))
+        );
+    }
+}
+
+/// Vectors showing where points moved since the previous iteration.
+pub struct Movement {
+    pub enabled: bool,
+    pub scale: f32
+}
+
+impl Overlay for Movement {
+    fn name(&self, language: Language) -> &str {
+        Text::MovementOverlay.tr(language)
+    }
+
+    fn enabled(&mut self) -> &mut bool {
+        &mut self.enabled
+    }
+
+    fn draw(&mut self, ctx: &OverlayContext) {
+        if let Some(previous) = ctx.history.previous() {
+            render::draw_movement(&(ctx.project)(&previous.generated), ctx.items, self.scale);
+        }
+    }
+
+    fn ui(&mut self, ui: &mut Ui, language: Language) {
+        ui.add(
+            egui::Slider::new(&mut self.scale, 1.0..=100.0)
+                .logarithmic(true)
+                .text(Text::VectorScale.tr(language))
+        );
+    }
+}
+
+/// The smallest of 1, 2 and 5 times a power of ten that is at least `min`.
+fn nice_step(min: f32) -> f32 {
+    let magnitude = 10f32.powf(min.log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0].into_iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= min)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// A coordinate grid with axes and a scale bar.
+///
+/// The projector doesn't expose its figure-to-canvas transform, so it's drawn in canvas
+/// units, with the axes crossing at the center of the canvas.
+pub struct Grid {
+    pub enabled: bool,
+    /// Minimum distance between grid lines.
+    pub spacing: f32
+}
+
+impl Overlay for Grid {
+    fn name(&self, language: Language) -> &str {
+        Text::GridOverlay.tr(language)
+    }
+
+    fn enabled(&mut self) -> &mut bool {
+        &mut self.enabled
+    }
+
+    fn below(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, ctx: &OverlayContext) {
+        let step = nice_step(self.spacing);
+        let origin = vec2(ctx.canvas.0 / 2.0, ctx.canvas.1 / 2.0);
+
+        render::draw_grid(ctx.canvas, origin, step);
+        render::draw_scale_bar(ctx.canvas, step, &Text::Units.fill(ctx.language, &[&step]));
+    }
+
+    fn ui(&mut self, ui: &mut Ui, language: Language) {
+        ui.add(
+            egui::Slider::new(&mut self.spacing, 10.0..=200.0)
+                .logarithmic(true)
+                .text(Text::GridSpacing.tr(language))
+        );
+    }
+}
+
+/// Items colored by the error of the rules referring to them, from blue where they're satisfied
+/// to red for the largest error in the figure.
+pub struct Heatmap {
+    pub enabled: bool,
+    /// Whether the engine reported rule errors when the overlay was last drawn.
+    supported: bool
+}
+
+impl Overlay for Heatmap {
+    fn name(&self, language: Language) -> &str {
+        Text::HeatmapOverlay.tr(language)
+    }
+
+    fn enabled(&mut self) -> &mut bool {
+        &mut self.enabled
+    }
+
+    fn below(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, ctx: &OverlayContext) {
+        let errors = ctx.history.latest().and_then(|frame| frame.rule_errors.as_deref());
+        self.supported = ctx.rules.is_some() && errors.is_some();
+
+        if let (Some(rules), Some(errors)) = (ctx.rules, errors) {
+            render::draw_heat(ctx.items, &item_errors(rules, errors, ctx.items.len()));
+        }
+    }
+
+    fn ui(&mut self, ui: &mut Ui, language: Language) {
+        if !self.supported {
+            ui.label(Text::NotSupportedByEngine.tr(language));
+        }
+    }
+}
+
+/// Summed error of the rules referring to each of `count` items, `None` for items no rule refers to.
+#[must_use]
+pub fn item_errors(rules: &[Rule], errors: &[f64], count: usize) -> Vec<Option<f64>> {
+    let mut items = vec![None; count];
+
+    for (rule, error) in rules.iter().zip(errors) {
+        for item in rule.items.iter().filter_map(|&i| items.get_mut(i)) {
+            *item = Some(item.unwrap_or(0.0) + error);
+        }
+    }
+
+    items
+}
+
+/// The built-in overlays followed by the registered ones, in drawing order.
+pub struct OverlayStack {
+    pub grid: Grid,
+    pub ghosts: Ghosts,
+    pub heatmap: Heatmap,
+    pub movement: Movement,
+    custom: Vec<Box<dyn Overlay>>
+}
+
+impl OverlayStack {
+    #[must_use]
+    pub fn new(settings: Overlays) -> Self {
+        Self {
+            grid: Grid {
+                enabled: settings.grid,
+                spacing: settings.grid_spacing
+            },
+            ghosts: Ghosts {
+                enabled: settings.ghosts,
+                count: settings.ghost_count
+            },
+            heatmap: Heatmap {
+                enabled: settings.heatmap,
+                supported: true
+            },
+            movement: Movement {
+                enabled: settings.movement,
+                scale: settings.movement_scale
+            },
+            custom: Vec::new()
+        }
+    }
+
+    pub fn register(&mut self, overlay: Box<dyn Overlay>) {
+        self.custom.push(overlay);
+    }
+
+    #[must_use]
+    pub fn settings(&self) -> Overlays {
+        Overlays {
+            movement: self.movement.enabled,
+            movement_scale: self.movement.scale,
+            ghosts: self.ghosts.enabled,
+            ghost_count: self.ghosts.count,
+            grid: self.grid.enabled,
+            grid_spacing: self.grid.spacing,
+            heatmap: self.heatmap.enabled
+        }
+    }
+
+    /// Apply built-in overlay settings. Registered overlays are left as they are.
+    pub fn apply(&mut self, settings: Overlays) {
+        let custom = std::mem::take(&mut self.custom);
+        *self = Self::new(settings);
+        self.custom = custom;
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut (dyn Overlay + 'static)> {
+        [&mut self.grid as &mut (dyn Overlay + 'static), &mut self.ghosts, &mut self.heatmap, &mut self.movement]
+            .into_iter()
+            .chain(self.custom.iter_mut().map(Box::as_mut))
+    }
+
+    /// Draw the enabled overlays that go under the figure, or over it if `below` is false.
+    pub fn draw(&mut self, ctx: &OverlayContext, below: bool) {
+        for overlay in self.iter_mut() {
+            if *overlay.enabled() && overlay.below() == below {
+                overlay.draw(ctx);
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, lang: Language) {
+        for overlay in self.iter_mut() {
+            let name = overlay.name(lang).to_owned();
+            let enabled = overlay.enabled();
+            ui.checkbox(enabled, name);
+            let enabled = *enabled;
+
+            ui.add_enabled_ui(enabled, |ui| overlay.ui(ui, lang));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(items: &[usize]) -> Rule {
+        Rule {
+            name: String::new(),
+            items: items.to_vec(),
+            enabled: true,
+            weight: None
+        }
+    }
+
+    #[test]
+    fn items_sum_the_errors_of_their_rules() {
+        let rules = [rule(&[0, 1]), rule(&[1, 2]), rule(&[7])];
+        let errors = item_errors(&rules, &[1.0, 0.5, 2.0], 4);
+
+        assert_eq!(errors, [Some(1.0), Some(1.5), Some(0.5), None]);
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// Frame rate used while nothing is happening, if power saving is on.
+pub const IDLE_FPS: u32 = 5;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrameRate {
+    /// Maximum frames per second, 0 for no limit.
+    pub cap: u32,
+    /// Drop to [`IDLE_FPS`] when there's no input and the figure doesn't change.
+    pub power_saving: bool
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self {
+            cap: 60,
+            power_saving: true
+        }
+    }
+}
+
+/// Sleeps away the rest of each frame.
+pub struct Pacer {
+    frame_start: Instant
+}
+
+impl Default for Pacer {
+    fn default() -> Self {
+        Self {
+            frame_start: Instant::now()
+        }
+    }
+}
+
+impl Pacer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until a frame at `fps` has passed since the last call.
+    ///
+    /// Browsers can't sleep and schedule frames themselves, so on the web this never waits.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub fn wait(&mut self, fps: Option<u32>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(fps) = fps.filter(|fps| *fps > 0) {
+            let frame = Duration::from_secs_f64(1.0 / f64::from(fps));
+
+            if let Some(rest) = frame.checked_sub(self.frame_start.elapsed()) {
+                thread::sleep(rest);
+            }
+        }
+
+        self.frame_start = Instant::now();
+    }
+}
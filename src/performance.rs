@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use egui::Context;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use crate::i18n::{Language, Text};
+use crate::runtime::Timings;
+
+/// Microseconds per cycle.
+fn per_cycle(total: Duration, cycles: usize) -> f64 {
+    total.as_secs_f64() * 1e6 / cycles as f64
+}
+
+/// The "Performance" window, charting where the worker spends its time.
+#[derive(Default)]
+pub struct PerformanceTool {
+    pub open: bool
+}
+
+impl PerformanceTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `timings` are (iteration, time spent) reports of the running engine.
+    pub fn show(&mut self, ctx: &Context, timings: &VecDeque<(usize, Timings)>, lang: Language) {
+        egui::Window::new(Text::Performance.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                let total = timings.iter().fold(Timings::default(), |total, (_, t)| Timings {
+                    cycles: total.cycles + t.cycles,
+                    cycle: total.cycle + t.cycle,
+                    checks: total.checks + t.checks,
+                    publish: total.publish + t.publish
+                });
+
+                if total.cycles == 0 {
+                    ui.label(Text::NoCyclesTimed.tr(lang));
+                    return;
+                }
+
+                let parts = [
+                    (Text::EngineCycle, total.cycle),
+                    (Text::BreakpointChecks, total.checks),
+                    (Text::FigureBuild, total.publish)
+                ];
+                let sum = total.cycle + total.checks + total.publish;
+
+                egui::Grid::new("performance-summary")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (name, time) in parts {
+                            ui.label(name.tr(lang));
+                            let micros = format!("{:.1}", per_cycle(time, total.cycles));
+                            ui.label(Text::MicrosecondsPerCycle.fill(lang, &[&micros]));
+                            ui.label(format!("{:.1}%", 100.0 * time.as_secs_f64() / sum.as_secs_f64()));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.label(Text::EngineCycleNote.tr(lang));
+
+                let series = |part: fn(&Timings) -> Duration| timings.iter()
+                    .map(|(iteration, t)| [*iteration as f64, per_cycle(part(t), t.cycles)])
+                    .collect::<Vec<_>>();
+
+                Plot::new("performance")
+                    .legend(Legend::default())
+                    .y_axis_label(Text::MicrosecondsPerCycleAxis.tr(lang))
+                    .show(ui, |plot| {
+                        plot.line(Line::new(PlotPoints::from(series(|t| t.cycle))).name(Text::EngineCycle.tr(lang)));
+                        plot.line(
+                            Line::new(PlotPoints::from(series(|t| t.checks))).name(Text::BreakpointChecks.tr(lang))
+                        );
+                        plot.line(Line::new(PlotPoints::from(series(|t| t.publish))).name(Text::FigureBuild.tr(lang)));
+                    });
+            });
+    }
+}
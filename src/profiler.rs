@@ -0,0 +1,155 @@
+//! Where the engine spends its cycles, expression by expression.
+
+use std::time::Duration;
+use egui::Ui;
+use crate::engine::{ExpressionStats, Rule};
+use crate::i18n::{Language, Text};
+use crate::runtime::Profile;
+
+/// Most expressions listed.
+const TOP: usize = 10;
+/// Cycles per profiling window until changed.
+const DEFAULT_WINDOW: usize = 1000;
+
+/// What the profiler asks of the debugger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Profile every given number of cycles, or stop if `None`.
+    Profile(Option<usize>),
+    /// Pick a rule, by its index.
+    PickRule(usize)
+}
+
+/// The "Profiler" section of the inspector.
+#[derive(Debug)]
+pub struct Profiler {
+    /// Cycles per profiling window.
+    pub window: usize
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_WINDOW
+        }
+    }
+}
+
+impl Profiler {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the profiling controls and the most time-consuming expressions of the latest `profile`.
+    /// `profiling` is the window being profiled, if any, and `unsupported` whether the engine turned profiling down.
+    pub fn ui(
+        &mut self,
+        ui: &mut Ui,
+        profiling: Option<usize>,
+        unsupported: bool,
+        profile: Option<&Profile>,
+        rules: Option<&[Rule]>,
+        lang: Language
+    ) -> Option<Action> {
+        if unsupported {
+            ui.label(Text::NotSupportedByEngine.tr(lang));
+            return None;
+        }
+
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            let mut enabled = profiling.is_some();
+
+            if ui.checkbox(&mut enabled, Text::ProfileExpressions.tr(lang)).changed() {
+                action = Some(Action::Profile(enabled.then_some(self.window)));
+            }
+
+            ui.label(Text::ProfileWindow.tr(lang));
+
+            if ui.add(egui::DragValue::new(&mut self.window).range(1..=1_000_000)).changed() && enabled {
+                action = Some(Action::Profile(Some(self.window)));
+            }
+        });
+
+        let Some(profile) = profile else {
+            ui.label(Text::NoProfileYet.tr(lang));
+            return action;
+        };
+
+        ui.label(Text::ProfiledCycles.fill(lang, &[&profile.cycles, &profile.iteration]));
+
+        let shares = shares(&profile.expressions);
+
+        egui::Grid::new("profile")
+            .num_columns(5)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(Text::Expression.tr(lang));
+                ui.strong(Text::Rule.tr(lang));
+                ui.strong(Text::Evaluations.tr(lang));
+                ui.strong(Text::MicrosecondsPerCycleAxis.tr(lang));
+                ui.strong(Text::ShareOfTime.tr(lang));
+                ui.end_row();
+
+                for (stats, share) in profile.expressions.iter().zip(shares).take(TOP) {
+                    ui.monospace(&stats.name);
+
+                    match stats.rule.and_then(|i| rules.and_then(|rules| rules.get(i)).map(|rule| (i, rule))) {
+                        Some((i, rule)) => {
+                            if ui.link(&rule.name).clicked() {
+                                action = Some(Action::PickRule(i));
+                            }
+                        }
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+
+                    ui.label(stats.evaluations.to_string());
+                    ui.label(format!("{:.3}", per_cycle(stats.time, profile.cycles)));
+                    ui.label(format!("{:.1}%", 100.0 * share));
+                    ui.end_row();
+                }
+            });
+
+        action
+    }
+}
+
+/// Microseconds per cycle.
+fn per_cycle(time: Duration, cycles: usize) -> f64 {
+    time.as_secs_f64() * 1e6 / cycles.max(1) as f64
+}
+
+/// The fraction of the total time each expression took, zero for all if none took any.
+#[must_use]
+pub fn shares(expressions: &[ExpressionStats]) -> Vec<f64> {
+    let total = expressions.iter().map(|stats| stats.time).sum::<Duration>().as_secs_f64();
+
+    expressions.iter()
+        .map(|stats| if total > 0.0 { stats.time.as_secs_f64() / total } else { 0.0 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(micros: u64) -> ExpressionStats {
+        ExpressionStats {
+            name: String::new(),
+            rule: None,
+            evaluations: 1,
+            time: Duration::from_micros(micros)
+        }
+    }
+
+    #[test]
+    fn shares_add_up_to_one() {
+        assert_eq!(shares(&[stats(3), stats(1)]), [0.75, 0.25]);
+        assert_eq!(shares(&[stats(0), stats(0)]), [0.0, 0.0]);
+        assert!(shares(&[]).is_empty());
+    }
+}
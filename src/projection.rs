@@ -0,0 +1,292 @@
+use geo_aid_internal::projector;
+use geo_aid_internal::projector::figure::{Item, Label, Position};
+use geo_aid_internal::script::figure::Generated;
+use geo_aid_internal::script::math::Flags;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ui")]
+use crate::i18n::Text;
+
+/// A fixed canvas aspect ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Aspect {
+    Square,
+    FourThree,
+    SixteenNine,
+    A4Portrait,
+    A4Landscape
+}
+
+impl Aspect {
+    pub const ALL: [Self; 5] = [Self::Square, Self::FourThree, Self::SixteenNine, Self::A4Portrait, Self::A4Landscape];
+
+    #[cfg(feature = "ui")]
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::Square => Text::Square,
+            Self::FourThree => Text::AspectFourThree,
+            Self::SixteenNine => Text::AspectSixteenNine,
+            Self::A4Portrait => Text::A4Portrait,
+            Self::A4Landscape => Text::A4Landscape
+        }
+    }
+
+    /// Width divided by height.
+    #[must_use]
+    pub fn ratio(self) -> f32 {
+        match self {
+            Self::Square => 1.0,
+            Self::FourThree => 4.0 / 3.0,
+            Self::SixteenNine => 16.0 / 9.0,
+            Self::A4Portrait => 210.0 / 297.0,
+            Self::A4Landscape => 297.0 / 210.0
+        }
+    }
+}
+
+/// Length lines and rays are drawn at when the fit is frozen, far past any canvas.
+const FROZEN_LINE_LENGTH: f64 = 1e6;
+
+/// How figures are projected for the canvas and exports.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Projection {
+    /// Fixed canvas size in canvas units, or `None` to fill the canvas tab.
+    pub size: Option<(u32, u32)>,
+    /// Aspect ratio the canvas is locked to when it fills the tab.
+    pub aspect: Option<Aspect>,
+    /// Space left empty on every side of the figure.
+    pub margin: u32,
+    /// Place every frame the way a reference frame was fitted instead of refitting it, so that entities running
+    /// off to infinity leave the canvas instead of shrinking the rest of the figure.
+    pub freeze_fit: bool
+}
+
+impl Projection {
+    /// The canvas size to use when the available area is `available`.
+    #[must_use]
+    pub fn canvas(&self, available: (f32, f32)) -> (f32, f32) {
+        if let Some((width, height)) = self.size {
+            return (width as f32, height as f32);
+        }
+
+        match self.aspect {
+            Some(aspect) => {
+                let ratio = aspect.ratio();
+                let width = available.0.min(available.1 * ratio);
+                (width, width / ratio)
+            }
+            None => available
+        }
+    }
+
+    /// Project a figure onto a canvas of the given size, keeping the margin empty.
+    ///
+    /// If the fit is frozen, the figure is then moved where `reference`'s fit would put it, see [`refit`].
+    #[must_use]
+    pub fn project(
+        &self,
+        generated: Generated,
+        reference: Option<&Generated>,
+        flags: &Flags,
+        canvas: (f32, f32)
+    ) -> Vec<Item> {
+        let mut items = self.fit(generated, flags, canvas);
+
+        if let Some(reference) = reference.filter(|_| self.freeze_fit) {
+            let fitted = self.fit(reference.clone(), flags, canvas);
+
+            if let Some(refit) = refit(&fitted, &items) {
+                for item in &mut items {
+                    refit.apply(item);
+                }
+            }
+        }
+
+        items
+    }
+
+    /// Fit a figure into the canvas with the projector, keeping the margin empty.
+    fn fit(&self, generated: Generated, flags: &Flags, canvas: (f32, f32)) -> Vec<Item> {
+        let margin = self.margin as f32;
+        let inner = (
+            (canvas.0 - 2.0 * margin).max(1.0),
+            (canvas.1 - 2.0 * margin).max(1.0)
+        );
+
+        let mut items = projector::project(generated, flags, (inner.0 as usize, inner.1 as usize)).items;
+
+        if self.margin > 0 {
+            for item in &mut items {
+                offset(item, f64::from(self.margin));
+            }
+        }
+
+        items
+    }
+}
+
+/// Move an item by `by` right and down.
+fn offset(item: &mut Item, by: f64) {
+    let shift = |position: &mut Position| {
+        position.x += by;
+        position.y += by;
+    };
+    let shift_label = |label: &mut Option<Label>| {
+        if let Some(label) = label {
+            label.position.x += by;
+            label.position.y += by;
+        }
+    };
+
+    match item {
+        Item::Point(pt) => {
+            shift(&mut pt.position);
+            shift_label(&mut pt.label);
+        }
+        Item::Line(ln) => {
+            shift(&mut ln.points.0);
+            shift(&mut ln.points.1);
+            shift_label(&mut ln.label);
+        }
+        Item::Segment(x)
+        | Item::Ray(x) => {
+            shift(&mut x.points.0);
+            shift(&mut x.points.1);
+            shift_label(&mut x.label);
+        }
+        Item::Circle(circle) => {
+            shift(&mut circle.center);
+            shift_label(&mut circle.label);
+        }
+    }
+}
+
+/// A uniform scale followed by a translation, taking items from one fit to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Refit {
+    pub scale: f64,
+    pub offset: (f64, f64)
+}
+
+impl Refit {
+    fn map(&self, position: &Position) -> Position {
+        Position {
+            x: self.scale * position.x + self.offset.0,
+            y: self.scale * position.y + self.offset.1
+        }
+    }
+
+    /// Move `position` and its label by the same amount, so that labels keep their place next to items.
+    fn move_with_label(&self, position: &mut Position, label: &mut Option<Label>) {
+        let moved = self.map(position);
+
+        if let Some(label) = label {
+            label.position.x += moved.x - position.x;
+            label.position.y += moved.y - position.y;
+        }
+
+        *position = moved;
+    }
+
+    /// Move an item. Lines and rays are extended, as the projector ends them where they leave the canvas.
+    pub fn apply(&self, item: &mut Item) {
+        match item {
+            Item::Point(pt) => self.move_with_label(&mut pt.position, &mut pt.label),
+            Item::Line(ln) => {
+                let (a, b) = (self.map(&ln.points.0), self.map(&ln.points.1));
+                self.move_with_label(&mut ln.points.0, &mut ln.label);
+                ln.points = (extend(&b, &a), extend(&a, &b));
+            }
+            Item::Segment(x) => {
+                x.points.1 = self.map(&x.points.1);
+                self.move_with_label(&mut x.points.0, &mut x.label);
+            }
+            Item::Ray(x) => {
+                let through = self.map(&x.points.1);
+                self.move_with_label(&mut x.points.0, &mut x.label);
+                x.points.1 = extend(&x.points.0, &through);
+            }
+            Item::Circle(circle) => {
+                self.move_with_label(&mut circle.center, &mut circle.label);
+                circle.radius *= self.scale;
+            }
+        }
+    }
+}
+
+/// The point [`FROZEN_LINE_LENGTH`] away from `from` in the direction of `through`.
+fn extend(from: &Position, through: &Position) -> Position {
+    let (dx, dy) = (through.x - from.x, through.y - from.y);
+    let length = dx.hypot(dy);
+    let reach = if length == 0.0 { 0.0 } else { FROZEN_LINE_LENGTH / length };
+
+    Position {
+        x: from.x + dx * reach,
+        y: from.y + dy * reach
+    }
+}
+
+/// Positions that don't depend on where the canvas ends: points, segment ends, ray origins and circle centers.
+fn anchors(items: &[Item]) -> Vec<(f64, f64)> {
+    let mut anchors = Vec::new();
+
+    for item in items {
+        match item {
+            Item::Point(pt) => anchors.push((pt.position.x, pt.position.y)),
+            Item::Line(_) => (),
+            Item::Segment(x) => anchors.extend([(x.points.0.x, x.points.0.y), (x.points.1.x, x.points.1.y)]),
+            Item::Ray(x) => anchors.push((x.points.0.x, x.points.0.y)),
+            Item::Circle(circle) => anchors.push((circle.center.x, circle.center.y))
+        }
+    }
+
+    anchors
+}
+
+/// The middle value, or `None` if there are none.
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    values.get(values.len() / 2).copied()
+}
+
+/// The coordinate-wise median of `positions`.
+fn median_position(positions: impl Iterator<Item = (f64, f64)> + Clone) -> Option<(f64, f64)> {
+    Some((median(positions.clone().map(|p| p.0).collect())?, median(positions.map(|p| p.1).collect())?))
+}
+
+/// How to move `items` where `reference`, the same figure fitted from another frame, has them.
+///
+/// The projector's fits only differ in scale and position, so with the figure standing still this is exact.
+/// The scale and offset are taken from medians, so that a few entities running away don't drag the rest along.
+/// Returns `None` if the items don't come from the same figure or have no finite anchors.
+#[must_use]
+pub fn refit(reference: &[Item], items: &[Item]) -> Option<Refit> {
+    let to = anchors(reference);
+    let from = anchors(items);
+
+    if to.len() != from.len() {
+        return None;
+    }
+
+    let pairs = from.into_iter()
+        .zip(to)
+        .filter(|(from, to)| [from.0, from.1, to.0, to.1].iter().all(|v| v.is_finite()))
+        .collect::<Vec<_>>();
+
+    let from_center = median_position(pairs.iter().map(|pair| pair.0))?;
+    let to_center = median_position(pairs.iter().map(|pair| pair.1))?;
+
+    let ratios = pairs.iter()
+        .filter_map(|(from, to)| {
+            let distance = (from.0 - from_center.0).hypot(from.1 - from_center.1);
+            (distance > 0.0).then(|| (to.0 - to_center.0).hypot(to.1 - to_center.1) / distance)
+        })
+        .collect();
+    let scale = median(ratios).unwrap_or(1.0);
+
+    Some(Refit {
+        scale,
+        offset: (to_center.0 - scale * from_center.0, to_center.1 - scale * from_center.1)
+    })
+}
@@ -0,0 +1,113 @@
+//! Stepping through a cycle one worker proposal at a time.
+
+use egui::{Color32, RichText, Ui};
+use crate::engine::Proposal;
+use crate::i18n::{Language, Text};
+
+const TAKEN_COLOR: Color32 = Color32::from_rgb(20, 150, 60);
+
+/// What the proposal stepper asks of the debugger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Start or stop reporting proposals.
+    Dissect(bool),
+    /// Step to the next cycle, past the last proposal of this one.
+    NextCycle
+}
+
+/// The "Proposals" section of the inspector.
+#[derive(Debug, Default)]
+pub struct Proposals {
+    /// (iteration, index) of the proposal shown.
+    cursor: (usize, usize)
+}
+
+impl Proposals {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show one of the latest `proposals`, reported for an iteration, and buttons to step through them.
+    /// `dissecting` is whether proposals are reported and `unsupported` whether the engine doesn't keep them.
+    pub fn ui(
+        &mut self,
+        ui: &mut Ui,
+        dissecting: bool,
+        unsupported: bool,
+        proposals: Option<&(usize, Vec<Proposal>)>,
+        lang: Language
+    ) -> Option<Action> {
+        if unsupported {
+            ui.label(Text::NotSupportedByEngine.tr(lang));
+            return None;
+        }
+
+        let mut enabled = dissecting;
+
+        if ui.checkbox(&mut enabled, Text::StepThroughProposals.tr(lang)).changed() {
+            return Some(Action::Dissect(enabled));
+        }
+
+        if !dissecting {
+            return None;
+        }
+
+        let Some((iteration, proposals)) = proposals.filter(|(_, proposals)| !proposals.is_empty()) else {
+            ui.label(Text::NoProposalsYet.tr(lang));
+            return ui.button(Text::NextProposal.tr(lang)).clicked().then_some(Action::NextCycle);
+        };
+
+        if self.cursor.0 != *iteration {
+            self.cursor = (*iteration, 0);
+        }
+
+        let index = self.cursor.1.min(proposals.len() - 1);
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(index > 0, egui::Button::new(Text::PreviousProposal.tr(lang))).clicked() {
+                self.cursor.1 = index - 1;
+            }
+
+            if ui.button(Text::NextProposal.tr(lang)).clicked() {
+                if index + 1 < proposals.len() {
+                    self.cursor.1 = index + 1;
+                } else {
+                    action = Some(Action::NextCycle);
+                }
+            }
+
+            let taken = proposals.iter().position(|proposal| proposal.accepted);
+
+            if ui.add_enabled(taken.is_some(), egui::Button::new(Text::TakenProposal.tr(lang))).clicked() {
+                self.cursor.1 = taken.unwrap_or(index);
+            }
+        });
+
+        let proposal = &proposals[index];
+        let quality = format!("{:.6}", proposal.quality);
+
+        ui.label(Text::ProposalOf.fill(lang, &[&(index + 1), &proposals.len(), iteration]));
+        ui.label(Text::ProposalDetails.fill(lang, &[&proposal.worker, &quality]));
+
+        if proposal.accepted {
+            ui.label(RichText::new(Text::ProposalTaken.tr(lang)).color(TAKEN_COLOR).strong());
+        } else {
+            ui.label(Text::ProposalDropped.tr(lang));
+        }
+
+        egui::Grid::new("proposal")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, change) in proposal.adjustment.iter().enumerate() {
+                    ui.label(format!("#{i}"));
+                    ui.label(format!("{change:+.6}"));
+                    ui.end_row();
+                }
+            });
+
+        action
+    }
+}
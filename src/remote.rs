@@ -0,0 +1,399 @@
+//! Running the generation on another machine.
+//!
+//! The server and the debugger exchange newline-delimited JSON over TCP. The debugger sends
+//! [`Message`]s; the server answers with [`Status`] reports and the adjustables of every published
+//! frame. The debugger compiles the same script locally and regenerates figures from those
+//! adjustables, so only the engine state goes over the wire.
+//!
+//! On connecting, the server introduces itself with a hash of its script and its number of adjustables. The debugger
+//! refuses servers generating anything else, as their states wouldn't fit its own figure.
+//!
+//! There is no authentication or encryption: anyone who can reach the server can drive its engine. Given only a port,
+//! the server listens on the loopback interface. Listen elsewhere only on trusted networks, or tunnel the port
+//! (e.g. over SSH).
+
+use std::env;
+use std::fs;
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::script::figure::Figure;
+use geo_aid_internal::script::math;
+use geo_aid_internal::script::math::Intermediate;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::runtime;
+use crate::runtime::{Crash, Frame, Message, Runtime, Status, Threading, Worker};
+
+/// How often the server checks for a newly published frame.
+const FRAME_POLL: Duration = Duration::from_millis(50);
+/// How long the debugger waits for the server to introduce itself.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const USAGE: &str = "usage: geo-aid-dbg --serve <[host:]port> <script> [worker count] [max adjustment]
+
+The server has no authentication. Without a host, it only listens on 127.0.0.1.";
+/// Host listened on when only a port is given.
+const DEFAULT_HOST: &str = "127.0.0.1";
+
+/// What the server sends.
+#[derive(Serialize, Deserialize)]
+enum Event {
+    /// Sent first, once the server's engine is ready.
+    Hello {
+        /// [`script_hash`] of the served script.
+        script: u64,
+        adjustables: usize
+    },
+    /// Sent instead of [`Event::Hello`] if the server's engine couldn't start.
+    Refused(String),
+    Status(Status),
+    Frame {
+        iteration: usize,
+        quality: f64,
+        state: Vec<f64>
+    }
+}
+
+/// FNV-1a hash of a script's source, stable across builds and platforms.
+#[must_use]
+pub fn script_hash(script: &str) -> u64 {
+    script.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn send(stream: &mut TcpStream, value: &impl Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_string(value)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+/// Command line of the headless server.
+pub struct ServeArgs {
+    /// `host:port`, or a port on [`DEFAULT_HOST`].
+    pub address: String,
+    pub script: PathBuf,
+    pub worker_count: usize,
+    pub max_adjustment: f64
+}
+
+impl ServeArgs {
+    /// `None` unless the debugger was started with `--serve`.
+    ///
+    /// # Errors
+    /// Returns the usage message if the arguments are malformed.
+    pub fn from_env() -> Option<Result<Self, String>> {
+        let mut args = env::args().skip(1);
+
+        if args.next().as_deref() != Some("--serve") {
+            return None;
+        }
+
+        let (Some(address), Some(script)) = (args.next(), args.next()) else {
+            return Some(Err(String::from(USAGE)));
+        };
+
+        let worker_count = args.next().map_or(Ok(runtime::DEFAULT_WORKER_COUNT), |arg| usize::from_str(&arg));
+        let max_adjustment = args.next().map_or(Ok(runtime::DEFAULT_MAX_ADJUSTMENT), |arg| f64::from_str(&arg));
+
+        Some(match (worker_count, max_adjustment) {
+            (Ok(worker_count), Ok(max_adjustment)) => Ok(Self {
+                address,
+                script: PathBuf::from(script),
+                worker_count,
+                max_adjustment
+            }),
+            _ => Err(String::from(USAGE))
+        })
+    }
+}
+
+/// The address to listen on for an address given as `[host:]port`.
+fn listen_address(address: &str) -> String {
+    if u16::from_str(address).is_ok() {
+        format!("{DEFAULT_HOST}:{address}")
+    } else if let Some(port) = address.strip_prefix(':') {
+        format!("{DEFAULT_HOST}:{port}")
+    } else {
+        address.to_owned()
+    }
+}
+
+/// Serve generations of the script to debuggers, one connection at a time. Every connection gets a fresh engine.
+///
+/// # Errors
+/// Returns an error if the script can't be compiled or the address can't be bound.
+pub fn serve(args: &ServeArgs) -> io::Result<()> {
+    let script = fs::read_to_string(&args.script)?;
+    let hash = script_hash(&script);
+    let intermediate = math::load_script(&script)
+        .map_err(|err| invalid_data(format!("{err:?}")))?;
+
+    let listener = TcpListener::bind(listen_address(&args.address))?;
+    let local = listener.local_addr()?;
+    info!("serving {} on {local}", args.script.display());
+
+    if !local.ip().is_loopback() {
+        warn!("{local} is reachable from other machines, and anyone connecting can drive the engine");
+    }
+
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        info!("debugger connected from {:?}", stream.peer_addr());
+
+        let rage = match panic::catch_unwind(AssertUnwindSafe(|| Rage::new(args.worker_count, &intermediate))) {
+            Ok(rage) => rage,
+            Err(payload) => {
+                let message = runtime::panic_message(payload.as_ref());
+                warn!("engine failed to start: {message}");
+                let _ = send(&mut stream, &Event::Refused(message));
+                continue;
+            }
+        };
+
+        let hello = Event::Hello {
+            script: hash,
+            adjustables: rage.gen().get_state().len()
+        };
+
+        let worker = runtime::spawn_worker(
+            rage,
+            args.max_adjustment,
+            intermediate.figure.clone(),
+            Threading::default()
+        );
+
+        if let Err(err) = send(&mut stream, &hello).and_then(|()| serve_connection(stream, worker)) {
+            warn!("connection closed: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+fn serve_connection(mut stream: TcpStream, worker: Worker) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let control = worker.control.clone();
+    let abort = Arc::clone(&worker.abort);
+
+    thread::spawn(move || {
+        for line in reader.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            match serde_json::from_str::<Message>(&line) {
+                Ok(message) => {
+                    if message.aborts() {
+                        abort.store(true, Ordering::Relaxed);
+                    }
+
+                    if control.send(message).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => warn!("invalid message: {err}")
+            }
+        }
+
+        abort.store(true, Ordering::Relaxed);
+        let _ = control.send(Message::Quit);
+    });
+
+    let mut revision = 0;
+
+    loop {
+        // The frame goes first, so that the debugger has it when the status referring to it arrives.
+        let frame = {
+            let frame = worker.frame.lock().unwrap_or_else(PoisonError::into_inner);
+
+            (frame.revision != revision).then(|| {
+                revision = frame.revision;
+
+                Event::Frame {
+                    iteration: frame.iteration,
+                    quality: frame.quality,
+                    state: frame.state.clone()
+                }
+            })
+        };
+
+        if let Some(frame) = frame {
+            send(&mut stream, &frame)?;
+        }
+
+        match worker.status.recv_timeout(FRAME_POLL) {
+            Ok(status) => send(&mut stream, &Event::Status(status))?,
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => return Ok(())
+        }
+    }
+}
+
+/// Read the server's introduction and check that it generates `script`, compiled as `adjustables` adjustables.
+fn handshake(reader: &mut BufReader<TcpStream>, script: &str, adjustables: usize) -> io::Result<()> {
+    reader.get_ref().set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    reader.get_ref().set_read_timeout(None)?;
+
+    match serde_json::from_str::<Event>(&line) {
+        Ok(Event::Hello { script: hash, .. }) if hash != script_hash(script) => Err(invalid_data(String::from(
+            "the server generates a different script; open the same script on both sides"
+        ))),
+        Ok(Event::Hello { adjustables: theirs, .. }) if theirs != adjustables => Err(invalid_data(format!(
+            "the server's engine has {theirs} adjustables, this one has {adjustables}; \
+            check that both use the same geo-aid version"
+        ))),
+        Ok(Event::Hello { .. }) => Ok(()),
+        Ok(Event::Refused(message)) => Err(invalid_data(format!("the server's engine failed to start: {message}"))),
+        Ok(_) | Err(_) => Err(invalid_data(String::from(
+            "the server didn't introduce itself; is it a geo-aid-dbg server?"
+        )))
+    }
+}
+
+/// Connect to a server generating `script`, compiled locally as `intermediate`.
+///
+/// # Errors
+/// Returns an error if the connection can't be made, the local engine can't start,
+/// or the server generates something else.
+pub fn connect(address: &str, script: &str, intermediate: Intermediate, max_adjustment: f64) -> io::Result<Runtime> {
+    // Figures are regenerated locally from the received adjustables.
+    let figure = intermediate.figure.clone();
+    let mut rage = panic::catch_unwind(AssertUnwindSafe(|| Rage::new(1, &intermediate)))
+        .map_err(|payload| invalid_data(runtime::panic_message(payload.as_ref())))?;
+    let adjustables = rage.gen().get_state().len();
+
+    let stream = TcpStream::connect(address)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    handshake(&mut reader, script, adjustables)?;
+
+    let frame = Arc::new(Mutex::new(Frame::default()));
+    let frame2 = Arc::clone(&frame);
+
+    let (control, control_recv) = mpsc::channel();
+    let (status_send, status) = mpsc::channel();
+
+    thread::spawn(move || {
+        for message in control_recv {
+            if matches!(message, Message::Quit) || send(&mut writer, &message).is_err() {
+                break;
+            }
+        }
+
+        let _ = writer.shutdown(Shutdown::Both);
+    });
+
+    let handle = thread::spawn(move || {
+        let mut iteration = 0;
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            receive(reader, &mut rage, &figure, &frame2, &status_send, adjustables, &mut iteration)
+        }));
+
+        let message = match result {
+            // The debugger has dropped the runtime.
+            Ok(None) => return,
+            Ok(Some(message)) => message,
+            Err(payload) => runtime::panic_message(payload.as_ref())
+        };
+
+        let _ = status_send.send(Status::Panicked(Crash { message, iteration }));
+    });
+
+    // Stopping is forwarded to the server, which aborts its own worker.
+    let worker = Worker {
+        control,
+        status,
+        frame,
+        abort: Arc::new(AtomicBool::new(false)),
+        handle
+    };
+
+    // The server starts with its own max adjustment.
+    let mut runtime = Runtime::from_worker(worker, max_adjustment, intermediate.flags);
+    runtime.set_max_adjustment(max_adjustment);
+    Ok(runtime)
+}
+
+/// Forward the server's events until the connection ends.
+///
+/// Returns why the connection ended, or `None` if the debugger stopped listening.
+fn receive(
+    reader: BufReader<TcpStream>,
+    rage: &mut Rage,
+    figure: &Figure,
+    frame: &Mutex<Frame>,
+    status: &mpsc::Sender<Status>,
+    adjustables: usize,
+    iteration: &mut usize
+) -> Option<String> {
+    let mut revision = 0;
+
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        match serde_json::from_str::<Event>(&line) {
+            Ok(Event::Status(report)) => {
+                if status.send(report).is_err() {
+                    return None;
+                }
+            }
+            Ok(Event::Frame { iteration: at, quality, state }) => {
+                if state.len() != adjustables {
+                    return Some(format!("the server sent {} adjustables, expected {adjustables}", state.len()));
+                }
+
+                rage.gen_mut().set_state(&state);
+                revision += 1;
+                *iteration = at;
+                let generated = rage.get_figure(figure.clone());
+
+                *frame.lock().unwrap_or_else(PoisonError::into_inner) = Frame {
+                    revision,
+                    iteration: at,
+                    generated,
+                    quality,
+                    state,
+                    // The server runs Rage, which reports nothing beyond its state.
+                    ..Frame::default()
+                };
+            }
+            Ok(Event::Hello { .. } | Event::Refused(_)) => warn!("unexpected handshake"),
+            Err(err) => warn!("invalid event: {err}")
+        }
+    }
+
+    Some(String::from("connection to the server lost"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ports_listen_on_loopback() {
+        assert_eq!(listen_address("7878"), "127.0.0.1:7878");
+        assert_eq!(listen_address(":7878"), "127.0.0.1:7878");
+        assert_eq!(listen_address("0.0.0.0:7878"), "0.0.0.0:7878");
+        assert_eq!(listen_address("[::1]:7878"), "[::1]:7878");
+    }
+}
@@ -0,0 +1,549 @@
+use std::collections::BTreeSet;
+use geo_aid_internal::projector::figure::{Item, Label, Position};
+use macroquad::prelude::*;
+use crate::annotation::Annotation;
+use crate::colors::ItemColors;
+use crate::font::Fonts;
+use crate::i18n::{Language, Text};
+use crate::math_label;
+use crate::math_label::Span;
+use crate::mesh::Batch;
+use crate::style::{DotStyle, ItemStyle};
+
+/// Smaller canvas dimension at which labels are drawn at their set size.
+const REFERENCE_SIZE: f32 = 800.0;
+/// How many times a single label may be pushed away from something it overlaps.
+const NUDGE_ATTEMPTS: usize = 8;
+/// Space kept free between a point's dot and labels.
+const DOT_CLEARANCE: f32 = 2.0;
+/// Stroke width and dot radius of ghosts and compared frames.
+const STROKE_WIDTH: f32 = 1.0;
+const DOT_RADIUS: f32 = 2.0;
+/// How much thicker strokes and larger dots are in high-contrast mode.
+pub const HIGH_CONTRAST_STROKE_SCALE: f32 = 2.5;
+pub const HIGH_CONTRAST_DOT_SCALE: f32 = 1.75;
+/// How much larger labels are in high-contrast mode.
+const HIGH_CONTRAST_LABEL_SCALE: f32 = 1.5;
+/// Length of the arrowhead sides of movement vectors.
+const ARROWHEAD_SIZE: f32 = 6.0;
+const MOVEMENT_COLOR: Color = ORANGE;
+const GHOST_COLOR: Color = DARKBLUE;
+/// Opacity of the most recent ghost.
+const GHOST_ALPHA: f32 = 0.4;
+/// Stroke width and dot radius of the rule error heatmap, drawn as a halo under the figure.
+const HEAT_WIDTH: f32 = 6.0;
+const HEAT_RADIUS: f32 = 7.0;
+/// Heatmap colors of satisfied rules and of the largest error.
+const HEAT_COLD: Color = Color::new(0.0, 0.35, 1.0, 0.6);
+const HEAT_HOT: Color = Color::new(1.0, 0.0, 0.0, 0.6);
+/// Colors of the two compared frames.
+const COMPARE_A_COLOR: Color = GRAY;
+const COMPARE_B_COLOR: Color = RED;
+const GRID_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.08);
+const AXIS_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.35);
+const ANNOTATION_COLOR: Color = MAGENTA;
+const ANNOTATION_TEXT_SIZE: f32 = 18.0;
+/// Distance of the scale bar from the bottom-left corner of the canvas.
+const SCALE_BAR_MARGIN: f32 = 16.0;
+/// With level of detail on, labels are left out if there are more than this many of them or they'd be
+/// smaller than [`MIN_LABEL_SIZE`].
+const MAX_LABELS: usize = 300;
+const MIN_LABEL_SIZE: u16 = 6;
+const NOTICE_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.5);
+
+/// Draws figures with the user's item colors.
+///
+/// This is what the debugger's canvas uses, exposed for embedding the canvas into other macroquad apps.
+/// Overlays are drawn separately, see [`crate::overlay`].
+pub struct Renderer<'a> {
+    pub colors: &'a ItemColors,
+    /// Indices of items not to draw.
+    pub hidden: &'a BTreeSet<usize>,
+    pub fonts: &'a Fonts,
+    /// Label font size on a canvas 800 pixels across.
+    pub label_size: f32,
+    pub style: &'a ItemStyle
+}
+
+impl Renderer<'_> {
+    /// Draw the figure on a canvas of the given size. Returns whether labels were left out for level of detail.
+    pub fn draw(&self, items: &[Item], canvas: (f32, f32)) -> bool {
+        draw_items(items, canvas, self.colors, self.hidden, self.fonts, self.label_size, self.style)
+    }
+}
+
+/// Resolved sizes of strokes and point dots.
+struct Strokes {
+    width: f32,
+    radius: f32,
+    dot: DotStyle
+}
+
+impl Strokes {
+    const THIN: Self = Self {
+        width: STROKE_WIDTH,
+        radius: DOT_RADIUS,
+        dot: DotStyle::Filled
+    };
+}
+
+struct PlacedLabel {
+    spans: Vec<Span>,
+    rect: Rect,
+    offset_y: f32
+}
+
+/// Draw projected figure items on a canvas of the given size, skipping the `hidden` ones and those off the canvas.
+///
+/// Returns whether labels were left out for level of detail.
+pub fn draw_items(
+    items: &[Item],
+    canvas: (f32, f32),
+    colors: &ItemColors,
+    hidden: &BTreeSet<usize>,
+    fonts: &Fonts,
+    label_size: f32,
+    style: &ItemStyle
+) -> bool {
+    let scale = (canvas.0.min(canvas.1) / REFERENCE_SIZE).clamp(0.5, 3.0);
+    let item_scale = if style.scale_with_canvas { scale } else { 1.0 };
+    let (stroke_scale, dot_scale, label_scale) = if colors.high_contrast {
+        (HIGH_CONTRAST_STROKE_SCALE, HIGH_CONTRAST_DOT_SCALE, HIGH_CONTRAST_LABEL_SCALE)
+    } else {
+        (1.0, 1.0, 1.0)
+    };
+    let strokes = Strokes {
+        width: style.stroke_width * stroke_scale * item_scale,
+        radius: style.point_radius * dot_scale * item_scale,
+        dot: style.dot
+    };
+    let clearance = strokes.radius + DOT_CLEARANCE;
+
+    let margin = strokes.width.max(strokes.radius);
+    let shown = |i: usize, item: &Item| !hidden.contains(&i) && on_canvas(item, canvas, margin);
+
+    draw_geometry(items, &strokes, |i, item| shown(i, item).then(|| colors.get(i, item)));
+
+    let mut labels = Vec::new();
+    let mut label_colors = Vec::new();
+    let mut obstacles = Vec::new();
+
+    for (i, item) in items.iter().enumerate() {
+        if !shown(i, item) {
+            continue;
+        }
+
+        match item {
+            Item::Point(pt) => {
+                obstacles.push(Rect::new(
+                    pt.position.x as f32 - clearance,
+                    pt.position.y as f32 - clearance,
+                    2.0 * clearance,
+                    2.0 * clearance
+                ));
+                labels.extend(&pt.label);
+            }
+            Item::Line(ln) => labels.extend(&ln.label),
+            Item::Segment(x)
+            | Item::Ray(x) => labels.extend(&x.label),
+            Item::Circle(circle) => labels.extend(&circle.label)
+        }
+
+        // Labels take the color of their item.
+        label_colors.resize(labels.len(), colors.get(i, item));
+    }
+
+    let size = (label_size * scale * label_scale).round() as u16;
+
+    // Label layout is quadratic in the number of labels, and tiny labels are unreadable anyway.
+    if style.level_of_detail && (labels.len() > MAX_LABELS || size < MIN_LABEL_SIZE) {
+        return true;
+    }
+
+    for (label, color) in layout_labels(&labels, obstacles, fonts, size).into_iter().zip(label_colors) {
+        math_label::draw(&label.spans, fonts, label.rect.x, label.rect.y + label.offset_y, size, color);
+    }
+
+    false
+}
+
+/// Whether any part of the item, widened by `margin`, is on the canvas.
+fn on_canvas(item: &Item, canvas: (f32, f32), margin: f32) -> bool {
+    let area = Rect::new(-margin, -margin, canvas.0 + 2.0 * margin, canvas.1 + 2.0 * margin);
+    let between = |a: &Position, b: &Position| {
+        let (a, b) = (vec2(a.x as f32, a.y as f32), vec2(b.x as f32, b.y as f32));
+        Rect::new(a.x.min(b.x), a.y.min(b.y), (a.x - b.x).abs(), (a.y - b.y).abs()).overlaps(&area)
+    };
+
+    match item {
+        Item::Point(pt) => area.contains(vec2(pt.position.x as f32, pt.position.y as f32)),
+        Item::Line(ln) => between(&ln.points.0, &ln.points.1),
+        Item::Segment(x)
+        | Item::Ray(x) => between(&x.points.0, &x.points.1),
+        Item::Circle(circle) => {
+            let center = vec2(circle.center.x as f32, circle.center.y as f32);
+            let radius = circle.radius as f32;
+            let bounds = Rect::new(center.x - radius, center.y - radius, 2.0 * radius, 2.0 * radius);
+            let corners = [
+                vec2(area.left(), area.top()),
+                vec2(area.right(), area.top()),
+                vec2(area.left(), area.bottom()),
+                vec2(area.right(), area.bottom())
+            ];
+            let farthest = corners.into_iter()
+                .map(|corner| corner.distance(center))
+                .fold(0.0, f32::max);
+
+            // A circle enclosing the whole canvas has nothing on it.
+            bounds.overlaps(&area) && farthest > radius
+        }
+    }
+}
+
+/// Draw fading copies of older figures, given oldest first.
+pub fn draw_ghosts<'a>(ghosts: impl ExactSizeIterator<Item = &'a [Item]>) {
+    let count = ghosts.len();
+
+    for (i, items) in ghosts.enumerate() {
+        let alpha = GHOST_ALPHA * (i + 1) as f32 / (count + 1) as f32;
+        draw_geometry(items, &Strokes::THIN, |_, _| Some(Color { a: alpha, ..GHOST_COLOR }));
+    }
+}
+
+/// Draw items in heatmap colors by their `errors`, relative to the largest one. Items without an error are skipped.
+pub fn draw_heat(items: &[Item], errors: &[Option<f64>]) {
+    let max = errors.iter().flatten().copied().fold(0.0, f64::max);
+    let strokes = Strokes {
+        width: HEAT_WIDTH,
+        radius: HEAT_RADIUS,
+        dot: DotStyle::Filled
+    };
+
+    draw_geometry(items, &strokes, |i, _| {
+        let error = errors.get(i).copied().flatten()?;
+        let heat = if max > 0.0 { (error / max).clamp(0.0, 1.0) as f32 } else { 0.0 };
+        Some(heat_color(heat))
+    });
+}
+
+/// Blend of the heatmap colors, from cold at 0 to hot at 1.
+fn heat_color(heat: f32) -> Color {
+    let blend = |cold: f32, hot: f32| cold + (hot - cold) * heat;
+
+    Color::new(
+        blend(HEAT_COLD.r, HEAT_HOT.r),
+        blend(HEAT_COLD.g, HEAT_HOT.g),
+        blend(HEAT_COLD.b, HEAT_HOT.b),
+        blend(HEAT_COLD.a, HEAT_HOT.a)
+    )
+}
+
+/// Draw two compared figures, `a` in grey and `b` in color on top.
+pub fn draw_comparison(a: &[Item], b: &[Item]) {
+    draw_geometry(a, &Strokes::THIN, |_, _| Some(COMPARE_A_COLOR));
+    draw_geometry(b, &Strokes::THIN, |_, _| Some(COMPARE_B_COLOR));
+}
+
+/// Draw a short note in the top-right corner of the canvas.
+pub fn draw_notice(canvas: (f32, f32), text: &str, fonts: &Fonts) {
+    let offset_y = measure_text(text, None, 16, 1.0).offset_y;
+    let x = canvas.0 - measure_runs(text, fonts, 16) - SCALE_BAR_MARGIN;
+    draw_runs(text, fonts, x, SCALE_BAR_MARGIN + offset_y, 16, NOTICE_COLOR);
+}
+
+/// Width of `text` drawn with [`draw_runs`].
+#[must_use]
+pub fn measure_runs(text: &str, fonts: &Fonts, size: u16) -> f32 {
+    fonts.runs(text)
+        .into_iter()
+        .map(|(run, font)| measure_text(run, font, size, 1.0).width)
+        .sum()
+}
+
+/// Draw UI text with the fonts covering its characters, so that translations outside ASCII render.
+pub fn draw_runs(text: &str, fonts: &Fonts, x: f32, y: f32, size: u16, color: Color) {
+    let mut x = x;
+
+    for (run, font) in fonts.runs(text) {
+        x += draw_text_ex(run, x, y, TextParams {
+            font,
+            font_size: size,
+            color,
+            ..TextParams::default()
+        }).width;
+    }
+}
+
+/// Draw grid lines `step` apart over the whole canvas, with axes crossing at `origin`.
+pub fn draw_grid(canvas: (f32, f32), origin: Vec2, step: f32) {
+    // Start from the first line left of / above the canvas so lines stay aligned with the origin.
+    let mut x = origin.x - (origin.x / step).floor() * step;
+    while x <= canvas.0 {
+        draw_line(x, 0.0, x, canvas.1, 1.0, GRID_COLOR);
+        x += step;
+    }
+
+    let mut y = origin.y - (origin.y / step).floor() * step;
+    while y <= canvas.1 {
+        draw_line(0.0, y, canvas.0, y, 1.0, GRID_COLOR);
+        y += step;
+    }
+
+    draw_line(origin.x, 0.0, origin.x, canvas.1, 1.0, AXIS_COLOR);
+    draw_line(0.0, origin.y, canvas.0, origin.y, 1.0, AXIS_COLOR);
+}
+
+/// Draw a bar `length` long in the bottom-left corner, labelled with `text`.
+pub fn draw_scale_bar(canvas: (f32, f32), length: f32, text: &str) {
+    let y = canvas.1 - SCALE_BAR_MARGIN;
+    let start = SCALE_BAR_MARGIN;
+    let end = start + length;
+
+    draw_line(start, y, end, y, 2.0, BLACK);
+    draw_line(start, y - 4.0, start, y + 4.0, 2.0, BLACK);
+    draw_line(end, y - 4.0, end, y + 4.0, 2.0, BLACK);
+    draw_text(text, start, y - 6.0, 16.0, BLACK);
+}
+
+/// Draw annotations on a canvas of the given size.
+pub fn draw_annotations<'a>(annotations: impl IntoIterator<Item = &'a Annotation>, canvas: (f32, f32)) {
+    for annotation in annotations {
+        let points = annotation.points(canvas)
+            .into_iter()
+            .map(|(x, y)| vec2(x, y))
+            .collect::<Vec<_>>();
+
+        match annotation {
+            Annotation::Text { text, .. } => {
+                draw_text(text, points[0].x, points[0].y, ANNOTATION_TEXT_SIZE, ANNOTATION_COLOR);
+            }
+            Annotation::Arrow { .. } => draw_arrow(points[0], points[1], ANNOTATION_COLOR),
+            Annotation::Freehand(_) => {
+                for pair in points.windows(2) {
+                    draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, 2.0, ANNOTATION_COLOR);
+                }
+            }
+        }
+    }
+}
+
+/// Draw items without labels in a single batch. Items `color` returns `None` for are skipped.
+fn draw_geometry(items: &[Item], strokes: &Strokes, color: impl Fn(usize, &Item) -> Option<Color>) {
+    let width = strokes.width;
+    let mut batch = Batch::new();
+
+    for (i, item) in items.iter().enumerate() {
+        let Some(color) = color(i, item) else {
+            continue;
+        };
+
+        match item {
+            Item::Point(pt) => {
+                if pt.display_dot {
+                    draw_dot(&mut batch, vec2(pt.position.x as f32, pt.position.y as f32), strokes, color);
+                }
+            }
+            Item::Line(ln) => draw_points(&mut batch, &ln.points, width, color),
+            Item::Segment(x)
+            | Item::Ray(x) => draw_points(&mut batch, &x.points, width, color),
+            Item::Circle(circle) => {
+                batch.circle_lines(
+                    vec2(circle.center.x as f32, circle.center.y as f32),
+                    circle.radius as f32,
+                    width, color
+                );
+            }
+        }
+    }
+
+    batch.draw();
+}
+
+fn draw_dot(batch: &mut Batch, at: Vec2, strokes: &Strokes, color: Color) {
+    let r = strokes.radius;
+
+    match strokes.dot {
+        DotStyle::Filled => batch.circle(at, r, color),
+        DotStyle::Hollow => batch.circle_lines(at, r, strokes.width, color),
+        DotStyle::Cross => {
+            batch.line(at + vec2(-r, -r), at + vec2(r, r), strokes.width, color);
+            batch.line(at + vec2(-r, r), at + vec2(r, -r), strokes.width, color);
+        }
+    }
+}
+
+/// Center each label on its projected position and nudge it away from already placed labels and point dots.
+fn layout_labels(labels: &[&Label], mut obstacles: Vec<Rect>, fonts: &Fonts, size: u16) -> Vec<PlacedLabel> {
+    let mut placed = Vec::with_capacity(labels.len());
+
+    for label in labels {
+        let spans = math_label::parse(&label.content.to_string());
+        let dims = math_label::measure(&spans, fonts, size);
+        let mut rect = Rect::new(
+            label.position.x as f32 - dims.width / 2.0,
+            label.position.y as f32 - dims.height / 2.0,
+            dims.width,
+            dims.height
+        );
+
+        for _ in 0..NUDGE_ATTEMPTS {
+            let Some(other) = obstacles.iter().find(|other| other.overlaps(&rect)) else {
+                break;
+            };
+
+            rect = rect.offset(nudge(&rect, other));
+        }
+
+        obstacles.push(rect);
+        placed.push(PlacedLabel {
+            spans,
+            rect,
+            offset_y: dims.offset_y
+        });
+    }
+
+    placed
+}
+
+/// The smallest axis-aligned shift moving `rect` out of `other`, directed away from `other`'s center.
+fn nudge(rect: &Rect, other: &Rect) -> Vec2 {
+    let away = rect.center() - other.center();
+
+    let dx = if away.x >= 0.0 {
+        other.right() - rect.left()
+    } else {
+        other.left() - rect.right()
+    };
+
+    let dy = if away.y >= 0.0 {
+        other.bottom() - rect.top()
+    } else {
+        other.top() - rect.bottom()
+    };
+
+    if dx.abs() < dy.abs() {
+        vec2(dx + dx.signum(), 0.0)
+    } else {
+        vec2(0.0, dy + dy.signum())
+    }
+}
+
+/// Draw arrows from each point's position in `previous` towards its position in `current`, scaled by `scale`.
+///
+/// Both item lists must come from the same figure, so that items correspond by index.
+pub fn draw_movement(previous: &[Item], current: &[Item], scale: f32) {
+    for (prev, cur) in previous.iter().zip(current) {
+        let (Item::Point(prev), Item::Point(cur)) = (prev, cur) else {
+            continue;
+        };
+
+        let start = vec2(prev.position.x as f32, prev.position.y as f32);
+        let end = vec2(cur.position.x as f32, cur.position.y as f32);
+        let tip = start + (end - start) * scale;
+
+        draw_arrow(start, tip, MOVEMENT_COLOR);
+    }
+}
+
+fn draw_arrow(start: Vec2, tip: Vec2, color: Color) {
+    let Some(dir) = (tip - start).try_normalize() else {
+        return;
+    };
+
+    draw_line(start.x, start.y, tip.x, tip.y, 1.0, color);
+
+    let back = tip - dir * ARROWHEAD_SIZE;
+    let side = dir.perp() * (ARROWHEAD_SIZE / 2.0);
+    draw_triangle(tip, back + side, back - side, color);
+}
+
+/// A short human-readable description of an item, e.g. "point A".
+#[must_use]
+pub fn describe(item: &Item, lang: Language) -> String {
+    let (kind, label) = match item {
+        Item::Point(pt) => (Text::ItemPoint, &pt.label),
+        Item::Line(ln) => (Text::ItemLine, &ln.label),
+        Item::Segment(x) => (Text::ItemSegment, &x.label),
+        Item::Ray(x) => (Text::ItemRay, &x.label),
+        Item::Circle(circle) => (Text::ItemCircle, &circle.label)
+    };
+
+    match label {
+        Some(label) => format!("{} {}", kind.tr(lang), label.content),
+        None => String::from(kind.tr(lang))
+    }
+}
+
+/// How many items of each kind a figure has.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ItemCounts {
+    pub points: usize,
+    pub lines: usize,
+    pub segments: usize,
+    pub rays: usize,
+    pub circles: usize,
+    pub labels: usize
+}
+
+#[must_use]
+pub fn count_items(items: &[Item]) -> ItemCounts {
+    let mut counts = ItemCounts::default();
+
+    for item in items {
+        let label = match item {
+            Item::Point(pt) => {
+                counts.points += 1;
+                &pt.label
+            }
+            Item::Line(ln) => {
+                counts.lines += 1;
+                &ln.label
+            }
+            Item::Segment(x) => {
+                counts.segments += 1;
+                &x.label
+            }
+            Item::Ray(x) => {
+                counts.rays += 1;
+                &x.label
+            }
+            Item::Circle(circle) => {
+                counts.circles += 1;
+                &circle.label
+            }
+        };
+
+        if label.is_some() {
+            counts.labels += 1;
+        }
+    }
+
+    counts
+}
+
+/// Indices of items whose projected coordinates are NaN or infinite.
+#[must_use]
+pub fn non_finite_items(items: &[Item]) -> Vec<usize> {
+    let finite = |pos: &Position| pos.x.is_finite() && pos.y.is_finite();
+
+    items.iter()
+        .enumerate()
+        .filter(|(_, item)| match item {
+            Item::Point(pt) => !finite(&pt.position),
+            Item::Line(ln) => !finite(&ln.points.0) || !finite(&ln.points.1),
+            Item::Segment(x)
+            | Item::Ray(x) => !finite(&x.points.0) || !finite(&x.points.1),
+            Item::Circle(circle) => !finite(&circle.center) || !circle.radius.is_finite()
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn draw_points(batch: &mut Batch, points: &(Position, Position), width: f32, color: Color) {
+    batch.line(
+        vec2(points.0.x as f32, points.0.y as f32),
+        vec2(points.1.x as f32, points.1.y as f32),
+        width,
+        color
+    );
+}
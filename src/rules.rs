@@ -0,0 +1,193 @@
+//! The engine's rules, linked to the items they refer to.
+
+use std::collections::BTreeSet;
+use egui::{Color32, Painter, RichText, Stroke, Ui, Vec2};
+use geo_aid_internal::projector::figure::Item;
+use crate::engine::Rule;
+use crate::i18n::{Language, Text};
+use crate::render;
+use crate::selection;
+
+const HIGHLIGHT_COLOR: Color32 = Color32::from_rgb(190, 30, 170);
+
+/// A change to a rule made in the inspector.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edit {
+    Enabled(usize, bool),
+    Weight(usize, f64)
+}
+
+/// Which rule is picked in the inspector.
+#[derive(Debug, Default)]
+pub struct Rules {
+    /// Index of the picked rule, its items are highlighted on the canvas.
+    pub picked: Option<usize>
+}
+
+impl Rules {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.picked = None;
+    }
+
+    /// Highlight the items the picked rule refers to. `origin` is the top-left corner of the canvas.
+    pub fn paint(&self, painter: &Painter, origin: Vec2, items: &[Item], rules: Option<&[Rule]>) {
+        let Some(rule) = self.picked.zip(rules).and_then(|(i, rules)| rules.get(i)) else {
+            return;
+        };
+
+        let stroke = Stroke::new(3.0, HIGHLIGHT_COLOR.gamma_multiply(0.7));
+
+        for item in rule.items.iter().filter_map(|i| items.get(*i)) {
+            selection::highlight(painter, origin, item, stroke);
+        }
+    }
+
+    /// List `rules` along with their `errors`. Rules referring to `selected` items are marked,
+    /// and clicking a rule picks it. Returns the change made to a rule, if any.
+    pub fn ui(
+        &mut self,
+        ui: &mut Ui,
+        rules: Option<&[Rule]>,
+        errors: Option<&[f64]>,
+        selected: &BTreeSet<usize>,
+        items: &[Item],
+        lang: Language
+    ) -> Option<Edit> {
+        let Some(rules) = rules else {
+            ui.label(Text::NotSupportedByEngine.tr(lang));
+            self.picked = None;
+            return None;
+        };
+
+        ui.label(Text::RulesHint.tr(lang));
+
+        let mut edit = None;
+
+        egui::Grid::new("rules")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("");
+                ui.strong(Text::Rule.tr(lang));
+                ui.strong(Text::RuleError.tr(lang));
+                ui.strong(Text::RuleWeight.tr(lang));
+                ui.end_row();
+
+                for (i, rule) in rules.iter().enumerate() {
+                    let mut enabled = rule.enabled;
+
+                    if ui.checkbox(&mut enabled, "").on_hover_text(Text::RuleEnabledHint.tr(lang)).changed() {
+                        edit = Some(Edit::Enabled(i, enabled));
+                    }
+
+                    let mut name = RichText::new(&rule.name).monospace();
+
+                    if !rule.enabled {
+                        name = name.weak();
+                    }
+
+                    if refers_to_any(rule, selected) {
+                        name = name.color(HIGHLIGHT_COLOR).strong();
+                    }
+
+                    let referred = rule.items.iter()
+                        .filter_map(|i| items.get(*i))
+                        .map(|item| render::describe(item, lang))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    if ui.selectable_label(self.picked == Some(i), name)
+                        .on_hover_text(Text::RefersTo.fill(lang, &[&referred]))
+                        .clicked()
+                    {
+                        self.picked = (self.picked != Some(i)).then_some(i);
+                    }
+
+                    match errors.and_then(|errors| errors.get(i)) {
+                        Some(error) => ui.label(format!("{error:.6}")),
+                        None => ui.label("-")
+                    };
+
+                    match rule.weight {
+                        Some(mut weight) => {
+                            let field = egui::DragValue::new(&mut weight)
+                                .speed(0.01)
+                                .range(0.0..=f64::MAX)
+                                .max_decimals(3);
+
+                            if ui.add(field).changed() {
+                                edit = Some(Edit::Weight(i, weight));
+                            }
+                        }
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+
+        edit
+    }
+}
+
+/// Whether `rule` refers to any of the `items`.
+#[must_use]
+pub fn refers_to_any(rule: &Rule, items: &BTreeSet<usize>) -> bool {
+    rule.items.iter().any(|item| items.contains(item))
+}
+
+/// Series of a stacked plot of `log`, (iteration, error of each rule) entries: the `n`th is the total error of
+/// the first `n + 1` rules at each logged iteration. Rules missing from an entry count as satisfied.
+#[must_use]
+pub fn stacked_errors(log: &[(usize, Vec<f64>)]) -> Vec<Vec<[f64; 2]>> {
+    let rules = log.iter().map(|(_, errors)| errors.len()).max().unwrap_or(0);
+    let mut series = vec![Vec::with_capacity(log.len()); rules];
+
+    for (iteration, errors) in log {
+        let mut total = 0.0;
+
+        for (i, points) in series.iter_mut().enumerate() {
+            total += errors.get(i).copied().unwrap_or(0.0);
+            points.push([*iteration as f64, total]);
+        }
+    }
+
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_of_selected_items_are_found() {
+        let rule = Rule {
+            name: String::from("dst(A, B) = dst(A, C)"),
+            items: vec![0, 1, 2],
+            enabled: true,
+            weight: None
+        };
+
+        assert!(refers_to_any(&rule, &BTreeSet::from([2, 5])));
+        assert!(!refers_to_any(&rule, &BTreeSet::from([3, 4])));
+        assert!(!refers_to_any(&rule, &BTreeSet::new()));
+    }
+
+    #[test]
+    fn errors_stack_up_by_rule() {
+        let log = [(0, vec![1.0, 2.0, 4.0]), (5, vec![0.5, 0.0])];
+
+        assert_eq!(stacked_errors(&log), [
+            vec![[0.0, 1.0], [5.0, 0.5]],
+            vec![[0.0, 3.0], [5.0, 0.5]],
+            vec![[0.0, 7.0], [5.0, 0.5]]
+        ]);
+        assert!(stacked_errors(&[]).is_empty());
+    }
+}
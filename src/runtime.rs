@@ -0,0 +1,1905 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::mem;
+use std::num::NonZeroUsize;
+#[cfg(not(target_arch = "wasm32"))]
+use std::panic;
+#[cfg(not(target_arch = "wasm32"))]
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, mpsc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread::JoinHandle;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use geo_aid_internal::script::figure::Generated;
+use geo_aid_internal::script::math::Flags;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use thread_priority::ThreadPriority;
+use tracing::{debug, error, info, warn};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+use crate::archive::Archive;
+use crate::breakpoint;
+use crate::breakpoint::Breakpoint;
+use crate::engine::{DebuggableEngine, Entity, ExpressionStats, Proposal, Rule, WorkerStats};
+use crate::history::History;
+use crate::memory;
+use crate::memory::MemoryUsage;
+use crate::stop::{StopCheck, StopCriteria, StopReason};
+use crate::trace::Trace;
+use crate::undo::Snapshot;
+
+/// How many cycles an unattended run performs between progress reports.
+const PROGRESS_INTERVAL: usize = 100;
+/// Worker count used when none is given.
+pub const DEFAULT_WORKER_COUNT: usize = 512;
+/// Max adjustment used when none is given.
+pub const DEFAULT_MAX_ADJUSTMENT: f64 = 0.5;
+/// Longest time between progress reports of an unattended run, so that slow cycles don't look like a hang.
+const PROGRESS_PERIOD: Duration = Duration::from_secs(1);
+/// Most timing reports kept.
+const MAX_TIMINGS: usize = 10_000;
+/// Minimum number of iterations the acceptance rate is averaged over.
+const ACCEPTANCE_WINDOW: usize = 100;
+/// How long (in seconds) iterations are counted before the throughput is recomputed.
+const THROUGHPUT_WINDOW: f64 = 0.5;
+/// How many of the latest quality reports the quality trend is estimated from.
+const TREND_SAMPLES: usize = 20;
+/// Shortest log thinned to fit the memory budget.
+const MIN_THINNED: usize = 2 * TREND_SAMPLES;
+/// Workers per core picked by [`auto_worker_count`] for small figures.
+const AUTO_WORKERS_PER_CORE: usize = 64;
+/// Adjustables times workers per core per cycle [`auto_worker_count`] aims for with larger figures.
+const AUTO_WORK_PER_CORE: usize = 4096;
+/// Range of worker counts [`auto_worker_count`] picks from.
+const AUTO_WORKER_RANGE: (usize, usize) = (16, 1024);
+/// How long a [`Stepper`] cycles per frame, leaving the rest of the frame to the UI.
+#[cfg(target_arch = "wasm32")]
+const STEP_BUDGET: Duration = Duration::from_millis(8);
+
+/// How often a freely running worker publishes its state: after `cycles` cycles or `interval_ms` milliseconds,
+/// whichever comes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Batching {
+    pub cycles: usize,
+    pub interval_ms: u64
+}
+
+impl Default for Batching {
+    fn default() -> Self {
+        Self {
+            cycles: 100,
+            interval_ms: 50
+        }
+    }
+}
+
+impl Batching {
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+/// Scheduling of a generation thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Threading {
+    /// Run below normal priority, so that the UI stays responsive.
+    pub low_priority: bool,
+    /// Index of the core to pin the thread to, for more stable measurements.
+    pub core: Option<usize>
+}
+
+impl Threading {
+    /// Apply to the current thread. Failures are logged and leave the thread as it was.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn apply(&self) {
+        if self.low_priority {
+            if let Err(err) = thread_priority::set_current_thread_priority(ThreadPriority::Min) {
+                warn!("could not lower the thread priority: {err:?}");
+            }
+        }
+
+        if let Some(core) = self.core {
+            let id = core_affinity::get_core_ids().and_then(|ids| ids.into_iter().find(|id| id.id == core));
+
+            if !id.is_some_and(core_affinity::set_for_current) {
+                warn!(core, "could not pin the thread");
+            }
+        }
+    }
+
+    /// There are no threads to schedule on the web.
+    #[cfg(target_arch = "wasm32")]
+    pub fn apply(&self) {}
+}
+
+/// Number of cores available to the debugger.
+#[must_use]
+pub fn core_count() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// A worker count for a figure with `adjustables` adjustables on this machine.
+///
+/// Every core gets a fixed number of workers, fewer for large figures, where each cycle costs more per worker.
+#[must_use]
+pub fn auto_worker_count(adjustables: usize) -> usize {
+    let cores = core_count();
+    let by_size = cores * AUTO_WORK_PER_CORE / adjustables.max(1);
+
+    (cores * AUTO_WORKERS_PER_CORE).min(by_size).clamp(AUTO_WORKER_RANGE.0, AUTO_WORKER_RANGE.1)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Message {
+    Next,
+    /// Cycle continuously, publishing as configured, until any other message arrives or the worker pauses itself.
+    Continue(Batching),
+    /// Run unattended until one of the criteria is met.
+    RunUntil(StopCriteria),
+    SetBreakpoints(Vec<Breakpoint>),
+    /// Recompute adjustment magnitudes for a new max adjustment.
+    Rebake(f64),
+    /// Replace the adjustment magnitudes until the next [`Message::Rebake`]. Ignored if the count doesn't match.
+    SetMagnitudes(Vec<f64>),
+    /// Replace the values of all adjustables.
+    Restore(Vec<f64>),
+    /// Start or stop reporting every state change in [`Status::Trace`].
+    SetTracing(bool),
+    /// Enable or disable a rule, by its index in [`Status::Structure`].
+    SetRuleEnabled(usize, bool),
+    /// Change the weight of a rule, by its index in [`Status::Structure`].
+    SetRuleWeight(usize, f64),
+    /// Report a [`Status::Profile`] every given number of cycles, or stop profiling if `None`.
+    SetProfiling(Option<usize>),
+    /// Start or stop reporting the proposals of every cycle in [`Status::Proposals`]. Meant for stepping.
+    SetDissecting(bool),
+    /// Hold an unattended run, keeping its progress, until [`Message::Resume`] or [`Message::Stop`].
+    Suspend,
+    Resume,
+    Stop,
+    Quit
+}
+
+impl Message {
+    /// Whether the message cancels the work in progress, see [`Worker::abort`].
+    #[must_use]
+    pub fn aborts(&self) -> bool {
+        matches!(self, Self::Stop | Self::Quit)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Status {
+    Progress {
+        iteration: usize,
+        quality: f64,
+        /// Total number of accepted cycles.
+        accepted: usize,
+        /// Time spent since the last report.
+        timings: Timings
+    },
+    Finished {
+        iteration: usize,
+        quality: f64,
+        accepted: usize,
+        reason: StopReason,
+        /// Time spent since the last progress report.
+        timings: Timings
+    },
+    Paused(Pause),
+    /// The adjustment magnitudes changed.
+    Magnitudes(Vec<f64>),
+    /// The engine's rules and entities, sent once at the start. Each is `None` if the engine doesn't report it.
+    Structure {
+        rules: Option<Vec<Rule>>,
+        entities: Option<Vec<Entity>>
+    },
+    /// (iteration, values of all adjustables) of state changes since the last report, while tracing.
+    Trace(Vec<(usize, Vec<f64>)>),
+    /// The latest profiling window, `None` if the engine can't profile.
+    Profile(Option<Profile>),
+    /// What the workers proposed in the cycle leading to `iteration`, `None` if the engine doesn't keep proposals.
+    Proposals {
+        iteration: usize,
+        proposals: Option<Vec<Proposal>>
+    },
+    /// A message was ignored, with why.
+    Rejected(String),
+    Panicked(Crash)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PauseReason {
+    /// Index of the triggered breakpoint in the list last sent to the worker.
+    Breakpoint(usize),
+    /// Indices of adjustables that became NaN or infinite.
+    NonFinite(Vec<usize>)
+}
+
+/// Time the worker spent on parts of its loop, summed over a number of cycles.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Timings {
+    pub cycles: usize,
+    /// Adjusting the adjustables and evaluating the rules, in the engine.
+    pub cycle: Duration,
+    /// Checking the state for breakpoints and non-finite values.
+    pub checks: Duration,
+    /// Building the figure and copying the state for the UI.
+    pub publish: Duration
+}
+
+impl Timings {
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.cycle + self.checks + self.publish
+    }
+}
+
+/// Where the engine spent a window of cycles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    /// Iteration the window ended at.
+    pub iteration: usize,
+    pub cycles: usize,
+    /// The engine's expressions, most time-consuming first.
+    pub expressions: Vec<ExpressionStats>
+}
+
+/// The worker paused itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pause {
+    pub reason: PauseReason,
+    pub iteration: usize
+}
+
+/// A panic captured in the worker thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Crash {
+    pub message: String,
+    /// The iteration during which the worker panicked.
+    pub iteration: usize
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    pub iteration: usize,
+    pub quality: f64,
+    /// Whether the worker is currently running on its own.
+    pub unattended: bool,
+    /// Whether the unattended run is suspended by the user.
+    pub suspended: bool,
+    /// Why the last unattended run ended, if any.
+    pub stopped: Option<StopReason>,
+    /// Why the worker paused itself, cleared when stepping resumes.
+    pub pause: Option<Pause>
+}
+
+/// Engine throughput measured from iteration reports.
+#[derive(Debug, Clone, Copy)]
+struct Throughput {
+    since: Instant,
+    iteration: usize,
+    rate: f64
+}
+
+impl Throughput {
+    fn new() -> Self {
+        Self {
+            since: Instant::now(),
+            iteration: 0,
+            rate: 0.0
+        }
+    }
+
+    fn update(&mut self, iteration: usize) {
+        let elapsed = self.since.elapsed().as_secs_f64();
+
+        if elapsed >= THROUGHPUT_WINDOW {
+            self.rate = iteration.saturating_sub(self.iteration) as f64 / elapsed;
+            self.iteration = iteration;
+            self.since = Instant::now();
+        }
+    }
+}
+
+/// A generated figure along with the engine state that produced it.
+#[derive(Clone, Default)]
+pub struct Frame {
+    /// Incremented on every publish, so that restored states at the same iteration are told apart.
+    pub revision: usize,
+    pub iteration: usize,
+    pub generated: Generated,
+    pub quality: f64,
+    /// Values of all adjustables.
+    pub state: Vec<f64>,
+    /// Error of each rule, if the engine reports them.
+    pub rule_errors: Option<Vec<f64>>,
+    /// What each worker has been doing, if the engine reports it.
+    pub workers: Option<Vec<WorkerStats>>
+}
+
+pub struct Runtime {
+    control: mpsc::Sender<Message>,
+    status: mpsc::Receiver<Status>,
+    pub flags: Arc<Flags>,
+    /// The max adjustment magnitudes are currently baked for.
+    pub max_adjustment: f64,
+    /// Adjustment magnitudes of all adjustables as last reported by the worker.
+    pub magnitudes: Vec<f64>,
+    /// (iteration, magnitudes) for every magnitude change, oldest first.
+    pub magnitude_log: Vec<(usize, Vec<f64>)>,
+    /// (iteration, error of each rule) of published frames, oldest first, if the engine reports rule errors.
+    pub rule_error_log: Vec<(usize, Vec<f64>)>,
+    /// Rules of the engine, `None` until the worker reports them or if the engine doesn't tell them apart.
+    pub rules: Option<Vec<Rule>>,
+    /// Entities of the engine and what they're derived from, like [`Self::rules`].
+    pub entities: Option<Vec<Entity>>,
+    /// Every state change reported since tracing started, if it's on.
+    pub trace: Option<Trace>,
+    /// The latest profiling window reported by the worker.
+    pub profile: Option<Profile>,
+    /// Cycles per profiling window, `None` when not profiling.
+    profiling: Option<usize>,
+    /// Set once the engine turns profiling down.
+    pub profiling_unsupported: bool,
+    /// (iteration, proposals) of the latest cycle reported while dissecting.
+    pub proposals: Option<(usize, Vec<Proposal>)>,
+    /// Whether the worker reports the proposals of every cycle.
+    dissecting: bool,
+    /// Set once the engine turns out not to keep its proposals.
+    pub dissecting_unsupported: bool,
+    frame: Arc<Mutex<Frame>>,
+    abort: Arc<AtomicBool>,
+    pub history: History,
+    /// The frame a frozen projection fit is taken from, the first one published unless picked again.
+    pub reference: Option<Frame>,
+    pub archive: Archive,
+    pub progress: Progress,
+    /// (iteration, quality) as reported by the worker.
+    pub quality_log: Vec<[f64; 2]>,
+    /// (iteration, time spent) as reported by the worker, oldest first.
+    pub timings: VecDeque<(usize, Timings)>,
+    /// Time the worker spent generating, summed from its reports.
+    pub elapsed: Duration,
+    /// (iteration, fraction of accepted cycles) averaged over [`ACCEPTANCE_WINDOW`] iterations.
+    pub acceptance_log: Vec<[f64; 2]>,
+    /// (iteration, accepted) at the start of the current acceptance window.
+    acceptance_since: (usize, usize),
+    /// (latest revision, log entries, budget) last found within the memory budget.
+    budget_checked: Option<(usize, usize, usize)>,
+    /// Set once the worker thread has died.
+    pub crash: Option<Crash>,
+    throughput: Throughput,
+    /// (criteria, starting iteration, start time) of the current unattended run.
+    run: Option<(StopCriteria, usize, Instant)>,
+    /// Since when the worker owes a report, if it does.
+    awaiting: Option<Instant>,
+    /// How the worker publishes while it's running freely, see [`Message::Continue`].
+    continuous: Option<Batching>,
+    #[cfg(not(target_arch = "wasm32"))]
+    handle: JoinHandle<()>,
+    #[cfg(target_arch = "wasm32")]
+    handle: Stepper
+}
+
+/// The UI's ends of a generation worker, local or remote.
+pub struct Worker {
+    pub control: mpsc::Sender<Message>,
+    pub status: mpsc::Receiver<Status>,
+    /// The latest frame published by the worker.
+    pub frame: Arc<Mutex<Frame>>,
+    /// Set along with sending [`Message::Stop`] or [`Message::Quit`]. Until the worker receives that message,
+    /// it ends its unattended run and skips queued steps, checking between cycles.
+    pub abort: Arc<AtomicBool>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub handle: JoinHandle<()>,
+    #[cfg(target_arch = "wasm32")]
+    pub handle: Stepper
+}
+
+/// A worker cycling on the UI thread, a slice of time per frame, for where there are no threads.
+///
+/// Panics abort on the web, so a stepped worker never reports a [`Crash`].
+#[cfg(target_arch = "wasm32")]
+pub struct Stepper(Box<dyn FnMut(Duration)>);
+
+#[cfg(target_arch = "wasm32")]
+impl Stepper {
+    /// Handle the messages sent since the last call, then cycle for up to `budget`.
+    pub fn step(&mut self, budget: Duration) {
+        (self.0)(budget);
+    }
+}
+
+/// Start a generation worker thread.
+#[cfg(not(target_arch = "wasm32"))]
+#[must_use]
+pub fn spawn_worker<E: DebuggableEngine>(
+    engine: E,
+    max_adjustment: f64,
+    figure: E::Figure,
+    threading: Threading
+) -> Worker {
+    let frame = Arc::new(Mutex::new(Frame::default()));
+    let frame2 = Arc::clone(&frame);
+    let abort = Arc::new(AtomicBool::new(false));
+    let abort2 = Arc::clone(&abort);
+
+    let (control, control_recv) = mpsc::channel();
+    let (status_send, status) = mpsc::channel();
+
+    Worker {
+        control,
+        status,
+        frame,
+        abort,
+        handle: thread::spawn(move || {
+            threading.apply();
+            worker(engine, control_recv, status_send, max_adjustment, figure, frame2, &abort2)
+        })
+    }
+}
+
+/// Start a generation worker stepped by [`Runtime::poll`].
+#[cfg(target_arch = "wasm32")]
+#[must_use]
+pub fn spawn_worker<E: DebuggableEngine>(
+    engine: E,
+    max_adjustment: f64,
+    figure: E::Figure,
+    _threading: Threading
+) -> Worker {
+    let frame = Arc::new(Mutex::new(Frame::default()));
+    let abort = Arc::new(AtomicBool::new(false));
+
+    let (control, control_recv) = mpsc::channel();
+    let (status_send, status) = mpsc::channel();
+
+    let mut generation = Generation::new(engine, status_send, figure, Arc::clone(&frame));
+    generation.report_structure();
+    generation.rebake(max_adjustment);
+
+    let mut stepped = Stepped {
+        generation,
+        control: control_recv,
+        abort: Arc::clone(&abort),
+        mode: Mode::Idle,
+        quit: false
+    };
+
+    Worker {
+        control,
+        status,
+        frame,
+        abort,
+        handle: Stepper(Box::new(move |budget| stepped.step(budget)))
+    }
+}
+
+impl Runtime {
+    #[must_use]
+    pub fn new<E: DebuggableEngine>(
+        engine: E,
+        max_adjustment: f64,
+        figure: E::Figure,
+        flags: Flags,
+        threading: Threading
+    ) -> Self {
+        Self::from_worker(spawn_worker(engine, max_adjustment, figure, threading), max_adjustment, flags)
+    }
+
+    #[must_use]
+    pub fn from_worker(worker: Worker, max_adjustment: f64, flags: Flags) -> Self {
+        Self {
+            control: worker.control,
+            status: worker.status,
+            flags: Arc::new(flags),
+            max_adjustment,
+            magnitudes: Vec::new(),
+            magnitude_log: Vec::new(),
+            rule_error_log: Vec::new(),
+            rules: None,
+            entities: None,
+            trace: None,
+            profile: None,
+            profiling: None,
+            profiling_unsupported: false,
+            proposals: None,
+            dissecting: false,
+            dissecting_unsupported: false,
+            frame: worker.frame,
+            abort: worker.abort,
+            history: History::new(),
+            reference: None,
+            archive: Archive::new(),
+            progress: Progress::default(),
+            quality_log: Vec::new(),
+            timings: VecDeque::new(),
+            elapsed: Duration::ZERO,
+            acceptance_log: Vec::new(),
+            acceptance_since: (0, 0),
+            budget_checked: None,
+            crash: None,
+            throughput: Throughput::new(),
+            run: None,
+            awaiting: None,
+            continuous: None,
+            handle: worker.handle
+        }
+    }
+
+    /// Begin an unattended run that ends when one of the criteria is met.
+    pub fn run_until(&mut self, criteria: StopCriteria) {
+        self.progress.unattended = true;
+        self.progress.stopped = None;
+        self.run = Some((criteria, self.progress.iteration, Instant::now()));
+        self.send(Message::RunUntil(criteria));
+    }
+
+    /// Keep the worker running freely with the given batching, or stop it if `None`.
+    /// Meant to be called every frame; messages are only sent when something changes.
+    pub fn set_running(&mut self, batching: Option<Batching>) {
+        if batching == self.continuous || self.progress.unattended {
+            return;
+        }
+
+        match batching {
+            Some(batching) => self.send(Message::Continue(batching)),
+            None => self.send(Message::Stop)
+        }
+    }
+
+    /// Have the worker re-bake its adjustment magnitudes. Takes effect from the next cycle.
+    pub fn set_max_adjustment(&mut self, max_adjustment: f64) {
+        self.max_adjustment = max_adjustment;
+        self.send(Message::Rebake(max_adjustment));
+    }
+
+    /// Start recording every state change from now on, dropping any earlier trace, or stop recording.
+    pub fn set_tracing(&mut self, tracing: bool) {
+        self.trace = tracing.then(Trace::default);
+        self.send(Message::SetTracing(tracing));
+    }
+
+    /// Have the engine count a rule toward the quality or leave it out. Takes effect from the next cycle.
+    pub fn set_rule_enabled(&mut self, rule: usize, enabled: bool) {
+        self.send(Message::SetRuleEnabled(rule, enabled));
+    }
+
+    /// Have the engine weight a rule's error differently. Takes effect from the next cycle.
+    pub fn set_rule_weight(&mut self, rule: usize, weight: f64) {
+        self.send(Message::SetRuleWeight(rule, weight));
+    }
+
+    /// Have the worker report where the engine spends every `window` cycles, or stop if `None`.
+    pub fn set_profiling(&mut self, window: Option<usize>) {
+        self.profiling = window;
+        self.send(Message::SetProfiling(window));
+    }
+
+    /// Cycles per profiling window, `None` when not profiling.
+    #[must_use]
+    pub fn profiling(&self) -> Option<usize> {
+        self.profiling
+    }
+
+    /// Start or stop having the worker report what the workers propose in every cycle.
+    pub fn set_dissecting(&mut self, dissecting: bool) {
+        self.dissecting = dissecting;
+        self.send(Message::SetDissecting(dissecting));
+    }
+
+    /// Whether the worker reports the proposals of every cycle.
+    #[must_use]
+    pub fn dissecting(&self) -> bool {
+        self.dissecting
+    }
+
+    /// The engine settings debugger-side edits change, as of the latest frame.
+    #[must_use]
+    pub fn snapshot(&self) -> Option<Snapshot> {
+        self.history.latest().map(|frame| Snapshot {
+            state: frame.state.clone(),
+            max_adjustment: self.max_adjustment
+        })
+    }
+
+    /// Bring the engine back to `snapshot`.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.send(Message::Restore(snapshot.state));
+
+        if snapshot.max_adjustment != self.max_adjustment {
+            self.set_max_adjustment(snapshot.max_adjustment);
+        }
+    }
+
+    /// Send a message to the worker. Messages to a dead worker are dropped, the crash is reported by [`Self::poll`].
+    pub fn send(&mut self, message: Message) {
+        if matches!(message, Message::Next | Message::Continue(_) | Message::RunUntil(_)) {
+            self.progress.pause = None;
+        }
+
+        // Any other message ends a free run.
+        self.continuous = match message {
+            Message::Continue(batching) => Some(batching),
+            _ => None
+        };
+
+        match message {
+            Message::Suspend => self.progress.suspended = true,
+            Message::Resume | Message::Stop => self.progress.suspended = false,
+            _ => ()
+        }
+
+        let answered = match message {
+            Message::Next | Message::Continue(_) | Message::RunUntil(_) => true,
+            Message::Suspend | Message::Resume | Message::Stop => self.progress.unattended,
+            _ => false
+        };
+
+        if answered {
+            self.awaiting.get_or_insert_with(Instant::now);
+        }
+
+        if message.aborts() {
+            self.abort.store(true, Ordering::Relaxed);
+        }
+
+        let _ = self.control.send(message);
+    }
+
+    /// Drain status reports sent by the worker and record the latest frame.
+    ///
+    /// Returns whether the worker paused itself since the last poll.
+    pub fn poll(&mut self) -> bool {
+        #[cfg(target_arch = "wasm32")]
+        self.handle.step(STEP_BUDGET);
+
+        let mut paused = false;
+
+        while let Ok(status) = self.status.try_recv() {
+            let report = matches!(
+                status,
+                Status::Progress { .. } | Status::Finished { .. } | Status::Paused(_) | Status::Panicked(_)
+            );
+
+            match status {
+                Status::Progress { iteration, quality, accepted, timings } => {
+                    self.progress.iteration = iteration;
+                    self.progress.quality = quality;
+                    self.quality_log.push([iteration as f64, quality]);
+                    self.record_acceptance(iteration, accepted);
+                    self.record_timings(iteration, timings);
+                }
+                Status::Finished { iteration, quality, accepted, reason, timings } => {
+                    self.progress.iteration = iteration;
+                    self.progress.quality = quality;
+                    self.quality_log.push([iteration as f64, quality]);
+                    self.record_acceptance(iteration, accepted);
+                    self.record_timings(iteration, timings);
+                    self.progress.unattended = false;
+                    self.progress.suspended = false;
+                    self.progress.stopped = Some(reason);
+                    self.run = None;
+                    info!(iteration, quality, "unattended run finished: {reason}");
+                }
+                Status::Paused(pause) => {
+                    warn!(iteration = pause.iteration, "worker paused: {:?}", pause.reason);
+                    self.progress.iteration = pause.iteration;
+                    self.progress.pause = Some(pause);
+                    self.continuous = None;
+                    paused = true;
+                }
+                Status::Trace(records) => {
+                    if let Some(trace) = &mut self.trace {
+                        trace.records.extend(records);
+                    }
+                }
+                Status::Magnitudes(magnitudes) => {
+                    self.magnitude_log.push((self.progress.iteration, magnitudes.clone()));
+                    self.magnitudes = magnitudes;
+                }
+                Status::Structure { rules, entities } => {
+                    self.rules = rules;
+                    self.entities = entities;
+                }
+                Status::Profile(Some(profile)) => self.profile = Some(profile),
+                Status::Profile(None) => {
+                    warn!("the engine can't profile expressions");
+                    self.profiling = None;
+                    self.profiling_unsupported = true;
+                }
+                Status::Proposals { iteration, proposals: Some(proposals) } => {
+                    self.proposals = Some((iteration, proposals));
+                }
+                Status::Proposals { proposals: None, .. } => {
+                    warn!("the engine doesn't keep its proposals");
+                    self.dissecting = false;
+                    self.dissecting_unsupported = true;
+                }
+                Status::Rejected(reason) => warn!("worker rejected a message: {reason}"),
+                Status::Panicked(crash) => {
+                    error!(iteration = crash.iteration, "worker panicked: {}", crash.message);
+                    self.progress.unattended = false;
+                    self.run = None;
+                    self.continuous = None;
+                    self.crash = Some(crash);
+                }
+            }
+
+            if report {
+                // A running worker owes the next progress report right away.
+                let running = ((self.progress.unattended && !self.progress.suspended) || self.continuous.is_some())
+                    && self.crash.is_none();
+                self.awaiting = running.then(Instant::now);
+            }
+        }
+
+        self.throughput.update(self.progress.iteration);
+        // Frames are replaced whole, so one left behind by a panicking thread is still consistent.
+        let frame = self.frame.lock().unwrap_or_else(PoisonError::into_inner);
+        let published = self.history.latest().map_or(true, |latest| latest.revision != frame.revision);
+
+        if let Some(errors) = frame.rule_errors.as_ref().filter(|_| published) {
+            match self.rule_error_log.last_mut() {
+                // Restoring a state or editing rules republishes the same iteration.
+                Some((iteration, logged)) if *iteration == frame.iteration => logged.clone_from(errors),
+                _ => self.rule_error_log.push((frame.iteration, errors.clone()))
+            }
+        }
+
+        self.history.record(&frame);
+        self.archive.record(&frame);
+
+        if self.reference.is_none() && frame.revision > 0 {
+            self.reference = Some(frame.clone());
+        }
+
+        paused
+    }
+
+    fn record_timings(&mut self, iteration: usize, timings: Timings) {
+        self.elapsed += timings.total();
+
+        if timings.cycles == 0 {
+            return;
+        }
+
+        if self.timings.len() == MAX_TIMINGS {
+            self.timings.pop_front();
+        }
+
+        self.timings.push_back((iteration, timings));
+    }
+
+    fn record_acceptance(&mut self, iteration: usize, accepted: usize) {
+        let (since_iteration, since_accepted) = self.acceptance_since;
+        let cycles = iteration.saturating_sub(since_iteration);
+
+        if cycles >= ACCEPTANCE_WINDOW {
+            let rate = accepted.saturating_sub(since_accepted) as f64 / cycles as f64;
+            self.acceptance_log.push([iteration as f64, rate]);
+            self.acceptance_since = (iteration, accepted);
+        }
+    }
+
+    /// Approximate memory used by the recorded frames, logs and trace.
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let frame_size = |frame: &Frame| {
+            let rule_errors = frame.rule_errors.as_ref().map_or(0, memory::vec_size);
+            let workers = frame.workers.as_ref().map_or(0, memory::vec_size);
+            mem::size_of::<Frame>() + memory::vec_size(&frame.state) + rule_errors + workers
+        };
+        let magnitudes = self.magnitude_log.first().map_or(0, |(_, magnitudes)| memory::vec_size(magnitudes));
+        let rule_errors = self.rule_error_log.first().map_or(0, |(_, errors)| memory::vec_size(errors));
+
+        MemoryUsage {
+            frames: self.history.iter().map(frame_size).sum(),
+            archive: self.archive.memory(),
+            logs: memory::vec_size(&self.quality_log)
+                + memory::vec_size(&self.acceptance_log)
+                + memory::deque_size(&self.timings)
+                + memory::vec_size(&self.magnitude_log)
+                + self.magnitude_log.len() * magnitudes
+                + memory::vec_size(&self.rule_error_log)
+                + self.rule_error_log.len() * rule_errors,
+            trace: self.trace.as_ref().map_or(0, |trace| {
+                let adjustables = trace.records.first().map_or(0, |(_, state)| memory::vec_size(state));
+                memory::vec_size(&trace.records) + trace.records.len() * adjustables
+            })
+        }
+    }
+
+    /// Thin out the older half of the logs and the archive until they fit in `budget` bytes along with the frames.
+    /// The trace isn't thinned, as it's meant to be complete.
+    ///
+    /// Usage is only measured again once a frame is recorded, a log grows or the budget changes.
+    ///
+    /// Returns whether anything was dropped.
+    pub fn enforce_budget(&mut self, budget: usize) -> bool {
+        let revision = self.history.latest().map_or(0, |frame| frame.revision);
+
+        if self.budget_checked == Some((revision, self.log_entries(), budget)) {
+            return false;
+        }
+
+        let mut thinned = false;
+
+        loop {
+            let usage = self.memory_usage();
+
+            if usage.total() - usage.trace <= budget {
+                break;
+            }
+
+            // Shorter logs are dominated by the frames, thinning them wouldn't help.
+            let logs = self.quality_log.len().max(self.magnitude_log.len()).max(self.rule_error_log.len())
+                > MIN_THINNED;
+
+            if logs {
+                memory::thin(&mut self.quality_log);
+                memory::thin(&mut self.acceptance_log);
+                memory::thin_deque(&mut self.timings);
+                memory::thin(&mut self.magnitude_log);
+                memory::thin(&mut self.rule_error_log);
+            }
+
+            if !(self.archive.thin() || logs) {
+                break;
+            }
+
+            thinned = true;
+        }
+
+        self.budget_checked = Some((revision, self.log_entries(), budget));
+
+        if thinned {
+            warn!(budget, "history over its memory budget, thinned old entries");
+        }
+
+        thinned
+    }
+
+    /// Entries in all the logs together.
+    fn log_entries(&self) -> usize {
+        self.quality_log.len()
+            + self.acceptance_log.len()
+            + self.timings.len()
+            + self.magnitude_log.len()
+            + self.rule_error_log.len()
+    }
+
+    /// Engine cycles per second, averaged over the last measurement window.
+    #[must_use]
+    pub fn steps_per_second(&self) -> f64 {
+        self.throughput.rate
+    }
+
+    /// How long the worker has owed a report for: a step's result or, during an unattended run,
+    /// the next progress report. `None` if it doesn't owe any.
+    #[must_use]
+    pub fn unanswered(&self) -> Option<Duration> {
+        self.awaiting.map(|since| since.elapsed())
+    }
+
+    /// Estimated time until the current unattended run ends, by whichever configured criterion
+    /// is expected first. The target quality is extrapolated linearly from recent progress.
+    #[must_use]
+    pub fn eta(&self) -> Option<Duration> {
+        let (criteria, start, started) = self.run?;
+        let rate = self.steps_per_second();
+
+        if self.progress.suspended || rate <= 0.0 {
+            return None;
+        }
+
+        let by_iterations = criteria.max_iterations.map(|max| {
+            let done = self.progress.iteration.saturating_sub(start);
+            max.saturating_sub(done) as f64 / rate
+        });
+
+        let by_time = criteria.time_limit.map(|limit| (limit - started.elapsed().as_secs_f64()).max(0.0));
+
+        let by_target = criteria.target_quality.and_then(|target| {
+            let recent = &self.quality_log[self.quality_log.len().saturating_sub(TREND_SAMPLES)..];
+            let ([first_it, first_q], [last_it, last_q]) = (recent.first()?, recent.last()?);
+            let slope = (last_q - first_q) / (last_it - first_it);
+
+            (slope > 0.0).then(|| ((target - last_q) / slope).max(0.0) / rate)
+        });
+
+        [by_iterations, by_time, by_target]
+            .into_iter()
+            .flatten()
+            .filter(|seconds| seconds.is_finite())
+            .min_by(f64::total_cmp)
+            .map(Duration::from_secs_f64)
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        self.abort.store(true, Ordering::Relaxed);
+        let _ = self.control.send(Message::Quit);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn worker<E: DebuggableEngine>(
+    engine: E,
+    control: mpsc::Receiver<Message>,
+    status: mpsc::Sender<Status>,
+    max_adjustment: f64,
+    figure: E::Figure,
+    frame: Arc<Mutex<Frame>>,
+    abort: &AtomicBool
+) {
+    let mut generation = Generation::new(engine, status, figure, frame);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        generation.report_structure();
+        generation.rebake(max_adjustment);
+        work(&mut generation, &control, abort);
+    }));
+
+    if let Err(payload) = result {
+        let message = panic_message(payload.as_ref());
+        let _ = generation.status.send(Status::Panicked(Crash { message, iteration: generation.iteration }));
+    }
+}
+
+/// The message a panic was raised with.
+#[must_use]
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>()
+        .map(|msg| (*msg).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("unknown panic payload"))
+}
+
+/// Messages affecting the control flow of the worker, left to the loop receiving them by
+/// [`Generation::handle_message`].
+enum Control {
+    Next,
+    Continue(Batching),
+    RunUntil(StopCriteria),
+    Suspend,
+    Resume,
+    Stop,
+    Quit
+}
+
+/// A worker's engine along with what it reports to the UI.
+struct Generation<E: DebuggableEngine> {
+    engine: E,
+    status: mpsc::Sender<Status>,
+    figure: E::Figure,
+    frame: Arc<Mutex<Frame>>,
+    magnitudes: Vec<f64>,
+    breakpoints: Vec<Breakpoint>,
+    /// Total number of accepted cycles.
+    accepted: usize,
+    /// Time spent since the last report.
+    timings: Timings,
+    /// State changes since the last report, while tracing.
+    trace: Option<Vec<(usize, Vec<f64>)>>,
+    /// (cycles per window, cycles since the last report) while profiling.
+    profiling: Option<(usize, usize)>,
+    /// Whether to report the proposals of every cycle.
+    dissecting: bool,
+    iteration: usize
+}
+
+impl<E: DebuggableEngine> Generation<E> {
+    fn new(engine: E, status: mpsc::Sender<Status>, figure: E::Figure, frame: Arc<Mutex<Frame>>) -> Self {
+        Self {
+            engine,
+            status,
+            figure,
+            frame,
+            magnitudes: Vec::new(),
+            breakpoints: Vec::new(),
+            accepted: 0,
+            timings: Timings::default(),
+            trace: None,
+            profiling: None,
+            dissecting: false,
+            iteration: 0
+        }
+    }
+
+    /// Bake magnitudes for a new max adjustment.
+    fn rebake(&mut self, max_adjustment: f64) {
+        self.magnitudes = self.engine.bake(max_adjustment);
+        self.report_magnitudes();
+    }
+
+    fn report_magnitudes(&self) {
+        let _ = self.status.send(Status::Magnitudes(self.magnitudes.clone()));
+    }
+
+    fn report_structure(&self) {
+        let _ = self.status.send(Status::Structure {
+            rules: self.engine.rules(),
+            entities: self.engine.entities()
+        });
+    }
+
+    /// Report where the engine spent the cycles since the last report, if profiling.
+    fn report_profile(&mut self) {
+        let Some((_, cycles)) = &mut self.profiling else {
+            return;
+        };
+
+        let cycles = mem::take(cycles);
+        let mut expressions = self.engine.take_profile();
+        expressions.sort_by(|a, b| b.time.cmp(&a.time));
+
+        let _ = self.status.send(Status::Profile(Some(Profile {
+            iteration: self.iteration,
+            cycles,
+            expressions
+        })));
+    }
+
+    fn report_trace(&mut self) {
+        if let Some(records) = self.trace.as_mut().filter(|records| !records.is_empty()) {
+            let _ = self.status.send(Status::Trace(mem::take(records)));
+        }
+    }
+
+    /// Publish the current state for the UI.
+    fn publish(&mut self) {
+        let started = Instant::now();
+        let generated = self.engine.figure(self.figure.clone());
+        let quality = self.engine.quality();
+        let state = self.engine.state().to_vec();
+        let rule_errors = self.engine.rule_errors();
+        let workers = self.engine.workers();
+
+        // The engine isn't called with the lock held, so that its panics can't poison the frame.
+        let mut frame = self.frame.lock().unwrap_or_else(PoisonError::into_inner);
+
+        *frame = Frame {
+            revision: frame.revision + 1,
+            iteration: self.iteration,
+            generated,
+            quality,
+            state,
+            rule_errors,
+            workers
+        };
+
+        self.timings.publish += started.elapsed();
+    }
+
+    /// Publish the current state and report the progress since the last report.
+    fn report_progress(&mut self) {
+        self.publish();
+        self.report_trace();
+
+        let _ = self.status.send(Status::Progress {
+            iteration: self.iteration,
+            quality: self.engine.quality(),
+            accepted: self.accepted,
+            timings: mem::take(&mut self.timings)
+        });
+    }
+
+    /// Perform a cycle and report whether the worker should pause.
+    /// The engine only keeps a proposal if it changes the quality, which is how accepted cycles are counted.
+    fn step(&mut self) -> bool {
+        let before = self.engine.quality();
+        let started = Instant::now();
+        let proposals = if self.dissecting {
+            self.engine.cycle_recorded(&self.magnitudes)
+        } else {
+            self.engine.cycle(&self.magnitudes);
+            None
+        };
+        let cycled = Instant::now();
+        self.iteration += 1;
+
+        if self.dissecting {
+            // Engines that don't keep their proposals won't start keeping them, so they're only asked once.
+            self.dissecting = proposals.is_some();
+            let _ = self.status.send(Status::Proposals { iteration: self.iteration, proposals });
+        }
+        self.timings.cycles += 1;
+        self.timings.cycle += cycled - started;
+
+        if self.engine.quality() != before {
+            self.accepted += 1;
+
+            if let Some(records) = &mut self.trace {
+                records.push((self.iteration, self.engine.state().to_vec()));
+            }
+        }
+
+        let window_ended = self.profiling.as_mut().is_some_and(|(window, cycles)| {
+            *cycles += 1;
+            *cycles >= *window
+        });
+
+        if window_ended {
+            self.report_profile();
+        }
+
+        let state = self.engine.state();
+        let non_finite = state.iter()
+            .enumerate()
+            .filter(|(_, v)| !v.is_finite())
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        let reason = if non_finite.is_empty() {
+            breakpoint::first_hit(&self.breakpoints, state, self.engine.quality())
+                .map(PauseReason::Breakpoint)
+        } else {
+            Some(PauseReason::NonFinite(non_finite))
+        };
+
+        let paused = reason.is_some();
+        self.timings.checks += cycled.elapsed();
+
+        if let Some(reason) = reason {
+            let _ = self.status.send(Status::Paused(Pause { reason, iteration: self.iteration }));
+        }
+
+        paused
+    }
+
+    /// Act on a message that doesn't affect the control flow, wherever the worker is. Returns the others.
+    fn handle_message(&mut self, message: Message) -> Option<Control> {
+        match message {
+            Message::Next => return Some(Control::Next),
+            Message::Continue(batching) => return Some(Control::Continue(batching)),
+            Message::RunUntil(criteria) => return Some(Control::RunUntil(criteria)),
+            Message::Suspend => return Some(Control::Suspend),
+            Message::Resume => return Some(Control::Resume),
+            Message::Stop => return Some(Control::Stop),
+            Message::Quit => return Some(Control::Quit),
+            Message::SetBreakpoints(list) => self.breakpoints = list,
+            Message::SetTracing(tracing) => {
+                // A trace starts from the current state, so that it can be replayed on its own.
+                self.trace = tracing.then(|| vec![(self.iteration, self.engine.state().to_vec())]);
+                self.report_trace();
+            }
+            Message::Rebake(max_adjustment) => {
+                debug!(max_adjustment, "re-baking magnitudes");
+                self.rebake(max_adjustment);
+            }
+            Message::SetMagnitudes(overridden) => {
+                if overridden.len() == self.magnitudes.len() {
+                    self.magnitudes = overridden;
+                }
+                self.report_magnitudes();
+            }
+            Message::Restore(state) => {
+                // Remote debuggers can send anything, and engines may not check the length themselves.
+                let adjustables = self.engine.state().len();
+
+                if state.len() != adjustables {
+                    let reason = format!("cannot restore {} adjustables, the engine has {adjustables}", state.len());
+                    let _ = self.status.send(Status::Rejected(reason));
+                    return None;
+                }
+
+                debug!(adjustables, "restoring state");
+                self.engine.set_state(&state);
+
+                if let Some(records) = &mut self.trace {
+                    records.push((self.iteration, state));
+                }
+
+                self.report_progress();
+            }
+            Message::SetRuleEnabled(rule, enabled) => {
+                if !self.engine.set_rule_enabled(rule, enabled) {
+                    let _ = self.status.send(Status::Rejected(format!("the engine can't toggle rule {rule}")));
+                    return None;
+                }
+
+                debug!(rule, enabled, "toggling a rule");
+                // The quality changes along with the rules, so the UI gets both at once.
+                self.report_structure();
+                self.report_progress();
+            }
+            Message::SetRuleWeight(rule, weight) => {
+                if !self.engine.set_rule_weight(rule, weight) {
+                    let reason = format!("the engine can't weight rule {rule} by {weight}");
+                    let _ = self.status.send(Status::Rejected(reason));
+                    return None;
+                }
+
+                debug!(rule, weight, "re-weighting a rule");
+                self.report_structure();
+                self.report_progress();
+            }
+            Message::SetProfiling(window) => {
+                if !self.engine.set_profiling(window.is_some()) {
+                    let _ = self.status.send(Status::Profile(None));
+                    return None;
+                }
+
+                debug!(?window, "setting profiling");
+                self.profiling = window.map(|window| (window, 0));
+            }
+            Message::SetDissecting(dissecting) => {
+                debug!(dissecting, "setting dissecting");
+                self.dissecting = dissecting;
+            }
+        }
+
+        None
+    }
+
+    /// Report the end of an unattended run.
+    fn finish(&mut self, reason: StopReason) {
+        self.publish();
+        self.report_trace();
+
+        let _ = self.status.send(Status::Finished {
+            iteration: self.iteration,
+            quality: self.engine.quality(),
+            accepted: self.accepted,
+            reason,
+            timings: mem::take(&mut self.timings)
+        });
+    }
+
+    /// Run unattended until one of the criteria is met. Returns why the run ended, or `None` if the worker should quit.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_until(
+        &mut self,
+        criteria: StopCriteria,
+        control: &mpsc::Receiver<Message>,
+        abort: &AtomicBool
+    ) -> Option<StopReason> {
+        debug!(?criteria, "unattended run started");
+        let mut check = StopCheck::new(criteria, self.engine.quality());
+        let mut done = 0;
+        let mut reported = Instant::now();
+
+        loop {
+            if let Some(reason) = check.check(self.engine.quality()) {
+                return Some(reason);
+            }
+
+            // The message setting the flag is handled when it's received.
+            if abort.load(Ordering::Relaxed) {
+                return Some(StopReason::User);
+            }
+
+            let received = match control.try_recv() {
+                Ok(message) => self.handle_message(message),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => return None
+            };
+
+            match received {
+                None | Some(Control::Resume) => (),
+                Some(Control::Quit) => return None,
+                Some(Control::Stop) => {
+                    abort.store(false, Ordering::Relaxed);
+                    return Some(StopReason::User);
+                }
+                Some(Control::Suspend) => {
+                    self.report_progress();
+
+                    match self.suspend(control) {
+                        Control::Resume => (),
+                        Control::Stop => {
+                            abort.store(false, Ordering::Relaxed);
+                            return Some(StopReason::User);
+                        }
+                        _ => return None
+                    }
+                }
+                Some(Control::Next | Control::Continue(_) | Control::RunUntil(_)) => {
+                    debug!("ignored a stepping message during an unattended run");
+                }
+            }
+
+            let paused = self.step();
+            check.cycle();
+            done += 1;
+
+            if paused {
+                return Some(StopReason::Paused);
+            }
+
+            if done % PROGRESS_INTERVAL == 0 || reported.elapsed() >= PROGRESS_PERIOD {
+                reported = Instant::now();
+                self.report_progress();
+            }
+        }
+    }
+
+    /// Hold an unattended run until it's resumed, stopped or the worker quits. Returns which of them.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn suspend(&mut self, control: &mpsc::Receiver<Message>) -> Control {
+        loop {
+            let Ok(message) = control.recv() else {
+                return Control::Quit;
+            };
+
+            match self.handle_message(message) {
+                None | Some(Control::Suspend) => (),
+                Some(ended @ (Control::Resume | Control::Stop | Control::Quit)) => return ended,
+                Some(Control::Next | Control::Continue(_) | Control::RunUntil(_)) => {
+                    debug!("ignored a stepping message during an unattended run");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn work<E: DebuggableEngine>(generation: &mut Generation<E>, control: &mpsc::Receiver<Message>, abort: &AtomicBool) {
+    let mut pending = None;
+
+    loop {
+        let Ok(message) = pending.take().map_or_else(|| control.recv(), Ok) else {
+            break;
+        };
+
+        match generation.handle_message(message) {
+            None | Some(Control::Suspend | Control::Resume) => (),
+            Some(Control::Quit) => break,
+            Some(Control::Stop) => abort.store(false, Ordering::Relaxed),
+            Some(Control::Next) if abort.load(Ordering::Relaxed) => (),
+            Some(Control::Next) => {
+                let paused = generation.step();
+                generation.report_progress();
+
+                if paused {
+                    pending = discard_steps(control);
+                }
+            }
+            Some(Control::Continue(batching)) => {
+                let mut unpublished = 0;
+                let mut published = Instant::now();
+
+                pending = loop {
+                    // The message setting the flag is handled when it's received.
+                    if abort.load(Ordering::Relaxed) {
+                        break None;
+                    }
+
+                    match control.try_recv() {
+                        Ok(message) => break Some(message),
+                        Err(TryRecvError::Disconnected) => return,
+                        Err(TryRecvError::Empty) => ()
+                    }
+
+                    if generation.step() {
+                        break None;
+                    }
+
+                    unpublished += 1;
+
+                    if unpublished >= batching.cycles || published.elapsed() >= batching.interval() {
+                        unpublished = 0;
+                        published = Instant::now();
+                        generation.report_progress();
+                    }
+                };
+
+                generation.report_progress();
+            }
+            Some(Control::RunUntil(criteria)) => {
+                let Some(reason) = generation.run_until(criteria, control, abort) else {
+                    return;
+                };
+
+                generation.finish(reason);
+            }
+        }
+    }
+}
+
+/// Drop steps queued before the UI learned about the worker pausing, returning the first other message.
+fn discard_steps(control: &mpsc::Receiver<Message>) -> Option<Message> {
+    loop {
+        match control.try_recv() {
+            Ok(Message::Next) => (),
+            Ok(message) => return Some(message),
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => return Some(Message::Quit)
+        }
+    }
+}
+
+/// What a [`Stepped`] worker does between messages, the state the threaded worker keeps in its loops.
+#[cfg(target_arch = "wasm32")]
+enum Mode {
+    Idle,
+    Continue {
+        batching: Batching,
+        unpublished: usize,
+        published: Instant
+    },
+    RunUntil(Run),
+    /// An unattended run on hold.
+    Suspended(Run)
+}
+
+/// An unattended run of a [`Stepped`] worker.
+#[cfg(target_arch = "wasm32")]
+struct Run {
+    check: StopCheck,
+    done: usize,
+    reported: Instant
+}
+
+/// The threaded worker's control flow as a state machine, advanced by [`Stepper::step`].
+#[cfg(target_arch = "wasm32")]
+struct Stepped<E: DebuggableEngine> {
+    generation: Generation<E>,
+    control: mpsc::Receiver<Message>,
+    abort: Arc<AtomicBool>,
+    mode: Mode,
+    quit: bool
+}
+
+#[cfg(target_arch = "wasm32")]
+impl<E: DebuggableEngine> Stepped<E> {
+    fn step(&mut self, budget: Duration) {
+        let deadline = Instant::now() + budget;
+
+        while !self.quit {
+            match self.control.try_recv() {
+                Ok(message) => self.receive(message),
+                Err(TryRecvError::Disconnected) => self.quit = true,
+                Err(TryRecvError::Empty) => {
+                    if matches!(self.mode, Mode::Idle | Mode::Suspended(_)) || Instant::now() >= deadline {
+                        return;
+                    }
+
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// Act on a message the way the threaded worker would in its current loop.
+    fn receive(&mut self, message: Message) {
+        // Any message ends a free run.
+        if matches!(self.mode, Mode::Continue { .. }) {
+            self.generation.report_progress();
+            self.mode = Mode::Idle;
+        }
+
+        let Some(control) = self.generation.handle_message(message) else {
+            return;
+        };
+
+        match mem::replace(&mut self.mode, Mode::Idle) {
+            Mode::Idle | Mode::Continue { .. } => self.start(control),
+            Mode::RunUntil(run) | Mode::Suspended(run) => match control {
+                Control::Quit => self.quit = true,
+                Control::Stop => {
+                    self.abort.store(false, Ordering::Relaxed);
+                    self.generation.finish(StopReason::User);
+                }
+                Control::Suspend => {
+                    self.generation.report_progress();
+                    self.mode = Mode::Suspended(run);
+                }
+                Control::Resume => self.mode = Mode::RunUntil(run),
+                Control::Next | Control::Continue(_) | Control::RunUntil(_) => {
+                    debug!("ignored a stepping message during an unattended run");
+                    self.mode = Mode::RunUntil(run);
+                }
+            }
+        }
+    }
+
+    /// Act on a control message received while idle.
+    fn start(&mut self, control: Control) {
+        match control {
+            Control::Suspend | Control::Resume => (),
+            Control::Quit => self.quit = true,
+            Control::Stop => self.abort.store(false, Ordering::Relaxed),
+            Control::Next if self.abort.load(Ordering::Relaxed) => (),
+            Control::Next => {
+                let paused = self.generation.step();
+                self.generation.report_progress();
+
+                if paused {
+                    if let Some(message) = discard_steps(&self.control) {
+                        self.receive(message);
+                    }
+                }
+            }
+            Control::Continue(batching) => {
+                self.mode = Mode::Continue {
+                    batching,
+                    unpublished: 0,
+                    published: Instant::now()
+                };
+            }
+            Control::RunUntil(criteria) => {
+                debug!(?criteria, "unattended run started");
+
+                self.mode = Mode::RunUntil(Run {
+                    check: StopCheck::new(criteria, self.generation.engine.quality()),
+                    done: 0,
+                    reported: Instant::now()
+                });
+            }
+        }
+    }
+
+    /// Perform a cycle of a free or unattended run.
+    fn advance(&mut self) {
+        match &mut self.mode {
+            Mode::Idle | Mode::Suspended(_) => (),
+            Mode::Continue { batching, unpublished, published } => {
+                // The message setting the flag is handled when it's received.
+                if self.abort.load(Ordering::Relaxed) || self.generation.step() {
+                    self.generation.report_progress();
+                    self.mode = Mode::Idle;
+                    return;
+                }
+
+                *unpublished += 1;
+
+                if *unpublished >= batching.cycles || published.elapsed() >= batching.interval() {
+                    *unpublished = 0;
+                    *published = Instant::now();
+                    self.generation.report_progress();
+                }
+            }
+            Mode::RunUntil(run) => {
+                let reason = if let Some(reason) = run.check.check(self.generation.engine.quality()) {
+                    Some(reason)
+                } else if self.abort.load(Ordering::Relaxed) {
+                    Some(StopReason::User)
+                } else {
+                    let paused = self.generation.step();
+                    run.check.cycle();
+                    run.done += 1;
+
+                    if !paused && (run.done % PROGRESS_INTERVAL == 0 || run.reported.elapsed() >= PROGRESS_PERIOD) {
+                        run.reported = Instant::now();
+                        self.generation.report_progress();
+                    }
+
+                    paused.then_some(StopReason::Paused)
+                };
+
+                if let Some(reason) = reason {
+                    self.mode = Mode::Idle;
+                    self.generation.finish(reason);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+    use crate::mock::MockEngine;
+
+    /// How long a test waits for a report.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    /// How long without reports a worker is considered done reporting.
+    const QUIET: Duration = Duration::from_millis(500);
+
+    fn spawn() -> Worker {
+        spawn_worker(MockEngine::new(mock::ADJUSTABLES), 0.5, (), Threading::default())
+    }
+
+    /// Statuses up to and including the first one matching `last`.
+    fn statuses_until(worker: &Worker, last: impl Fn(&Status) -> bool) -> Vec<Status> {
+        let mut received = Vec::new();
+
+        loop {
+            let status = worker.status.recv_timeout(TIMEOUT).expect("the worker stopped reporting");
+            let done = last(&status);
+            received.push(status);
+
+            if done {
+                return received;
+            }
+        }
+    }
+
+    fn progress_iterations(statuses: &[Status]) -> Vec<usize> {
+        statuses.iter()
+            .filter_map(|status| match status {
+                Status::Progress { iteration, .. } => Some(*iteration),
+                _ => None
+            })
+            .collect()
+    }
+
+    fn quit(worker: Worker) {
+        let _ = worker.control.send(Message::Quit);
+        worker.handle.join().unwrap();
+    }
+
+    #[test]
+    fn steps_report_progress() {
+        let worker = spawn();
+        let initial = statuses_until(&worker, |status| matches!(status, Status::Magnitudes(_)));
+        assert!(matches!(
+            &initial[..],
+            [Status::Structure { rules: Some(_), entities: Some(_) }, Status::Magnitudes(magnitudes)]
+                if magnitudes.len() == mock::ADJUSTABLES
+        ));
+
+        worker.control.send(Message::Next).unwrap();
+        worker.control.send(Message::Next).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Progress { iteration: 2, .. }));
+
+        assert_eq!(progress_iterations(&statuses), [1, 2]);
+        assert_eq!(worker.frame.lock().unwrap().iteration, 2);
+        quit(worker);
+    }
+
+    #[test]
+    fn restore_publishes_the_state() {
+        let worker = spawn();
+        let state = vec![0.25; mock::ADJUSTABLES];
+
+        worker.control.send(Message::Restore(state.clone())).unwrap();
+        statuses_until(&worker, |status| matches!(status, Status::Progress { .. }));
+
+        assert_eq!(worker.frame.lock().unwrap().state, state);
+        quit(worker);
+    }
+
+    #[test]
+    fn restore_checks_the_length() {
+        let worker = spawn();
+
+        worker.control.send(Message::Restore(vec![0.25; mock::ADJUSTABLES + 1])).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Rejected(_)));
+
+        assert!(progress_iterations(&statuses).is_empty());
+        assert_ne!(worker.frame.lock().unwrap().state.len(), mock::ADJUSTABLES + 1);
+        quit(worker);
+    }
+
+    #[test]
+    fn toggling_a_rule_reports_the_structure() {
+        let worker = spawn();
+        statuses_until(&worker, |status| matches!(status, Status::Magnitudes(_)));
+
+        worker.control.send(Message::SetRuleEnabled(0, false)).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Progress { .. }));
+
+        assert!(matches!(
+            &statuses[..],
+            [Status::Structure { rules: Some(rules), .. }, Status::Progress { .. }] if !rules[0].enabled
+        ));
+        assert_eq!(worker.frame.lock().unwrap().rule_errors.as_ref().unwrap()[0], 0.0);
+
+        worker.control.send(Message::SetRuleEnabled(mock::RULES.len(), false)).unwrap();
+        statuses_until(&worker, |status| matches!(status, Status::Rejected(_)));
+        quit(worker);
+    }
+
+    #[test]
+    fn reweighting_a_rule_scales_its_error() {
+        let worker = spawn();
+        // Weighting a rule as it is publishes the errors to compare with.
+        worker.control.send(Message::SetRuleWeight(2, 1.0)).unwrap();
+        statuses_until(&worker, |status| matches!(status, Status::Progress { .. }));
+        let before = worker.frame.lock().unwrap().rule_errors.clone().unwrap();
+
+        worker.control.send(Message::SetRuleWeight(2, 3.0)).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Progress { .. }));
+        let after = worker.frame.lock().unwrap().rule_errors.clone().unwrap();
+
+        assert!(matches!(
+            &statuses[..],
+            [Status::Structure { rules: Some(rules), .. }, Status::Progress { .. }] if rules[2].weight == Some(3.0)
+        ));
+        assert!((after[2] - 3.0 * before[2]).abs() < 1e-9);
+
+        worker.control.send(Message::SetRuleWeight(2, -1.0)).unwrap();
+        statuses_until(&worker, |status| matches!(status, Status::Rejected(_)));
+        quit(worker);
+    }
+
+    #[test]
+    fn profiling_reports_every_window() {
+        let worker = spawn();
+
+        worker.control.send(Message::SetProfiling(Some(3))).unwrap();
+        for _ in 0..3 {
+            worker.control.send(Message::Next).unwrap();
+        }
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Profile(_)));
+
+        let Some(Status::Profile(Some(profile))) = statuses.last() else {
+            panic!("no profile reported");
+        };
+        assert_eq!((profile.iteration, profile.cycles), (3, 3));
+        assert!(!profile.expressions.is_empty());
+        assert!(profile.expressions.windows(2).all(|pair| pair[0].time >= pair[1].time));
+        quit(worker);
+    }
+
+    #[test]
+    fn dissected_steps_report_proposals() {
+        let worker = spawn();
+
+        worker.control.send(Message::SetDissecting(true)).unwrap();
+        worker.control.send(Message::Next).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Progress { .. }));
+
+        let proposals = statuses.iter()
+            .find_map(|status| match status {
+                Status::Proposals { iteration: 1, proposals: Some(proposals) } => Some(proposals),
+                _ => None
+            })
+            .expect("no proposals reported");
+        assert!(proposals.iter().filter(|proposal| proposal.accepted).count() <= 1);
+        assert!(proposals.iter().all(|proposal| proposal.adjustment.len() == mock::ADJUSTABLES));
+        quit(worker);
+    }
+
+    #[test]
+    fn run_until_finishes() {
+        let worker = spawn();
+        let criteria = StopCriteria {
+            max_iterations: Some(250),
+            ..StopCriteria::default()
+        };
+
+        worker.control.send(Message::RunUntil(criteria)).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Finished { .. }));
+
+        assert!(matches!(
+            statuses.last(),
+            Some(Status::Finished { iteration: 250, reason: StopReason::MaxIterations, .. })
+        ));
+        assert_eq!(worker.frame.lock().unwrap().iteration, 250);
+        quit(worker);
+    }
+
+    #[test]
+    fn restore_applies_during_a_suspended_run() {
+        let worker = spawn();
+        let state = vec![0.75; mock::ADJUSTABLES];
+        let criteria = StopCriteria {
+            max_iterations: Some(usize::MAX),
+            ..StopCriteria::default()
+        };
+
+        worker.control.send(Message::RunUntil(criteria)).unwrap();
+        worker.control.send(Message::Suspend).unwrap();
+        worker.control.send(Message::Restore(state.clone())).unwrap();
+        worker.control.send(Message::Stop).unwrap();
+        statuses_until(&worker, |status| matches!(status, Status::Finished { reason: StopReason::User, .. }));
+
+        // Nothing cycles between the restore and stopping.
+        assert_eq!(worker.frame.lock().unwrap().state, state);
+        quit(worker);
+    }
+
+    #[test]
+    fn abort_ends_a_run() {
+        let worker = spawn();
+        let criteria = StopCriteria {
+            max_iterations: Some(usize::MAX),
+            ..StopCriteria::default()
+        };
+
+        worker.control.send(Message::RunUntil(criteria)).unwrap();
+        worker.abort.store(true, Ordering::Relaxed);
+        worker.control.send(Message::Stop).unwrap();
+        statuses_until(&worker, |status| matches!(status, Status::Finished { reason: StopReason::User, .. }));
+
+        // Receiving the stop clears the flag, so steps work again.
+        worker.control.send(Message::Next).unwrap();
+        statuses_until(&worker, |status| matches!(status, Status::Progress { .. }));
+        quit(worker);
+    }
+
+    #[test]
+    fn abort_skips_queued_steps() {
+        let worker = spawn();
+
+        worker.abort.store(true, Ordering::Relaxed);
+        worker.control.send(Message::Next).unwrap();
+        worker.control.send(Message::Next).unwrap();
+        worker.control.send(Message::Stop).unwrap();
+        worker.control.send(Message::Next).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Progress { .. }));
+
+        assert_eq!(progress_iterations(&statuses), [1]);
+        quit(worker);
+    }
+
+    #[test]
+    fn continue_publishes_in_batches() {
+        let worker = spawn();
+        let batching = Batching {
+            cycles: 10,
+            interval_ms: 60_000
+        };
+
+        worker.control.send(Message::Continue(batching)).unwrap();
+        let statuses = statuses_until(&worker, |status| matches!(status, Status::Progress { iteration: 30, .. }));
+        assert_eq!(progress_iterations(&statuses), [10, 20, 30]);
+
+        // Nothing cycles once the worker is stopped, so the restore reports the iteration it stopped at.
+        let state = vec![0.5; mock::ADJUSTABLES];
+        worker.control.send(Message::Stop).unwrap();
+        worker.control.send(Message::Restore(state.clone())).unwrap();
+
+        let mut iterations = Vec::new();
+
+        while let Ok(status) = worker.status.recv_timeout(QUIET) {
+            iterations.extend(progress_iterations(&[status]));
+        }
+
+        let [.., stopped, restored] = iterations[..] else {
+            panic!("expected progress from both stopping and restoring, got {iterations:?}");
+        };
+
+        assert_eq!(stopped, restored);
+        assert_eq!(worker.frame.lock().unwrap().state, state);
+        quit(worker);
+    }
+
+    #[test]
+    fn auto_worker_count_stays_in_range() {
+        for adjustables in [0, 1, 10, 100, 1000, 100_000, usize::MAX] {
+            let count = auto_worker_count(adjustables);
+            assert!((AUTO_WORKER_RANGE.0..=AUTO_WORKER_RANGE.1).contains(&count), "{count} for {adjustables}");
+        }
+
+        assert_eq!(auto_worker_count(usize::MAX), AUTO_WORKER_RANGE.0);
+    }
+
+    #[test]
+    fn auto_worker_count_scales_with_cores() {
+        let expected = (core_count() * AUTO_WORKERS_PER_CORE).clamp(AUTO_WORKER_RANGE.0, AUTO_WORKER_RANGE.1);
+
+        assert_eq!(auto_worker_count(0), expected);
+        assert_eq!(auto_worker_count(1), expected);
+    }
+
+    #[test]
+    fn larger_figures_get_fewer_workers() {
+        let counts = [1, 16, 64, 256, 1024, 4096, 65_536].map(auto_worker_count);
+
+        assert!(counts.windows(2).all(|pair| pair[0] >= pair[1]), "{counts:?}");
+    }
+
+    #[test]
+    fn the_budget_is_checked_again_once_something_changes() {
+        let engine = MockEngine::new(mock::ADJUSTABLES);
+        let flags = engine.flags();
+        let mut runtime = Runtime::new(engine, 0.5, (), flags, Threading::default());
+        let entries = |from: usize| (from..from + 4 * MIN_THINNED).map(|i| [i as f64, 1.0]);
+        runtime.quality_log.extend(entries(0));
+
+        assert!(!runtime.enforce_budget(usize::MAX));
+        assert!(runtime.enforce_budget(0));
+        assert!(runtime.quality_log.len() <= MIN_THINNED);
+        assert!(!runtime.enforce_budget(0));
+
+        runtime.quality_log.extend(entries(4 * MIN_THINNED));
+        assert!(runtime.enforce_budget(0));
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeSet;
+use egui::{Color32, Painter, PointerButton, Pos2, Rect, Response, Stroke, Ui, Vec2};
+use geo_aid_internal::projector::figure::{Item, Position};
+use crate::colors::{ItemColors, Rgba};
+use crate::i18n::{Language, Text};
+use crate::spatial::SpatialIndex;
+
+const SELECTION_COLOR: Color32 = Color32::from_rgb(30, 120, 255);
+const RUBBER_BAND_FILL: Color32 = Color32::from_rgba_premultiplied(15, 60, 128, 40);
+
+fn pos(position: &Position) -> Pos2 {
+    Pos2::new(position.x as f32, position.y as f32)
+}
+
+/// Whether the segment `ab` has any part inside `rect`, using Liang-Barsky clipping.
+fn segment_touches(a: Pos2, b: Pos2, rect: Rect) -> bool {
+    let d = b - a;
+    let mut t0: f32 = 0.0;
+    let mut t1: f32 = 1.0;
+
+    for (p, q) in [
+        (-d.x, a.x - rect.min.x),
+        (d.x, rect.max.x - a.x),
+        (-d.y, a.y - rect.min.y),
+        (d.y, rect.max.y - a.y)
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    t0 <= t1
+}
+
+/// Whether `item` has any part inside `rect`.
+fn touches(item: &Item, rect: Rect) -> bool {
+    match item {
+        Item::Point(pt) => rect.contains(pos(&pt.position)),
+        Item::Line(ln) => segment_touches(pos(&ln.points.0), pos(&ln.points.1), rect),
+        Item::Segment(x)
+        | Item::Ray(x) => segment_touches(pos(&x.points.0), pos(&x.points.1), rect),
+        Item::Circle(circle) => {
+            // The outline crosses the rectangle unless it's entirely inside the circle or misses it.
+            let center = pos(&circle.center);
+            let radius = circle.radius as f32;
+            let nearest = center.clamp(rect.min, rect.max).distance(center);
+            let farthest = [rect.left_top(), rect.right_top(), rect.left_bottom(), rect.right_bottom()]
+                .into_iter()
+                .map(|corner| corner.distance(center))
+                .fold(0.0, f32::max);
+
+            nearest <= radius && radius <= farthest
+        }
+    }
+}
+
+/// Trace an item with `stroke`. `origin` is the top-left corner of the canvas.
+pub fn highlight(painter: &Painter, origin: Vec2, item: &Item, stroke: Stroke) {
+    match item {
+        Item::Point(pt) => painter.circle_stroke(pos(&pt.position) + origin, 5.0, stroke),
+        Item::Line(ln) => painter.line_segment([pos(&ln.points.0) + origin, pos(&ln.points.1) + origin], stroke),
+        Item::Segment(x)
+        | Item::Ray(x) => painter.line_segment([pos(&x.points.0) + origin, pos(&x.points.1) + origin], stroke),
+        Item::Circle(circle) => painter.circle_stroke(pos(&circle.center) + origin, circle.radius as f32, stroke)
+    }
+}
+
+/// Box-selected items and the groups operations were applied to.
+pub struct Selection {
+    pub items: BTreeSet<usize>,
+    /// Items not drawn on the canvas.
+    pub hidden: BTreeSet<usize>,
+    /// Items that can't be selected or picked by the measure tool.
+    pub locked: BTreeSet<usize>,
+    /// The corners of the rubber band being dragged, in canvas coordinates.
+    drag: Option<(Pos2, Pos2)>,
+    color: Rgba
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Self {
+            items: BTreeSet::new(),
+            hidden: BTreeSet::new(),
+            locked: BTreeSet::new(),
+            drag: None,
+            color: [255, 0, 0, 255]
+        }
+    }
+}
+
+impl Selection {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Whether the item can be selected or picked.
+    #[must_use]
+    pub fn pickable(&self, item: usize) -> bool {
+        !self.hidden.contains(&item) && !self.locked.contains(&item)
+    }
+
+    /// Handle rubber band dragging on the canvas. With `add`, the selection is extended instead of replaced.
+    pub fn interact(&mut self, response: &Response, origin: Vec2, items: &[Item], index: &SpatialIndex, add: bool) {
+        let pointer = response.interact_pointer_pos().map(|pos| pos - origin);
+
+        if response.drag_started_by(PointerButton::Primary) {
+            self.drag = pointer.map(|pos| (pos, pos));
+        }
+
+        if let (Some((_, end)), Some(pointer)) = (&mut self.drag, pointer) {
+            *end = pointer;
+        }
+
+        if response.drag_stopped_by(PointerButton::Primary) {
+            if let Some((start, end)) = self.drag.take() {
+                let rect = Rect::from_two_pos(start, end);
+
+                if !add {
+                    self.items.clear();
+                }
+
+                let selected = index.in_rect(rect)
+                    .into_iter()
+                    .filter(|i| self.pickable(*i) && touches(&items[*i], rect))
+                    .collect::<Vec<_>>();
+
+                self.items.extend(selected);
+            }
+        }
+
+        if response.clicked() && !add {
+            self.items.clear();
+        }
+    }
+
+    /// Highlight selected items and draw the rubber band. `origin` is the top-left corner of the canvas.
+    pub fn paint(&self, painter: &Painter, origin: Vec2, items: &[Item]) {
+        let stroke = Stroke::new(3.0, SELECTION_COLOR.gamma_multiply(0.6));
+
+        for item in self.items.iter().filter_map(|i| items.get(*i)) {
+            highlight(painter, origin, item, stroke);
+        }
+
+        if let Some((start, end)) = self.drag {
+            let rect = Rect::from_two_pos(start + origin, end + origin);
+            painter.rect(rect, 0.0, RUBBER_BAND_FILL, Stroke::new(1.0, SELECTION_COLOR));
+        }
+    }
+
+    /// Group operations on the selection. Returns whether an export of the selection was requested.
+    pub fn ui(&mut self, ui: &mut Ui, colors: &mut ItemColors, lang: Language) -> bool {
+        let mut export = false;
+
+        ui.label(Text::ItemsSelected.fill(lang, &[&self.items.len()]));
+
+        ui.add_enabled_ui(!self.items.is_empty(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button(Text::Hide.tr(lang)).clicked() {
+                    self.hidden.extend(&self.items);
+                    self.items.clear();
+                }
+
+                if ui.button(Text::Lock.tr(lang)).on_hover_text(Text::LockHint.tr(lang)).clicked() {
+                    self.locked.extend(&self.items);
+                    self.items.clear();
+                }
+
+                // Browsers have no paths to export to.
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button(Text::Export.tr(lang)).clicked() {
+                    export = true;
+                }
+
+                if ui.button(Text::Deselect.tr(lang)).clicked() {
+                    self.items.clear();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba_unmultiplied(&mut self.color);
+
+                if ui.button(Text::ApplyColor.tr(lang)).clicked() {
+                    colors.items.extend(self.items.iter().map(|i| (*i, self.color)));
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!self.hidden.is_empty(), egui::Button::new(Text::ShowHidden.tr(lang))).clicked() {
+                self.hidden.clear();
+            }
+
+            if ui.add_enabled(!self.locked.is_empty(), egui::Button::new(Text::UnlockAll.tr(lang))).clicked() {
+                self.locked.clear();
+            }
+        });
+
+        export
+    }
+}
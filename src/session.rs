@@ -0,0 +1,112 @@
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use egui_dock::DockState;
+use serde::{Deserialize, Serialize};
+use crate::annotation::Annotation;
+use crate::bounds::BoundsAlarm;
+use crate::breakpoint::Breakpoint;
+use crate::colors::ItemColors;
+use crate::debugger::Tab;
+use crate::engine::EngineKind;
+use crate::font::LabelFont;
+use crate::i18n::Language;
+use crate::overlay::Overlays;
+use crate::pacing::FrameRate;
+use crate::projection::Projection;
+use crate::runtime;
+use crate::runtime::{Batching, Threading};
+use crate::stop::StopForm;
+use crate::style::ItemStyle;
+use crate::view::{Bookmark, View};
+
+/// Extension of session files.
+pub const EXTENSION: &str = "gdbg";
+
+/// Everything needed to restore a debugging setup.
+///
+/// Engine parameters are stored as typed by the user, so that an invalid value
+/// is restored for correction instead of silently replaced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub script: Option<PathBuf>,
+    pub worker_count: String,
+    pub max_adjustment: String,
+    pub stop: StopForm,
+    /// Address of a generation server, empty to generate locally.
+    pub remote: String,
+    /// Seconds a busy worker may go without reporting before it's shown as stuck.
+    pub watchdog_timeout: f64,
+    pub batching: Batching,
+    /// Seconds of generation after which Run stops, if set.
+    pub time_budget: Option<f64>,
+    pub engine: EngineKind,
+    pub threading: Threading,
+    /// MiB the runtime's recordings may take before old entries are thinned out.
+    pub memory_budget: usize,
+    pub overlays: Overlays,
+    pub projection: Projection,
+    pub view: View,
+    pub bookmarks: Vec<Bookmark>,
+    pub item_colors: ItemColors,
+    pub item_style: ItemStyle,
+    pub label_font: LabelFont,
+    pub breakpoints: Vec<Breakpoint>,
+    pub bounds: BoundsAlarm,
+    pub frame_rate: FrameRate,
+    pub annotations: Vec<Annotation>,
+    pub language: Language,
+    /// Arrangement of the dockable panels. The default layout is used if missing.
+    pub layout: Option<DockState<Tab>>
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            script: None,
+            worker_count: runtime::DEFAULT_WORKER_COUNT.to_string(),
+            max_adjustment: runtime::DEFAULT_MAX_ADJUSTMENT.to_string(),
+            stop: StopForm::default(),
+            remote: String::new(),
+            watchdog_timeout: 10.0,
+            batching: Batching::default(),
+            time_budget: None,
+            engine: EngineKind::default(),
+            threading: Threading::default(),
+            memory_budget: 256,
+            overlays: Overlays::default(),
+            projection: Projection::default(),
+            view: View::default(),
+            bookmarks: Vec::new(),
+            item_colors: ItemColors::default(),
+            item_style: ItemStyle::default(),
+            label_font: LabelFont::default(),
+            breakpoints: Vec::new(),
+            bounds: BoundsAlarm::default(),
+            frame_rate: FrameRate::default(),
+            annotations: Vec::new(),
+            language: Language::default(),
+            layout: None
+        }
+    }
+}
+
+impl Session {
+    /// # Errors
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(path, content)
+    }
+
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't a valid session.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+
+        ron::from_str(&content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
@@ -0,0 +1,302 @@
+//! Rendering figures into images, for visual regression tests of the renderer.
+//!
+//! Rendering needs macroquad's context, so tests using this have to run inside a macroquad window.
+//! Only the built-in font is used, so snapshots don't depend on the fonts installed. Images are kept
+//! bottom-up like everything macroquad reads back from the GPU, including the ones loaded from files.
+//!
+//! Set [`UPDATE_VAR`] to write the rendered images as the new snapshots instead of comparing against them.
+//! Without it, a missing snapshot is a failure, so a snapshot that was never committed doesn't pass silently.
+//!
+//! Multisampling differs between GPUs and drivers, so rendered images only match snapshots recorded on a similar
+//! setup. Snapshot tests are skipped unless [`RUN_VAR`] or [`UPDATE_VAR`] is set, see [`enabled`].
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use geo_aid_internal::projector::figure::Item;
+use macroquad::prelude::*;
+use crate::colors::ItemColors;
+use crate::font;
+use crate::font::Fonts;
+use crate::offscreen;
+use crate::render::Renderer;
+use crate::style::ItemStyle;
+
+/// Environment variable making [`check`] update snapshots.
+pub const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+/// Environment variable enabling snapshot tests.
+pub const RUN_VAR: &str = "RUN_SNAPSHOTS";
+/// Color marking differing pixels in diff images.
+const DIFF_COLOR: [u8; 4] = [255, 0, 0, 255];
+
+/// Whether snapshot tests should run, see the [module docs](self).
+#[must_use]
+pub fn enabled() -> bool {
+    env::var_os(RUN_VAR).is_some() || env::var_os(UPDATE_VAR).is_some()
+}
+
+/// Render `items` on a white `canvas`-sized image with the default colors and the given style.
+#[must_use]
+pub fn render(items: &[Item], canvas: (f32, f32), style: &ItemStyle) -> Image {
+    let colors = ItemColors::default();
+    let hidden = BTreeSet::new();
+    let fonts = Fonts::default();
+    let renderer = Renderer {
+        colors: &colors,
+        hidden: &hidden,
+        fonts: &fonts,
+        label_size: font::DEFAULT_SIZE,
+        style
+    };
+
+    offscreen::render(canvas, 1.0, || {
+        renderer.draw(items, canvas);
+    })
+}
+
+/// Flip an image upside down, between file and GPU row order.
+fn flip(image: &mut Image) {
+    let row = usize::from(image.width) * 4;
+    let rows = image.bytes.chunks_exact(row).rev().flatten().copied().collect();
+    image.bytes = rows;
+}
+
+/// Load a PNG snapshot.
+///
+/// # Errors
+/// Returns an error if the file can't be read or isn't a valid PNG.
+pub fn load(path: &Path) -> Result<Image, String> {
+    let bytes = fs::read(path).map_err(|err| err.to_string())?;
+    let mut image = Image::from_file_with_format(&bytes, Some(ImageFormat::Png)).map_err(|err| err.to_string())?;
+    flip(&mut image);
+    Ok(image)
+}
+
+/// How two images differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Difference {
+    /// Pixels with a channel differing by more than the tolerance.
+    pub pixels: usize,
+    /// Largest difference of a channel.
+    pub max: u8
+}
+
+/// Compare two images of the same size, ignoring channel differences up to `tolerance`.
+/// Returns `None` if the sizes differ.
+#[must_use]
+pub fn compare(expected: &Image, actual: &Image, tolerance: u8) -> Option<Difference> {
+    if (expected.width, expected.height) != (actual.width, actual.height) {
+        return None;
+    }
+
+    let mut difference = Difference { pixels: 0, max: 0 };
+
+    for (a, b) in expected.get_image_data().iter().zip(actual.get_image_data()) {
+        let max = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0);
+        difference.max = difference.max.max(max);
+
+        if max > tolerance {
+            difference.pixels += 1;
+        }
+    }
+
+    Some(difference)
+}
+
+/// `actual` faded, with pixels differing from `expected` by more than `tolerance` in red.
+/// The images have to be the same size.
+#[must_use]
+pub fn diff_image(expected: &Image, actual: &Image, tolerance: u8) -> Image {
+    let mut diff = actual.clone();
+
+    for (pixel, reference) in diff.get_image_data_mut().iter_mut().zip(expected.get_image_data()) {
+        let differs = pixel.iter().zip(reference).any(|(a, b)| a.abs_diff(*b) > tolerance);
+
+        *pixel = if differs {
+            DIFF_COLOR
+        } else {
+            pixel.map(|channel| 255 - (255 - channel) / 4)
+        };
+    }
+
+    diff
+}
+
+/// Why a rendered image doesn't match its snapshot.
+#[derive(Debug, Clone)]
+pub enum Mismatch {
+    /// There's no snapshot and [`UPDATE_VAR`] isn't set.
+    Missing(PathBuf),
+    /// The snapshot couldn't be loaded.
+    Load(PathBuf, String),
+    Size {
+        expected: (u16, u16),
+        actual: (u16, u16)
+    },
+    /// The rendered image and a diff were saved next to the snapshot.
+    Pixels {
+        difference: Difference,
+        actual: PathBuf,
+        diff: PathBuf
+    }
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(path) => write!(f, "no snapshot at {}, set {UPDATE_VAR} to record it", path.display()),
+            Self::Load(path, err) => write!(f, "could not load {}: {err}", path.display()),
+            Self::Size { expected, actual } => write!(
+                f,
+                "expected a {}x{} image, rendered {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            Self::Pixels { difference, actual, diff } => write!(
+                f,
+                "{} pixel(s) differ by up to {}, see {} and {}",
+                difference.pixels,
+                difference.max,
+                actual.display(),
+                diff.display()
+            )
+        }
+    }
+}
+
+/// Check `image` against the snapshot at `path`, allowing `max_pixels` pixels to differ by more than `tolerance`.
+///
+/// If [`UPDATE_VAR`] is set, the snapshot is written from `image` instead.
+///
+/// # Errors
+/// Returns how the image doesn't match, including the snapshot missing.
+pub fn check(image: &Image, path: &Path, tolerance: u8, max_pixels: usize) -> Result<(), Mismatch> {
+    if env::var_os(UPDATE_VAR).is_some() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| Mismatch::Load(path.to_path_buf(), err.to_string()))?;
+        }
+
+        image.export_png(&path.to_string_lossy());
+        return Ok(());
+    }
+
+    if !path.exists() {
+        return Err(Mismatch::Missing(path.to_path_buf()));
+    }
+
+    let expected = load(path).map_err(|err| Mismatch::Load(path.to_path_buf(), err))?;
+
+    let Some(difference) = compare(&expected, image, tolerance) else {
+        return Err(Mismatch::Size {
+            expected: (expected.width, expected.height),
+            actual: (image.width, image.height)
+        });
+    };
+
+    if difference.pixels <= max_pixels {
+        return Ok(());
+    }
+
+    let actual = path.with_extension("actual.png");
+    let diff = path.with_extension("diff.png");
+    image.export_png(&actual.to_string_lossy());
+    diff_image(&expected, image, tolerance).export_png(&diff.to_string_lossy());
+
+    Err(Mismatch::Pixels { difference, actual, diff })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    fn filled(width: u16, height: u16, color: [u8; 4]) -> Image {
+        Image {
+            bytes: color.repeat(usize::from(width) * usize::from(height)),
+            width,
+            height
+        }
+    }
+
+    /// A white image with the pixel at `index` set to `color`.
+    fn with_pixel(index: usize, color: [u8; 4]) -> Image {
+        let mut image = filled(4, 3, WHITE);
+        image.get_image_data_mut()[index] = color;
+        image
+    }
+
+    #[test]
+    fn flip_reverses_rows() {
+        let mut image = Image {
+            bytes: vec![1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4],
+            width: 2,
+            height: 2
+        };
+        let original = image.bytes.clone();
+
+        flip(&mut image);
+        assert_eq!(image.bytes, [3, 3, 3, 3, 4, 4, 4, 4, 1, 1, 1, 1, 2, 2, 2, 2]);
+
+        flip(&mut image);
+        assert_eq!(image.bytes, original);
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let image = with_pixel(5, [10, 20, 30, 255]);
+
+        assert_eq!(compare(&image, &image.clone(), 0), Some(Difference { pixels: 0, max: 0 }));
+    }
+
+    #[test]
+    fn one_pixel_differs() {
+        let expected = filled(4, 3, WHITE);
+        let actual = with_pixel(7, [255, 0, 255, 255]);
+
+        assert_eq!(compare(&expected, &actual, 0), Some(Difference { pixels: 1, max: 255 }));
+    }
+
+    #[test]
+    fn tolerance_is_inclusive() {
+        let expected = filled(4, 3, WHITE);
+        let at = with_pixel(0, [245, 255, 255, 255]);
+        let past = with_pixel(0, [244, 255, 255, 255]);
+
+        assert_eq!(compare(&expected, &at, 10), Some(Difference { pixels: 0, max: 10 }));
+        assert_eq!(compare(&expected, &past, 10), Some(Difference { pixels: 1, max: 11 }));
+    }
+
+    #[test]
+    fn sizes_have_to_match() {
+        assert_eq!(compare(&filled(4, 3, WHITE), &filled(3, 4, WHITE), 255), None);
+    }
+
+    #[test]
+    fn diff_marks_differing_pixels() {
+        let expected = filled(4, 3, WHITE);
+        let actual = with_pixel(2, [0, 0, 0, 255]);
+        let black = with_pixel(1, [0, 0, 0, 255]);
+
+        let diff = diff_image(&expected, &actual, 0);
+        let pixels = diff.get_image_data();
+        assert_eq!(pixels[2], DIFF_COLOR);
+        assert_eq!(pixels[0], WHITE);
+
+        // Matching pixels are faded toward white.
+        let diff = diff_image(&black, &black, 0);
+        assert_eq!(diff.get_image_data()[1], [192, 192, 192, 255]);
+    }
+
+    #[test]
+    fn missing_snapshot_fails() {
+        if env::var_os(UPDATE_VAR).is_some() {
+            return;
+        }
+
+        let path = env::temp_dir().join("geo-aid-dbg-missing-snapshot.png");
+        let result = check(&filled(1, 1, WHITE), &path, 0, 0);
+        assert!(matches!(result, Err(Mismatch::Missing(missing)) if missing == path));
+    }
+}
@@ -0,0 +1,61 @@
+//! Where the script passed on the command line comes from.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// A script argument: `-` for stdin, an `http(s)://` URL, or a file path.
+#[derive(Debug, Clone)]
+pub enum Source {
+    Stdin,
+    Url(String),
+    File(PathBuf)
+}
+
+impl Source {
+    /// The script argument, unless the debugger was started without one.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let arg = env::args().nth(1)?;
+
+        Some(if arg == "-" {
+            Self::Stdin
+        } else if arg.starts_with("http://") || arg.starts_with("https://") {
+            Self::Url(arg)
+        } else {
+            Self::File(PathBuf::from(arg))
+        })
+    }
+
+    /// # Errors
+    /// Returns an error if the script can't be read or downloaded.
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Self::Stdin => {
+                let mut script = String::new();
+                io::stdin().read_to_string(&mut script)?;
+                Ok(script)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Url(url) => ureq::get(url)
+                .call()
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("could not fetch {url}: {err}")))?
+                .into_string(),
+            // There are no command line arguments in the browser anyway.
+            #[cfg(target_arch = "wasm32")]
+            Self::Url(url) => Err(io::Error::new(io::ErrorKind::Unsupported, format!("could not fetch {url}"))),
+            Self::File(path) => fs::read_to_string(path)
+        }
+    }
+
+    /// The file the script can be saved back to, if any.
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::File(path) => Some(path),
+            Self::Stdin | Self::Url(_) => None
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use egui::{Pos2, Rect};
+use geo_aid_internal::projector::figure::{Item, Position};
+
+/// Side of a grid cell, in canvas units.
+const CELL_SIZE: f32 = 32.0;
+/// Most samples taken along one item. Only huge off-canvas items get this many.
+const MAX_SAMPLES: f32 = 4096.0;
+
+/// A uniform grid over projected items, so that hit-testing only looks at items near the cursor.
+///
+/// Rebuilt whenever the figure is projected again.
+#[derive(Default)]
+pub struct SpatialIndex {
+    cells: HashMap<(i32, i32), Vec<usize>>
+}
+
+fn pos(position: &Position) -> Pos2 {
+    Pos2::new(position.x as f32, position.y as f32)
+}
+
+fn cell_of(p: Pos2) -> (i32, i32) {
+    ((p.x / CELL_SIZE).floor() as i32, (p.y / CELL_SIZE).floor() as i32)
+}
+
+impl SpatialIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn build(items: &[Item]) -> Self {
+        let mut index = Self::new();
+
+        for (i, item) in items.iter().enumerate() {
+            match item {
+                Item::Point(pt) => index.insert_point(i, pos(&pt.position)),
+                Item::Line(ln) => index.insert_segment(i, pos(&ln.points.0), pos(&ln.points.1)),
+                Item::Segment(x)
+                | Item::Ray(x) => index.insert_segment(i, pos(&x.points.0), pos(&x.points.1)),
+                Item::Circle(circle) => index.insert_circle(i, pos(&circle.center), circle.radius as f32)
+            }
+        }
+
+        index
+    }
+
+    fn insert_point(&mut self, item: usize, p: Pos2) {
+        if !p.x.is_finite() || !p.y.is_finite() {
+            return;
+        }
+
+        let cell = self.cells.entry(cell_of(p)).or_default();
+
+        if cell.last() != Some(&item) {
+            cell.push(item);
+        }
+    }
+
+    /// Insert samples half a cell apart. A query looks one cell further than asked,
+    /// so a cell corner skipped between samples can't hide the item.
+    fn insert_segment(&mut self, item: usize, a: Pos2, b: Pos2) {
+        let length = a.distance(b);
+
+        if !length.is_finite() {
+            return;
+        }
+
+        let steps = (length / (CELL_SIZE / 2.0)).ceil().min(MAX_SAMPLES);
+
+        for step in 0..=steps as usize {
+            self.insert_point(item, a.lerp(b, step as f32 / steps.max(1.0)));
+        }
+    }
+
+    fn insert_circle(&mut self, item: usize, center: Pos2, radius: f32) {
+        let steps = (std::f32::consts::TAU * radius / (CELL_SIZE / 2.0)).ceil().clamp(8.0, MAX_SAMPLES);
+
+        if !steps.is_finite() {
+            return;
+        }
+
+        for step in 0..steps as usize {
+            let angle = std::f32::consts::TAU * step as f32 / steps;
+            self.insert_point(item, center + radius * egui::Vec2::angled(angle));
+        }
+    }
+
+    /// Indices of items that may be in `rect`, each listed once and in ascending order.
+    #[must_use]
+    pub fn in_rect(&self, rect: Rect) -> Vec<usize> {
+        let min = cell_of(rect.min);
+        let max = cell_of(rect.max);
+        let mut found = Vec::new();
+
+        for x in min.0 - 1..=max.0 + 1 {
+            for y in min.1 - 1..=max.1 + 1 {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    found.extend(cell);
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// Indices of items that may be within `radius` of `p`.
+    #[must_use]
+    pub fn near(&self, p: Pos2, radius: f32) -> Vec<usize> {
+        self.in_rect(Rect::from_center_size(p, egui::Vec2::splat(2.0 * radius)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_points_nearby() {
+        let mut index = SpatialIndex::new();
+        index.insert_point(0, Pos2::new(10.0, 10.0));
+        index.insert_point(1, Pos2::new(500.0, 500.0));
+
+        assert_eq!(index.near(Pos2::new(12.0, 8.0), 5.0), [0]);
+        assert_eq!(index.near(Pos2::new(490.0, 510.0), 20.0), [1]);
+        assert!(index.near(Pos2::new(250.0, 250.0), 10.0).is_empty());
+    }
+
+    #[test]
+    fn skips_points_off_to_infinity() {
+        let mut index = SpatialIndex::new();
+        index.insert_point(0, Pos2::new(f32::NAN, 0.0));
+        index.insert_segment(1, Pos2::ZERO, Pos2::new(f32::INFINITY, 0.0));
+
+        assert!(index.cells.is_empty());
+    }
+
+    #[test]
+    fn segments_are_found_along_their_length() {
+        let mut index = SpatialIndex::new();
+        index.insert_segment(3, Pos2::new(0.0, 0.0), Pos2::new(1000.0, 1000.0));
+
+        for t in [0.0, 0.13, 0.5, 0.77, 1.0] {
+            let p = Pos2::new(1000.0 * t, 1000.0 * t);
+            assert_eq!(index.near(p, 1.0), [3], "at {p:?}");
+        }
+
+        assert!(index.near(Pos2::new(1000.0, 0.0), 1.0).is_empty());
+    }
+
+    #[test]
+    fn circles_are_found_on_their_rim() {
+        let mut index = SpatialIndex::new();
+        index.insert_circle(2, Pos2::new(400.0, 400.0), 200.0);
+
+        assert_eq!(index.near(Pos2::new(600.0, 400.0), 1.0), [2]);
+        assert_eq!(index.near(Pos2::new(400.0, 200.0), 1.0), [2]);
+        assert!(index.near(Pos2::new(400.0, 400.0), 1.0).is_empty());
+    }
+
+    #[test]
+    fn results_are_sorted_and_unique() {
+        let mut index = SpatialIndex::new();
+        index.insert_segment(5, Pos2::new(0.0, 0.0), Pos2::new(200.0, 0.0));
+        index.insert_point(1, Pos2::new(100.0, 0.0));
+        index.insert_segment(3, Pos2::new(100.0, -100.0), Pos2::new(100.0, 100.0));
+
+        assert_eq!(index.in_rect(Rect::from_min_max(Pos2::new(-50.0, -50.0), Pos2::new(250.0, 50.0))), [1, 3, 5]);
+    }
+}
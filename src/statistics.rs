@@ -0,0 +1,311 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::mpsc::TryRecvError;
+use std::sync::Arc;
+use std::thread;
+use egui::{Color32, Context, RichText};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+use geo_aid_internal::script::math::Intermediate;
+use crate::engine::{DebuggableEngine, EngineKind};
+use crate::i18n::{Language, Text};
+
+/// How many points each convergence curve is sampled at.
+const CURVE_SAMPLES: usize = 200;
+/// How many bins the final quality histogram has.
+const HISTOGRAM_BINS: usize = 20;
+
+/// A single finished generation.
+pub struct Run {
+    pub seed: u64,
+    /// (iteration, quality) samples.
+    pub curve: Vec<[f64; 2]>,
+    pub quality: f64
+}
+
+/// Several independent generations of one script, run on a background thread.
+pub struct MultiRun {
+    results: mpsc::Receiver<Run>,
+    abort: Arc<AtomicBool>,
+    pub runs: Vec<Run>,
+    pub total: usize,
+    pub finished: bool
+}
+
+impl MultiRun {
+    /// Run `start.runs` generations of `E`, seeded with consecutive seeds from `start.seed`.
+    #[must_use]
+    pub fn spawn<E: DebuggableEngine>(
+        intermediate: Intermediate,
+        worker_count: usize,
+        max_adjustment: f64,
+        start: Start
+    ) -> Self {
+        let (send, results) = mpsc::channel();
+        let abort = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&abort);
+        let Start { runs, iterations, seed } = start;
+        let stride = (iterations / CURVE_SAMPLES).max(1);
+
+        thread::spawn(move || {
+            for seed in (0..runs as u64).map(|i| seed.wrapping_add(i)) {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let mut engine = E::compile(worker_count, &intermediate);
+                engine.reseed(seed);
+                let magnitudes = engine.bake(max_adjustment);
+                let mut curve = vec![[0.0, engine.quality()]];
+
+                for i in 1..=iterations {
+                    engine.cycle(&magnitudes);
+
+                    if i % stride == 0 {
+                        curve.push([i as f64, engine.quality()]);
+                    }
+                }
+
+                let run = Run {
+                    seed,
+                    curve,
+                    quality: engine.quality()
+                };
+
+                if send.send(run).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            results,
+            abort,
+            runs: Vec::new(),
+            total: runs,
+            finished: false
+        }
+    }
+
+    /// Stop after the run in progress.
+    pub fn cancel(&self) {
+        self.abort.store(true, Ordering::Relaxed);
+    }
+
+    pub fn poll(&mut self) {
+        loop {
+            match self.results.try_recv() {
+                Ok(run) => self.runs.push(run),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Final qualities binned over their range, as (bin center, count).
+    #[must_use]
+    pub fn histogram(&self) -> Vec<(f64, usize)> {
+        let min = self.runs.iter().map(|r| r.quality).fold(f64::INFINITY, f64::min);
+        let max = self.runs.iter().map(|r| r.quality).fold(f64::NEG_INFINITY, f64::max);
+
+        if !min.is_finite() || !max.is_finite() {
+            return Vec::new();
+        }
+
+        let width = ((max - min) / HISTOGRAM_BINS as f64).max(f64::EPSILON);
+        let mut counts = vec![0; HISTOGRAM_BINS];
+
+        for run in &self.runs {
+            let bin = ((run.quality - min) / width) as usize;
+            counts[bin.min(HISTOGRAM_BINS - 1)] += 1;
+        }
+
+        counts.into_iter()
+            .enumerate()
+            .map(|(i, count)| (min + (i as f64 + 0.5) * width, count))
+            .collect()
+    }
+}
+
+impl Drop for MultiRun {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+pub struct StatisticsTool {
+    pub open: bool,
+    runs: String,
+    runs_valid: bool,
+    iterations: String,
+    iterations_valid: bool,
+    seed: String,
+    seed_valid: bool,
+    multi_run: Option<MultiRun>
+}
+
+/// Requested run and iteration counts.
+pub struct Start {
+    pub runs: usize,
+    pub iterations: usize,
+    /// Seed of the first run, the others follow consecutively.
+    pub seed: u64
+}
+
+impl Default for StatisticsTool {
+    fn default() -> Self {
+        Self {
+            open: false,
+            runs: String::from("10"),
+            runs_valid: true,
+            iterations: String::from("1000"),
+            iterations_valid: true,
+            seed: String::from("0"),
+            seed_valid: true,
+            multi_run: None
+        }
+    }
+}
+
+impl StatisticsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, multi_run: MultiRun) {
+        self.multi_run = Some(multi_run);
+    }
+
+    /// `engine` is the engine runs are started with.
+    #[must_use]
+    pub fn show(&mut self, ctx: &Context, engine: EngineKind, lang: Language) -> Option<Start> {
+        let mut start = None;
+
+        if let Some(multi_run) = &mut self.multi_run {
+            multi_run.poll();
+        }
+
+        egui::Window::new(Text::MultiRunStatistics.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                let running = self.multi_run.as_ref().is_some_and(|m| !m.finished);
+
+                egui::Grid::new("statistics-params")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(Text::RunsField.tr(lang));
+                        ui.text_edit_singleline(&mut self.runs);
+                        ui.end_row();
+
+                        if !self.runs_valid {
+                            ui.label(RichText::new(Text::InvalidRunCount.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBePositiveInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label(Text::IterationsPerRun.tr(lang));
+                        ui.text_edit_singleline(&mut self.iterations);
+                        ui.end_row();
+
+                        if !self.iterations_valid {
+                            ui.label(RichText::new(Text::InvalidIterationCount.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBePositiveInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label(Text::FirstSeed.tr(lang));
+                        ui.text_edit_singleline(&mut self.seed);
+                        ui.end_row();
+
+                        if !self.seed_valid {
+                            ui.label(RichText::new(Text::InvalidSeed.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBeNonNegativeInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label("");
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(!running, egui::Button::new(Text::Start.tr(lang))).clicked() {
+                                let runs = usize::from_str(&self.runs).ok().filter(|r| *r > 0);
+                                let iterations = usize::from_str(&self.iterations).ok().filter(|i| *i > 0);
+                                let seed = u64::from_str(&self.seed).ok();
+
+                                self.runs_valid = runs.is_some();
+                                self.iterations_valid = iterations.is_some();
+                                self.seed_valid = seed.is_some();
+
+                                if let (Some(runs), Some(iterations), Some(seed)) = (runs, iterations, seed) {
+                                    start = Some(Start { runs, iterations, seed });
+                                }
+                            }
+
+                            if ui.add_enabled(running, egui::Button::new(Text::Cancel.tr(lang))).clicked() {
+                                if let Some(multi_run) = &self.multi_run {
+                                    multi_run.cancel();
+                                }
+                            }
+                        });
+                        ui.end_row();
+                    });
+
+                if !engine.seeded() {
+                    ui.label(Text::EngineTakesNoSeed.fill(lang, &[&engine.name()]));
+                }
+
+                let Some(multi_run) = &self.multi_run else {
+                    return;
+                };
+
+                if !multi_run.finished {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(Text::RunProgress.fill(lang, &[&(multi_run.runs.len() + 1), &multi_run.total]));
+                    });
+                }
+
+                if multi_run.runs.is_empty() {
+                    return;
+                }
+
+                let qualities = multi_run.runs.iter().map(|r| r.quality);
+                let mean = qualities.clone().sum::<f64>() / multi_run.runs.len() as f64;
+                let best = qualities.clone().fold(f64::NEG_INFINITY, f64::max);
+                let worst = qualities.fold(f64::INFINITY, f64::min);
+                ui.label(Text::FinalQualitySummary.fill(
+                    lang,
+                    &[&format!("{mean:.4}"), &format!("{best:.4}"), &format!("{worst:.4}")]
+                ));
+
+                ui.label(Text::FinalQualityDistribution.tr(lang));
+                let histogram = multi_run.histogram();
+                let width = histogram.get(1).map_or(0.01, |(x, _)| x - histogram[0].0);
+                let bars = histogram.into_iter()
+                    .map(|(x, count)| Bar::new(x, count as f64).width(width))
+                    .collect();
+
+                Plot::new("statistics-histogram")
+                    .height(150.0)
+                    .show(ui, |plot| plot.bar_chart(BarChart::new(bars)));
+
+                ui.label(Text::Convergence.tr(lang));
+                Plot::new("statistics-curves")
+                    .height(200.0)
+                    .legend(Legend::default())
+                    .show(ui, |plot| {
+                        for (i, run) in multi_run.runs.iter().enumerate() {
+                            plot.line(
+                                Line::new(PlotPoints::from(run.curve.clone()))
+                                    .name(Text::RunWithSeed.fill(lang, &[&(i + 1), &run.seed]))
+                            );
+                        }
+                    });
+            });
+
+        start
+    }
+}
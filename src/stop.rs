@@ -0,0 +1,347 @@
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "ui")]
+use std::str::FromStr;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+#[cfg(feature = "ui")]
+use egui::{Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ui")]
+use crate::i18n::{Language, Text};
+
+/// Quality has to improve by at least `min_improvement` every `window` iterations.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Stagnation {
+    pub window: usize,
+    pub min_improvement: f64
+}
+
+/// Conditions that end an unattended run. Disabled criteria are `None`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct StopCriteria {
+    pub target_quality: Option<f64>,
+    pub max_iterations: Option<usize>,
+    /// Wall-clock limit in seconds.
+    pub time_limit: Option<f64>,
+    pub stagnation: Option<Stagnation>
+}
+
+/// Why an unattended run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopReason {
+    TargetReached,
+    MaxIterations,
+    TimeLimit,
+    Stagnation,
+    /// A breakpoint triggered or an adjustable became NaN/Inf.
+    Paused,
+    /// Stopped by the user.
+    User
+}
+
+impl Display for StopReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TargetReached => write!(f, "target quality reached"),
+            Self::MaxIterations => write!(f, "max iterations done"),
+            Self::TimeLimit => write!(f, "time limit exceeded"),
+            Self::Stagnation => write!(f, "quality stagnated"),
+            Self::Paused => write!(f, "paused by a breakpoint"),
+            Self::User => write!(f, "stopped by the user")
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl StopReason {
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::TargetReached => Text::TargetReached,
+            Self::MaxIterations => Text::MaxIterationsDone,
+            Self::TimeLimit => Text::TimeLimitExceeded,
+            Self::Stagnation => Text::QualityStagnated,
+            Self::Paused => Text::PausedByBreakpoint,
+            Self::User => Text::StoppedByUser
+        }
+    }
+}
+
+/// Tracks a run against its criteria.
+pub struct StopCheck {
+    criteria: StopCriteria,
+    started: Instant,
+    /// Cycles done in this run.
+    done: usize,
+    /// Cycles done and quality at the start of the current stagnation window.
+    window: (usize, f64)
+}
+
+impl StopCheck {
+    #[must_use]
+    pub fn new(criteria: StopCriteria, quality: f64) -> Self {
+        Self {
+            criteria,
+            started: Instant::now(),
+            done: 0,
+            window: (0, quality)
+        }
+    }
+
+    /// Count a finished cycle.
+    pub fn cycle(&mut self) {
+        self.done += 1;
+    }
+
+    /// Check the criteria against the current quality.
+    pub fn check(&mut self, quality: f64) -> Option<StopReason> {
+        let criteria = self.criteria;
+
+        if criteria.target_quality.is_some_and(|target| quality >= target) {
+            return Some(StopReason::TargetReached);
+        }
+
+        if criteria.max_iterations.is_some_and(|max| self.done >= max) {
+            return Some(StopReason::MaxIterations);
+        }
+
+        if criteria.time_limit.is_some_and(|limit| self.started.elapsed().as_secs_f64() >= limit) {
+            return Some(StopReason::TimeLimit);
+        }
+
+        if let Some(stagnation) = criteria.stagnation {
+            let (start, start_quality) = self.window;
+
+            if self.done - start >= stagnation.window {
+                if quality - start_quality < stagnation.min_improvement {
+                    return Some(StopReason::Stagnation);
+                }
+
+                self.window = (self.done, quality);
+            }
+        }
+
+        None
+    }
+}
+
+/// The stop criteria editor. Values are kept as typed, so that invalid ones can be corrected.
+#[cfg(feature = "ui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StopForm {
+    pub use_target: bool,
+    pub target_quality: String,
+    pub use_max_iterations: bool,
+    pub max_iterations: String,
+    pub use_time_limit: bool,
+    pub time_limit: String,
+    pub use_stagnation: bool,
+    pub stagnation_window: String,
+    pub stagnation_improvement: String,
+    /// The criterion that failed to parse last time.
+    #[serde(skip)]
+    invalid: Option<Text>
+}
+
+#[cfg(feature = "ui")]
+impl Default for StopForm {
+    fn default() -> Self {
+        Self {
+            use_target: true,
+            target_quality: String::from("0.99"),
+            use_max_iterations: true,
+            max_iterations: String::from("100000"),
+            use_time_limit: false,
+            time_limit: String::from("60"),
+            use_stagnation: false,
+            stagnation_window: String::from("1000"),
+            stagnation_improvement: String::from("0.0001"),
+            invalid: None
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl StopForm {
+    /// The target quality if it's enabled and valid.
+    #[must_use]
+    pub fn target(&self) -> Option<f64> {
+        f64::from_str(&self.target_quality).ok().filter(|_| self.use_target)
+    }
+
+    fn criteria(&self) -> Result<StopCriteria, Text> {
+        fn field<T: FromStr>(enabled: bool, value: &str, invalid: Text) -> Result<Option<T>, Text> {
+            if enabled {
+                T::from_str(value.trim()).map(Some).map_err(|_| invalid)
+            } else {
+                Ok(None)
+            }
+        }
+
+        let window = field(self.use_stagnation, &self.stagnation_window, Text::InvalidStagnationWindow)?;
+        let min_improvement =
+            field(self.use_stagnation, &self.stagnation_improvement, Text::InvalidMinimumImprovement)?;
+
+        Ok(StopCriteria {
+            target_quality: field(self.use_target, &self.target_quality, Text::InvalidTargetQuality)?,
+            max_iterations: field(self.use_max_iterations, &self.max_iterations, Text::InvalidMaxIterations)?,
+            time_limit: field(self.use_time_limit, &self.time_limit, Text::InvalidTimeLimit)?,
+            stagnation: window.zip(min_improvement)
+                .map(|(window, min_improvement)| Stagnation { window, min_improvement })
+        })
+    }
+
+    /// Parse the enabled criteria. On failure, the invalid one is shown by [`Self::ui`].
+    pub fn parse(&mut self) -> Option<StopCriteria> {
+        match self.criteria() {
+            Ok(criteria) => {
+                self.invalid = None;
+                Some(criteria)
+            }
+            Err(invalid) => {
+                self.invalid = Some(invalid);
+                None
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, lang: Language) {
+        egui::Grid::new("stop-criteria")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.checkbox(&mut self.use_target, Text::TargetQualityField.tr(lang));
+                ui.text_edit_singleline(&mut self.target_quality);
+                ui.end_row();
+
+                ui.checkbox(&mut self.use_max_iterations, Text::MaxIterationsField.tr(lang));
+                ui.text_edit_singleline(&mut self.max_iterations);
+                ui.end_row();
+
+                ui.checkbox(&mut self.use_time_limit, Text::TimeLimitField.tr(lang));
+                ui.text_edit_singleline(&mut self.time_limit);
+                ui.end_row();
+
+                ui.checkbox(&mut self.use_stagnation, Text::StagnationWindowField.tr(lang))
+                    .on_hover_text(Text::StagnationWindowHint.tr(lang));
+                ui.text_edit_singleline(&mut self.stagnation_window);
+                ui.end_row();
+
+                ui.label(Text::MinimumImprovementField.tr(lang));
+                ui.add_enabled(self.use_stagnation, egui::TextEdit::singleline(&mut self.stagnation_improvement));
+                ui.end_row();
+            });
+
+        if let Some(invalid) = self.invalid {
+            ui.label(RichText::new(invalid.tr(lang)).color(Color32::RED));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn criteria() -> StopCriteria {
+        StopCriteria {
+            target_quality: Some(0.9),
+            max_iterations: Some(10),
+            time_limit: None,
+            stagnation: None
+        }
+    }
+
+    #[test]
+    fn target_takes_precedence() {
+        let mut check = StopCheck::new(criteria(), 0.0);
+
+        for _ in 0..10 {
+            check.cycle();
+        }
+
+        assert_eq!(check.check(0.95), Some(StopReason::TargetReached));
+        assert_eq!(check.check(0.5), Some(StopReason::MaxIterations));
+    }
+
+    #[test]
+    fn counts_cycles() {
+        let mut check = StopCheck::new(criteria(), 0.0);
+
+        for _ in 0..9 {
+            check.cycle();
+            assert_eq!(check.check(0.5), None);
+        }
+
+        check.cycle();
+        assert_eq!(check.check(0.5), Some(StopReason::MaxIterations));
+    }
+
+    #[test]
+    fn time_limit() {
+        let mut check = StopCheck::new(StopCriteria { time_limit: Some(0.0), ..StopCriteria::default() }, 0.0);
+
+        assert_eq!(check.check(0.0), Some(StopReason::TimeLimit));
+        assert_eq!(StopCheck::new(StopCriteria::default(), 0.0).check(1.0), None);
+    }
+
+    #[test]
+    fn stagnation_is_checked_every_window() {
+        let stagnation = Stagnation { window: 5, min_improvement: 0.1 };
+        let mut check = StopCheck::new(StopCriteria { stagnation: Some(stagnation), ..StopCriteria::default() }, 0.0);
+
+        // Enough improvement over the first window starts the next one from there.
+        for _ in 0..5 {
+            check.cycle();
+        }
+        assert_eq!(check.check(0.2), None);
+
+        for _ in 0..4 {
+            check.cycle();
+            assert_eq!(check.check(0.25), None);
+        }
+
+        check.cycle();
+        assert_eq!(check.check(0.25), Some(StopReason::Stagnation));
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn form_parses_enabled_criteria() {
+        let mut form = StopForm {
+            use_time_limit: true,
+            time_limit: String::from(" 2.5 "),
+            use_stagnation: true,
+            ..StopForm::default()
+        };
+
+        let criteria = form.parse().unwrap();
+        assert_eq!(criteria.target_quality, Some(0.99));
+        assert_eq!(criteria.max_iterations, Some(100_000));
+        assert_eq!(criteria.time_limit, Some(2.5));
+        assert!(criteria.stagnation.is_some_and(|s| s.window == 1000 && s.min_improvement == 0.0001));
+        assert_eq!(form.target(), Some(0.99));
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn form_reports_the_invalid_criterion() {
+        let mut form = StopForm {
+            max_iterations: String::from("many"),
+            ..StopForm::default()
+        };
+
+        assert!(form.parse().is_none());
+        assert_eq!(form.invalid, Some(Text::InvalidMaxIterations));
+
+        // Disabled criteria aren't parsed.
+        form.use_max_iterations = false;
+        assert!(form.parse().is_some_and(|criteria| criteria.max_iterations.is_none()));
+        assert_eq!(form.invalid, None);
+
+        form.use_target = false;
+        assert_eq!(form.target(), None);
+    }
+}
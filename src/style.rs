@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use crate::i18n::Text;
+
+pub const MIN_STROKE_WIDTH: f32 = 0.25;
+pub const MAX_STROKE_WIDTH: f32 = 10.0;
+pub const MIN_POINT_RADIUS: f32 = 0.5;
+pub const MAX_POINT_RADIUS: f32 = 15.0;
+
+/// How point dots are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DotStyle {
+    #[default]
+    Filled,
+    Hollow,
+    Cross
+}
+
+impl DotStyle {
+    pub const ALL: [Self; 3] = [Self::Filled, Self::Hollow, Self::Cross];
+
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::Filled => Text::DotFilled,
+            Self::Hollow => Text::DotHollow,
+            Self::Cross => Text::DotCross
+        }
+    }
+}
+
+/// Sizes figure items are drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemStyle {
+    pub stroke_width: f32,
+    pub point_radius: f32,
+    pub dot: DotStyle,
+    /// Grow strokes and dots with the canvas, the way labels do.
+    /// Keeps large canvases and high-resolution captures from ending up with hairlines.
+    pub scale_with_canvas: bool,
+    /// Leave out labels of huge figures, where they'd be unreadable and slow to lay out.
+    pub level_of_detail: bool
+}
+
+impl Default for ItemStyle {
+    fn default() -> Self {
+        Self {
+            stroke_width: 1.0,
+            point_radius: 2.0,
+            dot: DotStyle::Filled,
+            scale_with_canvas: false,
+            level_of_detail: true
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use egui::{Color32, Context, RichText};
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::script::math::Intermediate;
+use crate::i18n::{Language, Text};
+
+/// The engine parameter a sweep varies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parameter {
+    MaxAdjustment,
+    WorkerCount
+}
+
+impl Parameter {
+    pub const ALL: [Self; 2] = [Self::MaxAdjustment, Self::WorkerCount];
+
+    #[must_use]
+    pub fn name(self) -> Text {
+        match self {
+            Self::MaxAdjustment => Text::MaxAdjustment,
+            Self::WorkerCount => Text::WorkerCountParam
+        }
+    }
+}
+
+/// Final qualities of all runs of one configuration.
+pub struct Sample {
+    pub value: f64,
+    pub mean: f64,
+    pub best: f64,
+    pub worst: f64
+}
+
+/// A parameter sweep running on a background thread.
+pub struct Sweep {
+    results: mpsc::Receiver<Sample>,
+    pub parameter: Parameter,
+    pub samples: Vec<Sample>,
+    pub total: usize,
+    pub finished: bool
+}
+
+impl Sweep {
+    /// `worker_count` and `max_adjustment` are used for whichever parameter isn't swept.
+    #[must_use]
+    pub fn spawn(intermediate: Intermediate, start: Start, worker_count: usize, max_adjustment: f64) -> Self {
+        let (send, results) = mpsc::channel();
+        let Start { parameter, values, runs, iterations } = start;
+        let total = values.len();
+
+        thread::spawn(move || {
+            for value in values {
+                let (wc, ma) = match parameter {
+                    Parameter::MaxAdjustment => (worker_count, value),
+                    Parameter::WorkerCount => (value as usize, max_adjustment)
+                };
+
+                // Every engine starts from its own random state, so repeated runs stand in for seeds.
+                let qualities = (0..runs).map(|_| {
+                    let mut rage = Rage::new(wc, &intermediate);
+                    let magnitudes = rage.gen().bake_magnitudes(ma);
+
+                    for _ in 0..iterations {
+                        rage.gen_mut().cycle_prebaked(&magnitudes);
+                    }
+
+                    rage.gen().get_total_quality()
+                }).collect::<Vec<_>>();
+
+                let sample = Sample {
+                    value,
+                    mean: qualities.iter().sum::<f64>() / runs as f64,
+                    best: qualities.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    worst: qualities.iter().copied().fold(f64::INFINITY, f64::min)
+                };
+
+                if send.send(sample).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            results,
+            parameter,
+            samples: Vec::new(),
+            total,
+            finished: false
+        }
+    }
+
+    pub fn poll(&mut self) {
+        loop {
+            match self.results.try_recv() {
+                Ok(sample) => self.samples.push(sample),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// The sample with the best mean final quality.
+    #[must_use]
+    pub fn best(&self) -> Option<&Sample> {
+        self.samples.iter().max_by(|a, b| a.mean.total_cmp(&b.mean))
+    }
+}
+
+/// A requested sweep.
+pub struct Start {
+    pub parameter: Parameter,
+    pub values: Vec<f64>,
+    /// Runs per value.
+    pub runs: usize,
+    /// Iterations per run.
+    pub iterations: usize
+}
+
+pub enum Action {
+    Start(Start),
+    /// Use this value of the parameter in the setup.
+    Use(Parameter, f64)
+}
+
+/// Evenly spaced values from `from` to `to`. Worker counts are rounded and deduplicated.
+fn values(parameter: Parameter, from: f64, to: f64, steps: usize) -> Vec<f64> {
+    let mut values = (0..steps)
+        .map(|i| if steps == 1 {
+            from
+        } else {
+            from + (to - from) * i as f64 / (steps - 1) as f64
+        })
+        .collect::<Vec<_>>();
+
+    if parameter == Parameter::WorkerCount {
+        for value in &mut values {
+            *value = value.round();
+        }
+
+        values.dedup();
+    }
+
+    values
+}
+
+/// The "Parameter sweep" window.
+pub struct SweepTool {
+    pub open: bool,
+    parameter: Parameter,
+    from: String,
+    to: String,
+    range_valid: bool,
+    steps: String,
+    steps_valid: bool,
+    runs: String,
+    runs_valid: bool,
+    iterations: String,
+    iterations_valid: bool,
+    sweep: Option<Sweep>
+}
+
+impl Default for SweepTool {
+    fn default() -> Self {
+        Self {
+            open: false,
+            parameter: Parameter::MaxAdjustment,
+            from: String::from("0.1"),
+            to: String::from("1.0"),
+            range_valid: true,
+            steps: String::from("10"),
+            steps_valid: true,
+            runs: String::from("3"),
+            runs_valid: true,
+            iterations: String::from("1000"),
+            iterations_valid: true,
+            sweep: None
+        }
+    }
+}
+
+impl SweepTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, sweep: Sweep) {
+        self.sweep = Some(sweep);
+    }
+
+    /// The range as entered, if it's valid for the parameter.
+    fn range(&self) -> Option<(f64, f64)> {
+        let from = f64::from_str(self.from.trim()).ok()?;
+        let to = f64::from_str(self.to.trim()).ok()?;
+
+        let valid = match self.parameter {
+            Parameter::MaxAdjustment => from > 0.0 && to > 0.0,
+            Parameter::WorkerCount => from >= 1.0 && to >= 1.0
+        };
+
+        valid.then_some((from, to))
+    }
+
+    #[must_use]
+    pub fn show(&mut self, ctx: &Context, lang: Language) -> Option<Action> {
+        let mut action = None;
+
+        if let Some(sweep) = &mut self.sweep {
+            sweep.poll();
+        }
+
+        egui::Window::new(Text::ParameterSweep.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                let running = self.sweep.as_ref().is_some_and(|s| !s.finished);
+
+                egui::Grid::new("sweep-params")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(Text::ParameterField.tr(lang));
+                        egui::ComboBox::from_id_source("sweep-parameter")
+                            .selected_text(self.parameter.name().tr(lang))
+                            .show_ui(ui, |ui| {
+                                for parameter in Parameter::ALL {
+                                    ui.selectable_value(&mut self.parameter, parameter, parameter.name().tr(lang));
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(Text::From.tr(lang));
+                        ui.text_edit_singleline(&mut self.from);
+                        ui.end_row();
+
+                        ui.label(Text::To.tr(lang));
+                        ui.text_edit_singleline(&mut self.to);
+                        ui.end_row();
+
+                        if !self.range_valid {
+                            ui.label(RichText::new(Text::InvalidRange.tr(lang)).color(Color32::RED));
+                            ui.label(Text::InvalidRangeHint.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label(Text::Steps.tr(lang));
+                        ui.text_edit_singleline(&mut self.steps);
+                        ui.end_row();
+
+                        if !self.steps_valid {
+                            ui.label(RichText::new(Text::InvalidStepCount.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBePositiveInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label(Text::RunsPerValue.tr(lang));
+                        ui.text_edit_singleline(&mut self.runs);
+                        ui.end_row();
+
+                        if !self.runs_valid {
+                            ui.label(RichText::new(Text::InvalidRunCount.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBePositiveInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label(Text::IterationsPerRun.tr(lang));
+                        ui.text_edit_singleline(&mut self.iterations);
+                        ui.end_row();
+
+                        if !self.iterations_valid {
+                            ui.label(RichText::new(Text::InvalidIterationCount.tr(lang)).color(Color32::RED));
+                            ui.label(Text::MustBePositiveInteger.tr(lang));
+                            ui.end_row();
+                        }
+
+                        ui.label("");
+                        if ui.add_enabled(!running, egui::Button::new(Text::Start.tr(lang))).clicked() {
+                            let range = self.range();
+                            let steps = usize::from_str(&self.steps).ok().filter(|s| *s > 0);
+                            let runs = usize::from_str(&self.runs).ok().filter(|r| *r > 0);
+                            let iterations = usize::from_str(&self.iterations).ok().filter(|i| *i > 0);
+
+                            self.range_valid = range.is_some();
+                            self.steps_valid = steps.is_some();
+                            self.runs_valid = runs.is_some();
+                            self.iterations_valid = iterations.is_some();
+
+                            if let (Some((from, to)), Some(steps), Some(runs), Some(iterations)) =
+                                (range, steps, runs, iterations)
+                            {
+                                action = Some(Action::Start(Start {
+                                    parameter: self.parameter,
+                                    values: values(self.parameter, from, to, steps),
+                                    runs,
+                                    iterations
+                                }));
+                            }
+                        }
+                        ui.end_row();
+                    });
+
+                ui.label(Text::NoSeedNote.tr(lang));
+
+                let Some(sweep) = &self.sweep else {
+                    return;
+                };
+
+                ui.separator();
+
+                if !sweep.finished {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(Text::ValueProgress.fill(lang, &[&(sweep.samples.len() + 1), &sweep.total]));
+                    });
+                }
+
+                if sweep.samples.is_empty() {
+                    return;
+                }
+
+                egui::Grid::new("sweep-results")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong(sweep.parameter.name().tr(lang));
+                        ui.strong(Text::Mean.tr(lang));
+                        ui.strong(Text::Best.tr(lang));
+                        ui.strong(Text::Worst.tr(lang));
+                        ui.end_row();
+
+                        for sample in &sweep.samples {
+                            ui.label(sample.value.to_string());
+                            ui.label(format!("{:.4}", sample.mean));
+                            ui.label(format!("{:.4}", sample.best));
+                            ui.label(format!("{:.4}", sample.worst));
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(best) = sweep.best().filter(|_| sweep.finished) {
+                    ui.horizontal(|ui| {
+                        let parameter = sweep.parameter.name().tr(lang).to_lowercase();
+                        ui.label(Text::BestMeanQualityAt.fill(lang, &[&parameter, &best.value]));
+
+                        if ui.button(Text::Use.tr(lang)).clicked() {
+                            action = Some(Action::Use(sweep.parameter, best.value));
+                        }
+                    });
+                }
+
+                let series = |quality: fn(&Sample) -> f64| sweep.samples.iter()
+                    .map(|s| [s.value, quality(s)])
+                    .collect::<Vec<_>>();
+
+                Plot::new("sweep-qualities")
+                    .height(200.0)
+                    .legend(Legend::default())
+                    .x_axis_label(sweep.parameter.name().tr(lang))
+                    .y_axis_label(Text::FinalQuality.tr(lang))
+                    .show(ui, |plot| {
+                        plot.line(Line::new(PlotPoints::from(series(|s| s.mean))).name(Text::Mean.tr(lang)));
+                        plot.points(
+                            Points::new(PlotPoints::from(series(|s| s.best))).radius(3.0).name(Text::Best.tr(lang))
+                        );
+                        plot.points(
+                            Points::new(PlotPoints::from(series(|s| s.worst))).radius(3.0).name(Text::Worst.tr(lang))
+                        );
+                    });
+            });
+
+        action
+    }
+}
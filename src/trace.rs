@@ -0,0 +1,156 @@
+//! Recording every state the engine goes through, for replaying a generation path elsewhere.
+//!
+//! A trace file starts with [`MAGIC`], a format version byte and the adjustable count as a
+//! little-endian `u32`. Then come records of a little-endian `u64` iteration followed by the
+//! values of all adjustables as little-endian `f64`s. Only cycles that changed the state are recorded.
+
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use geo_aid_internal::engine::rage::Rage;
+use geo_aid_internal::script::math;
+
+/// Extension of trace files.
+pub const EXTENSION: &str = "gtrace";
+pub const MAGIC: &[u8; 4] = b"GADT";
+const VERSION: u8 = 1;
+const USAGE: &str = "usage: geo-aid-dbg --replay <script> <trace>";
+
+/// Every state change of a generation, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    /// (iteration, values of all adjustables) after every accepted cycle.
+    pub records: Vec<(usize, Vec<f64>)>
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Trace {
+    /// # Errors
+    /// Returns an error if the file can't be written or the records have different adjustable counts.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let adjustables = self.records.first().map_or(0, |(_, state)| state.len());
+
+        if self.records.iter().any(|(_, state)| state.len() != adjustables) {
+            return Err(invalid("records have different adjustable counts"));
+        }
+
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&[VERSION])?;
+        file.write_all(&u32::try_from(adjustables).map_err(|_| invalid("too many adjustables"))?.to_le_bytes())?;
+
+        for (iteration, state) in &self.records {
+            file.write_all(&(*iteration as u64).to_le_bytes())?;
+
+            for value in state {
+                file.write_all(&value.to_le_bytes())?;
+            }
+        }
+
+        file.flush()
+    }
+
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't a valid trace.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+
+        let mut header = [0; 9];
+        file.read_exact(&mut header)?;
+
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a trace file"));
+        }
+
+        if header[4] != VERSION {
+            return Err(invalid("unsupported trace version"));
+        }
+
+        let adjustables = u32::from_le_bytes([header[5], header[6], header[7], header[8]]) as usize;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+
+        let record_size = 8 * (adjustables + 1);
+
+        if content.len() % record_size != 0 {
+            return Err(invalid("truncated trace"));
+        }
+
+        let word = |bytes: &[u8]| <[u8; 8]>::try_from(bytes).unwrap();
+        let records = content.chunks_exact(record_size)
+            .map(|record| {
+                let (iteration, values) = record.split_at(8);
+
+                (
+                    u64::from_le_bytes(word(iteration)) as usize,
+                    values.chunks_exact(8).map(|value| f64::from_le_bytes(word(value))).collect()
+                )
+            })
+            .collect();
+
+        Ok(Self { records })
+    }
+}
+
+/// Command line of a headless replay.
+pub struct ReplayArgs {
+    pub script: PathBuf,
+    pub trace: PathBuf
+}
+
+impl ReplayArgs {
+    /// `None` unless the debugger was started with `--replay`.
+    ///
+    /// # Errors
+    /// Returns the usage message if the arguments are malformed.
+    pub fn from_env() -> Option<Result<Self, String>> {
+        let mut args = env::args().skip(1);
+
+        if args.next().as_deref() != Some("--replay") {
+            return None;
+        }
+
+        Some(match (args.next(), args.next()) {
+            (Some(script), Some(trace)) => Ok(Self {
+                script: PathBuf::from(script),
+                trace: PathBuf::from(trace)
+            }),
+            _ => Err(String::from(USAGE))
+        })
+    }
+}
+
+/// Put the engine through every recorded state, printing the quality of each as CSV.
+///
+/// # Errors
+/// Returns an error if the script or trace can't be loaded, or the trace is for a different script.
+pub fn replay(args: &ReplayArgs) -> io::Result<()> {
+    let script = fs::read_to_string(&args.script)?;
+    let intermediate = math::load_script(&script)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}")))?;
+    let trace = Trace::load(&args.trace)?;
+
+    // The state is set directly, so a single worker is enough.
+    let mut rage = Rage::new(1, &intermediate);
+    let expected = rage.gen().get_state().len();
+
+    if trace.records.first().is_some_and(|(_, state)| state.len() != expected) {
+        return Err(invalid("the trace was recorded for a script with a different adjustable count"));
+    }
+
+    let mut out = io::stdout().lock();
+    writeln!(out, "iteration,quality")?;
+
+    for (iteration, state) in &trace.records {
+        rage.gen_mut().set_state(state);
+        writeln!(out, "{iteration},{}", rage.gen().get_total_quality())?;
+    }
+
+    Ok(())
+}
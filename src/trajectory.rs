@@ -0,0 +1,102 @@
+use egui::Context;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use geo_aid_internal::projector::figure::Item;
+use crate::i18n::{Language, Text};
+
+/// Most samples kept. The oldest ones are dropped first.
+const MAX_SAMPLES: usize = 10_000;
+
+/// The "Point trajectory" window, tracking one point's position over iterations.
+#[derive(Default)]
+pub struct TrajectoryTool {
+    pub open: bool,
+    /// Index of the tracked point in the figure.
+    item: Option<usize>,
+    /// (iteration, canvas position) of the tracked point, one per new frame.
+    samples: Vec<(usize, [f64; 2])>,
+    /// Revision of the last recorded frame.
+    last_revision: Option<usize>,
+    /// Show the 2D path instead of x and y over iterations.
+    path: bool
+}
+
+impl TrajectoryTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.item = None;
+        self.samples.clear();
+        self.last_revision = None;
+    }
+
+    /// Record the tracked point's position in a newly projected frame.
+    pub fn record(&mut self, revision: usize, iteration: usize, items: &[Item]) {
+        if self.last_revision == Some(revision) {
+            return;
+        }
+
+        let Some(Item::Point(pt)) = self.item.and_then(|i| items.get(i)) else {
+            return;
+        };
+
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.remove(0);
+        }
+
+        self.samples.push((iteration, [pt.position.x, pt.position.y]));
+        self.last_revision = Some(revision);
+    }
+
+    /// `selected` is the selected point, if exactly one point is selected.
+    pub fn show(&mut self, ctx: &Context, selected: Option<usize>, lang: Language) {
+        egui::Window::new(Text::PointTrajectory.tr(lang))
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(selected.is_some(), egui::Button::new(Text::TrackSelectedPoint.tr(lang)))
+                        .on_disabled_hover_text(Text::SelectSinglePoint.tr(lang))
+                        .clicked()
+                    {
+                        self.item = selected;
+                        self.samples.clear();
+                        self.last_revision = None;
+                    }
+
+                    ui.radio_value(&mut self.path, false, Text::XAndY.tr(lang));
+                    ui.radio_value(&mut self.path, true, Text::Path.tr(lang));
+                });
+
+                let Some(item) = self.item else {
+                    ui.label(Text::NoPointTracked.tr(lang));
+                    return;
+                };
+
+                ui.label(Text::TrackedPoint.fill(lang, &[&item, &self.samples.len()]));
+
+                if self.path {
+                    // Canvas y points down, so it's flipped to look like the canvas.
+                    let path = self.samples.iter()
+                        .map(|(_, [x, y])| [*x, -*y])
+                        .collect::<Vec<_>>();
+
+                    Plot::new("trajectory-path")
+                        .data_aspect(1.0)
+                        .show(ui, |plot| plot.line(Line::new(PlotPoints::from(path))));
+                } else {
+                    let coordinate = |axis: usize| self.samples.iter()
+                        .map(|(iteration, position)| [*iteration as f64, position[axis]])
+                        .collect::<Vec<_>>();
+
+                    Plot::new("trajectory-xy")
+                        .legend(Legend::default())
+                        .show(ui, |plot| {
+                            plot.line(Line::new(PlotPoints::from(coordinate(0))).name("x"));
+                            plot.line(Line::new(PlotPoints::from(coordinate(1))).name("y"));
+                        });
+                }
+            });
+    }
+}
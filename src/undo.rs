@@ -0,0 +1,134 @@
+use std::collections::VecDeque;
+
+/// How many changes can be undone.
+const CAPACITY: usize = 100;
+
+/// Engine settings that debugger-side edits change.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Values of all adjustables.
+    pub state: Vec<f64>,
+    pub max_adjustment: f64
+}
+
+/// Undo and redo stacks of engine snapshots.
+#[derive(Default)]
+pub struct UndoStack {
+    /// Oldest first, so that the oldest snapshot is dropped once full.
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>
+}
+
+impl UndoStack {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember `snapshot`, taken right before a change.
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self.undo.len() == CAPACITY {
+            self.undo.pop_front();
+        }
+
+        self.undo.push_back(snapshot);
+        self.redo.clear();
+    }
+
+    /// The snapshot to go back to, given the `current` one.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(snapshot)
+    }
+
+    /// The snapshot to go forward to, given the `current` one.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(snapshot)
+    }
+
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    #[must_use]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(value: f64) -> Snapshot {
+        Snapshot {
+            state: vec![value],
+            max_adjustment: 0.5
+        }
+    }
+
+    fn value(snapshot: Option<Snapshot>) -> Option<f64> {
+        snapshot.map(|snapshot| snapshot.state[0])
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut stack = UndoStack::new();
+        assert!(!stack.can_undo());
+
+        stack.push(snapshot(0.0));
+        stack.push(snapshot(1.0));
+
+        assert_eq!(value(stack.undo(snapshot(2.0))), Some(1.0));
+        assert_eq!(value(stack.undo(snapshot(1.0))), Some(0.0));
+        assert_eq!(value(stack.undo(snapshot(0.0))), None);
+        assert!(stack.can_redo());
+
+        assert_eq!(value(stack.redo(snapshot(0.0))), Some(1.0));
+        assert_eq!(value(stack.redo(snapshot(1.0))), Some(2.0));
+        assert!(!stack.can_redo());
+        assert!(stack.can_undo());
+    }
+
+    #[test]
+    fn changes_clear_the_redo_stack() {
+        let mut stack = UndoStack::new();
+        stack.push(snapshot(0.0));
+        let _ = stack.undo(snapshot(1.0));
+
+        stack.push(snapshot(0.0));
+        assert!(!stack.can_redo());
+
+        stack.clear();
+        assert!(!stack.can_undo());
+    }
+
+    #[test]
+    fn oldest_changes_are_forgotten() {
+        let mut stack = UndoStack::new();
+
+        for i in 0..CAPACITY + 5 {
+            stack.push(snapshot(i as f64));
+        }
+
+        assert_eq!(stack.undo.len(), CAPACITY);
+
+        let mut undone = Vec::new();
+        while let Some(undone_value) = value(stack.undo(snapshot(-1.0))) {
+            undone.push(undone_value);
+        }
+
+        assert_eq!(undone.len(), CAPACITY);
+        assert_eq!(undone.first(), Some(&((CAPACITY + 4) as f64)));
+        assert_eq!(undone.last(), Some(&5.0));
+    }
+}
@@ -0,0 +1,314 @@
+//! Zooming into and panning around the canvas.
+//!
+//! The view is applied to projected items, so everything working in canvas coordinates (hit-testing, selection,
+//! measurements) sees the figure as drawn.
+
+use egui::{Key, Ui};
+use geo_aid_internal::projector::figure::{Item, Label, Position};
+use serde::{Deserialize, Serialize};
+use crate::i18n::{Language, Text};
+
+pub const MIN_ZOOM: f64 = 1.0;
+pub const MAX_ZOOM: f64 = 64.0;
+/// Keys jumping to the bookmark at the same position.
+pub const BOOKMARK_KEYS: [Key; 9] = [
+    Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9
+];
+
+/// The part of the whole figure shown on the canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct View {
+    pub zoom: f64,
+    /// Point of the whole figure in the middle of the canvas, as a fraction of the canvas size.
+    pub center: (f64, f64)
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            zoom: MIN_ZOOM,
+            center: (0.5, 0.5)
+        }
+    }
+}
+
+impl View {
+    #[must_use]
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > MIN_ZOOM
+    }
+
+    /// Where a point of the whole figure ends up on the canvas.
+    #[must_use]
+    pub fn to_view(&self, (x, y): (f64, f64), canvas: (f32, f32)) -> (f64, f64) {
+        let (width, height) = (f64::from(canvas.0), f64::from(canvas.1));
+
+        (
+            (x - self.center.0 * width) * self.zoom + width / 2.0,
+            (y - self.center.1 * height) * self.zoom + height / 2.0
+        )
+    }
+
+    /// The point of the whole figure under a point of the canvas.
+    #[must_use]
+    pub fn to_figure(&self, (x, y): (f64, f64), canvas: (f32, f32)) -> (f64, f64) {
+        let (width, height) = (f64::from(canvas.0), f64::from(canvas.1));
+
+        (
+            (x - width / 2.0) / self.zoom + self.center.0 * width,
+            (y - height / 2.0) / self.zoom + self.center.1 * height
+        )
+    }
+
+    fn map(&self, position: &mut Position, canvas: (f32, f32)) {
+        (position.x, position.y) = self.to_view((position.x, position.y), canvas);
+    }
+
+    fn map_label(&self, label: &mut Option<Label>, canvas: (f32, f32)) {
+        if let Some(label) = label {
+            self.map(&mut label.position, canvas);
+        }
+    }
+
+    /// Move items projected onto the whole canvas into the view.
+    pub fn apply(&self, items: &mut [Item], canvas: (f32, f32)) {
+        if *self == Self::default() {
+            return;
+        }
+
+        for item in items {
+            match item {
+                Item::Point(pt) => {
+                    self.map(&mut pt.position, canvas);
+                    self.map_label(&mut pt.label, canvas);
+                }
+                Item::Line(ln) => {
+                    self.map(&mut ln.points.0, canvas);
+                    self.map(&mut ln.points.1, canvas);
+                    self.map_label(&mut ln.label, canvas);
+                }
+                Item::Segment(x)
+                | Item::Ray(x) => {
+                    self.map(&mut x.points.0, canvas);
+                    self.map(&mut x.points.1, canvas);
+                    self.map_label(&mut x.label, canvas);
+                }
+                Item::Circle(circle) => {
+                    self.map(&mut circle.center, canvas);
+                    circle.radius *= self.zoom;
+                    self.map_label(&mut circle.label, canvas);
+                }
+            }
+        }
+    }
+
+    /// Zoom in by `factor`, keeping the figure point under `at` in place.
+    pub fn zoom_at(&mut self, factor: f64, at: (f64, f64), canvas: (f32, f32)) {
+        let anchor = self.to_figure(at, canvas);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+
+        let (width, height) = (f64::from(canvas.0), f64::from(canvas.1));
+        self.center = (
+            (anchor.0 - (at.0 - width / 2.0) / self.zoom) / width,
+            (anchor.1 - (at.1 - height / 2.0) / self.zoom) / height
+        );
+        self.clamp();
+    }
+
+    /// Move the figure by `delta` canvas pixels.
+    pub fn pan(&mut self, delta: (f64, f64), canvas: (f32, f32)) {
+        self.center.0 -= delta.0 / self.zoom / f64::from(canvas.0);
+        self.center.1 -= delta.1 / self.zoom / f64::from(canvas.1);
+        self.clamp();
+    }
+
+    /// Center the view on a point given as a fraction of the whole figure.
+    pub fn center_on(&mut self, center: (f64, f64)) {
+        self.center = center;
+        self.clamp();
+    }
+
+    /// Keep the view within the whole figure.
+    fn clamp(&mut self) {
+        let half = 0.5 / self.zoom;
+        self.center.0 = self.center.0.clamp(half, 1.0 - half);
+        self.center.1 = self.center.1.clamp(half, 1.0 - half);
+    }
+}
+
+/// A named view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub view: View
+}
+
+/// Saved views, the first nine of which are reachable with number keys.
+#[derive(Default)]
+pub struct Bookmarks {
+    pub list: Vec<Bookmark>,
+    /// Name of the next bookmark.
+    name: String
+}
+
+impl Bookmarks {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The view of the bookmark whose key was pressed, if any.
+    #[must_use]
+    pub fn pressed(&self, ui: &Ui) -> Option<View> {
+        ui.input(|input| {
+            BOOKMARK_KEYS.iter()
+                .zip(&self.list)
+                .find(|(key, _)| input.key_pressed(**key))
+                .map(|(_, bookmark)| bookmark.view)
+        })
+    }
+
+    /// Returns the view to jump to, if the user picked one.
+    #[must_use]
+    pub fn ui(&mut self, ui: &mut Ui, current: &View, lang: Language) -> Option<View> {
+        let mut jump = None;
+        let mut remove = None;
+
+        egui::Grid::new("bookmarks")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, bookmark) in self.list.iter_mut().enumerate() {
+                    ui.label(if i < BOOKMARK_KEYS.len() { format!("{}", i + 1) } else { String::new() });
+                    ui.text_edit_singleline(&mut bookmark.name);
+                    ui.horizontal(|ui| {
+                        if ui.small_button(Text::Go.tr(lang)).clicked() {
+                            jump = Some(bookmark.view);
+                        }
+
+                        if ui.small_button(Text::Update.tr(lang))
+                            .on_hover_text(Text::UpdateBookmarkHint.tr(lang))
+                            .clicked()
+                        {
+                            bookmark.view = *current;
+                        }
+
+                        if ui.small_button(Text::Remove.tr(lang)).clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        if let Some(i) = remove {
+            self.list.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.name);
+
+            if ui.button(Text::SaveCurrentView.tr(lang)).clicked() {
+                let name = if self.name.trim().is_empty() {
+                    Text::ViewNumber.fill(lang, &[&(self.list.len() + 1)])
+                } else {
+                    self.name.trim().to_owned()
+                };
+
+                self.list.push(Bookmark { name, view: *current });
+                self.name.clear();
+            }
+        });
+
+        ui.label(Text::BookmarkKeysHint.tr(lang));
+        jump
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANVAS: (f32, f32) = (800.0, 600.0);
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn default_view_is_identity() {
+        let view = View::default();
+
+        assert_close(view.to_view((123.0, 456.0), CANVAS), (123.0, 456.0));
+        assert_close(view.to_figure((123.0, 456.0), CANVAS), (123.0, 456.0));
+    }
+
+    #[test]
+    fn to_figure_inverts_to_view() {
+        let view = View {
+            zoom: 3.5,
+            center: (0.3, 0.8)
+        };
+
+        for point in [(0.0, 0.0), (400.0, 300.0), (-20.0, 1000.0), (799.0, 1.0)] {
+            assert_close(view.to_figure(view.to_view(point, CANVAS), CANVAS), point);
+            assert_close(view.to_view(view.to_figure(point, CANVAS), CANVAS), point);
+        }
+    }
+
+    #[test]
+    fn center_is_in_the_middle() {
+        let view = View {
+            zoom: 2.0,
+            center: (0.25, 0.75)
+        };
+
+        assert_close(view.to_view((200.0, 450.0), CANVAS), (400.0, 300.0));
+    }
+
+    #[test]
+    fn zoom_keeps_the_point_under_the_cursor() {
+        let mut view = View::default();
+        let at = (300.0, 200.0);
+        let under = view.to_figure(at, CANVAS);
+
+        view.zoom_at(4.0, at, CANVAS);
+        assert_eq!(view.zoom, 4.0);
+        assert_close(view.to_figure(at, CANVAS), under);
+
+        view.zoom_at(0.5, at, CANVAS);
+        assert_eq!(view.zoom, 2.0);
+        assert_close(view.to_figure(at, CANVAS), under);
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        let mut view = View::default();
+
+        view.zoom_at(1000.0, (400.0, 300.0), CANVAS);
+        assert_eq!(view.zoom, MAX_ZOOM);
+
+        view.zoom_at(0.001, (400.0, 300.0), CANVAS);
+        assert_eq!(view, View::default());
+    }
+
+    #[test]
+    fn view_stays_within_the_figure() {
+        let mut view = View {
+            zoom: 4.0,
+            center: (0.125, 0.125)
+        };
+
+        // Zooming in at the corner can't show anything past it.
+        view.zoom_at(2.0, (0.0, 0.0), CANVAS);
+        assert_close(view.center, (0.0625, 0.0625));
+        assert_close(view.to_figure((0.0, 0.0), CANVAS), (0.0, 0.0));
+
+        view.pan((-1e6, 1e6), CANVAS);
+        assert_close(view.center, (0.9375, 0.0625));
+
+        view.center_on((0.5, -1.0));
+        assert_close(view.center, (0.5, 0.0625));
+    }
+}
@@ -0,0 +1,85 @@
+//! A grid of the engine's workers, showing which of them get their proposals taken.
+
+use egui::{Color32, Rect, Sense, Ui, Vec2};
+use crate::engine::WorkerStats;
+use crate::i18n::{Language, Text};
+
+/// Side of a worker's cell, and space between cells.
+const CELL: f32 = 12.0;
+const GAP: f32 = 2.0;
+/// Color of workers with no proposals taken, and of the worker with the most taken.
+const IDLE_COLOR: Color32 = Color32::from_gray(180);
+const BUSY_COLOR: Color32 = Color32::from_rgb(20, 150, 60);
+
+/// Fraction of a worker's proposals that were taken.
+#[must_use]
+pub fn acceptance(stats: &WorkerStats) -> f64 {
+    let proposals = stats.accepted + stats.rejected;
+
+    if proposals == 0 {
+        0.0
+    } else {
+        stats.accepted as f64 / proposals as f64
+    }
+}
+
+/// The color `t` of the way from `from` to `to`.
+#[must_use]
+pub fn mix(from: Color32, to: Color32, t: f32) -> Color32 {
+    let channel = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+
+    Color32::from_rgb(channel(from.r(), to.r()), channel(from.g(), to.g()), channel(from.b(), to.b()))
+}
+
+/// Show `workers` as a grid shaded by their acceptance relative to the best worker, with details on hover.
+pub fn ui(ui: &mut Ui, workers: Option<&[WorkerStats]>, lang: Language) {
+    let Some(workers) = workers else {
+        ui.label(Text::NotSupportedByEngine.tr(lang));
+        return;
+    };
+
+    let contributing = workers.iter().filter(|stats| stats.accepted > 0).count();
+    ui.label(Text::ContributingWorkers.fill(lang, &[&contributing, &workers.len()]));
+
+    let best = workers.iter().map(acceptance).fold(0.0, f64::max);
+    let columns = ((ui.available_width() + GAP) / (CELL + GAP)).floor().max(1.0) as usize;
+    let rows = workers.len().div_ceil(columns);
+    let size = Vec2::new(columns as f32 * (CELL + GAP) - GAP, (rows as f32 * (CELL + GAP) - GAP).max(0.0));
+    let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+    let painter = ui.painter_at(rect);
+    let cell = |i: usize| {
+        let at = Vec2::new((i % columns) as f32, (i / columns) as f32) * (CELL + GAP);
+        Rect::from_min_size(rect.min + at, Vec2::splat(CELL))
+    };
+
+    for (i, stats) in workers.iter().enumerate() {
+        let share = if best > 0.0 { (acceptance(stats) / best) as f32 } else { 0.0 };
+        painter.rect_filled(cell(i), 1.0, mix(IDLE_COLOR, BUSY_COLOR, share));
+    }
+
+    let hovered = response.hover_pos().and_then(|pos| (0..workers.len()).find(|&i| cell(i).contains(pos)));
+
+    if let Some(i) = hovered {
+        let stats = &workers[i];
+        let quality = format!("{:.6}", stats.quality);
+        response.on_hover_text(Text::WorkerDetails.fill(lang, &[&i, &quality, &stats.accepted, &stats.rejected]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acceptance_is_the_share_taken() {
+        let stats = |accepted, rejected| WorkerStats {
+            quality: 0.0,
+            accepted,
+            rejected
+        };
+
+        assert_eq!(acceptance(&stats(0, 0)), 0.0);
+        assert_eq!(acceptance(&stats(1, 3)), 0.25);
+        assert_eq!(acceptance(&stats(2, 0)), 1.0);
+    }
+}
@@ -0,0 +1,74 @@
+//! Visual regression tests of the renderer, against the PNGs in `tests/snapshots`.
+//!
+//! Rendering needs a window, so this runs its own macroquad loop instead of the test harness, and needs a display.
+//! Failures are printed to stderr and fail the test through the exit code.
+//!
+//! The snapshots depend on how the GPU multisamples, so the test is skipped unless enabled with
+//! `RUN_SNAPSHOTS=1 cargo test --features snapshot --test snapshots`. Record new or changed snapshots with
+//! `UPDATE_SNAPSHOTS=1` instead.
+
+use std::path::Path;
+use std::process;
+use std::process::ExitCode;
+use geo_aid_dbg::snapshot;
+use geo_aid_dbg::style::{DotStyle, ItemStyle};
+use geo_aid_internal::projector::figure::{Item, PointItem, Position};
+
+const CANVAS: (f32, f32) = (200.0, 150.0);
+/// Channel differences left to antialiasing differences between drivers.
+const TOLERANCE: u8 = 16;
+const MAX_PIXELS: usize = 20;
+
+fn point(x: f64, y: f64) -> Item {
+    Item::Point(PointItem {
+        position: Position { x, y },
+        display_dot: true,
+        label: None
+    })
+}
+
+fn points() -> Vec<Item> {
+    vec![point(40.0, 30.0), point(100.0, 75.0), point(160.0, 120.0)]
+}
+
+/// Snapshot names with what they render.
+fn cases() -> Vec<(&'static str, Vec<Item>, ItemStyle)> {
+    let hollow = ItemStyle {
+        dot: DotStyle::Hollow,
+        point_radius: 4.0,
+        ..ItemStyle::default()
+    };
+
+    vec![
+        ("empty", Vec::new(), ItemStyle::default()),
+        ("points", points(), ItemStyle::default()),
+        ("points-hollow", points(), hollow)
+    ]
+}
+
+fn main() -> ExitCode {
+    if !snapshot::enabled() {
+        eprintln!("snapshot tests skipped, set {} to run them", snapshot::RUN_VAR);
+        return ExitCode::SUCCESS;
+    }
+
+    macroquad::Window::new("snapshots", async {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots");
+        let mut failed = false;
+
+        for (name, items, style) in cases() {
+            let image = snapshot::render(&items, CANVAS, &style);
+
+            if let Err(err) = snapshot::check(&image, &dir.join(format!("{name}.png")), TOLERANCE, MAX_PIXELS) {
+                eprintln!("{name}: {err}");
+                failed = true;
+            }
+        }
+
+        // The window's loop may never return, so the result is reported by exiting from within it.
+        process::exit(i32::from(failed));
+    });
+
+    // Only reached if the window closed before the snapshots were checked.
+    ExitCode::FAILURE
+}